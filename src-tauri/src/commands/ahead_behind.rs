@@ -0,0 +1,141 @@
+use crate::commands::error::AppError;
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::Manager;
+
+// Repository, branch tip, base tip
+type DivergenceKey = (String, Oid, Oid);
+
+/// Ahead/behind counts by tip pair. Tips are immutable commits, so an entry stays valid
+/// until either branch moves and the key changes.
+#[derive(Default)]
+pub struct AheadBehindCache(Mutex<HashMap<DivergenceKey, (usize, usize)>>);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchDivergence {
+    pub branch: String,
+    pub base: String,
+    pub ahead: usize,
+    pub behind: usize,
+}
+
+/// Tip of a branch given as get_detailed_branches names it: "main" or "origin/main".
+fn branch_tip(repo: &Repository, branch_name: &str) -> Option<Oid> {
+    repo.find_reference(&format!("refs/heads/{}", branch_name))
+        .or_else(|_| repo.find_reference(&format!("refs/remotes/{}", branch_name)))
+        .and_then(|reference| reference.peel_to_commit())
+        .map(|commit| commit.id())
+        .ok()
+}
+
+/// The branch others are compared against: what origin/HEAD points to, else a local
+/// main or master, else the current branch.
+fn default_base_branch(repo: &Repository) -> Option<String> {
+    if let Ok(origin_head) = repo.find_reference("refs/remotes/origin/HEAD") {
+        if let Some(target) = origin_head.symbolic_target() {
+            if let Some(name) = target.strip_prefix("refs/remotes/") {
+                return Some(name.to_string());
+            }
+        }
+    }
+
+    ["main", "master"]
+        .iter()
+        .find(|name| repo.find_branch(name, git2::BranchType::Local).is_ok())
+        .map(|name| name.to_string())
+        .or_else(|| repo.head().ok().and_then(|head| head.shorthand().map(|name| name.to_string())))
+}
+
+fn local_branch_names(repo: &Repository) -> Vec<String> {
+    repo.branches(Some(git2::BranchType::Local))
+        .map(|branches| {
+            branches
+                .flatten()
+                .filter_map(|(branch, _)| branch.name().ok().flatten().map(|name| name.to_string()))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Commits `tip` has that `base_tip` lacks, and the reverse, from the cache when known.
+pub fn cached_ahead_behind(
+    cache: &AheadBehindCache,
+    repo: &Repository,
+    repo_path: &str,
+    tip: Oid,
+    base_tip: Oid,
+) -> Result<(usize, usize), git2::Error> {
+    let key = (repo_path.to_string(), tip, base_tip);
+    if let Some(counts) = cache.0.lock().ok().and_then(|entries| entries.get(&key).copied()) {
+        return Ok(counts);
+    }
+
+    let counts = repo.graph_ahead_behind(tip, base_tip)?;
+    if let Ok(mut entries) = cache.0.lock() {
+        entries.insert(key, counts);
+    }
+    Ok(counts)
+}
+
+fn ahead_behind_blocking(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branches: Option<Vec<String>>,
+    base: Option<String>,
+    token: OperationToken,
+) -> Result<Vec<BranchDivergence>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+    let base = match base.or_else(|| default_base_branch(&repo)) {
+        Some(base) => base,
+        None => return Ok(Vec::new()), // An empty repository has nothing to compare
+    };
+    let base_tip = branch_tip(&repo, &base)
+        .ok_or_else(|| AppError::not_found(format!("Branch not found: {}", base)))?;
+    let branches = branches.unwrap_or_else(|| local_branch_names(&repo));
+    let cache = app.state::<AheadBehindCache>();
+
+    let mut divergences = Vec::new();
+    for branch in branches {
+        token.check()?;
+
+        // Branches deleted since the list was fetched are skipped rather than failing the batch
+        let tip = match branch_tip(&repo, &branch) {
+            Some(tip) => tip,
+            None => continue,
+        };
+        let (ahead, behind) = cached_ahead_behind(&cache, &repo, &repo_path, tip, base_tip)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to compare {} with {}", branch, base)))?;
+
+        divergences.push(BranchDivergence {
+            branch,
+            base: base.clone(),
+            ahead,
+            behind,
+        });
+    }
+
+    Ok(divergences)
+}
+
+/// How far each branch is ahead of and behind `base` (the default branch when not
+/// given). Separate from get_detailed_branches so the branch list shows immediately;
+/// counts are cached per tip pair and the walk can be cancelled.
+#[tauri::command]
+pub async fn get_branch_ahead_behind(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branches: Option<Vec<String>>,
+    base: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Vec<BranchDivergence>, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "ahead_behind", operation_id, move |token| {
+        ahead_behind_blocking(task_app, repo_path, branches, base, token)
+    })
+    .await
+}
@@ -0,0 +1,52 @@
+use crate::commands::error::AppError;
+use std::path::Path;
+
+const ARCHIVE_FORMATS: [&str; 3] = ["zip", "tar", "tar.gz"];
+
+/// Writes the tree at `rev` as a zip, tar or tar.gz archive.
+/// Uses `git archive`, so `export-ignore` and `export-subst` attributes are honored.
+#[tauri::command]
+pub fn export_archive(
+    repo_path: String,
+    rev: String,
+    format: String,
+    output_path: String,
+) -> Result<String, AppError> {
+    let repo = git2::Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+    if !ARCHIVE_FORMATS.contains(&format.as_str()) {
+        return Err(AppError::invalid_input(format!("Unsupported archive format: {}", format)));
+    }
+
+    let commit = repo.revparse_single(&rev)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| AppError::wrap(e, &format!("Failed to resolve '{}'", rev)))?;
+
+    if let Some(parent) = Path::new(&output_path).parent() {
+        if !parent.as_os_str().is_empty() && !parent.exists() {
+            return Err(AppError::not_found(format!("Output directory does not exist: {}", parent.display())));
+        }
+    }
+
+    // Top-level folder inside the archive, like GitHub's "Download source" archives
+    let repo_name = repo.workdir()
+        .unwrap_or_else(|| repo.path())
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or("archive")
+        .trim_end_matches(".git")
+        .to_string();
+    let prefix = format!("--prefix={}-{}/", repo_name, rev.replace('/', "-"));
+    let format_arg = format!("--format={}", format);
+    let commit_id = commit.id().to_string();
+
+    crate::run_git(
+        &repo_path,
+        &["archive", format_arg.as_str(), prefix.as_str(), "-o", output_path.as_str(), commit_id.as_str()],
+        "export archive",
+    )?;
+
+    println!("📦 Exported {} at {} to {}", rev, commit_id, output_path);
+    Ok(format!("Exported {} to {}", rev, output_path))
+}
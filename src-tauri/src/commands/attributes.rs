@@ -0,0 +1,291 @@
+use crate::commands::error::AppError;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const DEFAULT_ATTRIBUTES_FILE: &str = ".gitattributes";
+const INFO_ATTRIBUTES_FILE: &str = ".git/info/attributes";
+// Drivers git knows without any configuration
+const BUILTIN_DIFF_DRIVERS: [&str; 24] = [
+    "ada", "bash", "bibtex", "cpp", "csharp", "css", "dts", "elixir", "fortran", "fountain",
+    "golang", "html", "java", "kotlin", "markdown", "matlab", "objc", "pascal", "perl", "php",
+    "python", "ruby", "rust", "tex",
+];
+const BUILTIN_MERGE_DRIVERS: [&str; 3] = ["text", "binary", "union"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitAttribute {
+    pub name: String,
+    /// "set" (`name`), "unset" (`-name`), "unspecified" (`!name`) or "value" (`name=value`)
+    pub state: String,
+    pub value: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AttributeEntry {
+    pub line_number: usize,
+    pub pattern: String,
+    pub attributes: Vec<GitAttribute>,
+    pub is_macro: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PathAttribute {
+    pub name: String,
+    pub state: String,
+    pub value: Option<String>,
+}
+
+fn attributes_file(repo: &Repository, file_path: Option<&str>) -> Result<PathBuf, AppError> {
+    let file_path = file_path.unwrap_or(DEFAULT_ATTRIBUTES_FILE);
+    // info/attributes lives in the git dir, not the working tree, and linked worktrees
+    // share the one of their main repository
+    if file_path == INFO_ATTRIBUTES_FILE {
+        let git_dir = repo.path();
+        let common_dir = fs::read_to_string(git_dir.join("commondir"))
+            .map(|content| git_dir.join(content.trim()))
+            .unwrap_or_else(|_| git_dir.to_path_buf());
+        return Ok(common_dir.join("info").join("attributes"));
+    }
+
+    if file_path != DEFAULT_ATTRIBUTES_FILE && !file_path.ends_with("/.gitattributes") {
+        return Err(AppError::invalid_input(format!("Not an attributes file: {}", file_path)));
+    }
+    // Only plain names: no "..", and no root or drive prefix that would make join() escape the workdir
+    let mut components = Path::new(file_path).components();
+    if components.clone().any(|component| !matches!(component, Component::Normal(_)))
+        || components.next().is_some_and(|component| component.as_os_str() == ".git")
+    {
+        return Err(AppError::invalid_input(format!("Attributes file must be inside the repository: {}", file_path)));
+    }
+
+    let workdir = repo.workdir()
+        .ok_or_else(|| AppError::invalid_input("Bare repositories have no .gitattributes files"))?;
+    Ok(workdir.join(file_path))
+}
+
+fn parse_attribute(token: &str) -> GitAttribute {
+    if let Some(name) = token.strip_prefix('-') {
+        return GitAttribute { name: name.to_string(), state: "unset".to_string(), value: None };
+    }
+    if let Some(name) = token.strip_prefix('!') {
+        return GitAttribute { name: name.to_string(), state: "unspecified".to_string(), value: None };
+    }
+    match token.split_once('=') {
+        Some((name, value)) => GitAttribute {
+            name: name.to_string(),
+            state: "value".to_string(),
+            value: Some(value.to_string()),
+        },
+        None => GitAttribute { name: token.to_string(), state: "set".to_string(), value: None },
+    }
+}
+
+fn format_attribute(attribute: &GitAttribute) -> String {
+    match attribute.state.as_str() {
+        "unset" => format!("-{}", attribute.name),
+        "unspecified" => format!("!{}", attribute.name),
+        "value" => format!("{}={}", attribute.name, attribute.value.as_deref().unwrap_or("")),
+        _ => attribute.name.clone(),
+    }
+}
+
+/// Parses one attributes line, returning None for blank lines and comments.
+fn parse_line(line: &str) -> Option<(String, Vec<GitAttribute>)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    let mut tokens = line.split_whitespace();
+    let pattern = tokens.next()?.to_string();
+    Some((pattern, tokens.map(parse_attribute).collect()))
+}
+
+fn validate_entry(repo: &Repository, pattern: &str, attributes: &[GitAttribute]) -> Result<Vec<String>, AppError> {
+    if pattern.is_empty() || pattern.chars().any(char::is_whitespace) {
+        return Err(AppError::invalid_input(format!("Invalid pattern: '{}'", pattern)));
+    }
+    if pattern.starts_with('#') {
+        return Err(AppError::invalid_input("Patterns cannot start with '#'"));
+    }
+    if attributes.is_empty() {
+        return Err(AppError::invalid_input("At least one attribute is required"));
+    }
+
+    let config = repo.config().ok();
+    let mut warnings = Vec::new();
+
+    for attribute in attributes {
+        let valid_name = !attribute.name.is_empty()
+            && !attribute.name.starts_with('-')
+            && attribute.name.chars().all(|c| c.is_ascii_alphanumeric() || "-_.".contains(c));
+        if !valid_name {
+            return Err(AppError::invalid_input(format!("Invalid attribute name: '{}'", attribute.name)));
+        }
+
+        match attribute.state.as_str() {
+            "set" | "unset" | "unspecified" => {}
+            "value" => {
+                let value = attribute.value.as_deref().unwrap_or("");
+                if value.is_empty() || value.chars().any(char::is_whitespace) {
+                    return Err(AppError::invalid_input(format!("Invalid value for attribute '{}'", attribute.name)));
+                }
+
+                // Custom drivers only work once they are defined in git config
+                let (builtin, section): (&[&str], &str) = match attribute.name.as_str() {
+                    "diff" => (&BUILTIN_DIFF_DRIVERS, "diff"),
+                    "merge" => (&BUILTIN_MERGE_DRIVERS, "merge"),
+                    "filter" => (&[], "filter"),
+                    _ => continue,
+                };
+                let defined = builtin.contains(&value)
+                    || config.as_ref().map(|config| {
+                        config.entries(Some(&format!("{}\\.{}\\..*", section, regex_escape(value))))
+                            .map(|mut entries| entries.next().is_some())
+                            .unwrap_or(false)
+                    }).unwrap_or(false);
+                if !defined {
+                    warnings.push(format!(
+                        "{} driver '{}' is not defined in git config ({}.{}.*)",
+                        attribute.name, value, section, value
+                    ));
+                }
+            }
+            other => return Err(AppError::invalid_input(format!("Invalid attribute state: '{}'", other))),
+        }
+    }
+
+    Ok(warnings)
+}
+
+fn regex_escape(value: &str) -> String {
+    value.chars().fold(String::new(), |mut escaped, c| {
+        if !c.is_ascii_alphanumeric() {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+        escaped
+    })
+}
+
+/// Lists the entries of a .gitattributes file (the root one by default).
+#[tauri::command]
+pub fn get_gitattributes(repo_path: String, file_path: Option<String>) -> Result<Vec<AttributeEntry>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let path = attributes_file(&repo, file_path.as_deref())?;
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(AppError::wrap(e, &format!("Failed to read {}", path.display()))),
+    };
+
+    Ok(content
+        .lines()
+        .enumerate()
+        .filter_map(|(index, line)| {
+            parse_line(line).map(|(pattern, attributes)| AttributeEntry {
+                line_number: index + 1,
+                is_macro: pattern.starts_with("[attr]"),
+                pattern,
+                attributes,
+            })
+        })
+        .collect())
+}
+
+/// Adds or replaces the entry for `pattern`, keeping comments and other lines untouched.
+/// Returns validation warnings such as references to undefined drivers.
+#[tauri::command]
+pub fn set_gitattributes_entry(
+    repo_path: String,
+    pattern: String,
+    attributes: Vec<GitAttribute>,
+    file_path: Option<String>,
+) -> Result<Vec<String>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let path = attributes_file(&repo, file_path.as_deref())?;
+
+    let warnings = validate_entry(&repo, &pattern, &attributes)?;
+
+    let new_line = std::iter::once(pattern.clone())
+        .chain(attributes.iter().map(format_attribute))
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let content = fs::read_to_string(&path).unwrap_or_default();
+    let mut replaced = false;
+    let mut lines: Vec<String> = content
+        .lines()
+        .map(|line| match parse_line(line) {
+            Some((existing, _)) if existing == pattern && !replaced => {
+                replaced = true;
+                new_line.clone()
+            }
+            _ => line.to_string(),
+        })
+        .collect();
+    if !replaced {
+        lines.push(new_line);
+    }
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::wrap(e, "Failed to create directory"))?;
+    }
+    fs::write(&path, lines.join("\n") + "\n")
+        .map_err(|e| AppError::wrap(e, &format!("Failed to write {}", path.display())))?;
+
+    Ok(warnings)
+}
+
+#[tauri::command]
+pub fn remove_gitattributes_entry(repo_path: String, pattern: String, file_path: Option<String>) -> Result<String, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let path = attributes_file(&repo, file_path.as_deref())?;
+
+    let content = fs::read_to_string(&path)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to read {}", path.display())))?;
+
+    let lines: Vec<&str> = content
+        .lines()
+        .filter(|line| !matches!(parse_line(line), Some((existing, _)) if existing == pattern))
+        .collect();
+
+    if lines.len() == content.lines().count() {
+        return Err(AppError::not_found(format!("No attributes entry for '{}'", pattern)));
+    }
+
+    fs::write(&path, lines.join("\n") + "\n")
+        .map_err(|e| AppError::wrap(e, &format!("Failed to write {}", path.display())))?;
+
+    Ok(format!("Removed attributes for {}", pattern))
+}
+
+/// Effective values of the given attributes for a path, as git resolves them.
+#[tauri::command]
+pub fn check_attributes(repo_path: String, file_path: String, names: Vec<String>) -> Result<Vec<PathAttribute>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+    names
+        .into_iter()
+        .map(|name| {
+            let value = repo.get_attr(std::path::Path::new(&file_path), &name, git2::AttrCheckFlags::FILE_THEN_INDEX)
+                .map_err(|e| AppError::wrap(e, &format!("Failed to read attribute '{}'", name)))?;
+            let (state, value) = match git2::AttrValue::from_string(value) {
+                git2::AttrValue::True => ("set", None),
+                git2::AttrValue::False => ("unset", None),
+                git2::AttrValue::Unspecified => ("unspecified", None),
+                git2::AttrValue::String(value) => ("value", Some(value.to_string())),
+                git2::AttrValue::Bytes(value) => ("value", Some(String::from_utf8_lossy(value).to_string())),
+            };
+            Ok(PathAttribute { name, state: state.to_string(), value })
+        })
+        .collect()
+}
@@ -0,0 +1,110 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::{AppError, ErrorKind};
+use crate::commands::profiling::record_timing;
+use crate::database::{AuditEntry, RefChange};
+use git2::Repository;
+use std::collections::BTreeMap;
+use std::time::Instant;
+use tauri::{Manager, State};
+
+const DEFAULT_HISTORY_LIMIT: i64 = 200;
+
+/// Target of every ref plus HEAD, keyed by name. Empty when the repository can't be opened
+/// (e.g. before a clone).
+fn snapshot_refs(repo_path: &str) -> BTreeMap<String, String> {
+    let mut targets = BTreeMap::new();
+    let repo = match Repository::open(repo_path) {
+        Ok(repo) => repo,
+        Err(_) => return targets,
+    };
+
+    if let Ok(head) = repo.head() {
+        if let Some(target) = head.target() {
+            targets.insert("HEAD".to_string(), target.to_string());
+        }
+    }
+    if let Ok(references) = repo.references() {
+        for reference in references.flatten() {
+            if let (Some(name), Some(target)) = (reference.name(), reference.target()) {
+                targets.insert(name.to_string(), target.to_string());
+            }
+        }
+    }
+
+    targets
+}
+
+fn changed_refs(before: &BTreeMap<String, String>, after: &BTreeMap<String, String>) -> Vec<RefChange> {
+    let mut names: Vec<&String> = before.keys().chain(after.keys()).collect();
+    names.sort();
+    names.dedup();
+
+    names
+        .into_iter()
+        .filter(|name| before.get(*name) != after.get(*name))
+        .map(|name| RefChange {
+            ref_name: name.clone(),
+            old_target: before.get(name).cloned(),
+            new_target: after.get(name).cloned(),
+        })
+        .collect()
+}
+
+/// Runs a mutating command and records it in the audit log with the refs it moved and
+/// how it ended. Like the undo journal, recording is best effort.
+pub fn audited<T, F>(app: &tauri::AppHandle, repo_path: &str, command: &str, task: F) -> Result<T, AppError>
+where
+    F: FnOnce() -> Result<T, AppError>,
+{
+    let started_at = chrono::Utc::now();
+    let before = snapshot_refs(repo_path);
+
+    let started = Instant::now();
+    let result = task();
+    record_timing(app, command, Some(repo_path), started);
+
+    let (outcome, error) = match &result {
+        Ok(_) => ("success", None),
+        Err(e) if e.kind == ErrorKind::Cancelled => ("cancelled", Some(e.message.clone())),
+        Err(e) => ("failed", Some(e.message.clone())),
+    };
+    let entry = AuditEntry {
+        id: None,
+        repo_path: repo_path.to_string(),
+        command: command.to_string(),
+        ref_changes: changed_refs(&before, &snapshot_refs(repo_path)),
+        outcome: outcome.to_string(),
+        error,
+        started_at,
+        finished_at: chrono::Utc::now(),
+    };
+
+    if let Some(db_state) = app.try_state::<DatabaseState>() {
+        let recorded = tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.record_audit_entry(&entry).await
+        });
+        if let Err(e) = recorded {
+            println!("⚠️  Failed to record operation in audit log: {}", e);
+        }
+    }
+
+    result
+}
+
+#[tauri::command]
+pub async fn get_operation_history(
+    db_state: State<'_, DatabaseState>,
+    repo_path: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+) -> Result<Vec<AuditEntry>, AppError> {
+    let db = db_state.lock().await;
+    db.get_audit_entries(
+        repo_path.as_deref(),
+        limit.unwrap_or(DEFAULT_HISTORY_LIMIT),
+        offset.unwrap_or(0),
+    )
+    .await
+    .map_err(|e| AppError::wrap(e, "Failed to get operation history"))
+}
@@ -0,0 +1,244 @@
+use crate::commands::error::{AppError, ErrorKind};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Mutex;
+use std::time::{Duration, Instant, SystemTime};
+use tauri::{Manager, State};
+
+const AVATAR_DIR: &str = "avatars";
+const DEFAULT_AVATAR_SIZE: u32 = 64;
+// Pictures change rarely; refetch weekly, and retry emails without one daily
+const AVATAR_TTL: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+const MISSING_AVATAR_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+// Spacing between requests, and the pause after a provider answers 429
+const REQUEST_INTERVAL: Duration = Duration::from_millis(250);
+const RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(10 * 60);
+const DOWNLOAD_TIMEOUT_SECS: &str = "10";
+
+/// Serializes avatar downloads so a graph full of authors turns into a trickle of
+/// requests instead of a burst.
+#[derive(Default)]
+pub struct AvatarLimiter(Mutex<LimiterState>);
+
+#[derive(Default)]
+struct LimiterState {
+    last_request: Option<Instant>,
+    blocked_until: Option<Instant>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Avatar {
+    pub email: String,
+    pub data_url: Option<String>, // None when the author has no picture anywhere
+    pub source: Option<String>,   // github, gravatar
+}
+
+enum Download {
+    Found(Vec<u8>),
+    Missing,
+    RateLimited,
+}
+
+fn email_hash(email: &str) -> String {
+    Sha256::digest(email.trim().to_lowercase().as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+fn avatar_dir() -> PathBuf {
+    crate::database::codegit_data_dir().join(AVATAR_DIR)
+}
+
+/// GitHub noreply addresses ("12345+login@users.noreply.github.com" or
+/// "login@users.noreply.github.com") name the account directly.
+fn github_avatar_url(email: &str, size: u32) -> Option<String> {
+    let local = email.trim().to_lowercase().strip_suffix("@users.noreply.github.com")?.to_string();
+    let url = match local.split_once('+') {
+        Some((id, _)) if id.chars().all(|c| c.is_ascii_digit()) => format!("https://avatars.githubusercontent.com/u/{}", id),
+        _ => format!("https://avatars.githubusercontent.com/{}", local),
+    };
+    Some(format!("{}?s={}", url, size))
+}
+
+fn gravatar_url(hash: &str, size: u32) -> String {
+    // d=404 so a missing avatar is a 404 instead of a generated placeholder
+    format!("https://www.gravatar.com/avatar/{}?s={}&d=404", hash, size)
+}
+
+fn image_mime_type(bytes: &[u8]) -> &'static str {
+    if bytes.starts_with(b"\x89PNG") {
+        "image/png"
+    } else if bytes.starts_with(b"GIF8") {
+        "image/gif"
+    } else if bytes.len() > 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        "image/webp"
+    } else {
+        "image/jpeg"
+    }
+}
+
+fn data_url(bytes: &[u8]) -> String {
+    format!("data:{};base64,{}", image_mime_type(bytes), BASE64.encode(bytes))
+}
+
+fn is_fresh(path: &Path, ttl: Duration) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .map(|age| age < ttl)
+        .unwrap_or(false)
+}
+
+fn download(url: &str) -> Result<Download, AppError> {
+    let mut command = std::process::Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--location", "--max-time", DOWNLOAD_TIMEOUT_SECS])
+        .args(["--write-out", "\n%{http_code}", url])
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console window from the GUI
+        command.creation_flags(0x08000000);
+    }
+
+    let output = command.output()
+        .map_err(|e| AppError::wrap(e, "Failed to run curl"))?;
+    if !output.status.success() {
+        return Err(AppError::new(
+            ErrorKind::Network,
+            "avatar.unreachable",
+            format!("Request to {} failed: {}", url, String::from_utf8_lossy(&output.stderr).trim()),
+        ));
+    }
+
+    // The status code follows the image after the last newline
+    let split = output.stdout.iter().rposition(|byte| *byte == b'\n').unwrap_or(0);
+    let status: u16 = String::from_utf8_lossy(&output.stdout[split..]).trim().parse().unwrap_or(0);
+
+    match status {
+        200 => Ok(Download::Found(output.stdout[..split].to_vec())),
+        404 => Ok(Download::Missing),
+        429 => Ok(Download::RateLimited),
+        _ => Err(AppError::new(
+            ErrorKind::Network,
+            "avatar.error",
+            format!("Request to {} failed with status {}", url, status),
+        )),
+    }
+}
+
+/// Downloads through the limiter. Returns RateLimited without a request while backing off.
+fn limited_download(limiter: &AvatarLimiter, url: &str) -> Result<Download, AppError> {
+    let mut state = limiter.0.lock().map_err(|_| AppError::from("Avatar limiter is unavailable"))?;
+
+    if matches!(state.blocked_until, Some(until) if Instant::now() < until) {
+        return Ok(Download::RateLimited);
+    }
+    if let Some(last) = state.last_request {
+        let elapsed = last.elapsed();
+        if elapsed < REQUEST_INTERVAL {
+            std::thread::sleep(REQUEST_INTERVAL - elapsed);
+        }
+    }
+
+    let result = download(url);
+    state.last_request = Some(Instant::now());
+    if matches!(result, Ok(Download::RateLimited)) {
+        state.blocked_until = Some(Instant::now() + RATE_LIMIT_BACKOFF);
+    }
+    result
+}
+
+fn resolve_avatar(limiter: &AvatarLimiter, email: &str, size: u32) -> Result<Avatar, AppError> {
+    let hash = email_hash(email);
+    let dir = avatar_dir().join(size.to_string());
+    let image_path = dir.join(&hash);
+    let missing_path = dir.join(format!("{}.missing", hash));
+    let source_path = dir.join(format!("{}.source", hash));
+
+    let avatar = |data_url: Option<String>, source: Option<String>| Avatar {
+        email: email.to_string(),
+        data_url,
+        source,
+    };
+
+    if is_fresh(&image_path, AVATAR_TTL) {
+        if let Ok(bytes) = fs::read(&image_path) {
+            return Ok(avatar(Some(data_url(&bytes)), fs::read_to_string(&source_path).ok()));
+        }
+    }
+    if is_fresh(&missing_path, MISSING_AVATAR_TTL) {
+        return Ok(avatar(None, None));
+    }
+
+    let mut candidates = Vec::new();
+    if let Some(url) = github_avatar_url(email, size) {
+        candidates.push(("github", url));
+    }
+    candidates.push(("gravatar", gravatar_url(&hash, size)));
+
+    for (source, url) in candidates {
+        let download = match limited_download(limiter, &url) {
+            Ok(download) => download,
+            // Offline: an outdated picture beats an error
+            Err(e) => match fs::read(&image_path) {
+                Ok(bytes) => return Ok(avatar(Some(data_url(&bytes)), fs::read_to_string(&source_path).ok())),
+                Err(_) => return Err(e),
+            },
+        };
+        match download {
+            Download::Found(bytes) => {
+                fs::create_dir_all(&dir)
+                    .map_err(|e| AppError::wrap(e, "Failed to create avatar cache"))?;
+                fs::write(&image_path, &bytes)
+                    .map_err(|e| AppError::wrap(e, "Failed to cache avatar"))?;
+                let _ = fs::write(&source_path, source);
+                let _ = fs::remove_file(&missing_path);
+                return Ok(avatar(Some(data_url(&bytes)), Some(source.to_string())));
+            }
+            Download::Missing => continue,
+            // Serve a stale picture rather than none; don't remember the miss
+            Download::RateLimited => {
+                let stale = fs::read(&image_path).ok().map(|bytes| data_url(&bytes));
+                return Ok(avatar(stale, fs::read_to_string(&source_path).ok()));
+            }
+        }
+    }
+
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::wrap(e, "Failed to create avatar cache"))?;
+    let _ = fs::write(&missing_path, b"");
+    Ok(avatar(None, None))
+}
+
+/// Author picture for the commit graph and blame, from GitHub for noreply addresses and
+/// Gravatar otherwise. Results, including misses, are cached on disk and downloads are
+/// spaced out and paused when a provider rate-limits us.
+#[tauri::command]
+pub async fn get_avatar(
+    app: tauri::AppHandle,
+    email: String,
+    size: Option<u32>,
+) -> Result<Avatar, AppError> {
+    if !email.contains('@') {
+        return Err(AppError::invalid_input(format!("Not an email address: {}", email)));
+    }
+    let size = size.unwrap_or(DEFAULT_AVATAR_SIZE).clamp(16, 512);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let limiter: State<AvatarLimiter> = app.state();
+        resolve_avatar(&limiter, &email, size)
+    })
+    .await?
+}
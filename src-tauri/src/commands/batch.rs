@@ -0,0 +1,187 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use git2::build::CheckoutBuilder;
+use git2::{Index, IndexEntry, IndexTime, Repository};
+use serde::{Deserialize, Serialize};
+use std::path::{Component, Path};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileOperationResult {
+    pub path: String,
+    pub error: Option<String>,
+}
+
+/// Outcome of a batch: one result per requested path. The batch is all or nothing, so when
+/// any path fails `applied` is false and nothing was changed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    pub applied: bool,
+    pub results: Vec<FileOperationResult>,
+}
+
+/// Paths must be relative to the working tree and stay inside it.
+pub fn check_path(path: &str) -> Result<(), String> {
+    let relative = Path::new(path);
+    if path.is_empty() || relative.components().any(|component| !matches!(component, Component::Normal(_))) {
+        return Err(format!("Invalid path: {}", path));
+    }
+    Ok(())
+}
+
+/// `path` as a checkout pathspec that matches only itself. CheckoutBuilder can't turn
+/// pathspec matching off, so wildcard characters in file names are escaped instead.
+fn literal_pathspec(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        if matches!(c, '*' | '?' | '[' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Runs `operation` for every path without stopping at the first failure, so each path
+/// gets its result. The caller writes the changes only when the batch is `applied`.
+fn run_batch<F>(paths: &[String], mut operation: F) -> Result<BatchResult, AppError>
+where
+    F: FnMut(&str) -> Result<(), String>,
+{
+    if paths.is_empty() {
+        return Err(AppError::invalid_input("No files given"));
+    }
+
+    let results: Vec<FileOperationResult> = paths
+        .iter()
+        .map(|path| FileOperationResult {
+            path: path.clone(),
+            error: check_path(path).and_then(|_| operation(path)).err(),
+        })
+        .collect();
+
+    Ok(BatchResult {
+        applied: results.iter().all(|result| result.error.is_none()),
+        results,
+    })
+}
+
+fn repository_index(repo: &Repository) -> Result<Index, AppError> {
+    repo.index().map_err(|e| AppError::wrap(e, "Failed to get index"))
+}
+
+/// Stages every path in one index write: changed and new files are added, deleted files
+/// are removed from the index.
+#[tauri::command]
+pub fn stage_files(app: tauri::AppHandle, repo_path: String, paths: Vec<String>) -> Result<BatchResult, AppError> {
+    audited(&app, &repo_path, "stage_files", || {
+        let repo = crate::open_worktree_repository(&repo_path)?;
+        let workdir = repo.workdir()
+            .ok_or_else(|| AppError::invalid_input("Repository has no working tree"))?
+            .to_path_buf();
+        let mut index = repository_index(&repo)?;
+
+        let result = run_batch(
+            &paths,
+            |path| {
+                let relative = Path::new(path);
+                if workdir.join(relative).symlink_metadata().is_ok() {
+                    index.add_path(relative).map_err(|e| e.message().to_string())?;
+                } else if index.get_path(relative, 0).is_some() {
+                    index.remove_path(relative).map_err(|e| e.message().to_string())?;
+                } else {
+                    return Err(format!("{} does not exist", path));
+                }
+                Ok(())
+            },
+        )?;
+
+        if result.applied {
+            index.write().map_err(|e| AppError::wrap(e, "Failed to write index"))?;
+            println!("➕ Staged {} file(s)", paths.len());
+        }
+        Ok(result)
+    })
+}
+
+/// Unstages every path in one index write: paths go back to their HEAD version in the
+/// index, and files added since HEAD leave it. The working tree is not touched.
+#[tauri::command]
+pub fn unstage_files(app: tauri::AppHandle, repo_path: String, paths: Vec<String>) -> Result<BatchResult, AppError> {
+    audited(&app, &repo_path, "unstage_files", || {
+        let repo = crate::open_worktree_repository(&repo_path)?;
+        let head_tree = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(|e| AppError::wrap(e, "Failed to get HEAD tree"))?),
+            Err(_) => None, // Nothing committed yet: unstaging removes from the index
+        };
+        let mut index = repository_index(&repo)?;
+
+        let result = run_batch(
+            &paths,
+            |path| {
+                let relative = Path::new(path);
+                match head_tree.as_ref().and_then(|tree| tree.get_path(relative).ok()) {
+                    Some(entry) => {
+                        // Zeroed stat data makes the next status re-hash the file
+                        let zero = IndexTime::new(0, 0);
+                        index.add(&IndexEntry {
+                            ctime: zero,
+                            mtime: zero,
+                            dev: 0,
+                            ino: 0,
+                            mode: entry.filemode() as u32,
+                            uid: 0,
+                            gid: 0,
+                            file_size: 0,
+                            id: entry.id(),
+                            flags: 0,
+                            flags_extended: 0,
+                            path: path.as_bytes().to_vec(),
+                        })
+                        .map_err(|e| e.message().to_string())
+                    }
+                    None if index.get_path(relative, 0).is_some() => {
+                        index.remove_path(relative).map_err(|e| e.message().to_string())
+                    }
+                    None => Err(format!("{} is not staged", path)),
+                }
+            },
+        )?;
+
+        if result.applied {
+            index.write().map_err(|e| AppError::wrap(e, "Failed to write index"))?;
+            println!("➖ Unstaged {} file(s)", paths.len());
+        }
+        Ok(result)
+    })
+}
+
+/// Discards working tree changes of tracked files, restoring their staged version in one
+/// checkout. Untracked files are left alone.
+#[tauri::command]
+pub fn discard_files(app: tauri::AppHandle, repo_path: String, paths: Vec<String>) -> Result<BatchResult, AppError> {
+    audited(&app, &repo_path, "discard_files", || {
+        let repo = crate::open_worktree_repository(&repo_path)?;
+        let mut index = repository_index(&repo)?;
+        let tracked = |path: &str| index.get_path(Path::new(path), 0).is_some();
+
+        let mut checkout = CheckoutBuilder::new();
+        checkout.force();
+        let result = run_batch(
+            &paths,
+            |path| {
+                if !tracked(path) {
+                    return Err(format!("{} is not tracked", path));
+                }
+                checkout.path(literal_pathspec(path));
+                Ok(())
+            },
+        )?;
+
+        if result.applied {
+            repo.checkout_index(Some(&mut index), Some(&mut checkout))
+                .map_err(|e| AppError::wrap(e, "Failed to discard changes"))?;
+            println!("🗑️  Discarded changes in {} file(s)", paths.len());
+        }
+        Ok(result)
+    })
+}
@@ -0,0 +1,137 @@
+use crate::commands::encoding::{decode, detect_encoding, TextEncoding};
+use crate::commands::error::AppError;
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::{Blame, BlameOptions, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Consecutive lines last changed by the same commit. Lines not committed yet have an
+/// all-zero `commit_id`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlameHunk {
+    pub commit_id: String,
+    pub summary: String,
+    pub author: String,
+    pub email: String,
+    pub timestamp: i64,
+    pub start_line: usize, // 1-based, in the blamed version of the file
+    pub line_count: usize,
+    pub original_start_line: usize, // Where the lines were in `commit_id`
+    pub original_path: Option<String>, // Set when the file had another name in `commit_id`
+    pub content: String,
+}
+
+fn blame_blocking(repo_path: &str, file_path: &str, commit_id: Option<String>, token: &OperationToken) -> Result<Vec<BlameHunk>, AppError> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let path = Path::new(file_path);
+
+    let mut options = BlameOptions::new();
+    let newest = match &commit_id {
+        Some(commit_id) => repo.revparse_single(commit_id)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| AppError::wrap(e, &format!("Failed to resolve '{}'", commit_id)))?,
+        None => repo.head()
+            .and_then(|head| head.peel_to_commit())
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))?,
+    };
+    options.newest_commit(newest.id());
+
+    let committed = newest.tree()
+        .and_then(|tree| tree.get_path(path))
+        .and_then(|entry| repo.find_blob(entry.id()))
+        .map(|blob| blob.content().to_vec())
+        .map_err(|_| AppError::not_found(format!("{} does not exist at {}", file_path, newest.id())))?;
+    // Without a commit the working tree version is blamed, so local edits show as uncommitted
+    let content = match (&commit_id, repo.workdir()) {
+        (None, Some(workdir)) => std::fs::read(workdir.join(path)).unwrap_or_else(|_| committed.clone()),
+        _ => committed.clone(),
+    };
+    let encoding = detect_encoding(&content[..content.len().min(8000)]);
+    if encoding == TextEncoding::Binary {
+        return Err(AppError::invalid_input(format!("{} is a binary file", file_path)));
+    }
+
+    token.check()?;
+    let blame = repo.blame_file(path, Some(&mut options))
+        .map_err(|e| AppError::wrap(e, "Failed to blame file"))?;
+    token.check()?;
+
+    let text = decode(&content, encoding);
+    let lines: Vec<&str> = text.lines().collect();
+    if content == committed {
+        return blame_hunks(&repo, &blame, file_path, &lines, token);
+    }
+    let local = blame.blame_buffer(&content)
+        .map_err(|e| AppError::wrap(e, "Failed to blame local changes"))?;
+    blame_hunks(&repo, &local, file_path, &lines, token)
+}
+
+fn blame_hunks(repo: &Repository, blame: &Blame, file_path: &str, lines: &[&str], token: &OperationToken) -> Result<Vec<BlameHunk>, AppError> {
+    let mut summaries: HashMap<Oid, String> = HashMap::new();
+    let mut hunks = Vec::new();
+    for hunk in blame.iter() {
+        token.check()?;
+        let commit_id = hunk.final_commit_id();
+        let start = hunk.final_start_line().saturating_sub(1);
+        let end = (start + hunk.lines_in_hunk()).min(lines.len());
+        let content = lines.get(start..end).map(|lines| lines.join("\n")).unwrap_or_default();
+
+        let (summary, author, email, timestamp) = if commit_id.is_zero() {
+            // Hunks from the working tree have no signature at all
+            ("".to_string(), "Not Committed Yet".to_string(), String::new(), chrono::Utc::now().timestamp())
+        } else {
+            let signature = hunk.final_signature();
+            let summary = summaries
+                .entry(commit_id)
+                .or_insert_with(|| {
+                    repo.find_commit(commit_id)
+                        .map(|commit| commit.summary().unwrap_or("").to_string())
+                        .unwrap_or_default()
+                })
+                .clone();
+            (
+                summary,
+                signature.name().unwrap_or("Unknown").to_string(),
+                signature.email().unwrap_or("").to_string(),
+                signature.when().seconds(),
+            )
+        };
+
+        let original_path = hunk.path()
+            .map(|original| original.to_string_lossy().to_string())
+            .filter(|original| original != file_path);
+        hunks.push(BlameHunk {
+            commit_id: commit_id.to_string(),
+            summary,
+            author,
+            email,
+            timestamp,
+            start_line: hunk.final_start_line(),
+            line_count: hunk.lines_in_hunk(),
+            original_start_line: hunk.orig_start_line(),
+            original_path,
+            content,
+        });
+    }
+
+    Ok(hunks)
+}
+
+/// Who last changed each line of `file_path`, as hunks of consecutive lines. Blames the
+/// working tree version by default, or the file as of `commit_id`. Long histories make
+/// this slow, so it runs as a cancellable operation.
+#[tauri::command]
+pub async fn get_blame(
+    app: tauri::AppHandle,
+    repo_path: String,
+    file_path: String,
+    commit_id: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Vec<BlameHunk>, AppError> {
+    run_operation(&app, "blame", operation_id, move |token| {
+        blame_blocking(&repo_path, &file_path, commit_id, &token)
+    })
+    .await
+}
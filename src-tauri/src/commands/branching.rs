@@ -0,0 +1,308 @@
+use crate::commands::audit::audited;
+use crate::commands::commit_cache::extend_commit_cache;
+use crate::commands::database::repo_settings_for;
+use crate::commands::error::AppError;
+use crate::commands::identity::{create_annotated_tag, create_commit, resolve_signature, signing_config, with_sign_off};
+use crate::commands::journal::{journal_operation, ref_change};
+use crate::commands::operations::run_operation;
+use crate::database::{BranchingModel, RefChange};
+use git2::{Branch, BranchType, Commit, Oid, Repository, StatusOptions};
+use serde::{Deserialize, Serialize};
+
+/// The kinds of short-lived branches in the branching model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FlowBranchKind {
+    Feature,
+    Release,
+    Hotfix,
+}
+
+impl FlowBranchKind {
+    fn label(self) -> &'static str {
+        match self {
+            FlowBranchKind::Feature => "feature",
+            FlowBranchKind::Release => "release",
+            FlowBranchKind::Hotfix => "hotfix",
+        }
+    }
+
+    fn prefix(self, model: &BranchingModel) -> &str {
+        match self {
+            FlowBranchKind::Feature => &model.feature_prefix,
+            FlowBranchKind::Release => &model.release_prefix,
+            FlowBranchKind::Hotfix => &model.hotfix_prefix,
+        }
+    }
+
+    fn base(self, model: &BranchingModel) -> &str {
+        match self {
+            FlowBranchKind::Hotfix => &model.main_branch,
+            FlowBranchKind::Feature | FlowBranchKind::Release => &model.develop_branch,
+        }
+    }
+
+    /// Branches a finished branch is merged into, in order; the last one is checked out.
+    fn targets(self, model: &BranchingModel) -> Vec<String> {
+        match self {
+            FlowBranchKind::Feature => vec![model.develop_branch.clone()],
+            FlowBranchKind::Release | FlowBranchKind::Hotfix => vec![model.main_branch.clone(), model.develop_branch.clone()],
+        }
+    }
+}
+
+fn branch_name(kind: FlowBranchKind, model: &BranchingModel, name: &str) -> Result<String, AppError> {
+    let name = name.trim();
+    let full_name = format!("{}{}", kind.prefix(model), name);
+    if name.is_empty() || !Branch::name_is_valid(&full_name).unwrap_or(false) {
+        return Err(AppError::invalid_input(format!("Invalid {} name: {}", kind.label(), name)));
+    }
+    Ok(full_name)
+}
+
+fn branch_commit<'r>(repo: &'r Repository, name: &str) -> Result<Commit<'r>, AppError> {
+    repo.find_branch(name, BranchType::Local)
+        .map_err(|_| AppError::not_found(format!("Branch '{}' not found", name)))?
+        .get()
+        .peel_to_commit()
+        .map_err(|e| AppError::wrap(e, &format!("Failed to get the commit of '{}'", name)))
+}
+
+/// Tracked changes block finishing, since the checked-out branch may move under them.
+fn ensure_clean(repo: &Repository) -> Result<(), AppError> {
+    let mut options = StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+    let statuses = repo.statuses(Some(&mut options))
+        .map_err(|e| AppError::wrap(e, "Failed to get status"))?;
+    if !statuses.is_empty() {
+        let paths = statuses.iter().filter_map(|entry| entry.path().map(|path| path.to_string())).collect();
+        return Err(AppError::conflict("Commit or stash your changes first", paths));
+    }
+    Ok(())
+}
+
+/// Checks out `commit` and points HEAD at `branch`.
+fn checkout_branch(repo: &Repository, branch: &str, commit: &Commit) -> Result<(), AppError> {
+    repo.checkout_tree(commit.as_object(), None)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to check out '{}'", branch)))?;
+    repo.set_head(&format!("refs/heads/{}", branch))
+        .map_err(|e| AppError::wrap(e, "Failed to set HEAD"))
+}
+
+/// Creates `<prefix><name>` from its base branch (develop, or main for hotfixes) and checks
+/// it out. A missing develop branch is created from main first, like `git flow init`.
+fn start_blocking(app: &tauri::AppHandle, repo_path: &str, kind: FlowBranchKind, name: &str) -> Result<String, AppError> {
+    let repo = crate::open_worktree_repository(repo_path)?;
+    let model = repo_settings_for(app, repo_path).branching_model;
+    let full_name = branch_name(kind, &model, name)?;
+    if repo.find_branch(&full_name, BranchType::Local).is_ok() {
+        return Err(AppError::invalid_input(format!("Branch '{}' already exists", full_name)));
+    }
+
+    let base = kind.base(&model);
+    if base == model.develop_branch && repo.find_branch(base, BranchType::Local).is_err() {
+        let main_commit = branch_commit(&repo, &model.main_branch)?;
+        repo.branch(base, &main_commit, false)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to create '{}'", base)))?;
+    }
+    let base_commit = branch_commit(&repo, base)?;
+
+    repo.branch(&full_name, &base_commit, false)
+        .map_err(|e| AppError::wrap(e, "Failed to create branch"))?;
+    checkout_branch(&repo, &full_name, &base_commit)?;
+
+    Ok(format!("Started {} '{}' from '{}'", kind.label(), full_name, base))
+}
+
+/// Merges `source` into `target` with the model's strategy without touching any ref, and
+/// returns the resulting commit (the target itself when it already has the changes).
+#[allow(clippy::too_many_arguments)]
+fn merged_commit(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    strategy: &str,
+    source_name: &str,
+    source: &Commit,
+    target: &Commit,
+    message: Option<&str>,
+) -> Result<Oid, AppError> {
+    let merge_base = repo.merge_base(target.id(), source.id())
+        .map_err(|e| AppError::wrap(e, "Failed to find merge base"))?;
+    if merge_base == source.id() {
+        return Ok(target.id());
+    }
+    if strategy == "ff" && merge_base == target.id() {
+        return Ok(source.id());
+    }
+
+    let mut index = repo.merge_commits(target, source, None)
+        .map_err(|e| AppError::wrap(e, "Failed to merge"))?;
+    if index.has_conflicts() {
+        return Err(AppError::conflict(
+            format!("'{}' conflicts with the target branch; merge it manually", source_name),
+            crate::conflicted_paths(&index)?,
+        ));
+    }
+    let tree_id = index.write_tree_to(repo)
+        .map_err(|e| AppError::wrap(e, "Failed to write merged tree"))?;
+    let tree = repo.find_tree(tree_id)
+        .map_err(|e| AppError::wrap(e, "Failed to find merged tree"))?;
+
+    let signature = resolve_signature(app, repo, repo_path, None, None)?;
+    let signing = signing_config(app, repo, repo_path, "commit.gpgsign");
+    let (default_message, parents): (String, Vec<&Commit>) = if strategy == "squash" {
+        (format!("Squashed commit of branch '{}'", source_name), vec![target])
+    } else {
+        (format!("Merge branch '{}'", source_name), vec![target, source])
+    };
+    let message = message.map(|message| message.trim()).filter(|message| !message.is_empty()).unwrap_or(&default_message);
+    let message = with_sign_off(app, repo_path, message, &signature);
+
+    let commit_id = create_commit(repo, signing.as_ref(), None, &signature, &signature, &message, &tree, &parents)
+        .map_err(|e| AppError::wrap(e, "Failed to create merge commit"))?;
+    Ok(commit_id)
+}
+
+/// Merges `<prefix><name>` into its targets, tags releases and hotfixes on main, checks out
+/// the last target (develop) and deletes the branch. Every merge is computed before any
+/// ref moves, so a conflict leaves the repository untouched.
+fn finish_blocking(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    kind: FlowBranchKind,
+    name: &str,
+    keep_branch: bool,
+    message: Option<&str>,
+) -> Result<String, AppError> {
+    let repo = crate::open_worktree_repository(repo_path)?;
+    let model = repo_settings_for(app, repo_path).branching_model;
+    let full_name = branch_name(kind, &model, name)?;
+    let source = branch_commit(&repo, &full_name)?;
+    ensure_clean(&repo)?;
+
+    let mut merges: Vec<(String, Oid, Oid)> = Vec::new(); // (target, old tip, new tip)
+    for target_name in kind.targets(&model) {
+        let target = branch_commit(&repo, &target_name)?;
+        let merged = merged_commit(app, &repo, repo_path, &model.merge_strategy, &full_name, &source, &target, message)?;
+        merges.push((target_name, target.id(), merged));
+    }
+
+    // Everything that can refuse the tag is settled before any ref moves
+    let tag = if kind != FlowBranchKind::Feature {
+        let tag_name = format!("{}{}", model.version_tag_prefix, name.trim());
+        let tag_ref = format!("refs/tags/{}", tag_name);
+        if !git2::Reference::is_valid_name(&tag_ref) {
+            return Err(AppError::invalid_input(format!("'{}' is not a valid tag name", tag_name)));
+        }
+        if repo.find_reference(&tag_ref).is_ok() {
+            return Err(AppError::conflict(format!("Tag '{}' already exists", tag_name), Vec::new()));
+        }
+        let tagged = repo.find_object(merges[0].2, None)
+            .map_err(|e| AppError::wrap(e, "Failed to find the tagged commit"))?;
+        let tagger = resolve_signature(app, &repo, repo_path, None, None)?;
+        let signing = signing_config(app, &repo, repo_path, "tag.gpgsign");
+        Some((tag_name, tagged, tagger, signing))
+    } else {
+        None
+    };
+
+    let (checkout_name, _, checkout_id) = merges.last().cloned()
+        .ok_or_else(|| AppError::invalid_input("The branching model has no merge target"))?;
+    let checkout_commit = repo.find_commit(checkout_id)
+        .map_err(|e| AppError::wrap(e, "Failed to find merge commit"))?;
+    // Check out first: it is the step most likely to fail (untracked files in the way)
+    crate::create_backup_ref(&repo, "finish")?;
+    let head_ref = crate::head_ref_name(&repo);
+    let old_head = crate::ref_target(&repo, &head_ref);
+    repo.checkout_tree(checkout_commit.as_object(), None)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to check out '{}'", checkout_name)))?;
+
+    // Whatever was applied is journaled, also when a later step fails, so it can be undone
+    let mut ref_changes: Vec<RefChange> = Vec::new();
+    let result = (|| {
+        for (target_name, old_tip, new_tip) in &merges {
+            if old_tip == new_tip {
+                continue;
+            }
+            let ref_name = format!("refs/heads/{}", target_name);
+            repo.reference(&ref_name, *new_tip, true, &format!("finish {}: merge {}", kind.label(), full_name))
+                .map_err(|e| AppError::wrap(e, &format!("Failed to update '{}'", target_name)))?;
+            ref_changes.push(ref_change(&repo, &ref_name, Some(old_tip.to_string())));
+        }
+        repo.set_head(&format!("refs/heads/{}", checkout_name))
+            .map_err(|e| AppError::wrap(e, "Failed to set HEAD"))?;
+        if head_ref == "HEAD" {
+            ref_changes.push(ref_change(&repo, "HEAD", old_head.clone()));
+        }
+
+        let mut summary = format!(
+            "Finished {} '{}': merged into {}",
+            kind.label(),
+            full_name,
+            merges.iter().map(|(target, _, _)| format!("'{}'", target)).collect::<Vec<_>>().join(" and ")
+        );
+
+        if let Some((tag_name, tagged, tagger, signing)) = &tag {
+            let tag_message = message.map(|message| message.trim()).filter(|message| !message.is_empty()).unwrap_or(tag_name);
+            create_annotated_tag(&repo, signing.as_ref(), tag_name, tagged, tagger, tag_message)
+                .map_err(|e| AppError::wrap(e, &format!("Failed to create tag '{}'", tag_name)))?;
+            summary.push_str(&format!(", tagged {}", tag_name));
+        }
+
+        if !keep_branch {
+            let ref_name = format!("refs/heads/{}", full_name);
+            let old_target = crate::ref_target(&repo, &ref_name);
+            repo.find_branch(&full_name, BranchType::Local)
+                .and_then(|mut branch| branch.delete())
+                .map_err(|e| AppError::wrap(e, &format!("Failed to delete '{}'", full_name)))?;
+            ref_changes.push(ref_change(&repo, &ref_name, old_target));
+            summary.push_str(&format!(", deleted '{}'", full_name));
+        }
+        Ok(summary)
+    })();
+
+    if result.is_ok() || !ref_changes.is_empty() {
+        journal_operation(app, repo_path, "finish", format!("finish {} '{}'", kind.label(), full_name), "hard", ref_changes);
+        extend_commit_cache(app, &repo, repo_path);
+    }
+
+    result
+}
+
+/// Starts a feature (or, with `kind`, a release or hotfix) branch named after the
+/// repository's branching model and checks it out.
+#[tauri::command]
+pub fn start_feature(
+    app: tauri::AppHandle,
+    repo_path: String,
+    name: String,
+    kind: Option<FlowBranchKind>,
+) -> Result<String, AppError> {
+    let kind = kind.unwrap_or(FlowBranchKind::Feature);
+    audited(&app, &repo_path, "start_feature", || start_blocking(&app, &repo_path, kind, &name))
+}
+
+/// Finishes a feature, release or hotfix branch: merges it with the model's strategy, tags
+/// releases and hotfixes, and deletes the branch unless `keep_branch`. `message` replaces
+/// the merge commit and tag messages.
+#[tauri::command]
+pub async fn finish_feature(
+    app: tauri::AppHandle,
+    repo_path: String,
+    name: String,
+    kind: Option<FlowBranchKind>,
+    keep_branch: Option<bool>,
+    message: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let kind = kind.unwrap_or(FlowBranchKind::Feature);
+    let task_app = app.clone();
+    run_operation(&app, "merge", operation_id, move |_| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "finish_feature", || {
+            finish_blocking(&task_app, &repo_path, kind, &name, keep_branch.unwrap_or(false), message.as_deref())
+        })
+    })
+    .await
+}
@@ -0,0 +1,72 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use std::path::Path;
+
+// Bundle branches land under their own namespace so they never clobber local work
+const BUNDLE_REFSPEC: &str = "+refs/heads/*:refs/remotes/bundle/*";
+
+/// git would read a ref or path starting with '-' as an option (for `bundle create`, one
+/// of rev-list's), so those are refused.
+fn check_argument(value: &str, what: &str) -> Result<(), AppError> {
+    if value.starts_with('-') {
+        return Err(AppError::invalid_input(format!("Invalid {}: {}", what, value)));
+    }
+    Ok(())
+}
+
+/// Writes a git bundle with the given refs (all refs when empty) to `output_path`.
+#[tauri::command]
+pub fn create_bundle(repo_path: String, refs: Vec<String>, output_path: String) -> Result<String, AppError> {
+    git2::Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    check_argument(&output_path, "bundle path")?;
+    for reference in &refs {
+        check_argument(reference, "ref")?;
+    }
+
+    let mut args = vec!["bundle", "create", output_path.as_str()];
+    if refs.is_empty() {
+        args.push("--all");
+    } else {
+        args.extend(refs.iter().map(|r| r.as_str()));
+    }
+
+    crate::run_git(&repo_path, &args, "create bundle")?;
+    crate::run_git(&repo_path, &["bundle", "verify", output_path.as_str()], "verify bundle")?;
+
+    println!("📦 Bundle created at {}", output_path);
+    Ok(format!("Bundle created at {}", output_path))
+}
+
+/// Fetches the branches and tags of a bundle file into `refs/remotes/bundle/*`.
+#[tauri::command]
+pub fn fetch_from_bundle(app: tauri::AppHandle, repo_path: String, bundle_path: String) -> Result<Vec<String>, AppError> {
+    audited(&app, &repo_path, "fetch_from_bundle", || {
+        git2::Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+        check_argument(&bundle_path, "bundle path")?;
+        if !Path::new(&bundle_path).is_file() {
+            return Err(AppError::not_found(format!("Bundle not found: {}", bundle_path)));
+        }
+
+        // Checks that the bundle is valid and that its prerequisite commits exist here
+        crate::run_git(&repo_path, &["bundle", "verify", bundle_path.as_str()], "verify bundle")?;
+
+        let heads = crate::run_git(&repo_path, &["bundle", "list-heads", bundle_path.as_str()], "read bundle")?;
+        let refs: Vec<String> = heads
+            .lines()
+            .filter_map(|line| line.split_whitespace().nth(1))
+            .map(|name| name.to_string())
+            .collect();
+
+        crate::run_git(
+            &repo_path,
+            &["fetch", "--tags", bundle_path.as_str(), BUNDLE_REFSPEC],
+            "fetch from bundle",
+        )?;
+
+        println!("📦 Fetched {} refs from bundle {}", refs.len(), bundle_path);
+        Ok(refs)
+    })
+}
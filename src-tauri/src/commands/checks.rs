@@ -0,0 +1,219 @@
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_request, hosted_repository, HostedRepository, Provider, AZURE_API_VERSION};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CheckStatus {
+    pub name: String,
+    pub status: String, // pass, fail, pending
+    pub description: Option<String>,
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitChecks {
+    pub sha: String,
+    pub overall: String, // pass, fail, pending, none
+    pub checks: Vec<CheckStatus>,
+}
+
+fn text(value: &serde_json::Value, pointer: &str) -> Option<String> {
+    value.pointer(pointer)
+        .and_then(|v| v.as_str())
+        .filter(|text| !text.is_empty())
+        .map(|text| text.to_string())
+}
+
+/// GitHub Actions and apps report check runs; older integrations still use commit statuses.
+fn github_checks(hosted: &HostedRepository, auth: &str, sha: &str) -> Result<Vec<CheckStatus>, AppError> {
+    let base = format!("{}/repos/{}/{}/commits/{}", hosted.api_base(), hosted.owner, hosted.name, sha);
+    let mut checks = Vec::new();
+
+    let runs = api_request("GET", &format!("{}/check-runs?per_page=100", base), auth, None)?;
+    for run in runs["check_runs"].as_array().into_iter().flatten() {
+        let status = match (run["status"].as_str(), run["conclusion"].as_str()) {
+            (Some("completed"), Some("success")) | (Some("completed"), Some("neutral")) | (Some("completed"), Some("skipped")) => "pass",
+            (Some("completed"), _) => "fail",
+            _ => "pending",
+        };
+        checks.push(CheckStatus {
+            name: text(run, "/name").unwrap_or_default(),
+            status: status.to_string(),
+            description: text(run, "/output/title"),
+            url: text(run, "/html_url"),
+        });
+    }
+
+    let combined = api_request("GET", &format!("{}/status", base), auth, None)?;
+    for status in combined["statuses"].as_array().into_iter().flatten() {
+        let state = match status["state"].as_str() {
+            Some("success") => "pass",
+            Some("failure") | Some("error") => "fail",
+            _ => "pending",
+        };
+        checks.push(CheckStatus {
+            name: text(status, "/context").unwrap_or_default(),
+            status: state.to_string(),
+            description: text(status, "/description"),
+            url: text(status, "/target_url"),
+        });
+    }
+
+    Ok(checks)
+}
+
+fn bitbucket_checks(hosted: &HostedRepository, auth: &str, sha: &str) -> Result<Vec<CheckStatus>, AppError> {
+    let url = format!(
+        "{}/repositories/{}/{}/commit/{}/statuses?pagelen=100",
+        hosted.api_base(), hosted.owner, hosted.name, sha
+    );
+    let response = api_request("GET", &url, auth, None)?;
+
+    Ok(response["values"].as_array().into_iter().flatten().map(|status| {
+        let state = match status["state"].as_str() {
+            Some("SUCCESSFUL") => "pass",
+            Some("FAILED") | Some("STOPPED") => "fail",
+            _ => "pending", // INPROGRESS
+        };
+        CheckStatus {
+            name: text(status, "/name").or_else(|| text(status, "/key")).unwrap_or_default(),
+            status: state.to_string(),
+            description: text(status, "/description"),
+            url: text(status, "/url"),
+        }
+    }).collect())
+}
+
+fn gitlab_checks(hosted: &HostedRepository, auth: &str, sha: &str) -> Result<Vec<CheckStatus>, AppError> {
+    let url = format!(
+        "{}/projects/{}/repository/commits/{}/statuses?per_page=100",
+        hosted.api_base(), hosted.gitlab_project(), sha
+    );
+    let response = api_request("GET", &url, auth, None)?;
+
+    Ok(response.as_array().into_iter().flatten().map(|status| {
+        let state = match status["status"].as_str() {
+            Some("success") | Some("skipped") => "pass",
+            Some("failed") | Some("canceled") => "fail",
+            _ => "pending", // created, pending, running, manual
+        };
+        CheckStatus {
+            name: text(status, "/name").unwrap_or_default(),
+            status: state.to_string(),
+            description: text(status, "/description"),
+            url: text(status, "/target_url"),
+        }
+    }).collect())
+}
+
+// Recent builds searched for the commit; Azure can't filter builds by commit
+const AZURE_BUILD_SCAN_LIMIT: usize = 50;
+
+/// Azure Pipelines builds of the commit plus statuses other services posted on it.
+fn azure_checks(hosted: &HostedRepository, auth: &str, sha: &str) -> Result<Vec<CheckStatus>, AppError> {
+    let project_api = format!("{}/{}/_apis", hosted.api_base(), hosted.owner);
+    let mut checks = Vec::new();
+
+    let statuses_url = format!(
+        "{}/git/repositories/{}/commits/{}/statuses?latestOnly=true&api-version={}",
+        project_api, hosted.name, sha, AZURE_API_VERSION
+    );
+    let statuses = api_request("GET", &statuses_url, auth, None)?;
+    for status in statuses["value"].as_array().into_iter().flatten() {
+        let state = match status["state"].as_str() {
+            Some("succeeded") => "pass",
+            Some("failed") | Some("error") => "fail",
+            _ => "pending", // pending, notSet, notApplicable
+        };
+        checks.push(CheckStatus {
+            name: text(status, "/context/name").unwrap_or_default(),
+            status: state.to_string(),
+            description: text(status, "/description"),
+            url: text(status, "/targetUrl"),
+        });
+    }
+
+    // The builds API wants the repository id rather than its name
+    let repository = api_request(
+        "GET",
+        &format!("{}/git/repositories/{}?api-version={}", project_api, hosted.name, AZURE_API_VERSION),
+        auth,
+        None,
+    )?;
+    let repository_id = text(&repository, "/id").unwrap_or_default();
+    let builds_url = format!(
+        "{}/build/builds?repositoryId={}&repositoryType=TfsGit&queryOrder=queueTimeDescending&$top={}&api-version={}",
+        project_api, repository_id, AZURE_BUILD_SCAN_LIMIT, AZURE_API_VERSION
+    );
+    let builds = api_request("GET", &builds_url, auth, None)?;
+    for build in builds["value"].as_array().into_iter().flatten() {
+        if build["sourceVersion"].as_str() != Some(sha) {
+            continue;
+        }
+        let status = match (build["status"].as_str(), build["result"].as_str()) {
+            (Some("completed"), Some("succeeded")) | (Some("completed"), Some("partiallySucceeded")) => "pass",
+            (Some("completed"), _) => "fail",
+            _ => "pending", // notStarted, inProgress, cancelling, postponed
+        };
+        checks.push(CheckStatus {
+            name: text(build, "/definition/name").unwrap_or_default(),
+            status: status.to_string(),
+            description: text(build, "/buildNumber"),
+            url: text(build, "/_links/web/href"),
+        });
+    }
+
+    Ok(checks)
+}
+
+fn overall_status(checks: &[CheckStatus]) -> &'static str {
+    if checks.is_empty() {
+        "none"
+    } else if checks.iter().any(|check| check.status == "fail") {
+        "fail"
+    } else if checks.iter().any(|check| check.status == "pending") {
+        "pending"
+    } else {
+        "pass"
+    }
+}
+
+/// CI results the hosting provider has for a commit or branch tip, for the badges in the
+/// graph and the branch list.
+#[tauri::command]
+pub async fn get_checks(
+    app: tauri::AppHandle,
+    repo_path: String,
+    rev: String,
+    remote_name: Option<String>,
+) -> Result<CommitChecks, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let sha = repo.revparse_single(&rev)
+            .and_then(|object| object.peel_to_commit())
+            .map_err(|e| AppError::wrap(e, &format!("Failed to resolve '{}'", rev)))?
+            .id()
+            .to_string();
+
+        let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&app, &repo_path));
+        let hosted = hosted_repository(&repo, &remote_name)?;
+        let auth = hosted.authorization()?;
+
+        let checks = match hosted.provider {
+            Provider::Github => github_checks(&hosted, &auth, &sha),
+            Provider::Bitbucket => bitbucket_checks(&hosted, &auth, &sha),
+            Provider::AzureDevops => azure_checks(&hosted, &auth, &sha),
+            Provider::Gitlab => gitlab_checks(&hosted, &auth, &sha),
+        }
+        .map_err(|e| AppError::wrap(e, "Failed to get checks").with_host(Some(hosted.host.clone())))?;
+
+        Ok(CommitChecks {
+            sha,
+            overall: overall_status(&checks).to_string(),
+            checks,
+        })
+    })
+    .await?
+}
@@ -0,0 +1,280 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::{Status, StatusOptions};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanEntry {
+    pub path: String, // Relative to the repository; directories end with '/'
+    pub is_directory: bool,
+    pub is_ignored: bool,
+    pub size: u64, // Bytes, including everything inside a directory
+    pub error: Option<String>, // Set when removing this entry failed
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanReport {
+    pub dry_run: bool,
+    pub moved_to_trash: bool,
+    pub entries: Vec<CleanEntry>,
+    pub total_size: u64,
+}
+
+/// Size of a file or directory tree, without following symlinks.
+fn disk_size(path: &Path, token: &OperationToken) -> Result<u64, AppError> {
+    token.check()?;
+    let metadata = match path.symlink_metadata() {
+        Ok(metadata) => metadata,
+        Err(_) => return Ok(0),
+    };
+    if !metadata.is_dir() {
+        return Ok(metadata.len());
+    }
+    let mut size = 0;
+    if let Ok(entries) = std::fs::read_dir(path) {
+        for entry in entries.flatten() {
+            size += disk_size(&entry.path(), token)?;
+        }
+    }
+    Ok(size)
+}
+
+/// What `git clean` would remove: untracked files, plus untracked directories with
+/// `include_directories` (-d) and ignored files with `include_ignored` (-x). Nested
+/// repositories are never listed, as with `git clean -d`.
+fn clean_candidates(
+    repo: &git2::Repository,
+    include_ignored: bool,
+    include_directories: bool,
+    token: &OperationToken,
+) -> Result<Vec<CleanEntry>, AppError> {
+    let workdir = repo.workdir()
+        .ok_or_else(|| AppError::invalid_input("Repository has no working tree"))?;
+
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        // Untracked directories come back as one "dir/" entry, which only -d removes
+        .recurse_untracked_dirs(false)
+        .include_ignored(include_ignored)
+        .recurse_ignored_dirs(false);
+    let statuses = repo.statuses(Some(&mut options))
+        .map_err(|e| AppError::wrap(e, "Failed to get status"))?;
+    let ignored = if include_directories && !include_ignored { ignored_paths(repo)? } else { Vec::new() };
+
+    let mut entries = Vec::new();
+    for status in statuses.iter() {
+        let path = match status.path() {
+            Some(path) => path.to_string(),
+            None => continue,
+        };
+        let is_ignored = status.status().contains(Status::IGNORED);
+        if !is_ignored && !status.status().contains(Status::WT_NEW) {
+            continue;
+        }
+        let is_directory = path.ends_with('/');
+        if is_directory && (!include_directories || workdir.join(&path).join(".git").exists()) {
+            continue;
+        }
+        // `git clean -d` without -x keeps ignored files inside untracked directories
+        if is_directory && !include_ignored && ignored.iter().any(|ignored| ignored.starts_with(&path)) {
+            expand_directory(repo, workdir, &path, &ignored, &mut entries, token)?;
+            continue;
+        }
+        entries.push(CleanEntry {
+            size: disk_size(&workdir.join(&path), token)?,
+            path,
+            is_directory,
+            is_ignored,
+            error: None,
+        });
+    }
+    Ok(entries)
+}
+
+/// Ignored files and directories, for spotting untracked directories that hold some.
+fn ignored_paths(repo: &git2::Repository) -> Result<Vec<String>, AppError> {
+    let mut options = StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(true)
+        .recurse_ignored_dirs(false);
+    let statuses = repo.statuses(Some(&mut options))
+        .map_err(|e| AppError::wrap(e, "Failed to get status"))?;
+
+    Ok(statuses
+        .iter()
+        .filter(|status| status.status().contains(Status::IGNORED))
+        .filter_map(|status| status.path().map(|path| path.to_string()))
+        .collect())
+}
+
+/// Lists the untracked contents of `directory` instead of the directory itself, leaving
+/// out ignored entries. Subdirectories without ignored files are listed whole.
+fn expand_directory(
+    repo: &git2::Repository,
+    workdir: &Path,
+    directory: &str,
+    ignored: &[String],
+    entries: &mut Vec<CleanEntry>,
+    token: &OperationToken,
+) -> Result<(), AppError> {
+    let children = std::fs::read_dir(workdir.join(directory))
+        .map_err(|e| AppError::wrap(e, &format!("Failed to read {}", directory)))?;
+    for child in children.flatten() {
+        token.check()?;
+        let is_directory = child.file_type().map(|kind| kind.is_dir()).unwrap_or(false);
+        let name = child.file_name().to_string_lossy().to_string();
+        let path = format!("{}{}{}", directory, name, if is_directory { "/" } else { "" });
+        if repo.is_path_ignored(&path).unwrap_or(false) || (is_directory && child.path().join(".git").exists()) {
+            continue;
+        }
+        if is_directory && ignored.iter().any(|ignored| ignored.starts_with(&path)) {
+            expand_directory(repo, workdir, &path, ignored, entries, token)?;
+            continue;
+        }
+        entries.push(CleanEntry {
+            size: disk_size(&child.path(), token)?,
+            path,
+            is_directory,
+            is_ignored: false,
+            error: None,
+        });
+    }
+    Ok(())
+}
+
+/// Moves paths to the OS trash with the platform's own tool, so they can be restored from
+/// Finder, Explorer or the desktop's file manager.
+fn move_to_trash(paths: &[PathBuf]) -> Result<(), String> {
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let quoted: Vec<String> = paths
+            .iter()
+            .map(|path| format!("POSIX file \"{}\"", path.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"")))
+            .collect();
+        let mut command = Command::new("osascript");
+        command.args(["-e", &format!("tell application \"Finder\" to delete {{{}}}", quoted.join(", "))]);
+        command
+    };
+
+    #[cfg(target_os = "windows")]
+    let mut command = {
+        use std::os::windows::process::CommandExt;
+        let quoted: Vec<String> = paths
+            .iter()
+            .map(|path| format!("'{}'", path.to_string_lossy().replace('\'', "''")))
+            .collect();
+        let script = format!(
+            "Add-Type -AssemblyName Microsoft.VisualBasic; foreach ($p in @({})) {{ \
+             if (Test-Path -LiteralPath $p -PathType Container) {{ \
+             [Microsoft.VisualBasic.FileIO.FileSystem]::DeleteDirectory($p, 'OnlyErrorDialogs', 'SendToRecycleBin') }} \
+             else {{ [Microsoft.VisualBasic.FileIO.FileSystem]::DeleteFile($p, 'OnlyErrorDialogs', 'SendToRecycleBin') }} }}",
+            quoted.join(", ")
+        );
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            // CREATE_NO_WINDOW: don't flash a console window from the GUI
+            .creation_flags(0x08000000);
+        command
+    };
+
+    #[cfg(not(any(target_os = "macos", target_os = "windows")))]
+    let mut command = {
+        let mut command = Command::new("gio");
+        command.arg("trash").arg("--").args(paths);
+        command
+    };
+
+    let output = command.output()
+        .map_err(|e| format!("Failed to move files to the trash: {}", e))?;
+    if !output.status.success() {
+        return Err(format!("Failed to move files to the trash: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+    Ok(())
+}
+
+fn clean_blocking(
+    repo_path: &str,
+    include_ignored: bool,
+    include_directories: bool,
+    dry_run: bool,
+    use_trash: bool,
+    paths: Option<Vec<String>>,
+    token: &OperationToken,
+) -> Result<CleanReport, AppError> {
+    let repo = crate::open_worktree_repository(repo_path)?;
+    let workdir = repo.workdir()
+        .ok_or_else(|| AppError::invalid_input("Repository has no working tree"))?
+        .to_path_buf();
+
+    let mut entries = clean_candidates(&repo, include_ignored, include_directories, token)?;
+    // A selection from an earlier dry run: only what is still a candidate is removed
+    if let Some(paths) = paths {
+        entries.retain(|entry| paths.iter().any(|path| path.trim_end_matches('/') == entry.path.trim_end_matches('/')));
+    }
+    let total_size = entries.iter().map(|entry| entry.size).sum();
+
+    if !dry_run {
+        token.check()?;
+        if use_trash {
+            let targets: Vec<PathBuf> = entries.iter().map(|entry| workdir.join(entry.path.trim_end_matches('/'))).collect();
+            move_to_trash(&targets)?;
+        } else {
+            for entry in entries.iter_mut() {
+                let target = workdir.join(entry.path.trim_end_matches('/'));
+                let removed = if entry.is_directory {
+                    std::fs::remove_dir_all(&target)
+                } else {
+                    std::fs::remove_file(&target)
+                };
+                entry.error = removed.err().map(|e| e.to_string());
+            }
+        }
+        println!("🧹 Cleaned {} entries from {}", entries.len(), repo_path);
+    }
+
+    Ok(CleanReport {
+        dry_run,
+        moved_to_trash: use_trash && !dry_run,
+        entries,
+        total_size,
+    })
+}
+
+/// Removes untracked files like `git clean`, and untracked directories and ignored files
+/// when asked. `dry_run` only lists what would go; passing those `paths` back removes
+/// just that selection. With `use_trash` entries go to the OS trash instead of being
+/// deleted for good.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn clean_working_directory(
+    app: tauri::AppHandle,
+    repo_path: String,
+    include_ignored: bool,
+    include_directories: bool,
+    dry_run: bool,
+    use_trash: Option<bool>,
+    paths: Option<Vec<String>>,
+    operation_id: Option<String>,
+) -> Result<CleanReport, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "clean", operation_id, move |token| {
+        let run = || clean_blocking(&repo_path, include_ignored, include_directories, dry_run, use_trash.unwrap_or(false), paths, &token);
+        if dry_run {
+            run()
+        } else {
+            audited(&task_app, &repo_path, "clean_working_directory", run)
+        }
+    })
+    .await
+}
@@ -0,0 +1,58 @@
+use crate::commands::deep_links::URL_SCHEME;
+use crate::commands::hosting::percent_encode;
+use git2::Repository;
+use std::path::Path;
+
+const USAGE: &str = "Usage:
+  codegit [path]                 Open the repository containing path (default: the current directory)
+  codegit open [path]            Same as above
+  codegit clone <url>            Clone url from the CodeGit window
+  codegit checkout <branch>      Check out branch in the repository of the current directory
+  codegit help                   Show this help";
+
+/// What the command line asks the app to do.
+pub enum CliRequest {
+    Launch(Option<String>), // codegit:// link to hand to the window, if any
+    Help,
+}
+
+/// Working tree root of the repository containing `path`.
+fn repository_root(path: &str) -> Result<String, String> {
+    let repo = Repository::discover(path).map_err(|_| format!("{} is not inside a Git repository", path))?;
+    let root = repo.workdir().unwrap_or_else(|| repo.path());
+    let root = std::fs::canonicalize(root).unwrap_or_else(|_| root.to_path_buf());
+    Ok(root.to_string_lossy().trim_end_matches(['/', '\\']).to_string())
+}
+
+fn link(action: &str, parameters: &[(&str, &str)]) -> String {
+    let query: Vec<String> = parameters
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, percent_encode(value, false)))
+        .collect();
+    format!("{}://{}?{}", URL_SCHEME, action, query.join("&"))
+}
+
+/// Reads the launch arguments: a codegit:// link from the OS, or a CLI subcommand turned
+/// into one. macOS adds -psn_… when launching from Finder, which is ignored.
+pub fn parse_cli(args: &[String]) -> Result<CliRequest, String> {
+    let args: Vec<&str> = args.iter().map(|arg| arg.as_str()).filter(|arg| !arg.starts_with("-psn")).collect();
+
+    match args.as_slice() {
+        [] => Ok(CliRequest::Launch(None)),
+        [raw] if raw.starts_with(&format!("{}://", URL_SCHEME)) => Ok(CliRequest::Launch(Some(raw.to_string()))),
+        ["help"] | ["--help"] | ["-h"] => Ok(CliRequest::Help),
+        ["open"] => Ok(CliRequest::Launch(Some(link("open", &[("path", &repository_root(".")?)])))),
+        ["open", path] => Ok(CliRequest::Launch(Some(link("open", &[("path", &repository_root(path)?)])))),
+        ["clone", url] => Ok(CliRequest::Launch(Some(link("clone", &[("url", url)])))),
+        ["checkout", branch] => {
+            let root = repository_root(".")?;
+            Ok(CliRequest::Launch(Some(link("checkout", &[("repo", &root), ("branch", branch)]))))
+        }
+        [path] if Path::new(path).exists() => Ok(CliRequest::Launch(Some(link("open", &[("path", &repository_root(path)?)])))),
+        _ => Err(format!("Unrecognized arguments: {}\n\n{}", args.join(" "), USAGE)),
+    }
+}
+
+pub fn print_usage() {
+    println!("{}", USAGE);
+}
@@ -0,0 +1,544 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::discovery::DiscoveryFilter;
+use crate::commands::error::AppError;
+use crate::commands::hosting::percent_decode;
+use crate::database::{Organization, Repository as SavedRepository};
+use chrono::Utc;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::sqlite::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::State;
+
+// Colors cycled through for organizations created by an import
+const ORGANIZATION_COLORS: [&str; 6] = ["#3b82f6", "#10b981", "#f59e0b", "#ef4444", "#8b5cf6", "#06b6d4"];
+// VS Code builds whose recently opened lists are read
+const VSCODE_FLAVORS: [&str; 3] = ["Code", "Code - Insiders", "VSCodium"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum GitClient {
+    Sourcetree,
+    Gitkraken,
+    Vscode,
+}
+
+impl GitClient {
+    fn label(self) -> &'static str {
+        match self {
+            GitClient::Sourcetree => "SourceTree",
+            GitClient::Gitkraken => "GitKraken",
+            GitClient::Vscode => "VS Code",
+        }
+    }
+}
+
+/// A repository known to another Git client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientRepository {
+    pub client: GitClient,
+    pub path: String,
+    pub name: String,
+    pub group: Option<String>, // Bookmark folder, GitKraken profile or VS Code workspace; becomes an organization
+    #[serde(default)]
+    pub already_added: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientImportSummary {
+    pub repositories_added: usize,
+    pub organizations_added: usize,
+    pub skipped: usize, // Already in CodeGit, excluded from discovery, or no longer a repository
+}
+
+fn short_hash(value: &str) -> String {
+    let digest = Sha256::digest(value.as_bytes());
+    digest.iter().take(8).map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_xml(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Local path of a `file://` URI; remote URIs (vscode-remote://, ...) have none.
+fn file_uri_path(uri: &str) -> Option<PathBuf> {
+    let path = percent_decode(uri.strip_prefix("file://")?);
+    // file:///c%3A/src/app on Windows
+    let bytes = path.as_bytes();
+    if bytes.len() > 2 && bytes[0] == b'/' && bytes[2] == b':' && bytes[1].is_ascii_alphabetic() {
+        return Some(PathBuf::from(&path[1..]));
+    }
+    Some(PathBuf::from(path))
+}
+
+/// JSON with comments and trailing commas, as VS Code writes it, made parseable.
+fn strip_jsonc(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            output.push(c);
+            match c {
+                '\\' => {
+                    if let Some(escaped) = chars.next() {
+                        output.push(escaped);
+                    }
+                }
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                output.push(c);
+            }
+            ('/', Some('/')) => {
+                for skipped in chars.by_ref() {
+                    if skipped == '\n' {
+                        output.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for skipped in chars.by_ref() {
+                    if previous == '*' && skipped == '/' {
+                        break;
+                    }
+                    previous = skipped;
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    regex::Regex::new(r",(\s*[}\]])")
+        .map(|trailing| trailing.replace_all(&output, "$1").to_string())
+        .unwrap_or(output)
+}
+
+fn entry(client: GitClient, path: PathBuf, group: Option<String>) -> ClientRepository {
+    let path = path.to_string_lossy().trim_end_matches(['/', '\\']).to_string();
+    let path = path.strip_suffix("/.git").or_else(|| path.strip_suffix("\\.git")).unwrap_or(&path).to_string();
+    let name = Path::new(&path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.clone());
+    ClientRepository { client, path, name, group, already_added: false }
+}
+
+/// Every string object in a binary property list ("bplist00"), read through the offset
+/// table described by the 32-byte trailer.
+fn binary_plist_strings(data: &[u8]) -> Vec<String> {
+    let read_int = |start: usize, size: usize| -> Option<usize> {
+        let bytes = data.get(start..start.checked_add(size)?)?;
+        Some(bytes.iter().fold(0usize, |value, byte| (value << 8) | *byte as usize))
+    };
+    if !data.starts_with(b"bplist00") || data.len() < 40 {
+        return Vec::new();
+    }
+    let trailer = data.len() - 32;
+    let offset_size = data[trailer + 6] as usize;
+    let (object_count, offset_table) = match (read_int(trailer + 8, 8), read_int(trailer + 24, 8)) {
+        (Some(object_count), Some(offset_table)) => (object_count, offset_table),
+        _ => return Vec::new(),
+    };
+
+    let mut strings = Vec::new();
+    for index in 0..object_count {
+        let offset = match read_int(offset_table + index * offset_size, offset_size) {
+            Some(offset) if offset < data.len() => offset,
+            _ => break,
+        };
+        let marker = data[offset];
+        if marker >> 4 != 0x5 && marker >> 4 != 0x6 {
+            continue;
+        }
+        // A length of 0xF means the real length follows as an integer object
+        let (length, start) = match marker & 0x0f {
+            0x0f => match data.get(offset + 1) {
+                Some(int_marker) if int_marker >> 4 == 0x1 => {
+                    let size = 1usize << (int_marker & 0x0f);
+                    match read_int(offset + 2, size) {
+                        Some(length) => (length, offset + 2 + size),
+                        None => continue,
+                    }
+                }
+                _ => continue,
+            },
+            length => (length as usize, offset + 1),
+        };
+
+        if marker >> 4 == 0x5 {
+            if let Some(bytes) = data.get(start..start + length) {
+                strings.push(String::from_utf8_lossy(bytes).to_string());
+            }
+        } else if let Some(bytes) = data.get(start..start + length * 2) {
+            let units: Vec<u16> = bytes.chunks(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+            strings.push(String::from_utf16_lossy(&units));
+        }
+    }
+    strings
+}
+
+/// SourceTree bookmarks. Windows keeps them in bookmarks.xml, where each node carries its
+/// depth in <Level>, so a bookmark belongs to the top-level folder above it.
+fn sourcetree_repositories() -> Vec<ClientRepository> {
+    let mut repositories = Vec::new();
+
+    if let Some(local_data) = tauri::api::path::local_data_dir() {
+        let bookmarks = local_data.join("Atlassian").join("SourceTree").join("bookmarks.xml");
+        if let Ok(xml) = fs::read_to_string(&bookmarks) {
+            let field = |node: &str, tag: &str| -> Option<String> {
+                let start = node.find(&format!("<{}>", tag))? + tag.len() + 2;
+                let end = start + node[start..].find(&format!("</{}>", tag))?;
+                Some(decode_xml(node[start..end].trim()))
+            };
+
+            let mut folders: Vec<String> = Vec::new();
+            for node in xml.split("<TreeViewNode").skip(1) {
+                let level = field(node, "Level").and_then(|level| level.parse::<usize>().ok()).unwrap_or(0);
+                folders.truncate(level);
+                match field(node, "Path") {
+                    Some(path) if !path.is_empty() => {
+                        repositories.push(entry(GitClient::Sourcetree, PathBuf::from(path), folders.first().cloned()));
+                    }
+                    _ if node.contains("BookmarkFolderNode") => {
+                        folders.push(field(node, "Name").unwrap_or_default());
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    // macOS keeps bookmarks in a keyed-archive binary plist; its absolute paths are the
+    // bookmarks (folders are not recoverable from the archive)
+    if let Some(config) = tauri::api::path::config_dir() {
+        if let Ok(plist) = fs::read(config.join("SourceTree").join("browser.plist")) {
+            for candidate in binary_plist_strings(&plist) {
+                if candidate.starts_with('/') && candidate.len() > 1 {
+                    repositories.push(entry(GitClient::Sourcetree, PathBuf::from(candidate), None));
+                }
+            }
+        }
+    }
+
+    repositories
+}
+
+/// Repositories GitKraken has opened, per profile. Profiles become organizations only when
+/// there is more than one.
+fn gitkraken_repositories() -> Vec<ClientRepository> {
+    let root = match std::env::var("APPDATA").ok().filter(|_| cfg!(target_os = "windows")) {
+        Some(app_data) => PathBuf::from(app_data).join(".gitkraken"),
+        None => match crate::user_home_dir() {
+            Some(home) => home.join(".gitkraken"),
+            None => return Vec::new(),
+        },
+    };
+    let profiles: Vec<PathBuf> = match fs::read_dir(root.join("profiles")) {
+        Ok(entries) => entries.flatten().map(|entry| entry.path()).filter(|path| path.is_dir()).collect(),
+        Err(_) => return Vec::new(),
+    };
+
+    let mut repositories = Vec::new();
+    for profile_dir in &profiles {
+        let profile: serde_json::Value = fs::read_to_string(profile_dir.join("profile"))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let group = if profiles.len() > 1 {
+            profile["profileName"].as_str().or_else(|| profile["name"].as_str()).map(|name| name.to_string())
+        } else {
+            None
+        };
+
+        let mut paths: Vec<String> = fs::read_to_string(profile_dir.join("localRepoCache"))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        paths.extend(
+            profile["tabInfo"]["tabs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|tab| tab["repoPath"].as_str().map(|path| path.to_string())),
+        );
+        repositories.extend(paths.into_iter().map(|path| entry(GitClient::Gitkraken, PathBuf::from(path), group.clone())));
+    }
+    repositories
+}
+
+/// Recently opened folder and workspace URIs. Current VS Code keeps the list in the
+/// state.vscdb SQLite store; older versions kept it in storage.json.
+async fn vscode_recent_entries() -> Vec<serde_json::Value> {
+    let config = match tauri::api::path::config_dir() {
+        Some(config) => config,
+        None => return Vec::new(),
+    };
+
+    let mut entries = Vec::new();
+    for flavor in VSCODE_FLAVORS {
+        let global_storage = config.join(flavor).join("User").join("globalStorage");
+
+        let state_db = global_storage.join("state.vscdb");
+        if state_db.is_file() {
+            let database_url = format!("sqlite:{}?mode=ro", state_db.to_string_lossy());
+            if let Ok(pool) = SqlitePool::connect(&database_url).await {
+                let value: Option<String> = sqlx::query_scalar(
+                    "SELECT value FROM ItemTable WHERE key = 'history.recentlyOpenedPathsList'"
+                )
+                .fetch_optional(&pool)
+                .await
+                .ok()
+                .flatten();
+                pool.close().await;
+
+                if let Some(list) = value.and_then(|value| serde_json::from_str::<serde_json::Value>(&value).ok()) {
+                    entries.extend(list["entries"].as_array().cloned().unwrap_or_default());
+                }
+            }
+        }
+
+        let storage: serde_json::Value = fs::read_to_string(global_storage.join("storage.json"))
+            .ok()
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default();
+        let opened = &storage["openedPathsList"];
+        entries.extend(opened["entries"].as_array().cloned().unwrap_or_default());
+        for uri in opened["workspaces3"].as_array().into_iter().flatten() {
+            match uri.as_str() {
+                Some(folder) => entries.push(serde_json::json!({ "folderUri": folder })),
+                None => entries.push(serde_json::json!({ "workspace": uri })),
+            }
+        }
+    }
+    entries
+}
+
+/// Folders of a multi-root .code-workspace file, relative to the file unless absolute.
+fn code_workspace_folders(workspace_file: &Path) -> Vec<PathBuf> {
+    let workspace: serde_json::Value = match fs::read_to_string(workspace_file)
+        .ok()
+        .and_then(|text| serde_json::from_str(&strip_jsonc(&text)).ok())
+    {
+        Some(workspace) => workspace,
+        None => return Vec::new(),
+    };
+    let base = workspace_file.parent().unwrap_or_else(|| Path::new(""));
+
+    workspace["folders"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|folder| match (folder["path"].as_str(), folder["uri"].as_str()) {
+            (Some(path), _) => Some(base.join(path)),
+            (None, Some(uri)) => file_uri_path(uri),
+            _ => None,
+        })
+        .collect()
+}
+
+/// VS Code folders opened directly, plus the folders of opened workspaces, grouped by
+/// workspace name.
+fn vscode_repositories(entries: &[serde_json::Value]) -> Vec<ClientRepository> {
+    let mut repositories = Vec::new();
+
+    for recent in entries {
+        if let Some(folder) = recent["folderUri"].as_str().and_then(file_uri_path) {
+            repositories.push(entry(GitClient::Vscode, folder, None));
+            continue;
+        }
+        let workspace_file = match recent["workspace"]["configPath"].as_str().and_then(file_uri_path) {
+            Some(workspace_file) => workspace_file,
+            None => continue,
+        };
+        let group = workspace_file
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string());
+        for folder in code_workspace_folders(&workspace_file) {
+            repositories.push(entry(GitClient::Vscode, folder, group.clone()));
+        }
+    }
+    repositories
+}
+
+/// Keeps existing repositories outside the discovery exclusions, once per path. A
+/// repository listed by several clients keeps the first group it was found in.
+fn filter_candidates(
+    app: &tauri::AppHandle,
+    candidates: Vec<ClientRepository>,
+    known_paths: &HashSet<String>,
+) -> Vec<ClientRepository> {
+    let filter = DiscoveryFilter::load(app);
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    let mut repositories: Vec<ClientRepository> = Vec::new();
+
+    for mut candidate in candidates {
+        if let Some(&index) = seen.get(&candidate.path) {
+            if repositories[index].group.is_none() {
+                repositories[index].group = candidate.group;
+            }
+            continue;
+        }
+        let path = PathBuf::from(&candidate.path);
+        if filter.is_excluded(&path) || Repository::open(&path).is_err() {
+            continue;
+        }
+        candidate.already_added = known_paths.contains(&candidate.path);
+        seen.insert(candidate.path.clone(), repositories.len());
+        repositories.push(candidate);
+    }
+    repositories
+}
+
+/// Repositories bookmarked in SourceTree, opened in GitKraken or opened as folders and
+/// workspaces in VS Code, for the onboarding import. `clients` limits the sources; entries
+/// that are no longer repositories are left out.
+#[tauri::command]
+pub async fn find_client_repositories(
+    app: tauri::AppHandle,
+    db_state: State<'_, DatabaseState>,
+    clients: Option<Vec<GitClient>>,
+) -> Result<Vec<ClientRepository>, AppError> {
+    let clients = clients.unwrap_or_else(|| vec![GitClient::Sourcetree, GitClient::Gitkraken, GitClient::Vscode]);
+    // Every workspace counts: a path registered elsewhere must not be imported again
+    let known_paths: HashSet<String> = {
+        let db = db_state.lock().await;
+        db.get_all_repository_paths()
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to get repositories"))?
+            .into_iter()
+            .collect()
+    };
+    let vscode_entries = if clients.contains(&GitClient::Vscode) {
+        vscode_recent_entries().await
+    } else {
+        Vec::new()
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut candidates = Vec::new();
+        for client in clients {
+            match client {
+                GitClient::Sourcetree => candidates.extend(sourcetree_repositories()),
+                GitClient::Gitkraken => candidates.extend(gitkraken_repositories()),
+                GitClient::Vscode => candidates.extend(vscode_repositories(&vscode_entries)),
+            }
+        }
+        Ok(filter_candidates(&app, candidates, &known_paths))
+    })
+    .await?
+}
+
+/// Saves the chosen repositories from find_client_repositories, creating an organization
+/// for each group (or reusing one with the same name). Repositories already in CodeGit
+/// keep their current organization.
+#[tauri::command]
+pub async fn import_client_repositories(
+    db_state: State<'_, DatabaseState>,
+    repositories: Vec<ClientRepository>,
+) -> Result<ClientImportSummary, AppError> {
+    let db = db_state.lock().await;
+    // Saving a path registered in another workspace would replace its row (path is
+    // unique) and lose its id, organization, tags and pins, so every row counts
+    let known_paths: HashSet<String> = db.get_all_repository_paths()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get repositories"))?
+        .into_iter()
+        .collect();
+    let organizations = db.get_organizations()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get organizations"))?;
+    let mut organization_ids: HashMap<String, String> = organizations
+        .iter()
+        .map(|organization| (organization.name.to_lowercase(), organization.id.clone()))
+        .collect();
+
+    let mut summary = ClientImportSummary { repositories_added: 0, organizations_added: 0, skipped: 0 };
+    for candidate in repositories {
+        if known_paths.contains(&candidate.path) {
+            summary.skipped += 1;
+            continue;
+        }
+        let info = match crate::check_git_repository(&PathBuf::from(&candidate.path)) {
+            Some(info) => info,
+            None => {
+                summary.skipped += 1;
+                continue;
+            }
+        };
+        let now = Utc::now();
+
+        let organization_id = match candidate.group.as_deref().map(str::trim).filter(|group| !group.is_empty()) {
+            Some(group) => match organization_ids.get(&group.to_lowercase()) {
+                Some(id) => Some(id.clone()),
+                None => {
+                    let organization = Organization {
+                        id: format!("org_{}", short_hash(group)),
+                        name: group.to_string(),
+                        color: ORGANIZATION_COLORS[(organizations.len() + summary.organizations_added) % ORGANIZATION_COLORS.len()].to_string(),
+                        description: Some(format!("Imported from {}", candidate.client.label())),
+                        avatar: None,
+                        user_id: None,
+                        workspace_id: None,
+                        sign_off: false,
+                        created_at: now,
+                        updated_at: now,
+                    };
+                    db.save_organization(&organization)
+                        .await
+                        .map_err(|e| AppError::wrap(e, "Failed to save organization"))?;
+                    organization_ids.insert(group.to_lowercase(), organization.id.clone());
+                    summary.organizations_added += 1;
+                    Some(organization.id)
+                }
+            },
+            None => None,
+        };
+
+        let remote_url = Repository::open(&candidate.path).ok().and_then(|repo| {
+            let remote = repo.find_remote("origin").ok()?;
+            remote.url().map(|url| url.to_string())
+        });
+        let repository = SavedRepository {
+            id: format!("repo_{}", short_hash(&candidate.path)),
+            name: info.name,
+            path: candidate.path,
+            organization_id,
+            remote_url,
+            current_branch: info.current_branch,
+            last_commit: info.last_commit,
+            is_dirty: info.is_dirty,
+            is_favorite: false,
+            is_pinned: false,
+            position: None,
+            tags: serde_json::json!([]),
+            workspace_id: None,
+            last_accessed: now,
+            created_at: now,
+            updated_at: now,
+        };
+        db.save_repository(&repository)
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to save repository"))?;
+        summary.repositories_added += 1;
+    }
+
+    Ok(summary)
+}
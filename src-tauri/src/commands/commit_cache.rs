@@ -0,0 +1,246 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::commands::operations::OperationToken;
+use crate::database::CachedCommit;
+use git2::{Oid, Repository};
+use std::collections::{BinaryHeap, HashMap, HashSet};
+use std::sync::Mutex;
+use tauri::{Manager, State};
+
+/// Repositories whose commit cache is being built in the background.
+#[derive(Default)]
+pub struct CommitCacheBuilds(Mutex<HashSet<String>>);
+
+/// HEAD plus every local and remote-tracking branch, peeled to commits.
+fn current_tips(repo: &Repository) -> Vec<Oid> {
+    let mut tips = Vec::new();
+
+    if let Ok(head) = repo.head().and_then(|head| head.peel_to_commit()) {
+        tips.push(head.id());
+    }
+    for glob in ["refs/heads/*", "refs/remotes/*"] {
+        if let Ok(references) = repo.references_glob(glob) {
+            for reference in references.flatten() {
+                if let Ok(commit) = reference.peel_to_commit() {
+                    tips.push(commit.id());
+                }
+            }
+        }
+    }
+
+    tips.sort();
+    tips.dedup();
+    tips
+}
+
+/// Commits reachable from the current tips but not from the previously cached ones.
+fn walk_new_commits(
+    repo: &Repository,
+    cached_tips: &[String],
+    tips: &[Oid],
+    token: Option<&OperationToken>,
+) -> Result<Vec<CachedCommit>, String> {
+    let mut revwalk = repo.revwalk()
+        .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+
+    for tip in tips {
+        revwalk.push(*tip)
+            .map_err(|e| format!("Failed to push {}: {}", tip, e))?;
+    }
+    for cached_tip in cached_tips {
+        // A tip rewritten away and garbage collected since can't be hidden
+        if let Ok(oid) = Oid::from_str(cached_tip) {
+            if repo.find_commit(oid).is_ok() {
+                revwalk.hide(oid)
+                    .map_err(|e| format!("Failed to hide {}: {}", oid, e))?;
+            }
+        }
+    }
+
+    let mut commits = Vec::new();
+    for oid_result in revwalk {
+        if let Some(token) = token {
+            token.check()?;
+        }
+
+        let oid = oid_result.map_err(|e| format!("Failed to get OID: {}", e))?;
+        let commit = repo.find_commit(oid)
+            .map_err(|e| format!("Failed to find commit: {}", e))?;
+        let author = commit.author();
+
+        commits.push(CachedCommit {
+            id: oid.to_string(),
+            parents: commit.parent_ids().map(|parent| parent.to_string()).collect(),
+            author: author.name().unwrap_or("Unknown").to_string(),
+            email: author.email().unwrap_or("unknown@email.com").to_string(),
+            message: commit.message().unwrap_or("No message").to_string(),
+            timestamp: commit.time().seconds(),
+        });
+    }
+
+    Ok(commits)
+}
+
+/// Brings the cache up to date with the branches. With `build` false a repository that
+/// was never cached is left alone, so a commit or fetch doesn't trigger a full walk.
+fn update_commit_cache(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    token: Option<&OperationToken>,
+    build: bool,
+) -> Result<(), String> {
+    let db_state = app.try_state::<DatabaseState>()
+        .ok_or_else(|| "Database is unavailable".to_string())?;
+
+    let cached_tips = tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.get_commit_cache_tips(repo_path).await
+    })
+    .map_err(|e| format!("Failed to read commit cache: {}", e))?;
+
+    if cached_tips.is_empty() && !build {
+        return Ok(());
+    }
+
+    let tips = current_tips(repo);
+    let tip_ids: Vec<String> = tips.iter().map(|tip| tip.to_string()).collect();
+    if !cached_tips.is_empty() && tip_ids.iter().all(|tip| cached_tips.contains(tip)) {
+        return Ok(());
+    }
+
+    let commits = walk_new_commits(repo, &cached_tips, &tips, token)?;
+
+    tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.save_commit_cache(repo_path, &commits, &tip_ids).await
+    })
+    .map_err(|e| format!("Failed to update commit cache: {}", e))
+}
+
+/// Adds the commits created by a commit, fetch or pull to an already built cache.
+pub fn extend_commit_cache(app: &tauri::AppHandle, repo: &Repository, repo_path: &str) {
+    if let Err(e) = update_commit_cache(app, repo, repo_path, None, false) {
+        println!("⚠️  Failed to update commit cache for {}: {}", repo_path, e);
+    }
+}
+
+/// Builds the cache of a repository that has none on its own thread, so the first page of
+/// history comes from the ODB instead of waiting for every commit to be stored.
+fn build_in_background(app: &tauri::AppHandle, repo_path: &str) {
+    let started = app.try_state::<CommitCacheBuilds>()
+        .and_then(|builds| builds.0.lock().ok().map(|mut builds| builds.insert(repo_path.to_string())))
+        .unwrap_or(false);
+    if !started {
+        return;
+    }
+
+    let (app, repo_path) = (app.clone(), repo_path.to_string());
+    std::thread::spawn(move || {
+        let result = Repository::open(&repo_path)
+            .map_err(|e| format!("Failed to open repository: {}", e))
+            .and_then(|repo| update_commit_cache(&app, &repo, &repo_path, None, true));
+        if let Err(e) = result {
+            println!("⚠️  Failed to build commit cache for {}: {}", repo_path, e);
+        }
+        if let Ok(mut builds) = app.state::<CommitCacheBuilds>().0.lock() {
+            builds.remove(&repo_path);
+        }
+    });
+}
+
+/// Up to `limit` commits reachable from HEAD, newest first, served from the cache. None
+/// when the cache can't answer and the caller should walk the ODB; a repository without a
+/// cache gets one built in the background meanwhile.
+pub fn cached_history(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    limit: usize,
+    token: &OperationToken,
+) -> Result<Option<Vec<CachedCommit>>, String> {
+    let head = match repo.head().and_then(|head| head.peel_to_commit()) {
+        Ok(head) => head.id().to_string(),
+        Err(_) => return Ok(None),
+    };
+
+    let db_state = match app.try_state::<DatabaseState>() {
+        Some(db_state) => db_state,
+        None => return Ok(None),
+    };
+
+    let is_built = tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.get_commit_cache_tips(repo_path).await
+    })
+    .map(|tips| !tips.is_empty())
+    .unwrap_or(false);
+    if !is_built {
+        build_in_background(app, repo_path);
+        return Ok(None);
+    }
+
+    if let Err(e) = update_commit_cache(app, repo, repo_path, Some(token), false) {
+        token.check()?;
+        println!("⚠️  Commit cache unavailable for {}: {}", repo_path, e);
+        return Ok(None);
+    }
+
+    tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+
+        // Newest first by commit time, the order of an unsorted revwalk
+        let mut queue = BinaryHeap::new();
+        let mut pending = HashMap::new();
+        let mut seen = HashSet::new();
+        let mut history = Vec::new();
+
+        match db.get_cached_commit(repo_path, &head).await {
+            Ok(Some(commit)) => {
+                queue.push((commit.timestamp, commit.id.clone()));
+                seen.insert(commit.id.clone());
+                pending.insert(commit.id.clone(), commit);
+            }
+            _ => return Ok(None),
+        }
+
+        while let Some((_, id)) = queue.pop() {
+            if history.len() >= limit {
+                break;
+            }
+            token.check()?;
+
+            let commit: CachedCommit = match pending.remove(&id) {
+                Some(commit) => commit,
+                None => return Ok(None),
+            };
+            for parent in &commit.parents {
+                if !seen.insert(parent.clone()) {
+                    continue;
+                }
+                match db.get_cached_commit(repo_path, parent).await {
+                    Ok(Some(parent_commit)) => {
+                        queue.push((parent_commit.timestamp, parent.clone()));
+                        pending.insert(parent.clone(), parent_commit);
+                    }
+                    // A hole in the cache; fall back rather than show a truncated graph
+                    _ => return Ok(None),
+                }
+            }
+            history.push(commit);
+        }
+
+        Ok(Some(history))
+    })
+}
+
+#[tauri::command]
+pub async fn clear_commit_cache(
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.clear_commit_cache(&repo_path)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to clear commit cache"))
+}
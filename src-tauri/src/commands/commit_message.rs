@@ -0,0 +1,264 @@
+use crate::commands::error::AppError;
+use git2::{Delta, Diff, DiffFindOptions, DiffOptions, Patch};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+const MAX_SUBJECT_CHARS: usize = 72;
+const MAX_BODY_FILES: usize = 20;
+
+/// A draft commit message built from the staged changes. `kind` and `scope` are offered
+/// separately so the frontend can prefix the subject Conventional Commits style
+/// ("feat(commands): …") when the repository uses it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommitMessageSuggestion {
+    pub subject: String,
+    pub body: String,
+    pub kind: String, // 'feat' | 'fix' | 'refactor' | 'docs' | 'test' | 'build'
+    pub scope: Option<String>, // Directory or module most of the changes are in
+}
+
+/// One staged file, with its line counts (None for binary files).
+struct StagedChange {
+    status: Delta,
+    old_path: String,
+    path: String,
+    lines: Option<(usize, usize)>, // (added, deleted)
+}
+
+#[derive(PartialEq)]
+enum FileRole {
+    Docs,
+    Test,
+    Build,
+    Code,
+}
+
+fn file_role(path: &str) -> FileRole {
+    let lower = path.to_lowercase();
+    let file_name = lower.rsplit('/').next().unwrap_or(&lower);
+    let in_dir = |dir: &str| lower.starts_with(&format!("{}/", dir)) || lower.contains(&format!("/{}/", dir));
+
+    if in_dir("docs") || in_dir("doc") || [".md", ".rst", ".adoc", ".txt"].iter().any(|ext| file_name.ends_with(ext)) {
+        FileRole::Docs
+    } else if in_dir("tests") || in_dir("test") || in_dir("__tests__") || in_dir("spec")
+        || [".test.", ".spec.", "_test.", "_spec."].iter().any(|marker| file_name.contains(marker))
+    {
+        FileRole::Test
+    } else if lower.starts_with(".github/") || lower.starts_with(".gitlab")
+        || [
+            "cargo.toml", "cargo.lock", "package.json", "package-lock.json", "yarn.lock", "pnpm-lock.yaml",
+            "go.mod", "go.sum", "makefile", "dockerfile", "build.rs", "tsconfig.json",
+        ]
+        .contains(&file_name)
+    {
+        FileRole::Build
+    } else {
+        FileRole::Code
+    }
+}
+
+fn staged_changes(diff: &Diff) -> Result<Vec<StagedChange>, AppError> {
+    let mut changes = Vec::new();
+    for (index, delta) in diff.deltas().enumerate() {
+        let path_of = |file: git2::DiffFile| file.path().map(|path| path.to_string_lossy().to_string()).unwrap_or_default();
+        let lines = Patch::from_diff(diff, index)
+            .map_err(|e| AppError::wrap(e, "Failed to read staged diff"))?
+            .filter(|_| !delta.flags().is_binary())
+            .and_then(|patch| patch.line_stats().ok())
+            .map(|(_, added, deleted)| (added, deleted));
+        changes.push(StagedChange {
+            status: delta.status(),
+            old_path: path_of(delta.old_file()),
+            path: path_of(delta.new_file()),
+            lines,
+        });
+    }
+    Ok(changes)
+}
+
+/// The deepest directory holding every change, else the top-level directory holding most
+/// of them.
+fn dominant_directory(changes: &[StagedChange]) -> Option<String> {
+    let directories: Vec<Vec<&str>> = changes
+        .iter()
+        .map(|change| {
+            let mut components: Vec<&str> = change.path.split('/').collect();
+            components.pop();
+            components
+        })
+        .collect();
+
+    let first = directories.first()?;
+    let common = (0..first.len())
+        .take_while(|&depth| directories.iter().all(|components| components.get(depth) == first.get(depth)))
+        .count();
+    if common > 0 {
+        return Some(first[..common].join("/"));
+    }
+
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for top in directories.iter().filter_map(|components| components.first()) {
+        *counts.entry(*top).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .filter(|(_, count)| count * 2 > changes.len())
+        .max_by_key(|(_, count)| *count)
+        .map(|(top, _)| top.to_string())
+}
+
+fn file_name(path: &str) -> &str {
+    path.rsplit('/').next().unwrap_or(path)
+}
+
+/// Short name for a directory in a subject: its last component, skipping generic ones
+/// ("src/commands" → "commands", "app/src" → "app").
+fn scope_name(directory: &str) -> Option<String> {
+    directory
+        .rsplit('/')
+        .find(|component| !["src", "lib", "source", "sources", "app", "pkg", "internal"].contains(component))
+        .or_else(|| directory.split('/').next())
+        .filter(|component| !component.is_empty())
+        .map(|component| component.to_string())
+}
+
+fn verb(status: Delta) -> &'static str {
+    match status {
+        Delta::Added | Delta::Copied => "Add",
+        Delta::Deleted => "Remove",
+        Delta::Renamed => "Rename",
+        _ => "Update",
+    }
+}
+
+fn subject_for(changes: &[StagedChange], roles: &[FileRole], directory: Option<&str>, scope: Option<&str>) -> String {
+    if let [change] = changes {
+        return match change.status {
+            Delta::Renamed if Path::new(&change.old_path).parent() == Path::new(&change.path).parent() => {
+                format!("Rename {} to {}", file_name(&change.old_path), file_name(&change.path))
+            }
+            Delta::Renamed => format!("Move {} to {}", file_name(&change.old_path), change.path),
+            status => format!("{} {}", verb(status), file_name(&change.path)),
+        };
+    }
+
+    let count = changes.len();
+    let place = directory
+        .filter(|directory| !directory.is_empty())
+        .map(|directory| format!(" in {}", directory))
+        .unwrap_or_default();
+    if roles.iter().all(|role| *role == FileRole::Docs) {
+        return match scope {
+            Some(scope) => format!("Update {} documentation", scope),
+            None => "Update documentation".to_string(),
+        };
+    }
+    if roles.iter().all(|role| *role == FileRole::Test) {
+        let verb = if changes.iter().all(|change| change.status == Delta::Added) { "Add" } else { "Update" };
+        return match scope {
+            Some(scope) => format!("{} tests for {}", verb, scope),
+            None => format!("{} tests", verb),
+        };
+    }
+
+    let first = verb(changes[0].status);
+    if changes.iter().all(|change| verb(change.status) == first) {
+        let noun = if first == "Rename" { "Move" } else { first };
+        return format!("{} {} files{}", noun, count, place);
+    }
+    match scope {
+        Some(scope) => format!("Update {}", scope),
+        None => format!("Update {} files", count),
+    }
+}
+
+fn kind_for(changes: &[StagedChange], roles: &[FileRole]) -> &'static str {
+    if roles.iter().all(|role| *role == FileRole::Docs) {
+        return "docs";
+    }
+    if roles.iter().all(|role| matches!(role, FileRole::Test | FileRole::Docs)) {
+        return "test";
+    }
+    if roles.iter().all(|role| matches!(role, FileRole::Build | FileRole::Docs)) {
+        return "build";
+    }
+    let code: Vec<&StagedChange> = changes.iter().zip(roles).filter(|(_, role)| **role == FileRole::Code).map(|(change, _)| change).collect();
+    if code.is_empty() {
+        return "build"; // Tests together with manifests or CI files
+    }
+    if code.iter().all(|change| matches!(change.status, Delta::Renamed | Delta::Deleted)) {
+        return "refactor";
+    }
+    if code.iter().any(|change| change.status == Delta::Added) {
+        return "feat";
+    }
+    // Edits that mostly remove lines read as a cleanup, the rest as fixes
+    let (added, deleted) = code.iter().filter_map(|change| change.lines).fold((0, 0), |(a, d), (added, deleted)| (a + added, d + deleted));
+    if deleted >= added { "refactor" } else { "fix" }
+}
+
+fn body_for(changes: &[StagedChange]) -> String {
+    let mut lines: Vec<String> = changes
+        .iter()
+        .take(MAX_BODY_FILES)
+        .map(|change| {
+            let counts = match change.lines {
+                Some((added, deleted)) if added + deleted > 0 => format!(" (+{}/-{})", added, deleted),
+                _ => String::new(),
+            };
+            match change.status {
+                Delta::Renamed => format!("- Rename {} to {}{}", change.old_path, change.path, counts),
+                status => format!("- {} {}{}", verb(status), change.path, counts),
+            }
+        })
+        .collect();
+    if changes.len() > MAX_BODY_FILES {
+        lines.push(format!("- …and {} more files", changes.len() - MAX_BODY_FILES));
+    }
+    lines.join("\n")
+}
+
+fn truncate_subject(subject: String) -> String {
+    if subject.chars().count() <= MAX_SUBJECT_CHARS {
+        return subject;
+    }
+    let mut truncated: String = subject.chars().take(MAX_SUBJECT_CHARS - 1).collect();
+    truncated.push('…');
+    truncated
+}
+
+/// Proposes a commit message from what is staged: the subject names the change (added,
+/// removed, renamed or updated files, or the module they are in) and the body lists the
+/// files. Purely local heuristics; nothing leaves the machine.
+#[tauri::command]
+pub fn suggest_commit_message(repo_path: String) -> Result<CommitMessageSuggestion, AppError> {
+    let repo = crate::open_worktree_repository(&repo_path)?;
+    let head_tree = match repo.head() {
+        Ok(head) => Some(head.peel_to_tree().map_err(|e| AppError::wrap(e, "Failed to get HEAD tree"))?),
+        Err(_) => None,
+    };
+    let index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+
+    let mut diff_opts = DiffOptions::new();
+    let mut diff = repo.diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut diff_opts))
+        .map_err(|e| AppError::wrap(e, "Failed to get staged changes"))?;
+    diff.find_similar(Some(DiffFindOptions::new().renames(true)))
+        .map_err(|e| AppError::wrap(e, "Failed to detect renames"))?;
+
+    let changes = staged_changes(&diff)?;
+    if changes.is_empty() {
+        return Err(AppError::invalid_input("Nothing is staged"));
+    }
+    let roles: Vec<FileRole> = changes.iter().map(|change| file_role(&change.path)).collect();
+    let directory = dominant_directory(&changes);
+    let scope = directory.as_deref().and_then(scope_name);
+
+    Ok(CommitMessageSuggestion {
+        subject: truncate_subject(subject_for(&changes, &roles, directory.as_deref(), scope.as_deref())),
+        body: if changes.len() > 1 { body_for(&changes) } else { String::new() },
+        kind: kind_for(&changes, &roles).to_string(),
+        scope,
+    })
+}
@@ -1,5 +1,8 @@
-use crate::database::{Database, UserInfo, Organization, Repository, AppSettings};
+use crate::commands::error::AppError;
+use crate::commands::profiling::Profiler;
+use crate::database::{database_path, validate_database_file, Database, UserInfo, Organization, Repository, RepositoryTag, Workspace, AppSettings, IdentityProfile, Keybinding, PanelLayout, RepoSettings, CustomTheme};
 use tauri::{State, Manager};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use sqlx::Row;
@@ -7,30 +10,213 @@ use sqlx::Row;
 pub type DatabaseState = Arc<Mutex<Database>>;
 
 #[tauri::command]
-pub async fn init_database(app: tauri::AppHandle) -> Result<(), String> {
-    let db = Database::new().await.map_err(|e| format!("Failed to initialize database: {}", e))?;
+pub async fn init_database(app: tauri::AppHandle) -> Result<(), AppError> {
+    let db = Database::new().await.map_err(|e| AppError::wrap(e, "Failed to initialize database"))?;
     app.manage(Arc::new(Mutex::new(db)));
     Ok(())
 }
 
+#[tauri::command]
+pub async fn backup_database(
+    db_state: State<'_, DatabaseState>,
+    path: String,
+) -> Result<String, AppError> {
+    let target = PathBuf::from(&path);
+    // VACUUM INTO refuses existing files, so write next to the target and swap it in
+    let partial = PathBuf::from(format!("{}.partial", path));
+    if partial.exists() {
+        std::fs::remove_file(&partial)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to remove {}", partial.display())))?;
+    }
+
+    let db = db_state.lock().await;
+    db.backup_to(&partial)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to back up database"))?;
+
+    std::fs::rename(&partial, &target)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to write backup to {}", target.display())))?;
+
+    Ok(format!("Database backed up to {}", target.display()))
+}
+
+#[tauri::command]
+pub async fn restore_database(
+    db_state: State<'_, DatabaseState>,
+    path: String,
+) -> Result<String, AppError> {
+    let source = PathBuf::from(&path);
+    if !source.is_file() {
+        return Err(AppError::not_found(format!("Backup file not found: {}", path)));
+    }
+
+    validate_database_file(&source)
+        .await
+        .map_err(|e| AppError::wrap(e, "Invalid database backup"))?;
+
+    let mut db = db_state.lock().await;
+    let safety_backup = db.automatic_backup("pre-restore")
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to back up current database"))?;
+
+    db.close().await;
+    let replaced = replace_database_file(&source, &database_path());
+
+    // Reopen even if the copy failed so the app keeps a working connection
+    *db = Database::new()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to reopen database"))?;
+    replaced?;
+
+    Ok(format!(
+        "Database restored from {} (previous database saved to {})",
+        source.display(),
+        safety_backup.display()
+    ))
+}
+
+fn replace_database_file(source: &Path, target: &Path) -> Result<(), String> {
+    let staged = target.with_extension("sqlite.restore");
+    std::fs::copy(source, &staged)
+        .map_err(|e| format!("Failed to copy backup: {}", e))?;
+
+    // Stale WAL/SHM files from the old database must not be replayed onto the restored one
+    for suffix in ["-wal", "-shm"] {
+        let sidecar = PathBuf::from(format!("{}{}", target.display(), suffix));
+        if sidecar.exists() {
+            let _ = std::fs::remove_file(sidecar);
+        }
+    }
+
+    std::fs::rename(&staged, target)
+        .map_err(|e| format!("Failed to replace database: {}", e))
+}
+
 // User commands
 #[tauri::command]
 pub async fn save_user_info(
     db_state: State<'_, DatabaseState>,
     user: UserInfo,
-) -> Result<i64, String> {
+) -> Result<i64, AppError> {
     let db = db_state.lock().await;
     db.save_user(&user)
         .await
-        .map_err(|e| format!("Failed to save user: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to save user"))
 }
 
 #[tauri::command]
-pub async fn get_user_info(db_state: State<'_, DatabaseState>) -> Result<Option<UserInfo>, String> {
+pub async fn get_user_info(db_state: State<'_, DatabaseState>) -> Result<Option<UserInfo>, AppError> {
     let db = db_state.lock().await;
     db.get_user()
         .await
-        .map_err(|e| format!("Failed to get user: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to get user"))
+}
+
+#[tauri::command]
+pub async fn list_users(db_state: State<'_, DatabaseState>) -> Result<Vec<UserInfo>, AppError> {
+    let db = db_state.lock().await;
+    db.list_users()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to list users"))
+}
+
+#[tauri::command]
+pub async fn set_active_user(
+    db_state: State<'_, DatabaseState>,
+    id: i64,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+
+    let exists = db.list_users()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to list users"))?
+        .iter()
+        .any(|user| user.id == Some(id));
+    if !exists {
+        return Err(AppError::not_found(format!("User profile not found: {}", id)));
+    }
+
+    db.set_active_user(id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to set active user"))
+}
+
+#[tauri::command]
+pub async fn delete_user(
+    db_state: State<'_, DatabaseState>,
+    id: i64,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.delete_user(id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to delete user"))
+}
+
+// Workspace commands
+#[tauri::command]
+pub async fn create_workspace(
+    db_state: State<'_, DatabaseState>,
+    workspace: Workspace,
+) -> Result<(), AppError> {
+    if workspace.id.trim().is_empty() || workspace.name.trim().is_empty() {
+        return Err(AppError::invalid_input("Workspace id and name are required"));
+    }
+
+    let db = db_state.lock().await;
+    db.save_workspace(&workspace)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save workspace"))
+}
+
+#[tauri::command]
+pub async fn get_workspaces(db_state: State<'_, DatabaseState>) -> Result<Vec<Workspace>, AppError> {
+    let db = db_state.lock().await;
+    db.get_workspaces()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get workspaces"))
+}
+
+#[tauri::command]
+pub async fn switch_workspace(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+
+    let exists = db.get_workspaces()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get workspaces"))?
+        .iter()
+        .any(|workspace| workspace.id == id);
+    if !exists {
+        return Err(AppError::not_found(format!("Workspace not found: {}", id)));
+    }
+
+    db.set_active_workspace(&id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to switch workspace"))
+}
+
+#[tauri::command]
+pub async fn delete_workspace(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+
+    let workspaces = db.get_workspaces()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get workspaces"))?;
+    if !workspaces.iter().any(|workspace| workspace.id == id) {
+        return Err(AppError::not_found(format!("Workspace not found: {}", id)));
+    }
+    if workspaces.len() == 1 {
+        return Err(AppError::conflict("Cannot delete the only workspace", Vec::new()));
+    }
+
+    db.delete_workspace(&id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to delete workspace"))
 }
 
 // Organization commands
@@ -38,32 +224,32 @@ pub async fn get_user_info(db_state: State<'_, DatabaseState>) -> Result<Option<
 pub async fn save_organization(
     db_state: State<'_, DatabaseState>,
     organization: Organization,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db = db_state.lock().await;
     db.save_organization(&organization)
         .await
-        .map_err(|e| format!("Failed to save organization: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to save organization"))
 }
 
 #[tauri::command]
 pub async fn get_organizations(
     db_state: State<'_, DatabaseState>,
-) -> Result<Vec<Organization>, String> {
+) -> Result<Vec<Organization>, AppError> {
     let db = db_state.lock().await;
     db.get_organizations()
         .await
-        .map_err(|e| format!("Failed to get organizations: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to get organizations"))
 }
 
 #[tauri::command]
 pub async fn delete_organization(
     db_state: State<'_, DatabaseState>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db = db_state.lock().await;
     db.delete_organization(&id)
         .await
-        .map_err(|e| format!("Failed to delete organization: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to delete organization"))
 }
 
 // Repository commands
@@ -71,89 +257,454 @@ pub async fn delete_organization(
 pub async fn save_repository(
     db_state: State<'_, DatabaseState>,
     repository: Repository,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db = db_state.lock().await;
     db.save_repository(&repository)
         .await
-        .map_err(|e| format!("Failed to save repository: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to save repository"))
 }
 
 #[tauri::command]
 pub async fn get_repositories(
     db_state: State<'_, DatabaseState>,
-) -> Result<Vec<Repository>, String> {
+) -> Result<Vec<Repository>, AppError> {
     let db = db_state.lock().await;
     db.get_repositories()
         .await
-        .map_err(|e| format!("Failed to get repositories: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to get repositories"))
 }
 
 #[tauri::command]
 pub async fn delete_repository(
     db_state: State<'_, DatabaseState>,
     id: String,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db = db_state.lock().await;
     db.delete_repository(&id)
         .await
-        .map_err(|e| format!("Failed to delete repository: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to delete repository"))
+}
+
+#[tauri::command]
+pub async fn toggle_repository_favorite(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+) -> Result<bool, AppError> {
+    let db = db_state.lock().await;
+    db.toggle_repository_favorite(&id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to update favorite"))?
+        .ok_or_else(|| AppError::not_found(format!("Repository not found: {}", id)))
+}
+
+#[tauri::command]
+pub async fn set_repository_pinned(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+    pinned: bool,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    let updated = db.set_repository_pinned(&id, pinned)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to update pin"))?;
+
+    if !updated {
+        return Err(AppError::not_found(format!("Repository not found: {}", id)));
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn reorder_repositories(
+    db_state: State<'_, DatabaseState>,
+    ids: Vec<String>,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.reorder_repositories(&ids)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save repository order"))
+}
+
+// Repository tag commands
+#[tauri::command]
+pub async fn add_repo_tag(
+    db_state: State<'_, DatabaseState>,
+    repository_id: String,
+    tag: String,
+) -> Result<(), AppError> {
+    let tag = tag.trim();
+    if tag.is_empty() {
+        return Err(AppError::invalid_input("Tag cannot be empty"));
+    }
+
+    let db = db_state.lock().await;
+    db.add_repository_tag(&repository_id, tag)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to add tag"))
+}
+
+#[tauri::command]
+pub async fn remove_repo_tag(
+    db_state: State<'_, DatabaseState>,
+    repository_id: String,
+    tag: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.remove_repository_tag(&repository_id, tag.trim())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to remove tag"))
+}
+
+#[tauri::command]
+pub async fn list_repo_tags(
+    db_state: State<'_, DatabaseState>,
+    repository_id: Option<String>,
+) -> Result<Vec<RepositoryTag>, AppError> {
+    let db = db_state.lock().await;
+    db.list_repository_tags(repository_id.as_deref())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to list tags"))
+}
+
+#[tauri::command]
+pub async fn get_repositories_by_tag(
+    db_state: State<'_, DatabaseState>,
+    tag: String,
+) -> Result<Vec<Repository>, AppError> {
+    let db = db_state.lock().await;
+    db.get_repositories_by_tag(&tag)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get repositories"))
+}
+
+// Identity profile commands
+#[tauri::command]
+pub async fn save_identity_profile(
+    db_state: State<'_, DatabaseState>,
+    profile: IdentityProfile,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.save_identity_profile(&profile)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save identity profile"))
+}
+
+#[tauri::command]
+pub async fn get_identity_profiles(
+    db_state: State<'_, DatabaseState>,
+) -> Result<Vec<IdentityProfile>, AppError> {
+    let db = db_state.lock().await;
+    db.get_identity_profiles()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get identity profiles"))
+}
+
+#[tauri::command]
+pub async fn delete_identity_profile(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.delete_identity_profile(&id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to delete identity profile"))
+}
+
+#[tauri::command]
+pub async fn assign_identity_profile(
+    db_state: State<'_, DatabaseState>,
+    target_type: String,
+    target_id: String,
+    profile_id: Option<String>,
+) -> Result<(), AppError> {
+    if target_type != "organization" && target_type != "repository" {
+        return Err(AppError::invalid_input(format!("Invalid assignment target: {}", target_type)));
+    }
+
+    let db = db_state.lock().await;
+    db.assign_identity_profile(&target_type, &target_id, profile_id.as_deref())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to assign identity profile"))
+}
+
+#[tauri::command]
+pub async fn get_repository_identity(
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+) -> Result<Option<IdentityProfile>, AppError> {
+    let db = db_state.lock().await;
+    db.resolve_identity_profile(&repo_path)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to resolve identity profile"))
 }
 
 // Settings commands
 #[tauri::command]
-pub async fn get_app_settings(db_state: State<'_, DatabaseState>) -> Result<AppSettings, String> {
+pub async fn get_app_settings(db_state: State<'_, DatabaseState>) -> Result<AppSettings, AppError> {
     let db = db_state.lock().await;
     db.get_settings()
         .await
-        .map_err(|e| format!("Failed to get settings: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to get settings"))
 }
 
 #[tauri::command]
 pub async fn update_app_settings(
     db_state: State<'_, DatabaseState>,
+    profiler: State<'_, Profiler>,
     settings: AppSettings,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db = db_state.lock().await;
     db.update_settings(&settings)
         .await
-        .map_err(|e| format!("Failed to update settings: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to update settings"))?;
+    profiler.sync_with_settings(&settings.settings_json);
+    Ok(())
+}
+
+// Repository settings commands
+#[tauri::command]
+pub async fn get_repo_settings(
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+) -> Result<RepoSettings, AppError> {
+    let db = db_state.lock().await;
+    db.get_repo_settings(&repo_path)
+        .await
+        .map(|settings| settings.unwrap_or_else(|| RepoSettings::defaults(&repo_path)))
+        .map_err(|e| AppError::wrap(e, "Failed to get repository settings"))
+}
+
+#[tauri::command]
+pub async fn save_repo_settings(
+    db_state: State<'_, DatabaseState>,
+    settings: RepoSettings,
+) -> Result<(), AppError> {
+    if !["current", "upstream", "gerrit"].contains(&settings.push_behavior.as_str()) {
+        return Err(AppError::invalid_input(format!("Invalid push behavior: {}", settings.push_behavior)));
+    }
+    if !["merge", "rebase", "ff-only"].contains(&settings.pull_strategy.as_str()) {
+        return Err(AppError::invalid_input(format!("Invalid pull strategy: {}", settings.pull_strategy)));
+    }
+    if !["none", "all", "change", "eol"].contains(&settings.diff_whitespace.as_str()) {
+        return Err(AppError::invalid_input(format!("Invalid whitespace mode: {}", settings.diff_whitespace)));
+    }
+    if matches!(settings.auto_fetch_interval, Some(minutes) if minutes <= 0) {
+        return Err(AppError::invalid_input("Auto-fetch interval must be a positive number of minutes"));
+    }
+    for pattern in &settings.issue_patterns {
+        if let Err(e) = regex::Regex::new(pattern) {
+            return Err(AppError::invalid_input(format!("Invalid issue pattern '{}': {}", pattern, e)));
+        }
+    }
+    let model = &settings.branching_model;
+    if !["no-ff", "ff", "squash"].contains(&model.merge_strategy.as_str()) {
+        return Err(AppError::invalid_input(format!("Invalid merge strategy: {}", model.merge_strategy)));
+    }
+    if model.main_branch.trim().is_empty() || model.develop_branch.trim().is_empty() || model.main_branch == model.develop_branch {
+        return Err(AppError::invalid_input("The main and develop branches must be two different branches"));
+    }
+    let prefixes = [&model.feature_prefix, &model.release_prefix, &model.hotfix_prefix];
+    if prefixes.iter().any(|prefix| prefix.trim().is_empty()) || prefixes[0] == prefixes[1] || prefixes[0] == prefixes[2] || prefixes[1] == prefixes[2] {
+        return Err(AppError::invalid_input("Feature, release and hotfix prefixes must be set and distinct"));
+    }
+
+    let db = db_state.lock().await;
+    db.save_repo_settings(&settings)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save repository settings"))
 }
 
+/// Settings for git commands; defaults when none are stored or the database is unavailable.
+pub fn repo_settings_for(app: &tauri::AppHandle, repo_path: &str) -> RepoSettings {
+    let stored = app.try_state::<DatabaseState>().and_then(|db_state| {
+        tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.get_repo_settings(repo_path).await
+        })
+        .ok()
+        .flatten()
+    });
+
+    stored.unwrap_or_else(|| RepoSettings::defaults(repo_path))
+}
+
+// Theme commands
 #[tauri::command]
-pub async fn complete_onboarding_db(db_state: State<'_, DatabaseState>) -> Result<(), String> {
+pub async fn save_theme(
+    db_state: State<'_, DatabaseState>,
+    theme: CustomTheme,
+) -> Result<(), AppError> {
+    if theme.base_mode != "dark" && theme.base_mode != "light" {
+        return Err(AppError::invalid_input(format!("Invalid base mode: {}", theme.base_mode)));
+    }
+    let tokens = theme.tokens.as_object()
+        .ok_or_else(|| AppError::invalid_input("Theme tokens must be an object of color values"))?;
+    if let Some((token, _)) = tokens.iter().find(|(_, value)| !value.is_string()) {
+        return Err(AppError::invalid_input(format!("Theme token '{}' must be a color string", token)));
+    }
+
+    let db = db_state.lock().await;
+    db.save_theme(&theme)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save theme"))
+}
+
+#[tauri::command]
+pub async fn get_themes(db_state: State<'_, DatabaseState>) -> Result<Vec<CustomTheme>, AppError> {
+    let db = db_state.lock().await;
+    db.get_themes()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get themes"))
+}
+
+#[tauri::command]
+pub async fn delete_theme(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.delete_theme(&id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to delete theme"))
+}
+
+/// Activates a custom theme, or the built-in dark/light theme when `theme_id` is None.
+#[tauri::command]
+pub async fn set_active_theme(
+    db_state: State<'_, DatabaseState>,
+    theme_id: Option<String>,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+
+    if let Some(theme_id) = &theme_id {
+        let exists = db.get_themes()
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to get themes"))?
+            .iter()
+            .any(|theme| &theme.id == theme_id);
+        if !exists {
+            return Err(AppError::not_found(format!("Theme not found: {}", theme_id)));
+        }
+    }
+
+    db.set_active_theme(theme_id.as_deref())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to set active theme"))
+}
+
+// Keybinding and layout commands
+#[tauri::command]
+pub async fn get_keybindings(db_state: State<'_, DatabaseState>) -> Result<Vec<Keybinding>, AppError> {
+    let db = db_state.lock().await;
+    db.get_keybindings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get keybindings"))
+}
+
+#[tauri::command]
+pub async fn save_keybinding(
+    db_state: State<'_, DatabaseState>,
+    action: String,
+    keys: String,
+) -> Result<(), AppError> {
+    if action.trim().is_empty() || keys.trim().is_empty() {
+        return Err(AppError::invalid_input("Keybinding action and keys are required"));
+    }
+
+    let db = db_state.lock().await;
+
+    // One shortcut can only trigger one action
+    let conflict = db.get_keybindings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get keybindings"))?
+        .into_iter()
+        .find(|binding| binding.action != action && binding.keys.eq_ignore_ascii_case(&keys));
+    if let Some(conflict) = conflict {
+        return Err(AppError::conflict(format!("{} is already bound to {}", keys, conflict.action), Vec::new()));
+    }
+
+    db.save_keybinding(&action, &keys)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save keybinding"))
+}
+
+#[tauri::command]
+pub async fn reset_keybindings(
+    db_state: State<'_, DatabaseState>,
+    action: Option<String>,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.reset_keybindings(action.as_deref())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to reset keybindings"))
+}
+
+#[tauri::command]
+pub async fn get_panel_layouts(db_state: State<'_, DatabaseState>) -> Result<Vec<PanelLayout>, AppError> {
+    let db = db_state.lock().await;
+    db.get_panel_layouts()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get panel layouts"))
+}
+
+#[tauri::command]
+pub async fn save_panel_layout(
+    db_state: State<'_, DatabaseState>,
+    name: String,
+    layout: serde_json::Value,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.save_panel_layout(&name, &layout)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save panel layout"))
+}
+
+#[tauri::command]
+pub async fn reset_panel_layouts(
+    db_state: State<'_, DatabaseState>,
+    name: Option<String>,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.reset_panel_layouts(name.as_deref())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to reset panel layouts"))
+}
+
+#[tauri::command]
+pub async fn complete_onboarding_db(db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
     let db = db_state.lock().await;
     db.complete_onboarding()
         .await
-        .map_err(|e| format!("Failed to complete onboarding: {}", e))
+        .map_err(|e| AppError::wrap(e, "Failed to complete onboarding"))
 }
 
 // Debug and verification commands
 #[tauri::command]
-pub async fn get_database_info(db_state: State<'_, DatabaseState>) -> Result<serde_json::Value, String> {
+pub async fn get_database_info(db_state: State<'_, DatabaseState>) -> Result<serde_json::Value, AppError> {
     let db = db_state.lock().await;
     
     let user_count = sqlx::query("SELECT COUNT(*) as count FROM users")
         .fetch_one(db.pool())
         .await
-        .map_err(|e| format!("Failed to count users: {}", e))?
+        .map_err(|e| AppError::wrap(e, "Failed to count users"))?
         .get::<i64, _>("count");
         
     let org_count = sqlx::query("SELECT COUNT(*) as count FROM organizations")
         .fetch_one(db.pool())
         .await
-        .map_err(|e| format!("Failed to count organizations: {}", e))?
+        .map_err(|e| AppError::wrap(e, "Failed to count organizations"))?
         .get::<i64, _>("count");
         
     let repo_count = sqlx::query("SELECT COUNT(*) as count FROM repositories")
         .fetch_one(db.pool())
         .await
-        .map_err(|e| format!("Failed to count repositories: {}", e))?
+        .map_err(|e| AppError::wrap(e, "Failed to count repositories"))?
         .get::<i64, _>("count");
         
     let settings_exists = sqlx::query("SELECT COUNT(*) as count FROM app_settings")
         .fetch_one(db.pool())
         .await
-        .map_err(|e| format!("Failed to check settings: {}", e))?
+        .map_err(|e| AppError::wrap(e, "Failed to check settings"))?
         .get::<i64, _>("count") > 0;
     
     // Get actual database path
@@ -175,14 +726,14 @@ pub async fn get_database_info(db_state: State<'_, DatabaseState>) -> Result<ser
     }))
 }
 
-#[tauri::command] 
-pub async fn verify_data_migration(db_state: State<'_, DatabaseState>) -> Result<serde_json::Value, String> {
+#[tauri::command]
+pub async fn verify_data_migration(db_state: State<'_, DatabaseState>) -> Result<serde_json::Value, AppError> {
     let db = db_state.lock().await;
     
     // Check if we have actual user data (not just defaults)
-    let user_data = db.get_user().await.map_err(|e| format!("Failed to get user: {}", e))?;
-    let orgs = db.get_organizations().await.map_err(|e| format!("Failed to get orgs: {}", e))?;
-    let settings = db.get_settings().await.map_err(|e| format!("Failed to get settings: {}", e))?;
+    let user_data = db.get_user().await.map_err(|e| AppError::wrap(e, "Failed to get user"))?;
+    let orgs = db.get_organizations().await.map_err(|e| AppError::wrap(e, "Failed to get orgs"))?;
+    let settings = db.get_settings().await.map_err(|e| AppError::wrap(e, "Failed to get settings"))?;
     
     let has_user_data = user_data.is_some() && 
         user_data.as_ref().unwrap().name.len() > 0 &&
@@ -211,7 +762,7 @@ pub async fn verify_data_migration(db_state: State<'_, DatabaseState>) -> Result
 pub async fn migrate_from_localstorage(
     db_state: State<'_, DatabaseState>,
     local_storage_data: serde_json::Value,
-) -> Result<(), String> {
+) -> Result<(), AppError> {
     let db = db_state.lock().await;
 
     // Parse localStorage data and migrate to SQLite
@@ -234,6 +785,7 @@ pub async fn migrate_from_localstorage(
                     .get("workspace_name")
                     .and_then(|v| v.as_str())
                     .map(|s| s.to_string()),
+                is_active: true,
                 created_at: chrono::Utc::now(),
                 updated_at: None,
             };
@@ -241,13 +793,34 @@ pub async fn migrate_from_localstorage(
             if !user_info.name.is_empty() && !user_info.email.is_empty() {
                 db.save_user(&user_info)
                     .await
-                    .map_err(|e| format!("Failed to migrate user: {}", e))?;
+                    .map_err(|e| AppError::wrap(e, "Failed to migrate user"))?;
             }
         }
 
         // Migrate organizations
         if let Some(workspaces) = state.get("workspaces") {
-            for (_, workspace) in workspaces.as_object().unwrap_or(&serde_json::Map::new()) {
+            let mut migrated_ids = Vec::new();
+            for (workspace_id, workspace) in workspaces.as_object().unwrap_or(&serde_json::Map::new()) {
+                let workspace_record = Workspace {
+                    id: workspace_id.clone(),
+                    name: workspace
+                        .get("name")
+                        .and_then(|v| v.as_str())
+                        .unwrap_or(workspace_id)
+                        .to_string(),
+                    color: workspace
+                        .get("color")
+                        .and_then(|v| v.as_str())
+                        .map(|s| s.to_string()),
+                    is_active: false,
+                    created_at: chrono::Utc::now(),
+                    updated_at: chrono::Utc::now(),
+                };
+                db.save_workspace(&workspace_record)
+                    .await
+                    .map_err(|e| AppError::wrap(e, "Failed to migrate workspace"))?;
+                migrated_ids.push(workspace_id.clone());
+
                 if let Some(organizations) = workspace.get("organizations") {
                     if let Some(orgs_array) = organizations.as_array() {
                         for org_value in orgs_array {
@@ -275,6 +848,9 @@ pub async fn migrate_from_localstorage(
                                     .get("avatar")
                                     .and_then(|v| v.as_str())
                                     .map(|s| s.to_string()),
+                                user_id: None,
+                                workspace_id: Some(workspace_id.clone()),
+                                sign_off: false,
                                 created_at: chrono::Utc::now(),
                                 updated_at: chrono::Utc::now(),
                             };
@@ -282,12 +858,25 @@ pub async fn migrate_from_localstorage(
                             if !org.id.is_empty() && !org.name.is_empty() {
                                 db.save_organization(&org)
                                     .await
-                                    .map_err(|e| format!("Failed to migrate organization: {}", e))?;
+                                    .map_err(|e| AppError::wrap(e, "Failed to migrate organization"))?;
                             }
                         }
                     }
                 }
             }
+
+            // Only the active workspace's organizations and repositories are visible, so
+            // the seeded default one would hide everything that was just migrated
+            let active_id = state
+                .get("active_workspace")
+                .and_then(|v| v.as_str())
+                .filter(|id| migrated_ids.iter().any(|migrated| migrated == id))
+                .or_else(|| migrated_ids.first().map(|id| id.as_str()));
+            if let Some(active_id) = active_id {
+                db.set_active_workspace(active_id)
+                    .await
+                    .map_err(|e| AppError::wrap(e, "Failed to activate migrated workspace"))?;
+            }
         }
 
         // Update onboarding status
@@ -299,7 +888,7 @@ pub async fn migrate_from_localstorage(
         if !is_first_time {
             db.complete_onboarding()
                 .await
-                .map_err(|e| format!("Failed to update onboarding status: {}", e))?;
+                .map_err(|e| AppError::wrap(e, "Failed to update onboarding status"))?;
         }
     }
 
@@ -0,0 +1,433 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::{AppError, ErrorKind};
+use crate::commands::hosting::{percent_decode, split_remote_url};
+use crate::commands::session::warm_start;
+use crate::database::SessionState;
+use git2::{BranchType, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{Ipv4Addr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::api::dialog::blocking as dialog;
+use tauri::Manager;
+
+pub const URL_SCHEME: &str = "codegit";
+// Holds the port of the running instance, which later launches hand their link to
+const PORT_FILE: &str = "deep-link.port";
+const MAX_LINK_BYTES: u64 = 8 * 1024;
+
+/// What a link asks for, checked against the local machine. Nothing is cloned, opened or
+/// checked out until the user confirms it; see handle_deep_link.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+pub enum DeepLinkAction {
+    Clone { url: String, name: String },
+    Open { path: String },
+    Checkout { repo_path: String, branch: String },
+    Invalid { link: String, message: String },
+}
+
+/// Emitted as `deep-link-handled` once a link has been carried out, declined or rejected.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeepLinkOutcome {
+    pub action: DeepLinkAction,
+    pub completed: bool,
+    pub message: String,
+}
+
+/// Query parameters of a link, decoded ('+' is a space, as in forms).
+fn query_parameters(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            (percent_decode(&key.replace('+', " ")), percent_decode(&value.replace('+', " ")))
+        })
+        .collect()
+}
+
+fn required<'a>(parameters: &'a HashMap<String, String>, name: &str) -> Result<&'a str, String> {
+    parameters
+        .get(name)
+        .map(|value| value.trim())
+        .filter(|value| !value.is_empty())
+        .ok_or_else(|| format!("The link is missing '{}'", name))
+}
+
+/// Only network remotes can be cloned from a link; file://, ext:: and local paths would let
+/// a link reach into the machine.
+fn is_network_remote(url: &str) -> bool {
+    let lower = url.to_lowercase();
+    if ["https://", "http://", "ssh://", "git://"].iter().any(|scheme| lower.starts_with(scheme)) {
+        return true;
+    }
+    // git@host:owner/repo
+    match url.split_once(':') {
+        Some((user_host, path)) => {
+            user_host.contains('@') && !user_host.contains(|c: char| c == '/' || c.is_whitespace()) && !path.starts_with("//")
+        }
+        None => false,
+    }
+}
+
+/// Normalized host/path of a remote, so https and SSH URLs of one repository compare equal.
+fn remote_key(url: &str) -> Option<String> {
+    let (host, path) = split_remote_url(url)?;
+    Some(format!("{}/{}", host.to_lowercase(), path.to_lowercase()))
+}
+
+/// A repository `repo` names: a local path, or the remote URL of a registered repository.
+async fn resolve_repository(app: &tauri::AppHandle, repo: &str) -> Result<String, String> {
+    if Path::new(repo).is_dir() {
+        return Repository::open(repo)
+            .map(|_| repo.to_string())
+            .map_err(|_| format!("{} is not a Git repository", repo));
+    }
+
+    let key = remote_key(repo).ok_or_else(|| format!("Unknown repository: {}", repo))?;
+    let db_state = app
+        .try_state::<DatabaseState>()
+        .ok_or_else(|| "The database is not ready".to_string())?;
+    let repositories = db_state
+        .lock()
+        .await
+        .get_repositories()
+        .await
+        .map_err(|e| format!("Failed to get repositories: {}", e))?;
+
+    repositories
+        .into_iter()
+        .find(|repository| repository.remote_url.as_deref().and_then(remote_key).as_deref() == Some(key.as_str()))
+        .map(|repository| repository.path)
+        .ok_or_else(|| format!("No repository in CodeGit has the remote {}", repo))
+}
+
+fn has_branch(repo_path: &str, branch: &str) -> bool {
+    let repo = match Repository::open(repo_path) {
+        Ok(repo) => repo,
+        Err(_) => return false,
+    };
+    if repo.find_branch(branch, BranchType::Local).is_ok() {
+        return true;
+    }
+    repo.branches(Some(BranchType::Remote))
+        .map(|branches| {
+            branches.flatten().any(|(remote_branch, _)| {
+                remote_branch
+                    .name()
+                    .ok()
+                    .flatten()
+                    .and_then(|name| name.split_once('/'))
+                    .map(|(_, name)| name == branch)
+                    .unwrap_or(false)
+            })
+        })
+        .unwrap_or(false)
+}
+
+/// codegit://clone?url=…, codegit://open?path=… or codegit://checkout?repo=…&branch=…
+async fn resolve_link(app: &tauri::AppHandle, link: &str) -> Result<DeepLinkAction, String> {
+    let rest = link
+        .strip_prefix(&format!("{}://", URL_SCHEME))
+        .ok_or_else(|| format!("Not a {}:// link", URL_SCHEME))?;
+    let (action, query) = rest.split_once('?').unwrap_or((rest, ""));
+    let parameters = query_parameters(query);
+
+    match action.trim_end_matches('/') {
+        "clone" => {
+            let url = required(&parameters, "url")?;
+            if !is_network_remote(url) {
+                return Err(format!("Only network remotes can be cloned from a link: {}", url));
+            }
+            let name = url
+                .trim_end_matches('/')
+                .rsplit(['/', ':'])
+                .next()
+                .unwrap_or("")
+                .trim_end_matches(".git")
+                .to_string();
+            Ok(DeepLinkAction::Clone { url: url.to_string(), name })
+        }
+        "open" => {
+            let path = required(&parameters, "path")?;
+            Repository::open(path).map_err(|_| format!("{} is not a Git repository", path))?;
+            Ok(DeepLinkAction::Open { path: path.to_string() })
+        }
+        "checkout" => {
+            let repo_path = resolve_repository(app, required(&parameters, "repo")?).await?;
+            let branch = required(&parameters, "branch")?;
+            if !has_branch(&repo_path, branch) {
+                return Err(format!("Branch '{}' not found in {}", branch, repo_path));
+            }
+            Ok(DeepLinkAction::Checkout { repo_path, branch: branch.to_string() })
+        }
+        other => Err(format!("Unsupported link action: {}", other)),
+    }
+}
+
+fn confirmation(action: &DeepLinkAction) -> String {
+    match action {
+        DeepLinkAction::Clone { url, .. } => format!("A link asks CodeGit to clone {}.\n\nClone it?", url),
+        DeepLinkAction::Open { path } => format!("A link asks CodeGit to open {}.\n\nOpen it?", path),
+        DeepLinkAction::Checkout { repo_path, branch } => {
+            format!("A link asks CodeGit to check out '{}' in {}.\n\nSwitch branches?", branch, repo_path)
+        }
+        DeepLinkAction::Invalid { message, .. } => message.clone(),
+    }
+}
+
+/// Yes/no dialog over the main window. Dialogs block, so they run off the async workers.
+async fn ask_user(app: &tauri::AppHandle, question: String) -> bool {
+    let window = app.get_window("main");
+    tauri::async_runtime::spawn_blocking(move || dialog::ask(window.as_ref(), "CodeGit", question))
+        .await
+        .unwrap_or(false)
+}
+
+async fn tell_user(app: &tauri::AppHandle, message: String) {
+    let window = app.get_window("main");
+    let _ = tauri::async_runtime::spawn_blocking(move || dialog::message(window.as_ref(), "CodeGit", message)).await;
+}
+
+/// Makes `repo_path` the session repository and pre-opens it, as a restart would.
+async fn open_in_session(app: &tauri::AppHandle, repo_path: &str) -> Result<(), AppError> {
+    let db_state = app
+        .try_state::<DatabaseState>()
+        .ok_or_else(|| AppError::from("The database is not ready"))?;
+    let branch = Repository::open(repo_path)
+        .ok()
+        .and_then(|repo| repo.head().ok().and_then(|head| head.shorthand().map(|name| name.to_string())));
+    {
+        let db = db_state.lock().await;
+        let open_panels = db
+            .get_session_state()
+            .await
+            .ok()
+            .flatten()
+            .map(|session| session.open_panels)
+            .unwrap_or_default();
+        db.save_session_state(&SessionState {
+            repo_path: Some(repo_path.to_string()),
+            branch,
+            open_panels,
+            updated_at: chrono::Utc::now(),
+        })
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save session"))?;
+    }
+
+    let warm_app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || warm_start(&warm_app));
+    Ok(())
+}
+
+async fn carry_out(app: &tauri::AppHandle, action: &DeepLinkAction) -> Result<String, AppError> {
+    match action {
+        DeepLinkAction::Clone { url, name } => {
+            let window = app.get_window("main");
+            let parent = tauri::async_runtime::spawn_blocking(move || {
+                let mut picker = dialog::FileDialogBuilder::new().set_title("Clone into");
+                if let Some(window) = &window {
+                    picker = picker.set_parent(window);
+                }
+                picker.pick_folder()
+            })
+            .await
+            .ok()
+            .flatten()
+            .ok_or_else(AppError::cancelled)?;
+
+            let destination = parent.join(name);
+            if destination.exists() {
+                return Err(AppError::conflict(
+                    format!("{} already exists", destination.display()),
+                    vec![destination.to_string_lossy().to_string()],
+                ));
+            }
+            let path = destination.to_string_lossy().to_string();
+            crate::clone_repository(app.clone(), url.clone(), path.clone(), None).await?;
+            open_in_session(app, &path).await?;
+            Ok(format!("Cloned {} into {}", url, path))
+        }
+        DeepLinkAction::Open { path } => {
+            open_in_session(app, path).await?;
+            Ok(format!("Opened {}", path))
+        }
+        DeepLinkAction::Checkout { repo_path, branch } => {
+            let (task_app, repo_path, branch) = (app.clone(), repo_path.clone(), branch.clone());
+            tauri::async_runtime::spawn_blocking(move || crate::switch_branch(task_app, repo_path, branch, None, None))
+                .await
+                .map_err(|e| AppError::from(e.to_string()))?
+        }
+        DeepLinkAction::Invalid { message, .. } => Err(AppError::invalid_input(message.clone())),
+    }
+}
+
+/// Resolves a link, asks the user to confirm what it asks for, and carries it out.
+async fn handle_deep_link(app: tauri::AppHandle, link: String) {
+    let action = match resolve_link(&app, &link).await {
+        Ok(action) => action,
+        Err(message) => DeepLinkAction::Invalid { link, message },
+    };
+
+    let result = match &action {
+        DeepLinkAction::Invalid { .. } => carry_out(&app, &action).await,
+        _ if ask_user(&app, confirmation(&action)).await => carry_out(&app, &action).await,
+        _ => Err(AppError::cancelled()),
+    };
+    let outcome = match result {
+        Ok(message) => DeepLinkOutcome { action, completed: true, message },
+        Err(error) => {
+            if error.kind != ErrorKind::Cancelled {
+                tell_user(&app, error.message.clone()).await;
+            }
+            DeepLinkOutcome { action, completed: false, message: error.message }
+        }
+    };
+    let _ = app.emit_all("deep-link-handled", outcome);
+}
+
+/// Brings the window forward and handles the link once the user has confirmed it.
+pub fn receive_deep_link(app: &tauri::AppHandle, link: &str) {
+    println!("🔗 Received link: {}", link);
+    if let Some(window) = app.get_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+    }
+    tauri::async_runtime::spawn(handle_deep_link(app.clone(), link.to_string()));
+}
+
+fn port_file() -> PathBuf {
+    crate::database::codegit_data_dir().join(PORT_FILE)
+}
+
+/// Hands `link` to an already running instance; false when there is none.
+pub fn forward_deep_link(link: &str) -> bool {
+    let port: u16 = match std::fs::read_to_string(port_file()).ok().and_then(|port| port.trim().parse().ok()) {
+        Some(port) => port,
+        None => return false,
+    };
+    let address = (Ipv4Addr::LOCALHOST, port).into();
+    let mut stream = match TcpStream::connect_timeout(&address, Duration::from_secs(1)) {
+        Ok(stream) => stream,
+        Err(_) => return false,
+    };
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+    if writeln!(stream, "{}", link).is_err() {
+        return false;
+    }
+
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply).is_ok() && reply.trim() == "ok"
+}
+
+/// Accepts links from later launches on a loopback port recorded in the data directory.
+pub fn start_deep_link_listener(app: tauri::AppHandle) {
+    let listener = match TcpListener::bind((Ipv4Addr::LOCALHOST, 0)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            println!("⚠️  Failed to listen for links: {}", e);
+            return;
+        }
+    };
+    let port = match listener.local_addr() {
+        Ok(address) => address.port(),
+        Err(_) => return,
+    };
+    let _ = std::fs::create_dir_all(crate::database::codegit_data_dir());
+    if let Err(e) = std::fs::write(port_file(), port.to_string()) {
+        println!("⚠️  Failed to record the link port: {}", e);
+        return;
+    }
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = stream.set_read_timeout(Some(Duration::from_secs(2)));
+            let mut line = String::new();
+            let mut reader = BufReader::new((&stream).take(MAX_LINK_BYTES));
+            if reader.read_line(&mut line).is_err() {
+                continue;
+            }
+            let link = line.trim();
+            if link.starts_with(&format!("{}://", URL_SCHEME)) {
+                receive_deep_link(&app, link);
+                let _ = (&stream).write_all(b"ok\n");
+            }
+        }
+    });
+}
+
+/// Makes this executable the handler of codegit:// links for the current user on Windows
+/// and Linux, where the link arrives as a launch argument. Nothing is written when the
+/// scheme already points at this executable. macOS delivers links through Apple Events,
+/// which Tauri 1 does not surface, so it is not registered there.
+pub fn register_url_scheme() {
+    if cfg!(debug_assertions) {
+        return; // Development builds would take over links from the installed app
+    }
+    let executable = match std::env::current_exe() {
+        Ok(executable) => executable.to_string_lossy().to_string(),
+        Err(_) => return,
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        let key = format!(r"HKCU\Software\Classes\{}", URL_SCHEME);
+        let command = format!("\"{}\" \"%1\"", executable);
+        let registered = std::process::Command::new("reg")
+            .args(["query", &format!(r"{}\shell\open\command", key), "/ve"])
+            .creation_flags(0x08000000)
+            .output()
+            .map(|output| output.status.success() && String::from_utf8_lossy(&output.stdout).contains(&command))
+            .unwrap_or(false);
+        if registered {
+            return;
+        }
+        let entries: [&[&str]; 3] = [
+            &["add", &key, "/ve", "/d", "URL:CodeGit", "/f"],
+            &["add", &key, "/v", "URL Protocol", "/d", "", "/f"],
+            &["add", &format!(r"{}\shell\open\command", key), "/ve", "/d", &command, "/f"],
+        ];
+        for arguments in entries.iter() {
+            let result = std::process::Command::new("reg")
+                .args(arguments.iter())
+                // CREATE_NO_WINDOW: don't flash a console window from the GUI
+                .creation_flags(0x08000000)
+                .status();
+            if !matches!(result, Ok(status) if status.success()) {
+                println!("⚠️  Failed to register the {}:// scheme", URL_SCHEME);
+                return;
+            }
+        }
+    }
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        let applications = match crate::user_home_dir() {
+            Some(home) => home.join(".local/share/applications"),
+            None => return,
+        };
+        let desktop_file = format!("{}-url-handler.desktop", URL_SCHEME);
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName=CodeGit\nExec=\"{}\" %u\nTerminal=false\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            executable, URL_SCHEME
+        );
+        if std::fs::read_to_string(applications.join(&desktop_file)).ok().as_deref() == Some(entry.as_str()) {
+            return;
+        }
+        if std::fs::create_dir_all(&applications).is_err() || std::fs::write(applications.join(&desktop_file), entry).is_err() {
+            println!("⚠️  Failed to register the {}:// scheme", URL_SCHEME);
+            return;
+        }
+        let _ = std::process::Command::new("xdg-mime")
+            .args(["default", &desktop_file, &format!("x-scheme-handler/{}", URL_SCHEME)])
+            .status();
+    }
+
+    #[cfg(target_os = "macos")]
+    let _ = executable;
+}
@@ -0,0 +1,146 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::database::DiscoveryExclusion;
+use std::path::{Path, PathBuf};
+use tauri::{Manager, State};
+
+const EXCLUSION_KINDS: [&str; 3] = ["glob", "path", "tombstone"];
+
+/// Exclusion rules applied while walking directories during discovery.
+#[derive(Debug, Default)]
+pub struct DiscoveryFilter {
+    name_globs: Vec<glob::Pattern>, // Patterns without '/' match a directory name
+    path_globs: Vec<glob::Pattern>, // Patterns with '/' match the full path
+    paths: Vec<PathBuf>,            // Excluded together with everything below them
+    tombstones: Vec<PathBuf>,       // Repositories that must never be indexed again
+}
+
+impl DiscoveryFilter {
+    pub fn new(exclusions: &[DiscoveryExclusion]) -> Self {
+        let mut filter = DiscoveryFilter::default();
+
+        for exclusion in exclusions {
+            match exclusion.kind.as_str() {
+                "glob" => match glob::Pattern::new(&exclusion.pattern) {
+                    Ok(pattern) if exclusion.pattern.contains('/') => filter.path_globs.push(pattern),
+                    Ok(pattern) => filter.name_globs.push(pattern),
+                    Err(e) => println!("⚠️  Ignoring invalid discovery exclusion '{}': {}", exclusion.pattern, e),
+                },
+                "path" => filter.paths.push(PathBuf::from(&exclusion.pattern)),
+                "tombstone" => filter.tombstones.push(PathBuf::from(&exclusion.pattern)),
+                _ => {}
+            }
+        }
+
+        filter
+    }
+
+    /// Rules from the database; without one, discovery falls back to the built-in defaults.
+    pub fn load(app: &tauri::AppHandle) -> Self {
+        let db_state = match app.try_state::<DatabaseState>() {
+            Some(db_state) => db_state,
+            None => return Self::defaults(),
+        };
+
+        let exclusions = tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.get_discovery_exclusions().await
+        });
+
+        match exclusions {
+            Ok(exclusions) => Self::new(&exclusions),
+            Err(e) => {
+                println!("⚠️  Failed to load discovery exclusions: {}", e);
+                Self::defaults()
+            }
+        }
+    }
+
+    fn defaults() -> Self {
+        let exclusions: Vec<DiscoveryExclusion> = ["node_modules", "target", "dist", "build"]
+            .iter()
+            .map(|pattern| DiscoveryExclusion {
+                id: None,
+                kind: "glob".to_string(),
+                pattern: pattern.to_string(),
+                created_at: chrono::Utc::now(),
+            })
+            .collect();
+        Self::new(&exclusions)
+    }
+
+    /// Whether discovery should neither descend into nor index `path`.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        let name = path.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+
+        self.name_globs.iter().any(|pattern| pattern.matches(&name))
+            || self.path_globs.iter().any(|pattern| pattern.matches_path(path))
+            || self.paths.iter().any(|excluded| path.starts_with(excluded))
+            || self.tombstones.iter().any(|tombstone| path == tombstone)
+    }
+}
+
+#[tauri::command]
+pub async fn get_discovery_exclusions(
+    db_state: State<'_, DatabaseState>,
+) -> Result<Vec<DiscoveryExclusion>, AppError> {
+    let db = db_state.lock().await;
+    db.get_discovery_exclusions()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get discovery exclusions"))
+}
+
+#[tauri::command]
+pub async fn add_discovery_exclusion(
+    db_state: State<'_, DatabaseState>,
+    kind: String,
+    pattern: String,
+) -> Result<i64, AppError> {
+    if !EXCLUSION_KINDS.contains(&kind.as_str()) {
+        return Err(AppError::invalid_input(format!("Invalid exclusion kind: {}", kind)));
+    }
+
+    let pattern = pattern.trim();
+    if pattern.is_empty() {
+        return Err(AppError::invalid_input("Exclusion pattern cannot be empty"));
+    }
+    if kind == "glob" {
+        glob::Pattern::new(pattern)
+            .map_err(|e| AppError::invalid_input(format!("Invalid glob pattern '{}': {}", pattern, e)))?;
+    }
+
+    let db = db_state.lock().await;
+    db.add_discovery_exclusion(&kind, pattern)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to add discovery exclusion"))
+}
+
+#[tauri::command]
+pub async fn remove_discovery_exclusion(
+    db_state: State<'_, DatabaseState>,
+    id: i64,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.delete_discovery_exclusion(id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to remove discovery exclusion"))
+}
+
+/// "Never index this repository again": tombstones the path and drops its saved record.
+#[tauri::command]
+pub async fn forget_repository(
+    db_state: State<'_, DatabaseState>,
+    path: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+
+    db.add_discovery_exclusion("tombstone", &path)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to add discovery exclusion"))?;
+    db.delete_repository_by_path(&path)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to delete repository"))?;
+    db.clear_commit_cache(&path)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to clear commit cache"))
+}
@@ -0,0 +1,221 @@
+use std::io::{BufRead, Read};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+    Utf8,
+    Utf8Bom,
+    Utf16Le,
+    Utf16Be,
+    Latin1,
+    Binary,
+}
+
+impl TextEncoding {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "utf-8",
+            TextEncoding::Utf8Bom => "utf-8-bom",
+            TextEncoding::Utf16Le => "utf-16le",
+            TextEncoding::Utf16Be => "utf-16be",
+            TextEncoding::Latin1 => "latin-1",
+            TextEncoding::Binary => "binary",
+        }
+    }
+
+    pub fn bom_len(&self) -> usize {
+        match self {
+            TextEncoding::Utf8Bom => 3,
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => 2,
+            _ => 0,
+        }
+    }
+}
+
+/// Guesses the encoding from the first bytes of a file: a BOM when there is one,
+/// otherwise the NUL pattern of BOM-less UTF-16, then UTF-8 validity.
+pub fn detect_encoding(sample: &[u8]) -> TextEncoding {
+    if sample.starts_with(&[0xEF, 0xBB, 0xBF]) {
+        return TextEncoding::Utf8Bom;
+    }
+    if sample.starts_with(&[0xFF, 0xFE]) {
+        return TextEncoding::Utf16Le;
+    }
+    if sample.starts_with(&[0xFE, 0xFF]) {
+        return TextEncoding::Utf16Be;
+    }
+
+    // ASCII text in UTF-16 has a NUL in every other byte
+    let units = sample.len() / 2;
+    if units >= 2 {
+        let even_zeros = sample.iter().step_by(2).filter(|&&b| b == 0).count();
+        let odd_zeros = sample.iter().skip(1).step_by(2).filter(|&&b| b == 0).count();
+        if odd_zeros * 10 >= units * 4 && even_zeros * 20 < units {
+            return TextEncoding::Utf16Le;
+        }
+        if even_zeros * 10 >= units * 4 && odd_zeros * 20 < units {
+            return TextEncoding::Utf16Be;
+        }
+    }
+
+    if sample.contains(&0) {
+        return TextEncoding::Binary;
+    }
+    match std::str::from_utf8(sample) {
+        Ok(_) => TextEncoding::Utf8,
+        // The sample may end in the middle of a multi-byte character
+        Err(e) if e.error_len().is_none() => TextEncoding::Utf8,
+        Err(_) => TextEncoding::Latin1,
+    }
+}
+
+pub fn decode(bytes: &[u8], encoding: TextEncoding) -> String {
+    match encoding {
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let units: Vec<u16> = bytes
+                .chunks(2)
+                .map(|pair| {
+                    let (first, second) = (pair[0], pair.get(1).copied().unwrap_or(0));
+                    if encoding == TextEncoding::Utf16Le {
+                        u16::from_le_bytes([first, second])
+                    } else {
+                        u16::from_be_bytes([first, second])
+                    }
+                })
+                .collect();
+            String::from_utf16_lossy(&units)
+        }
+        TextEncoding::Latin1 => bytes.iter().map(|&b| b as char).collect(),
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+/// Length of the longest prefix of `bytes` that doesn't end inside a character, for
+/// cutting a file into chunks that decode on their own.
+pub fn complete_prefix_len(bytes: &[u8], encoding: TextEncoding) -> usize {
+    match encoding {
+        TextEncoding::Utf16Le | TextEncoding::Utf16Be => {
+            let end = bytes.len() & !1;
+            if end < 2 {
+                return end;
+            }
+            let last = [bytes[end - 2], bytes[end - 1]];
+            let unit = if encoding == TextEncoding::Utf16Le { u16::from_le_bytes(last) } else { u16::from_be_bytes(last) };
+            // A high surrogate needs the unit after it
+            if (0xD800..0xDC00).contains(&unit) { end - 2 } else { end }
+        }
+        TextEncoding::Utf8 | TextEncoding::Utf8Bom => match std::str::from_utf8(bytes) {
+            // error_len() is None only for an incomplete sequence at the very end
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            _ => bytes.len(),
+        },
+        TextEncoding::Latin1 | TextEncoding::Binary => bytes.len(),
+    }
+}
+
+// Lines being skipped are read in pieces of this size so a huge line can't fill memory
+const SKIP_CHUNK: usize = 64 * 1024;
+
+/// Reads one line including its terminator, at most `limit` bytes of it. Returns false
+/// when the line continues past `limit`; an empty `line` means end of file. UTF-16 needs
+/// care because 0x0A can also be half of an unrelated code unit.
+fn read_line_bytes<R: BufRead>(
+    reader: &mut R,
+    encoding: TextEncoding,
+    line: &mut Vec<u8>,
+    limit: usize,
+) -> std::io::Result<bool> {
+    line.clear();
+
+    loop {
+        let remaining = limit.saturating_sub(line.len());
+        if remaining == 0 {
+            return Ok(false);
+        }
+        if reader.by_ref().take(remaining as u64).read_until(b'\n', line)? == 0 {
+            return Ok(true);
+        }
+        let end = line.len() - 1;
+        if line[end] != b'\n' {
+            // Either the end of the file without a trailing newline, or the limit
+            return Ok(line.len() < limit);
+        }
+
+        match encoding {
+            TextEncoding::Utf16Le if end % 2 == 0 => {
+                // 0x0A is the low byte of a unit; it's a newline if the high byte is 0
+                let mut high = [0u8; 1];
+                if reader.read(&mut high)? == 0 {
+                    return Ok(true);
+                }
+                line.push(high[0]);
+                if high[0] == 0 {
+                    return Ok(true);
+                }
+            }
+            TextEncoding::Utf16Be if end % 2 == 1 && line[end - 1] == 0 => return Ok(true),
+            TextEncoding::Utf16Le | TextEncoding::Utf16Be => {}
+            _ => return Ok(true),
+        }
+    }
+}
+
+pub struct TextPage {
+    pub content: String,
+    pub line_count: usize,
+    pub has_more: bool,
+}
+
+/// Streams `max_lines` lines starting at `start_line` (0-based) without reading the
+/// rest of the file, stopping early once `max_bytes` of content have been collected.
+pub fn read_text_lines<R: BufRead>(
+    mut reader: R,
+    encoding: TextEncoding,
+    start_line: usize,
+    max_lines: Option<usize>,
+    max_bytes: usize,
+) -> Result<TextPage, String> {
+    let read_error = |e: std::io::Error| format!("Failed to read file: {}", e);
+
+    let mut bom = vec![0u8; encoding.bom_len()];
+    reader.read_exact(&mut bom).map_err(read_error)?;
+
+    let mut line = Vec::new();
+    let mut skipped = 0;
+    while skipped < start_line {
+        let complete = read_line_bytes(&mut reader, encoding, &mut line, SKIP_CHUNK).map_err(read_error)?;
+        if line.is_empty() {
+            break;
+        }
+        if complete {
+            skipped += 1;
+        }
+    }
+
+    let mut bytes = Vec::new();
+    let mut line_count = 0;
+    while max_lines.map(|max_lines| line_count < max_lines).unwrap_or(true) {
+        let remaining = max_bytes.saturating_sub(bytes.len());
+        if remaining == 0 {
+            break;
+        }
+
+        let complete = read_line_bytes(&mut reader, encoding, &mut line, remaining).map_err(read_error)?;
+        if line.is_empty() {
+            return Ok(TextPage { content: decode(&bytes, encoding), line_count, has_more: false });
+        }
+        if !complete {
+            // A line that doesn't fit is left for the next page, unless it alone exceeds the budget
+            if line_count == 0 {
+                bytes.extend_from_slice(&line);
+                line_count = 1;
+            }
+            return Ok(TextPage { content: decode(&bytes, encoding), line_count, has_more: true });
+        }
+
+        bytes.extend_from_slice(&line);
+        line_count += 1;
+    }
+
+    let has_more = !reader.fill_buf().map_err(read_error)?.is_empty();
+    Ok(TextPage { content: decode(&bytes, encoding), line_count, has_more })
+}
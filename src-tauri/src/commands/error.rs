@@ -0,0 +1,184 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorKind {
+    AuthRequired,
+    Conflict,
+    NotFound,
+    InvalidInput,
+    Cancelled,
+    Locked,
+    Network,
+    Io,
+    Database,
+    Git,
+    Internal,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ErrorDetails {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub paths: Vec<String>, // Conflicting or offending paths
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>, // Remote host that rejected or asked for credentials
+}
+
+/// Error returned by every command. `kind` is what the frontend branches on (e.g. an
+/// auth dialog for auth_required); `code` is finer grained and stable, `message` is
+/// for display.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AppError {
+    pub kind: ErrorKind,
+    pub code: String,
+    pub message: String,
+    #[serde(default)]
+    pub details: ErrorDetails,
+}
+
+impl AppError {
+    pub fn new(kind: ErrorKind, code: &str, message: impl Into<String>) -> Self {
+        AppError {
+            kind,
+            code: code.to_string(),
+            message: message.into(),
+            details: ErrorDetails::default(),
+        }
+    }
+
+    pub fn cancelled() -> Self {
+        Self::new(ErrorKind::Cancelled, "operation.cancelled", "Operation cancelled")
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::InvalidInput, "input.invalid", message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorKind::NotFound, "not_found", message)
+    }
+
+    pub fn conflict(message: impl Into<String>, paths: Vec<String>) -> Self {
+        Self::new(ErrorKind::Conflict, "conflict", message).with_paths(paths)
+    }
+
+    /// Classifies `error` and prefixes its message with `context`, keeping the
+    /// "Failed to ...: cause" wording the messages always had.
+    pub fn wrap(error: impl Into<AppError>, context: &str) -> Self {
+        let mut error = error.into();
+        error.message = format!("{}: {}", context, error.message);
+        error
+    }
+
+    pub fn with_paths(mut self, paths: Vec<String>) -> Self {
+        self.details.paths = paths;
+        self
+    }
+
+    pub fn with_host(mut self, host: Option<String>) -> Self {
+        self.details.host = host;
+        self
+    }
+}
+
+impl fmt::Display for AppError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+/// Errors from helpers that still return plain messages.
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::new(ErrorKind::Internal, "error", message)
+    }
+}
+
+impl From<&str> for AppError {
+    fn from(message: &str) -> Self {
+        AppError::from(message.to_string())
+    }
+}
+
+/// Lets helpers that return `Result<_, String>` keep using `?` on typed errors.
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.message
+    }
+}
+
+impl From<git2::Error> for AppError {
+    fn from(error: git2::Error) -> Self {
+        use git2::{ErrorClass, ErrorCode};
+
+        let kind = match (error.code(), error.class()) {
+            (ErrorCode::Auth, _) | (ErrorCode::Certificate, _) => ErrorKind::AuthRequired,
+            (ErrorCode::Conflict, _) | (ErrorCode::MergeConflict, _) | (ErrorCode::Unmerged, _) => ErrorKind::Conflict,
+            (ErrorCode::NotFound, _) | (ErrorCode::UnbornBranch, _) => ErrorKind::NotFound,
+            (ErrorCode::Locked, _) => ErrorKind::Locked,
+            (ErrorCode::User, _) => ErrorKind::Cancelled,
+            (ErrorCode::InvalidSpec, _) | (ErrorCode::Invalid, _) | (ErrorCode::Exists, _) => ErrorKind::InvalidInput,
+            (_, ErrorClass::Net) | (_, ErrorClass::Http) | (_, ErrorClass::Ssh) | (_, ErrorClass::Ssl) => ErrorKind::Network,
+            (_, ErrorClass::Os) => ErrorKind::Io,
+            _ => ErrorKind::Git,
+        };
+
+        AppError::new(
+            kind,
+            &format!("git.{:?}", error.code()).to_lowercase(),
+            error.message().to_string(),
+        )
+    }
+}
+
+impl From<sqlx::Error> for AppError {
+    fn from(error: sqlx::Error) -> Self {
+        let kind = match error {
+            sqlx::Error::RowNotFound => ErrorKind::NotFound,
+            _ => ErrorKind::Database,
+        };
+        AppError::new(kind, "database", error.to_string())
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        let kind = match error.kind() {
+            std::io::ErrorKind::NotFound => ErrorKind::NotFound,
+            _ => ErrorKind::Io,
+        };
+        AppError::new(kind, &format!("io.{:?}", error.kind()).to_lowercase(), error.to_string())
+    }
+}
+
+impl From<notify::Error> for AppError {
+    fn from(error: notify::Error) -> Self {
+        AppError::new(ErrorKind::Io, "watcher", error.to_string())
+    }
+}
+
+/// A blocking task that panicked or was dropped.
+impl From<tauri::Error> for AppError {
+    fn from(error: tauri::Error) -> Self {
+        AppError::new(ErrorKind::Internal, "task", error.to_string())
+    }
+}
+
+impl From<serde_json::Error> for AppError {
+    fn from(error: serde_json::Error) -> Self {
+        AppError::new(ErrorKind::InvalidInput, "json", error.to_string())
+    }
+}
+
+/// Host of a remote URL (https://host/..., ssh://user@host:22/..., git@host:path).
+pub fn remote_host(url: &str) -> Option<String> {
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let authority = without_scheme.split(['/', ':']).next()?;
+    let host = authority.rsplit('@').next()?;
+
+    if host.is_empty() || (!url.contains("://") && !url.contains('@')) {
+        return None; // A local path
+    }
+    Some(host.to_string())
+}
@@ -0,0 +1,217 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::process::Command;
+use tauri::{Manager, State};
+
+/// Commands that hand a repository off to a terminal or editor, stored as
+/// "external_tools" in the settings JSON. Templates are split into arguments like a shell
+/// would (quotes group words) and then expanded, so paths with spaces need no quoting:
+/// {path} is the repository, {file} the file to open (the repository when none) and
+/// {line} its line (1 when none). Unset templates fall back to the per-OS defaults.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExternalTools {
+    pub terminal: Option<String>,
+    pub editor: Option<String>,
+}
+
+impl ExternalTools {
+    fn from_settings(settings_json: &serde_json::Value) -> Self {
+        settings_json
+            .get("external_tools")
+            .and_then(|tools| serde_json::from_value(tools.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+// Tried in order until one starts
+#[cfg(target_os = "macos")]
+const DEFAULT_TERMINALS: &[&str] = &["open -a Terminal {path}"];
+// cmd.exe re-parses its command line, so the path (which may contain & or ^) is never put
+// on it: the new console starts in the working directory `launch` sets
+#[cfg(target_os = "windows")]
+const DEFAULT_TERMINALS: &[&str] = &["wt.exe -d {path}", "cmd.exe /C start cmd.exe"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_TERMINALS: &[&str] = &[
+    "x-terminal-emulator",
+    "gnome-terminal --working-directory={path}",
+    "konsole --workdir {path}",
+    "xfce4-terminal --working-directory={path}",
+    "xterm",
+];
+
+#[cfg(target_os = "macos")]
+const DEFAULT_EDITORS: &[&str] = &["code --goto {file}:{line}", "open -a \"Visual Studio Code\" {file}"];
+#[cfg(target_os = "windows")]
+const DEFAULT_EDITORS: &[&str] = &["code.cmd --goto {file}:{line}"];
+#[cfg(not(any(target_os = "macos", target_os = "windows")))]
+const DEFAULT_EDITORS: &[&str] = &["code --goto {file}:{line}", "xdg-open {file}"];
+
+/// Arguments of a command template, with double or single quotes grouping words.
+fn split_template(template: &str) -> Vec<String> {
+    let mut arguments = Vec::new();
+    let mut current = String::new();
+    let mut quote: Option<char> = None;
+    let mut in_argument = false;
+
+    for c in template.chars() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), c) => current.push(c),
+            (None, '"') | (None, '\'') => {
+                quote = Some(c);
+                in_argument = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut current));
+                    in_argument = false;
+                }
+            }
+            (None, c) => {
+                current.push(c);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(current);
+    }
+    arguments
+}
+
+/// Starts the command without waiting for it, from the repository directory.
+fn launch(template: &str, repo_path: &str, file: &str, line: u32) -> Result<(), AppError> {
+    let arguments: Vec<String> = split_template(template)
+        .into_iter()
+        .map(|argument| {
+            argument
+                .replace("{path}", repo_path)
+                .replace("{file}", file)
+                .replace("{line}", &line.to_string())
+        })
+        .collect();
+    let (program, arguments) = arguments
+        .split_first()
+        .ok_or_else(|| AppError::invalid_input("The command template is empty"))?;
+
+    Command::new(program)
+        .args(arguments)
+        .current_dir(repo_path)
+        .spawn()
+        .map_err(|e| AppError::wrap(e, &format!("Failed to start '{}'", program)))?;
+    println!("🚀 Opened {} with {}", file, program);
+    Ok(())
+}
+
+/// The configured template, else the first default that starts.
+fn launch_tool(configured: Option<String>, defaults: &[&str], repo_path: &str, file: &str, line: u32) -> Result<(), AppError> {
+    if let Some(template) = configured.filter(|template| !template.trim().is_empty()) {
+        return launch(&template, repo_path, file, line);
+    }
+
+    let mut last_error = AppError::not_found("No default command is available on this platform");
+    for template in defaults {
+        match launch(template, repo_path, file, line) {
+            Ok(()) => return Ok(()),
+            Err(e) => last_error = e,
+        }
+    }
+    Err(last_error)
+}
+
+fn configured_tools(app: &tauri::AppHandle) -> ExternalTools {
+    let settings = app.try_state::<DatabaseState>().and_then(|db_state| {
+        tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.get_settings().await
+        })
+        .ok()
+    });
+
+    settings
+        .map(|settings| ExternalTools::from_settings(&settings.settings_json))
+        .unwrap_or_default()
+}
+
+fn existing_directory(repo_path: &str) -> Result<(), AppError> {
+    if Path::new(repo_path).is_dir() {
+        Ok(())
+    } else {
+        Err(AppError::not_found(format!("Repository folder not found: {}", repo_path)))
+    }
+}
+
+#[tauri::command]
+pub async fn open_in_terminal(app: tauri::AppHandle, repo_path: String) -> Result<(), AppError> {
+    existing_directory(&repo_path)?;
+    tauri::async_runtime::spawn_blocking(move || {
+        let tools = configured_tools(&app);
+        launch_tool(tools.terminal, DEFAULT_TERMINALS, &repo_path, &repo_path, 1)
+    })
+    .await?
+}
+
+/// Opens the repository, or `file` (relative to it) at `line`, in the editor.
+#[tauri::command]
+pub async fn open_in_editor(
+    app: tauri::AppHandle,
+    repo_path: String,
+    file: Option<String>,
+    line: Option<u32>,
+) -> Result<(), AppError> {
+    existing_directory(&repo_path)?;
+    let target = match &file {
+        Some(file) => {
+            let target = Path::new(&repo_path).join(file);
+            if !target.exists() {
+                return Err(AppError::not_found(format!("File not found: {}", file)));
+            }
+            target.to_string_lossy().to_string()
+        }
+        None => repo_path.clone(),
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let tools = configured_tools(&app);
+        launch_tool(tools.editor, DEFAULT_EDITORS, &repo_path, &target, line.unwrap_or(1).max(1))
+    })
+    .await?
+}
+
+#[tauri::command]
+pub async fn get_external_tools(db_state: State<'_, DatabaseState>) -> Result<ExternalTools, AppError> {
+    let db = db_state.lock().await;
+    let settings = db.get_settings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get settings"))?;
+    Ok(ExternalTools::from_settings(&settings.settings_json))
+}
+
+/// Stores the terminal and editor templates; None or an empty template restores the default.
+#[tauri::command]
+pub async fn save_external_tools(
+    db_state: State<'_, DatabaseState>,
+    tools: ExternalTools,
+) -> Result<(), AppError> {
+    let normalize = |template: Option<String>| template.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    let tools = ExternalTools {
+        terminal: normalize(tools.terminal),
+        editor: normalize(tools.editor),
+    };
+
+    let db = db_state.lock().await;
+    let mut settings = db.get_settings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get settings"))?;
+    if let Some(settings_json) = settings.settings_json.as_object_mut() {
+        let value = serde_json::to_value(&tools)
+            .map_err(|e| AppError::wrap(e, "Failed to serialize external tools"))?;
+        settings_json.insert("external_tools".to_string(), value);
+    }
+    db.update_settings(&settings)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to update settings"))
+}
@@ -0,0 +1,303 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_request, hosted_repository, parse_remote_url, HostedRepository, Provider, AZURE_API_VERSION};
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+const DEFAULT_UPSTREAM_REMOTE: &str = "upstream";
+
+/// The repository a fork was created from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkParent {
+    pub owner: String,
+    pub name: String,
+    pub clone_url: String, // Same scheme (SSH or HTTPS) as the fork's remote
+    pub web_url: String,
+    pub default_branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForkInfo {
+    pub is_fork: bool,
+    pub parent: Option<ForkParent>,
+    pub upstream_remote: Option<String>, // Local remote already pointing at the parent
+}
+
+fn is_ssh_url(url: &str) -> bool {
+    url.starts_with("ssh://") || (!url.contains("://") && url.contains('@'))
+}
+
+fn parent_clone_url(hosted: &HostedRepository, owner: &str, name: &str, ssh: bool) -> String {
+    if ssh {
+        format!("git@{}:{}/{}.git", hosted.host, owner, name)
+    } else {
+        format!("https://{}/{}/{}.git", hosted.host, owner, name)
+    }
+}
+
+fn fetch_fork_parent(hosted: &HostedRepository, ssh: bool) -> Result<Option<ForkParent>, AppError> {
+    let auth = hosted.authorization()?;
+
+    let parent = match hosted.provider {
+        Provider::Github => {
+            let url = format!("{}/repos/{}/{}", hosted.api_base(), hosted.owner, hosted.name);
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to get repository").with_host(Some(hosted.host.clone())))?;
+            if response["fork"].as_bool() != Some(true) {
+                return Ok(None);
+            }
+            let parent = &response["parent"];
+            let owner = parent.pointer("/owner/login").and_then(|v| v.as_str()).unwrap_or("");
+            let name = parent["name"].as_str().unwrap_or("");
+            ForkParent {
+                owner: owner.to_string(),
+                name: name.to_string(),
+                clone_url: parent_clone_url(hosted, owner, name, ssh),
+                web_url: parent["html_url"].as_str().unwrap_or("").to_string(),
+                default_branch: parent["default_branch"].as_str().map(|branch| branch.to_string()),
+            }
+        }
+        Provider::Bitbucket => {
+            let url = format!("{}/repositories/{}/{}", hosted.api_base(), hosted.owner, hosted.name);
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to get repository").with_host(Some(hosted.host.clone())))?;
+            let parent = &response["parent"];
+            let (owner, name) = match parent["full_name"].as_str().and_then(|full_name| full_name.split_once('/')) {
+                Some(full_name) => full_name,
+                None => return Ok(None),
+            };
+            ForkParent {
+                owner: owner.to_string(),
+                name: name.to_string(),
+                clone_url: parent_clone_url(hosted, owner, name, ssh),
+                web_url: parent.pointer("/links/html/href").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                // The parent summary doesn't include its main branch
+                default_branch: None,
+            }
+        }
+        Provider::Gitlab => {
+            let url = format!("{}/projects/{}", hosted.api_base(), hosted.gitlab_project());
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to get repository").with_host(Some(hosted.host.clone())))?;
+            let parent = &response["forked_from_project"];
+            let (owner, name) = match parent["path_with_namespace"].as_str().and_then(|path| path.rsplit_once('/')) {
+                Some(path) => path,
+                None => return Ok(None),
+            };
+            ForkParent {
+                owner: owner.to_string(),
+                name: name.to_string(),
+                clone_url: parent_clone_url(hosted, owner, name, ssh),
+                web_url: parent["web_url"].as_str().unwrap_or("").to_string(),
+                default_branch: parent["default_branch"].as_str().map(|branch| branch.to_string()),
+            }
+        }
+        Provider::AzureDevops => {
+            let url = format!(
+                "{}/{}/_apis/git/repositories/{}?api-version={}",
+                hosted.api_base(), hosted.owner, hosted.name, AZURE_API_VERSION
+            );
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to get repository").with_host(Some(hosted.host.clone())))?;
+            if response["isFork"].as_bool() != Some(true) {
+                return Ok(None);
+            }
+            let parent = &response["parentRepository"];
+            let web_url = parent["remoteUrl"].as_str().unwrap_or("").to_string();
+            let clone_url = if ssh { parent["sshUrl"].as_str().map(|url| url.to_string()) } else { None };
+            // Forks can cross projects, so the parent's project comes from the response
+            let owner = parse_remote_url(&web_url)
+                .map(|parent_repository| parent_repository.owner)
+                .unwrap_or_default();
+            ForkParent {
+                owner,
+                name: parent["name"].as_str().unwrap_or("").to_string(),
+                clone_url: clone_url.unwrap_or_else(|| web_url.clone()),
+                web_url,
+                default_branch: None,
+            }
+        }
+    };
+
+    Ok(Some(parent))
+}
+
+/// A local remote whose URL names the parent repository, whatever its scheme.
+fn remote_for_parent(repo: &Repository, host: &str, parent: &ForkParent) -> Option<String> {
+    let remotes = repo.remotes().ok()?;
+    let found = remotes.iter().flatten().find(|remote_name| {
+        repo.find_remote(remote_name)
+            .ok()
+            .and_then(|remote| remote.url().and_then(parse_remote_url))
+            .map(|hosted| {
+                hosted.host == host
+                    && hosted.owner.eq_ignore_ascii_case(&parent.owner)
+                    && hosted.name.eq_ignore_ascii_case(&parent.name)
+            })
+            .unwrap_or(false)
+    });
+    found.map(|remote_name| remote_name.to_string())
+}
+
+fn fork_info_blocking(app: &tauri::AppHandle, repo_path: &str, remote_name: Option<String>) -> Result<ForkInfo, AppError> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(app, repo_path));
+    let hosted = hosted_repository(&repo, &remote_name)?;
+    let ssh = repo.find_remote(&remote_name)
+        .ok()
+        .and_then(|remote| remote.url().map(is_ssh_url))
+        .unwrap_or(false);
+
+    let parent = fetch_fork_parent(&hosted, ssh)?;
+    let upstream_remote = parent.as_ref().and_then(|parent| remote_for_parent(&repo, &hosted.host, parent));
+
+    Ok(ForkInfo {
+        is_fork: parent.is_some(),
+        parent,
+        upstream_remote,
+    })
+}
+
+/// Whether the default remote (or `remote_name`) is a fork, and of what.
+#[tauri::command]
+pub async fn get_fork_info(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: Option<String>,
+) -> Result<ForkInfo, AppError> {
+    tauri::async_runtime::spawn_blocking(move || fork_info_blocking(&app, &repo_path, remote_name)).await?
+}
+
+/// Adds the fork's parent as a remote ("upstream" unless named otherwise) with the
+/// standard fetch refspec. An existing remote of that name is repointed at the parent.
+#[tauri::command]
+pub async fn configure_upstream(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: Option<String>,
+    upstream_name: Option<String>,
+) -> Result<String, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let upstream_name = upstream_name.unwrap_or_else(|| DEFAULT_UPSTREAM_REMOTE.to_string());
+        let info = fork_info_blocking(&app, &repo_path, remote_name)?;
+        let parent = info.parent
+            .ok_or_else(|| AppError::invalid_input("This repository is not a fork"))?;
+
+        audited(&app, &repo_path, "configure_upstream", || {
+            let repo = Repository::open(&repo_path)
+                .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+            let refspec = format!("+refs/heads/*:refs/remotes/{}/*", upstream_name);
+
+            let existing = repo.find_remote(&upstream_name);
+            let message = match existing {
+                Ok(remote) => {
+                    if remote.url() != Some(parent.clone_url.as_str()) {
+                        repo.remote_set_url(&upstream_name, &parent.clone_url)
+                            .map_err(|e| AppError::wrap(e, "Failed to update remote URL"))?;
+                    }
+                    let has_refspec = remote.fetch_refspecs()
+                        .map(|refspecs| refspecs.iter().flatten().any(|existing| existing == refspec))
+                        .unwrap_or(false);
+                    if !has_refspec {
+                        repo.remote_add_fetch(&upstream_name, &refspec)
+                            .map_err(|e| AppError::wrap(e, "Failed to set fetch refspec"))?;
+                    }
+                    format!("Updated remote '{}' to {}", upstream_name, parent.clone_url)
+                }
+                Err(_) => {
+                    // git2 gives a new remote the standard refspec
+                    repo.remote(&upstream_name, &parent.clone_url)
+                        .map_err(|e| AppError::wrap(e, "Failed to add remote"))?;
+                    format!("Added remote '{}' with URL: {}", upstream_name, parent.clone_url)
+                }
+            };
+
+            Ok(message)
+        })
+    })
+    .await?
+}
+
+fn sync_fork_blocking(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branch: Option<String>,
+    upstream_name: String,
+    remote_name: String,
+    token: OperationToken,
+) -> Result<String, AppError> {
+    crate::fetch_from_remote_blocking(app.clone(), repo_path.clone(), Some(upstream_name.clone()), token.clone())?;
+    token.check()?;
+
+    let repo = crate::open_worktree_repository(&repo_path)?;
+    let branch = match branch {
+        Some(branch) => branch,
+        None => repo.head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(|name| name.to_string()))
+            .ok_or_else(|| AppError::invalid_input("Check out a branch to sync, or choose one"))?,
+    };
+
+    let local_ref = format!("refs/heads/{}", branch);
+    let local = repo.refname_to_id(&local_ref)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find branch '{}'", branch)))?;
+    let upstream = repo.refname_to_id(&format!("refs/remotes/{}/{}", upstream_name, branch))
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find '{}/{}'", upstream_name, branch)))?;
+
+    let updated = if local == upstream || repo.graph_descendant_of(local, upstream).unwrap_or(false) {
+        false
+    } else if repo.graph_descendant_of(upstream, local).unwrap_or(false) {
+        let upstream_commit = repo.find_commit(upstream)
+            .map_err(|e| AppError::wrap(e, "Failed to get upstream commit"))?;
+        let is_checked_out = repo.head().ok().and_then(|head| head.name().map(|name| name == local_ref)).unwrap_or(false);
+        if is_checked_out {
+            // Safe checkout refuses to overwrite local changes instead of discarding them
+            repo.checkout_tree(upstream_commit.as_object(), Some(git2::build::CheckoutBuilder::new().safe()))
+                .map_err(|e| AppError::wrap(e, "Failed to checkout"))?;
+        }
+        repo.find_reference(&local_ref)
+            .and_then(|mut reference| reference.set_target(upstream, &format!("sync fork: fast-forward to {}/{}", upstream_name, branch)))
+            .map_err(|e| AppError::wrap(e, "Failed to update branch reference"))?;
+        true
+    } else {
+        return Err(AppError::invalid_input(format!(
+            "Cannot sync '{}': it has diverged from '{}/{}'; merge or rebase it first",
+            branch, upstream_name, branch
+        )));
+    };
+    token.check()?;
+
+    crate::push_to_remote_blocking(app, repo_path, Some(remote_name.clone()), branch.clone(), token)?;
+
+    Ok(if updated {
+        format!("Fast-forwarded '{}' to '{}/{}' and pushed it to '{}'", branch, upstream_name, branch, remote_name)
+    } else {
+        format!("'{}' already contains '{}/{}'; pushed it to '{}'", branch, upstream_name, branch, remote_name)
+    })
+}
+
+/// Brings a branch of the fork (the current one by default) up to date with the same
+/// branch upstream: fetch upstream, fast-forward the local branch, push it to the fork.
+#[tauri::command]
+pub async fn sync_fork(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branch: Option<String>,
+    upstream_name: Option<String>,
+    remote_name: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "sync_fork", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "sync_fork", || {
+            let upstream_name = upstream_name.unwrap_or_else(|| DEFAULT_UPSTREAM_REMOTE.to_string());
+            let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&task_app, &repo_path));
+            sync_fork_blocking(task_app.clone(), repo_path, branch, upstream_name, remote_name, token)
+        })
+    })
+    .await
+}
@@ -0,0 +1,154 @@
+use crate::commands::audit::audited;
+use crate::commands::error::{remote_host, AppError};
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::{PushOptions, Repository};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// How a change is uploaded to Gerrit. Everything is optional: the target branch defaults to
+/// the branch's upstream, else the branch's own name.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GerritPushOptions {
+    pub target_branch: Option<String>,
+    pub topic: Option<String>,
+    pub reviewers: Vec<String>,
+    pub cc: Vec<String>,
+    pub work_in_progress: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GerritPushResult {
+    pub target_ref: String,
+    pub change_urls: Vec<String>, // Changes created or updated by the push
+    pub remote_messages: Vec<String>, // What Gerrit printed, for errors like "no new changes"
+}
+
+/// Gerrit push options ("-o topic=…" on the command line) for `options`.
+fn push_option_values(options: &GerritPushOptions) -> Result<Vec<String>, AppError> {
+    let mut values = Vec::new();
+    if let Some(topic) = options.topic.as_deref().map(str::trim).filter(|topic| !topic.is_empty()) {
+        values.push(format!("topic={}", topic));
+    }
+    values.extend(options.reviewers.iter().map(|reviewer| format!("r={}", reviewer.trim())));
+    values.extend(options.cc.iter().map(|cc| format!("cc={}", cc.trim())));
+    if options.work_in_progress {
+        values.push("wip".to_string());
+    }
+
+    if let Some(invalid) = values.iter().find(|value| value.contains(['\r', '\n']) || value.ends_with('=')) {
+        return Err(AppError::invalid_input(format!("Invalid Gerrit push option: {}", invalid)));
+    }
+    Ok(values)
+}
+
+/// Change URLs in Gerrit's reply, e.g. "  https://review.example.com/c/project/+/1234 Subject [NEW]".
+fn change_urls(messages: &[String]) -> Vec<String> {
+    let mut urls: Vec<String> = Vec::new();
+    for message in messages {
+        for word in message.split_whitespace() {
+            if (word.starts_with("https://") || word.starts_with("http://")) && !urls.iter().any(|url| url == word) {
+                urls.push(word.to_string());
+            }
+        }
+    }
+    urls
+}
+
+/// Pushes `branch_name` to refs/for/<target> so Gerrit creates or updates a change, and
+/// returns the change URLs Gerrit reports.
+pub fn push_for_review_blocking(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    remote_name: Option<String>,
+    branch_name: &str,
+    options: &GerritPushOptions,
+    token: &OperationToken,
+) -> Result<GerritPushResult, AppError> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(app, repo_path));
+    let mut remote = repo.find_remote(&remote_name)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
+    let host = remote.url().and_then(remote_host);
+
+    let target_branch = options.target_branch
+        .clone()
+        .filter(|target| !target.trim().is_empty())
+        .or_else(|| crate::upstream_branch_name(&repo, branch_name, &remote_name))
+        .unwrap_or_else(|| branch_name.to_string());
+    let target_ref = format!("refs/for/{}", target_branch);
+    let refspec = format!("refs/heads/{}:{}", branch_name, target_ref);
+    let push_option_values = push_option_values(options)?;
+    let push_option_refs: Vec<&str> = push_option_values.iter().map(|value| value.as_str()).collect();
+
+    // Gerrit answers on the sideband and rejects through the ref status, which libgit2
+    // reports without failing the push
+    let output = Arc::new(Mutex::new(Vec::<u8>::new()));
+    let rejection: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let mut callbacks = crate::cancellable_callbacks(token);
+    let sideband_output = output.clone();
+    let sideband_token = token.clone();
+    callbacks.sideband_progress(move |data| {
+        if let Ok(mut output) = sideband_output.lock() {
+            output.extend_from_slice(data);
+        }
+        !sideband_token.is_cancelled()
+    });
+    let update_rejection = rejection.clone();
+    callbacks.push_update_reference(move |_, status| {
+        if let (Some(status), Ok(mut rejection)) = (status, update_rejection.lock()) {
+            *rejection = Some(status.to_string());
+        }
+        Ok(())
+    });
+
+    let mut push_options = PushOptions::new();
+    push_options.remote_callbacks(callbacks);
+    push_options.remote_push_options(&push_option_refs);
+
+    token.check()?;
+    remote.push(&[&refspec], Some(&mut push_options))
+        .map_err(|e| AppError::wrap(e, &format!("Failed to push to remote '{}'", remote_name)).with_host(host.clone()))?;
+
+    let remote_messages: Vec<String> = output.lock()
+        .map(|output| {
+            String::from_utf8_lossy(&output)
+                .split(['\n', '\r'])
+                .map(|line| line.trim_end().to_string())
+                .filter(|line| !line.trim().is_empty())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    if let Some(reason) = rejection.lock().ok().and_then(|rejection| rejection.clone()) {
+        return Err(AppError::invalid_input(format!("Gerrit rejected the push to {}: {}", target_ref, reason)).with_host(host));
+    }
+
+    Ok(GerritPushResult {
+        target_ref,
+        change_urls: change_urls(&remote_messages),
+        remote_messages,
+    })
+}
+
+/// Uploads `branch_name` for review on a Gerrit remote, with an optional topic, reviewers,
+/// CCs and work-in-progress flag.
+#[tauri::command]
+pub async fn push_for_review(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branch_name: String,
+    remote_name: Option<String>,
+    options: Option<GerritPushOptions>,
+    operation_id: Option<String>,
+) -> Result<GerritPushResult, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "push", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "push_for_review", || {
+            push_for_review_blocking(&task_app, &repo_path, remote_name, &branch_name, &options.unwrap_or_default(), &token)
+        })
+    })
+    .await
+}
@@ -0,0 +1,349 @@
+use crate::commands::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
+use std::process::Stdio;
+use tauri::Manager;
+
+/// What the command runner accepts for one git subcommand. Options are matched exactly,
+/// so git's abbreviated long options and short options with an attached value are
+/// rejected; an entry ending in '=' takes its value as `--name=value`.
+struct SubcommandRules {
+    name: &'static str,
+    actions: &'static [&'static str], // Allowed first positional argument, when the subcommand has sub-actions
+    options: &'static [&'static [&'static str]],
+}
+
+// "-<n>" stands for a count such as `-5`
+const HISTORY_OPTIONS: &[&str] = &[
+    "-<n>", "-n", "--max-count=", "--skip=", "--oneline", "--graph", "--all", "--decorate", "--no-decorate",
+    "--stat", "--shortstat", "--numstat", "--name-only", "--name-status", "-p", "--patch", "--no-patch",
+    "--no-merges", "--merges", "--first-parent", "--reverse", "--follow", "--author=", "--committer=",
+    "--grep=", "--since=", "--until=", "--after=", "--before=", "--format=", "--pretty", "--pretty=",
+    "--date=", "--abbrev-commit", "--color", "--color=", "--no-color", "--left-right", "--boundary",
+    "--branches", "--tags", "--remotes", "--count", "--parents", "--walk-reflogs", "-g",
+];
+const DIFF_OPTIONS: &[&str] = &[
+    "--stat", "--shortstat", "--numstat", "--name-only", "--name-status", "--summary", "--check",
+    "--cached", "--staged", "--word-diff", "--word-diff=", "--color-words", "-w", "--ignore-all-space",
+    "-b", "--ignore-space-change", "--unified=", "--diff-filter=", "-M", "--find-renames", "--color",
+    "--color=", "--no-color", "--no-renames", "--merge-base",
+];
+const SEQUENCER_OPTIONS: &[&str] = &["--continue", "--abort", "--skip", "--quit"];
+const QUIET_VERBOSE: &[&str] = &["-q", "--quiet", "-v", "--verbose"];
+
+const SUBCOMMANDS: &[SubcommandRules] = &[
+    SubcommandRules {
+        name: "status",
+        actions: &[],
+        options: &[&[
+            "-s", "--short", "-b", "--branch", "--porcelain", "--porcelain=", "--long", "-u",
+            "--untracked-files=", "--ignored", "--ignored=", "-z",
+        ]],
+    },
+    SubcommandRules { name: "log", actions: &[], options: &[HISTORY_OPTIONS] },
+    SubcommandRules { name: "show", actions: &[], options: &[HISTORY_OPTIONS] },
+    SubcommandRules { name: "rev-list", actions: &[], options: &[HISTORY_OPTIONS] },
+    SubcommandRules { name: "shortlog", actions: &[], options: &[HISTORY_OPTIONS, &["-s", "--summary", "--numbered", "-e", "--email", "--group="]] },
+    SubcommandRules { name: "reflog", actions: &["show", "exists"], options: &[HISTORY_OPTIONS] },
+    SubcommandRules { name: "diff", actions: &[], options: &[DIFF_OPTIONS, &["-p", "--patch"]] },
+    SubcommandRules {
+        name: "branch",
+        actions: &[],
+        options: &[&[
+            "-a", "--all", "-r", "--remotes", "-v", "-vv", "--list", "--merged", "--no-merged", "--contains",
+            "--sort=", "--format=", "-d", "-D", "--delete", "-m", "-M", "--move", "--show-current",
+            "--set-upstream-to=", "--unset-upstream",
+        ]],
+    },
+    SubcommandRules {
+        name: "tag",
+        actions: &[],
+        options: &[&["-l", "--list", "-n", "-a", "--annotate", "-m", "--message=", "-d", "--delete", "--sort=", "--contains", "--points-at", "-f", "--force"]],
+    },
+    SubcommandRules {
+        name: "fetch",
+        actions: &[],
+        options: &[QUIET_VERBOSE, &[
+            "--all", "--prune", "-p", "--prune-tags", "--tags", "--no-tags", "--depth=", "--unshallow",
+            "--dry-run", "-f", "--force", "--recurse-submodules", "--recurse-submodules=",
+        ]],
+    },
+    SubcommandRules {
+        name: "pull",
+        actions: &[],
+        options: &[QUIET_VERBOSE, &[
+            "--rebase", "--rebase=", "--no-rebase", "--ff", "--ff-only", "--no-ff", "--autostash",
+            "--no-autostash", "--squash", "--no-commit", "--all", "--tags", "--prune", "--depth=",
+        ]],
+    },
+    SubcommandRules {
+        name: "push",
+        actions: &[],
+        options: &[QUIET_VERBOSE, &[
+            "-u", "--set-upstream", "--tags", "--follow-tags", "--all", "-d", "--delete", "--force-with-lease",
+            "--force-with-lease=", "-f", "--force", "-n", "--dry-run", "--atomic", "--prune",
+        ]],
+    },
+    SubcommandRules {
+        name: "remote",
+        actions: &["show", "add", "remove", "rm", "rename", "set-url", "get-url", "prune", "update", "set-head"],
+        options: &[QUIET_VERBOSE, &["--push", "--all", "--add", "--delete", "-n", "--dry-run", "-f", "--tags", "--no-tags", "-t", "-m", "-a", "--auto", "-d"]],
+    },
+    SubcommandRules {
+        name: "stash",
+        actions: &["list", "show", "push", "save", "pop", "apply", "drop", "branch", "clear"],
+        options: &[&[
+            "-m", "--message=", "-u", "--include-untracked", "-a", "--all", "-k", "--keep-index", "--index",
+            "--staged", "-q", "--quiet", "--stat",
+        ]],
+    },
+    SubcommandRules {
+        name: "rev-parse",
+        actions: &[],
+        options: &[&[
+            "--abbrev-ref", "--short", "--short=", "--verify", "-q", "--quiet", "--symbolic-full-name",
+            "--show-toplevel", "--show-prefix", "--git-dir", "--git-common-dir", "--absolute-git-dir",
+            "--is-inside-work-tree", "--all", "--branches", "--tags", "--remotes",
+        ]],
+    },
+    SubcommandRules {
+        name: "describe",
+        actions: &[],
+        options: &[&["--tags", "--all", "--long", "--always", "--abbrev=", "--dirty", "--dirty=", "--exact-match", "--contains", "--match=", "--exclude="]],
+    },
+    SubcommandRules {
+        name: "blame",
+        actions: &[],
+        options: &[&["-L", "-w", "-M", "-C", "-e", "--show-email", "-s", "-l", "--porcelain", "--line-porcelain", "--incremental", "--date=", "--root", "--show-stats"]],
+    },
+    SubcommandRules {
+        name: "ls-files",
+        actions: &[],
+        options: &[&[
+            "-c", "--cached", "-d", "--deleted", "-m", "--modified", "-o", "--others", "-i", "--ignored",
+            "-s", "--stage", "-u", "--unmerged", "-z", "--exclude-standard", "--directory", "--full-name",
+            "--error-unmatch", "-t", "--eol", "--recurse-submodules",
+        ]],
+    },
+    SubcommandRules {
+        name: "ls-tree",
+        actions: &[],
+        options: &[&["-r", "-d", "-t", "-l", "--long", "-z", "--name-only", "--name-status", "--full-name", "--full-tree", "--abbrev", "--abbrev=", "--object-only"]],
+    },
+    SubcommandRules {
+        name: "ls-remote",
+        actions: &[],
+        options: &[&["--heads", "--tags", "-h", "-t", "--refs", "-q", "--quiet", "--symref", "--sort=", "--get-url"]],
+    },
+    SubcommandRules {
+        name: "cherry-pick",
+        actions: &[],
+        options: &[SEQUENCER_OPTIONS, &["-n", "--no-commit", "-x", "-m", "--mainline=", "-s", "--signoff", "--ff", "--allow-empty", "--keep-redundant-commits"]],
+    },
+    SubcommandRules {
+        name: "revert",
+        actions: &[],
+        options: &[SEQUENCER_OPTIONS, &["-n", "--no-commit", "-m", "--mainline=", "-s", "--signoff", "--no-edit"]],
+    },
+    SubcommandRules {
+        name: "merge",
+        actions: &[],
+        options: &[SEQUENCER_OPTIONS, &[
+            "--ff", "--no-ff", "--ff-only", "--squash", "--commit", "--no-commit", "-m", "--message=",
+            "--no-edit", "--strategy-option=", "-X", "--allow-unrelated-histories", "--stat", "--no-stat",
+            "--log", "--autostash",
+        ]],
+    },
+    SubcommandRules {
+        name: "rebase",
+        actions: &[],
+        options: &[SEQUENCER_OPTIONS, QUIET_VERBOSE, &[
+            "--onto", "--keep-base", "--root", "--autostash", "--no-autostash", "--autosquash",
+            "--no-autosquash", "--rebase-merges", "--update-refs", "--signoff", "--stat",
+            "--committer-date-is-author-date",
+        ]],
+    },
+    SubcommandRules {
+        name: "gc",
+        actions: &[],
+        options: &[&["--aggressive", "--auto", "--prune", "--prune=", "--no-prune", "-q", "--quiet", "--force"]],
+    },
+    SubcommandRules {
+        name: "maintenance",
+        actions: &["run"],
+        options: &[&["--task=", "--auto", "--schedule=", "--quiet"]],
+    },
+    SubcommandRules {
+        name: "fsck",
+        actions: &[],
+        options: &[&[
+            "--full", "--strict", "--unreachable", "--dangling", "--no-dangling", "--connectivity-only",
+            "--name-objects", "--no-reflogs", "--root", "--tags", "--cache", "--progress", "--no-progress",
+        ]],
+    },
+    SubcommandRules { name: "count-objects", actions: &[], options: &[&["-v", "--verbose", "-H", "--human-readable"]] },
+    SubcommandRules {
+        name: "worktree",
+        actions: &["list", "add", "prune", "remove", "lock", "unlock", "move", "repair"],
+        options: &[QUIET_VERBOSE, &[
+            "-b", "-B", "--detach", "-f", "--force", "--lock", "--reason=", "--porcelain", "-z", "-n",
+            "--dry-run", "--expire=", "--checkout", "--no-checkout", "--track", "--no-track", "--orphan",
+        ]],
+    },
+    // No `foreach`: it runs any shell command
+    SubcommandRules {
+        name: "submodule",
+        actions: &["status", "summary", "init", "deinit", "sync", "update", "absorbgitdirs"],
+        options: &[&[
+            "--recursive", "--init", "--remote", "--force", "-f", "--all", "--cached", "--files", "-n",
+            "--summary-limit=", "--depth=", "--jobs=", "--rebase", "--merge", "--checkout", "--no-fetch",
+            "-q", "--quiet",
+        ]],
+    },
+    SubcommandRules {
+        name: "lfs",
+        actions: &["ls-files", "status", "fetch", "pull", "push", "track", "untrack", "env", "prune", "fsck", "locks", "lock", "unlock", "version"],
+        options: &[&[
+            "--all", "--include=", "--exclude=", "--recent", "--dry-run", "--verify-remote", "-l", "--long",
+            "-s", "--size", "-n", "--name-only", "--json", "--porcelain", "-f", "--force", "--lockable",
+            "--path=", "--id=", "--verbose",
+        ]],
+    },
+    SubcommandRules {
+        name: "config",
+        actions: &[],
+        options: &[READ_ONLY_CONFIG_OPTIONS, &[
+            "--global", "--local", "--system", "--worktree", "--name-only", "--show-scope", "-z", "--null",
+            "--type=", "--bool", "--int", "--default=", "--includes", "--no-includes",
+        ]],
+    },
+];
+const READ_ONLY_CONFIG_OPTIONS: &[&str] = &["--get", "--get-all", "--get-regexp", "--list", "-l", "--show-origin"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitCommandOutput {
+    pub repo_path: String,
+    pub stream: String, // 'stdout' | 'stderr'
+    pub line: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GitCommandResult {
+    pub command: String,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+fn option_allowed(rules: &SubcommandRules, arg: &str) -> bool {
+    let options = || rules.options.iter().flat_map(|group| group.iter());
+    if arg.len() > 1 && arg[1..].bytes().all(|byte| byte.is_ascii_digit()) {
+        return options().any(|option| *option == "-<n>");
+    }
+    match arg.split_once('=') {
+        Some((name, _)) if name.starts_with("--") => options().any(|option| option.strip_suffix('=') == Some(name)),
+        Some(_) => false,
+        None => options().any(|option| *option == arg),
+    }
+}
+
+fn validate_git_args(args: &[String]) -> Result<(), String> {
+    let subcommand = args.first()
+        .ok_or_else(|| "No git command given".to_string())?;
+
+    // Global options (-c, --git-dir, --exec-path, ...) come before the subcommand and are never allowed
+    let rules = SUBCOMMANDS.iter()
+        .find(|rules| rules.name == subcommand)
+        .ok_or_else(|| format!("git {} is not allowed from the command runner", subcommand))?;
+
+    let mut first_positional = true;
+    let mut end_of_options = false;
+    for arg in &args[1..] {
+        if arg.contains('\0') || arg.contains('\n') {
+            return Err("Arguments cannot contain control characters".to_string());
+        }
+        if end_of_options || !arg.starts_with('-') || arg == "-" {
+            let is_action = first_positional && !end_of_options && !rules.actions.is_empty();
+            if is_action && !rules.actions.contains(&arg.as_str()) {
+                return Err(format!("git {} {} is not allowed from the command runner", subcommand, arg));
+            }
+            first_positional = false;
+        } else if arg == "--" || arg == "--end-of-options" {
+            end_of_options = true;
+        } else if !option_allowed(rules, arg) {
+            return Err(format!("Option {} is not allowed for git {} from the command runner", arg, subcommand));
+        }
+    }
+
+    if subcommand == "config" && !args[1..].iter().any(|arg| READ_ONLY_CONFIG_OPTIONS.contains(&arg.as_str())) {
+        return Err("Only reading git config is allowed from the command runner".to_string());
+    }
+
+    Ok(())
+}
+
+/// Runs a whitelisted git command with the system git binary, emitting every output
+/// line as a `git-command-output` event while it runs.
+#[tauri::command]
+pub async fn run_git_command(
+    app: tauri::AppHandle,
+    repo_path: String,
+    args: Vec<String>,
+) -> Result<GitCommandResult, AppError> {
+    git2::Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    validate_git_args(&args)?;
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let mut child = crate::git_cli(Some(&repo_path))
+            .args(&args)
+            // Never wait for an editor or a password prompt
+            .env("GIT_EDITOR", "true")
+            .env("GIT_SEQUENCE_EDITOR", "true")
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| AppError::wrap(e, "Failed to run git"))?;
+
+        let stdout = child.stdout.take()
+            .ok_or_else(|| "Failed to capture git output".to_string())?;
+        let stderr = child.stderr.take()
+            .ok_or_else(|| "Failed to capture git output".to_string())?;
+
+        // stderr is drained on its own thread so neither pipe can fill up and block git
+        let stderr_app = app.clone();
+        let stderr_repo = repo_path.clone();
+        let stderr_reader = std::thread::spawn(move || stream_lines(&stderr_app, &stderr_repo, "stderr", stderr));
+        let stdout_text = stream_lines(&app, &repo_path, "stdout", stdout);
+        let stderr_text = stderr_reader.join().unwrap_or_default();
+
+        let status = child.wait()
+            .map_err(|e| AppError::wrap(e, "Failed to wait for git"))?;
+
+        Ok(GitCommandResult {
+            command: format!("git {}", args.join(" ")),
+            exit_code: status.code().unwrap_or(-1),
+            stdout: stdout_text,
+            stderr: stderr_text,
+        })
+    })
+    .await
+    .map_err(|e| AppError::wrap(e, "Git command task failed"))?
+}
+
+fn stream_lines(app: &tauri::AppHandle, repo_path: &str, stream: &str, pipe: impl std::io::Read) -> String {
+    let mut collected = String::new();
+
+    for line in BufReader::new(pipe).lines().map_while(Result::ok) {
+        let _ = app.emit_all("git-command-output", GitCommandOutput {
+            repo_path: repo_path.to_string(),
+            stream: stream.to_string(),
+            line: line.clone(),
+        });
+        collected.push_str(&line);
+        collected.push('\n');
+    }
+
+    collected
+}
@@ -0,0 +1,206 @@
+use crate::commands::error::AppError;
+use crate::commands::identity::identity_warning;
+use chrono::{DateTime, Utc};
+use git2::{BranchType, ObjectType, Repository, RepositoryState, TreeWalkMode, TreeWalkResult};
+use serde::{Deserialize, Serialize};
+
+const STALE_BRANCH_DAYS: i64 = 90;
+const LARGE_FILE_BYTES: u64 = 10 * 1024 * 1024;
+const LARGE_FILES_LIMIT: usize = 20;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthIssue {
+    pub severity: String, // 'error' | 'warning' | 'info'
+    pub code: String,     // e.g. "health.detached_head", for the frontend to pick an action
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StaleBranch {
+    pub name: String,
+    pub last_commit_at: i64,
+    pub days_inactive: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UnpushedBranch {
+    pub name: String,
+    pub upstream: String,
+    pub ahead: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LargeFile {
+    pub path: String,
+    pub size: u64,
+}
+
+/// Everything the health panel shows for one repository, plus `issues` summarizing what
+/// needs attention, most severe first.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryHealth {
+    pub repo_path: String,
+    pub current_branch: Option<String>,
+    pub detached_head: bool,
+    pub operation_in_progress: Option<String>, // 'merge' | 'rebase' | 'cherry-pick' | 'revert' | 'bisect' | 'am'
+    pub user_name: Option<String>,
+    pub user_email: Option<String>,
+    pub stale_branches: Vec<StaleBranch>,
+    pub gone_upstreams: Vec<String>, // Local branches whose tracked remote branch was deleted
+    pub unpushed_branches: Vec<UnpushedBranch>,
+    pub large_files: Vec<LargeFile>, // Files in HEAD of at least 10 MiB, largest first
+    pub issues: Vec<HealthIssue>,
+    pub checked_at: DateTime<Utc>,
+}
+
+fn issue(severity: &str, code: &str, message: String) -> HealthIssue {
+    HealthIssue { severity: severity.to_string(), code: code.to_string(), message }
+}
+
+pub fn operation_name(state: RepositoryState) -> Option<&'static str> {
+    match state {
+        RepositoryState::Clean => None,
+        RepositoryState::Merge => Some("merge"),
+        RepositoryState::Revert | RepositoryState::RevertSequence => Some("revert"),
+        RepositoryState::CherryPick | RepositoryState::CherryPickSequence => Some("cherry-pick"),
+        RepositoryState::Bisect => Some("bisect"),
+        RepositoryState::Rebase | RepositoryState::RebaseInteractive | RepositoryState::RebaseMerge => Some("rebase"),
+        RepositoryState::ApplyMailbox | RepositoryState::ApplyMailboxOrRebase => Some("am"),
+    }
+}
+
+fn large_files(repo: &Repository) -> Result<Vec<LargeFile>, String> {
+    let tree = match repo.head().and_then(|head| head.peel_to_tree()) {
+        Ok(tree) => tree,
+        Err(_) => return Ok(Vec::new()), // Unborn branch
+    };
+    let odb = repo.odb()
+        .map_err(|e| format!("Failed to open object database: {}", e))?;
+
+    let mut files = Vec::new();
+    tree.walk(TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() == Some(ObjectType::Blob) {
+            let size = odb.read_header(entry.id()).map(|(size, _)| size as u64).unwrap_or(0);
+            if size >= LARGE_FILE_BYTES {
+                files.push(LargeFile { path: format!("{}{}", root, entry.name().unwrap_or("")), size });
+            }
+        }
+        TreeWalkResult::Ok
+    })
+    .map_err(|e| format!("Failed to walk tree: {}", e))?;
+
+    files.sort_by_key(|file| std::cmp::Reverse(file.size));
+    files.truncate(LARGE_FILES_LIMIT);
+    Ok(files)
+}
+
+fn repository_health(app: &tauri::AppHandle, repo_path: &str) -> Result<RepositoryHealth, AppError> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let now = Utc::now();
+
+    let detached_head = repo.head_detached().unwrap_or(false);
+    let current_branch = if detached_head {
+        None
+    } else {
+        crate::head_ref_name(&repo).strip_prefix("refs/heads/").map(|name| name.to_string())
+    };
+    let operation_in_progress = operation_name(repo.state()).map(|name| name.to_string());
+
+    let config = repo.config()
+        .map_err(|e| AppError::wrap(e, "Failed to open repository config"))?;
+    let user_name = config.get_string("user.name").ok().filter(|name| !name.trim().is_empty());
+    let user_email = config.get_string("user.email").ok().filter(|email| !email.trim().is_empty());
+
+    let mut stale_branches = Vec::new();
+    let mut gone_upstreams = Vec::new();
+    let mut unpushed_branches = Vec::new();
+    let branches = repo.branches(Some(BranchType::Local))
+        .map_err(|e| AppError::wrap(e, "Failed to get branches"))?;
+
+    for (branch, _) in branches.flatten() {
+        let name = match branch.name() {
+            Ok(Some(name)) => name.to_string(),
+            _ => continue,
+        };
+        let tip = match branch.get().peel_to_commit() {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+
+        let last_commit_at = tip.time().seconds();
+        let days_inactive = (now.timestamp() - last_commit_at) / 86_400;
+        if days_inactive >= STALE_BRANCH_DAYS && Some(&name) != current_branch.as_ref() {
+            stale_branches.push(StaleBranch { name: name.clone(), last_commit_at, days_inactive });
+        }
+
+        match branch.upstream() {
+            Ok(upstream) => {
+                let upstream_tip = upstream.get().target();
+                let upstream_name = upstream.name().ok().flatten().unwrap_or("").to_string();
+                if let Some(upstream_tip) = upstream_tip {
+                    if let Ok((ahead, _)) = repo.graph_ahead_behind(tip.id(), upstream_tip) {
+                        if ahead > 0 {
+                            unpushed_branches.push(UnpushedBranch { name: name.clone(), upstream: upstream_name, ahead });
+                        }
+                    }
+                }
+            }
+            // Configured to track a remote branch that no longer exists
+            Err(_) if config.get_string(&format!("branch.{}.merge", name)).is_ok() => gone_upstreams.push(name.clone()),
+            Err(_) => {}
+        }
+    }
+    stale_branches.sort_by_key(|branch| std::cmp::Reverse(branch.days_inactive));
+
+    let large_files = large_files(&repo)?;
+
+    let mut issues = Vec::new();
+    if let Some(operation) = &operation_in_progress {
+        issues.push(issue("error", "health.operation_in_progress", format!("A {} is in progress; continue or abort it", operation)));
+    }
+    if user_name.is_none() || user_email.is_none() {
+        issues.push(issue("error", "health.missing_identity", "user.name or user.email is not set in git config".to_string()));
+    } else if let Some(warning) = identity_warning(app, repo_path) {
+        issues.push(issue("warning", "health.identity_mismatch", warning));
+    }
+    if detached_head {
+        issues.push(issue("warning", "health.detached_head", "HEAD is detached; new commits won't be on a branch".to_string()));
+    }
+    if !unpushed_branches.is_empty() {
+        let commits: usize = unpushed_branches.iter().map(|branch| branch.ahead).sum();
+        issues.push(issue("warning", "health.unpushed_commits", format!("{} unpushed commit(s) on {} branch(es)", commits, unpushed_branches.len())));
+    }
+    if !gone_upstreams.is_empty() {
+        issues.push(issue("warning", "health.gone_upstreams", format!("{} branch(es) track a deleted remote branch", gone_upstreams.len())));
+    }
+    if !large_files.is_empty() {
+        issues.push(issue("info", "health.large_files", format!("{} file(s) of 10 MiB or more; consider Git LFS", large_files.len())));
+    }
+    if !stale_branches.is_empty() {
+        issues.push(issue("info", "health.stale_branches", format!("{} branch(es) without commits in {} days", stale_branches.len(), STALE_BRANCH_DAYS)));
+    }
+
+    Ok(RepositoryHealth {
+        repo_path: repo_path.to_string(),
+        current_branch,
+        detached_head,
+        operation_in_progress,
+        user_name,
+        user_email,
+        stale_branches,
+        gone_upstreams,
+        unpushed_branches,
+        large_files,
+        issues,
+        checked_at: now,
+    })
+}
+
+/// Checks a repository for common problems: unfinished operations, a missing or mismatched
+/// identity, a detached HEAD, unpushed commits, branches tracking deleted remote branches,
+/// large files and stale branches.
+#[tauri::command]
+pub async fn get_repository_health(app: tauri::AppHandle, repo_path: String) -> Result<RepositoryHealth, AppError> {
+    tauri::async_runtime::spawn_blocking(move || repository_health(&app, &repo_path)).await?
+}
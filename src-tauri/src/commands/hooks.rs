@@ -0,0 +1,220 @@
+use crate::commands::error::AppError;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::Manager;
+
+const KNOWN_HOOKS: [&str; 13] = [
+    "applypatch-msg", "pre-applypatch", "post-applypatch", "pre-commit", "prepare-commit-msg",
+    "commit-msg", "post-commit", "pre-rebase", "post-checkout", "post-merge", "pre-push",
+    "pre-auto-gc", "post-rewrite",
+];
+// Suffix CodeGit uses for hooks switched off from the UI
+const DISABLED_SUFFIX: &str = ".disabled";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HookInfo {
+    pub name: String,
+    pub path: String,
+    pub is_enabled: bool,
+    pub is_executable: bool,
+    pub has_sample: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HookResult {
+    pub repo_path: String,
+    pub hook: String,
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// The hooks directory, honoring core.hooksPath.
+fn hooks_dir(repo: &Repository) -> PathBuf {
+    let configured = repo.config()
+        .and_then(|config| config.get_path("core.hooksPath"))
+        .ok();
+
+    match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or_else(|| repo.path()).join(path),
+        None => repo.path().join("hooks"),
+    }
+}
+
+fn validate_hook_name(name: &str) -> Result<(), String> {
+    if KNOWN_HOOKS.contains(&name) {
+        Ok(())
+    } else {
+        Err(format!("Unknown hook: {}", name))
+    }
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+    fs::metadata(path).map(|meta| meta.permissions().mode() & 0o111 != 0).unwrap_or(false)
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<(), String> {
+    use std::os::unix::fs::PermissionsExt;
+    let mut permissions = fs::metadata(path)
+        .map_err(|e| format!("Failed to read hook permissions: {}", e))?
+        .permissions();
+    permissions.set_mode(permissions.mode() | 0o755);
+    fs::set_permissions(path, permissions)
+        .map_err(|e| format!("Failed to make hook executable: {}", e))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<(), String> {
+    Ok(())
+}
+
+#[tauri::command]
+pub fn list_hooks(repo_path: String) -> Result<Vec<HookInfo>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let dir = hooks_dir(&repo);
+
+    Ok(KNOWN_HOOKS
+        .iter()
+        .filter_map(|name| {
+            let enabled_path = dir.join(name);
+            let disabled_path = dir.join(format!("{}{}", name, DISABLED_SUFFIX));
+            let has_sample = dir.join(format!("{}.sample", name)).exists();
+
+            let (path, is_enabled) = if enabled_path.is_file() {
+                (enabled_path, true)
+            } else if disabled_path.is_file() {
+                (disabled_path, false)
+            } else if has_sample {
+                (dir.join(format!("{}.sample", name)), false)
+            } else {
+                return None;
+            };
+
+            Some(HookInfo {
+                name: name.to_string(),
+                is_executable: is_executable(&path),
+                path: path.to_string_lossy().to_string(),
+                is_enabled,
+                has_sample,
+            })
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn read_hook(repo_path: String, name: String) -> Result<String, AppError> {
+    validate_hook_name(&name)?;
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let dir = hooks_dir(&repo);
+
+    [name.clone(), format!("{}{}", name, DISABLED_SUFFIX), format!("{}.sample", name)]
+        .iter()
+        .map(|file| dir.join(file))
+        .find(|path| path.is_file())
+        .ok_or_else(|| AppError::not_found(format!("Hook {} does not exist", name)))
+        .and_then(|path| fs::read_to_string(&path).map_err(|e| AppError::wrap(e, "Failed to read hook")))
+}
+
+/// Enables or disables a hook by renaming it to/from `<name>.disabled`.
+/// Enabling a hook that only has a `.sample` installs the sample.
+#[tauri::command]
+pub fn toggle_hook(repo_path: String, name: String, enabled: bool) -> Result<String, AppError> {
+    validate_hook_name(&name)?;
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let dir = hooks_dir(&repo);
+
+    let active = dir.join(&name);
+    let disabled = dir.join(format!("{}{}", name, DISABLED_SUFFIX));
+    let sample = dir.join(format!("{}.sample", name));
+
+    if enabled {
+        if active.is_file() {
+            return Ok(format!("Hook {} is already enabled", name));
+        }
+        if disabled.is_file() {
+            fs::rename(&disabled, &active)
+                .map_err(|e| AppError::wrap(e, "Failed to enable hook"))?;
+        } else if sample.is_file() {
+            fs::copy(&sample, &active)
+                .map_err(|e| AppError::wrap(e, "Failed to install sample hook"))?;
+        } else {
+            return Err(AppError::not_found(format!("Hook {} does not exist", name)));
+        }
+        make_executable(&active)?;
+        Ok(format!("Enabled hook {}", name))
+    } else {
+        if !active.is_file() {
+            return Ok(format!("Hook {} is already disabled", name));
+        }
+        fs::rename(&active, &disabled)
+            .map_err(|e| AppError::wrap(e, "Failed to disable hook"))?;
+        Ok(format!("Disabled hook {}", name))
+    }
+}
+
+/// Runs a hook the way the git CLI does and reports the result as a `hook-output` event.
+/// Returns None when the hook is not installed.
+pub fn run_hook(app: &tauri::AppHandle, repo: &Repository, name: &str, args: &[&str]) -> Result<Option<HookResult>, String> {
+    let path = hooks_dir(repo).join(name);
+    if !path.is_file() || !is_executable(&path) {
+        return Ok(None);
+    }
+
+    let workdir = repo.workdir().unwrap_or_else(|| repo.path());
+
+    // Windows has no shebang support; Git for Windows runs hooks through its bundled sh
+    let mut command = if cfg!(windows) {
+        let mut command = std::process::Command::new("sh");
+        command.arg(&path);
+        command
+    } else {
+        std::process::Command::new(&path)
+    };
+
+    let output = command
+        .args(args)
+        .current_dir(workdir)
+        .env("GIT_DIR", repo.path())
+        .env("GIT_INDEX_FILE", repo.path().join("index"))
+        .output()
+        .map_err(|e| format!("Failed to run {} hook: {}", name, e))?;
+
+    let result = HookResult {
+        repo_path: workdir.to_string_lossy().to_string(),
+        hook: name.to_string(),
+        exit_code: output.status.code().unwrap_or(-1),
+        stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+        stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+    };
+
+    let _ = app.emit_all("hook-output", result.clone());
+    Ok(Some(result))
+}
+
+/// Runs a hook that can abort the operation, turning a non-zero exit into an error.
+pub fn run_blocking_hook(app: &tauri::AppHandle, repo: &Repository, name: &str, args: &[&str]) -> Result<(), String> {
+    match run_hook(app, repo, name, args)? {
+        Some(result) if result.exit_code != 0 => Err(format!(
+            "{} hook failed (exit code {}):\n{}{}",
+            name,
+            result.exit_code,
+            result.stdout,
+            result.stderr
+        )),
+        _ => Ok(()),
+    }
+}
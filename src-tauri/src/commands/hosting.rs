@@ -0,0 +1,339 @@
+use crate::commands::error::{AppError, ErrorKind};
+use crate::commands::secrets::load_secret;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+
+// Whole request, connection included; provider APIs answer well within it
+const API_TIMEOUT_SECS: &str = "30";
+// Release assets can be large
+const UPLOAD_TIMEOUT_SECS: &str = "600";
+/// REST API version every Azure DevOps request pins.
+pub const AZURE_API_VERSION: &str = "7.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Provider {
+    Github,
+    Bitbucket, // Bitbucket Cloud
+    AzureDevops,
+    Gitlab,
+}
+
+/// A repository on a hosting provider, as identified by one of the local remotes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedRepository {
+    pub provider: Provider,
+    pub host: String,
+    pub owner: String, // "organization/project" on dev.azure.com, "project" on *.visualstudio.com
+    pub name: String,
+}
+
+/// Root of the provider's REST API on `host`.
+pub fn api_base(provider: Provider, host: &str) -> String {
+    match provider {
+        Provider::Github if host == "github.com" => "https://api.github.com".to_string(),
+        // GitHub Enterprise Server
+        Provider::Github => format!("https://{}/api/v3", host),
+        Provider::Bitbucket => "https://api.bitbucket.org/2.0".to_string(),
+        // Azure DevOps APIs live under the project: <base>/<owner>/_apis/...
+        Provider::AzureDevops => format!("https://{}", host),
+        Provider::Gitlab => format!("https://{}/api/v4", host),
+    }
+}
+
+/// Authorization header value for the host's API, from the access token saved with
+/// save_secret as "provider-token:<host>": a personal access token on GitHub, GitLab and
+/// Azure DevOps, a repository or workspace access token on Bitbucket.
+pub fn authorization(provider: Provider, host: &str) -> Result<String, AppError> {
+    let token = load_secret(&format!("provider-token:{}", host))?
+        .ok_or_else(|| {
+            AppError::new(
+                ErrorKind::AuthRequired,
+                "provider.token_missing",
+                format!("No access token saved for {}", host),
+            )
+            .with_host(Some(host.to_string()))
+        })?;
+
+    Ok(match provider {
+        // Azure DevOps personal access tokens go in basic auth with an empty user name
+        Provider::AzureDevops => format!("Basic {}", BASE64.encode(format!(":{}", token))),
+        _ => format!("Bearer {}", token),
+    })
+}
+
+impl HostedRepository {
+    pub fn api_base(&self) -> String {
+        api_base(self.provider, &self.host)
+    }
+
+    pub fn authorization(&self) -> Result<String, AppError> {
+        authorization(self.provider, &self.host)
+    }
+
+    /// GitLab addresses projects by their URL-encoded path, subgroups included.
+    pub fn gitlab_project(&self) -> String {
+        percent_encode(&format!("{}/{}", self.owner, self.name), false)
+    }
+
+    /// The repository's page on the provider's website.
+    pub fn web_url(&self) -> String {
+        match self.provider {
+            Provider::AzureDevops => format!("https://{}/{}/_git/{}", self.host, self.owner, self.name),
+            _ => format!("https://{}/{}/{}", self.host, self.owner, self.name),
+        }
+    }
+}
+
+/// Percent-encodes a path or query value for a URL, keeping '/' when `keep_slash` is set.
+pub fn percent_encode(value: &str, keep_slash: bool) -> String {
+    value.bytes().map(|byte| match byte {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (byte as char).to_string(),
+        b'/' if keep_slash => "/".to_string(),
+        _ => format!("%{:02X}", byte),
+    }).collect()
+}
+
+/// Decodes %XX escapes; invalid escapes are kept as they are.
+pub fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Provider hosted on `host`. Self-hosted GitHub Enterprise and GitLab are recognized by a
+/// "github." or "gitlab." host name.
+pub fn detect_provider(host: &str) -> Option<Provider> {
+    if host == "github.com" || host.starts_with("github.") {
+        return Some(Provider::Github);
+    }
+    if host == "gitlab.com" || host.starts_with("gitlab.") {
+        return Some(Provider::Gitlab);
+    }
+    if host == "bitbucket.org" {
+        return Some(Provider::Bitbucket);
+    }
+    None
+}
+
+/// Host and repository path of an https://host/path(.git), ssh://git@host/path.git or
+/// git@host:path.git remote, with the ".git" suffix and surrounding slashes removed.
+pub fn split_remote_url(url: &str) -> Option<(String, String)> {
+    let host = crate::commands::error::remote_host(url)?;
+    let without_scheme = url.split_once("://").map(|(_, rest)| rest).unwrap_or(url);
+    let after_host = &without_scheme[without_scheme.find(host.as_str())? + host.len()..];
+
+    // Skip an ssh://host:22/ port or the ':' of scp-like syntax
+    let path = match after_host.strip_prefix(':') {
+        Some(rest) if url.contains("://") => rest.split_once('/').map(|(_, path)| path).unwrap_or(""),
+        Some(rest) => rest,
+        None => after_host,
+    };
+    let path = path.trim_matches('/');
+    let path = path.strip_suffix(".git").unwrap_or(path);
+
+    Some((host, path.to_string()))
+}
+
+/// Azure DevOps remotes: https://dev.azure.com/org/project/_git/repo,
+/// https://org.visualstudio.com/project/_git/repo and their SSH forms
+/// git@ssh.dev.azure.com:v3/org/project/repo and org@vs-ssh.visualstudio.com:v3/org/project/repo.
+fn azure_repository(host: &str, path: &str) -> Option<HostedRepository> {
+    let (host, owner, name) = if host == "ssh.dev.azure.com" || host == "vs-ssh.visualstudio.com" {
+        let mut parts = path.strip_prefix("v3/")?.splitn(3, '/');
+        let (organization, project, name) = (parts.next()?, parts.next()?, parts.next()?);
+        if host == "ssh.dev.azure.com" {
+            ("dev.azure.com".to_string(), format!("{}/{}", organization, project), name)
+        } else {
+            (format!("{}.visualstudio.com", organization), project.to_string(), name)
+        }
+    } else {
+        let (owner, name) = path.split_once("/_git/")?;
+        // Older visualstudio.com URLs include the collection
+        let owner = owner.strip_prefix("DefaultCollection/").unwrap_or(owner);
+        (host.to_string(), owner.to_string(), name)
+    };
+
+    if owner.is_empty() || name.is_empty() || name.contains('/') {
+        return None;
+    }
+    Some(HostedRepository {
+        provider: Provider::AzureDevops,
+        host,
+        owner,
+        name: name.to_string(),
+    })
+}
+
+/// Parses a remote URL (any form split_remote_url accepts) on a known provider.
+pub fn parse_remote_url(url: &str) -> Option<HostedRepository> {
+    let (host, path) = split_remote_url(url)?;
+    let host = host.to_lowercase();
+    if host == "dev.azure.com" || host.ends_with(".visualstudio.com") || host == "ssh.dev.azure.com" {
+        return azure_repository(&host, &path);
+    }
+
+    let (owner, name) = path.rsplit_once('/')?;
+    if owner.is_empty() || name.is_empty() {
+        return None;
+    }
+
+    Some(HostedRepository {
+        provider: detect_provider(&host)?,
+        host,
+        owner: owner.to_string(),
+        name: name.to_string(),
+    })
+}
+
+pub fn hosted_repository(repo: &Repository, remote_name: &str) -> Result<HostedRepository, AppError> {
+    let remote = repo.find_remote(remote_name)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
+    let url = remote.url().unwrap_or("");
+
+    parse_remote_url(url).ok_or_else(|| {
+        AppError::invalid_input(format!("Remote '{}' is not on a supported hosting provider: {}", remote_name, url))
+    })
+}
+
+/// Quotes a value for a curl config file.
+fn curl_config_value(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn provider_error_message(body: &serde_json::Value) -> Option<String> {
+    body.get("message")
+        .or_else(|| body.get("error").and_then(|error| error.get("message")))
+        .or_else(|| body.get("Message")) // Azure DevOps
+        .and_then(|message| message.as_str())
+        .map(|message| message.to_string())
+}
+
+fn request_config(authorization: &str) -> Vec<String> {
+    vec![
+        format!("header = {}", curl_config_value(&format!("Authorization: {}", authorization))),
+        format!("header = {}", curl_config_value("Accept: application/json")),
+        format!("header = {}", curl_config_value("User-Agent: CodeGit")),
+    ]
+}
+
+/// Calls a provider REST API through curl, which every supported OS ships. The credentials
+/// and body go in a config file on stdin so they never appear in the process list.
+pub fn api_request(
+    method: &str,
+    url: &str,
+    authorization: &str,
+    body: Option<&serde_json::Value>,
+) -> Result<serde_json::Value, AppError> {
+    let mut config = request_config(authorization);
+    if let Some(body) = body {
+        config.push(format!("header = {}", curl_config_value("Content-Type: application/json")));
+        config.push(format!("data-binary = {}", curl_config_value(&body.to_string())));
+    }
+
+    send_request(method, url, config, API_TIMEOUT_SECS)
+}
+
+/// Uploads a file with POST, as the raw request body or, with `form_field`, as that field
+/// of a multipart form.
+pub fn api_upload(
+    url: &str,
+    authorization: &str,
+    file: &Path,
+    form_field: Option<&str>,
+) -> Result<serde_json::Value, AppError> {
+    let mut config = request_config(authorization);
+    let file = file.to_string_lossy();
+    match form_field {
+        Some(field) => config.push(format!("form = {}", curl_config_value(&format!("{}=@{}", field, file)))),
+        None => {
+            config.push(format!("header = {}", curl_config_value("Content-Type: application/octet-stream")));
+            config.push(format!("data-binary = {}", curl_config_value(&format!("@{}", file))));
+        }
+    }
+
+    send_request("POST", url, config, UPLOAD_TIMEOUT_SECS)
+}
+
+fn send_request(
+    method: &str,
+    url: &str,
+    config: Vec<String>,
+    timeout_secs: &str,
+) -> Result<serde_json::Value, AppError> {
+    let mut command = std::process::Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--max-time", timeout_secs, "--config", "-"])
+        // Log downloads redirect to storage; curl drops the Authorization header on the new host
+        .arg("--location")
+        .args(["--request", method, "--write-out", "\n%{http_code}", url])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console window from the GUI
+        command.creation_flags(0x08000000);
+    }
+
+    let mut child = command.spawn()
+        .map_err(|e| AppError::wrap(e, "Failed to run curl"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(config.join("\n").as_bytes())
+            .map_err(|e| AppError::wrap(e, "Failed to send request to curl"))?;
+    }
+    let output = child.wait_with_output()
+        .map_err(|e| AppError::wrap(e, "Failed to run curl"))?;
+
+    if !output.status.success() {
+        return Err(AppError::new(
+            ErrorKind::Network,
+            "provider.unreachable",
+            format!("Request to {} failed: {}", url, String::from_utf8_lossy(&output.stderr).trim()),
+        ));
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let (response, status) = stdout.rsplit_once('\n').unwrap_or(("", &stdout));
+    let status: u16 = status.trim().parse().unwrap_or(0);
+    let response: serde_json::Value = if response.trim().is_empty() {
+        serde_json::Value::Null
+    } else {
+        serde_json::from_str(response).unwrap_or_else(|_| serde_json::Value::String(response.to_string()))
+    };
+
+    // Azure DevOps answers a rejected token with 203 and a sign-in page
+    if (200..300).contains(&status) && status != 203 {
+        return Ok(response);
+    }
+
+    let message = provider_error_message(&response)
+        .unwrap_or_else(|| format!("Request to {} failed with status {}", url, status));
+    let (kind, code) = match status {
+        401 | 403 | 203 => (ErrorKind::AuthRequired, "provider.unauthorized"),
+        404 => (ErrorKind::NotFound, "provider.not_found"),
+        409 | 422 => (ErrorKind::InvalidInput, "provider.rejected"),
+        _ => (ErrorKind::Network, "provider.error"),
+    };
+    Err(AppError::new(kind, code, message))
+}
@@ -0,0 +1,463 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::database::{IdentityProfile, UserInfo};
+use git2::{Config, ConfigLevel, Oid, Repository, Signature};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tauri::Manager;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IdentityCheck {
+    pub effective_name: Option<String>,
+    pub effective_email: Option<String>,
+    pub profile: Option<IdentityProfile>,
+    pub is_mismatch: bool,
+    pub warning: Option<String>,
+}
+
+/// The identity profile assigned to a repository (directly or via its organization).
+/// Best effort: without a database there is simply no profile.
+pub fn repository_profile(app: &tauri::AppHandle, repo_path: &str) -> Option<IdentityProfile> {
+    let db_state = app.try_state::<DatabaseState>()?;
+
+    let result = tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.resolve_identity_profile(repo_path).await
+    });
+
+    match result {
+        Ok(profile) => profile,
+        Err(e) => {
+            println!("⚠️  Failed to resolve identity profile: {}", e);
+            None
+        }
+    }
+}
+
+/// Whether the repository (or its organization) asks for Signed-off-by trailers.
+fn sign_off_enabled(app: &tauri::AppHandle, repo_path: &str) -> bool {
+    let db_state = match app.try_state::<DatabaseState>() {
+        Some(db_state) => db_state,
+        None => return false,
+    };
+
+    tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.resolve_sign_off(repo_path).await
+    })
+    .unwrap_or(false)
+}
+
+/// `message` with a "Signed-off-by: Name <email>" trailer for `committer` when the
+/// repository requires sign-off (DCO). Like `git commit -s`, the trailer joins an existing
+/// trailer block and is not repeated when it is already the last sign-off.
+pub fn with_sign_off(app: &tauri::AppHandle, repo_path: &str, message: &str, committer: &Signature) -> String {
+    if !sign_off_enabled(app, repo_path) {
+        return message.to_string();
+    }
+
+    let trailer = format!(
+        "Signed-off-by: {} <{}>",
+        committer.name().unwrap_or(""),
+        committer.email().unwrap_or("")
+    );
+    let message = message.trim_end();
+    let last_paragraph = message.rsplit("\n\n").next().unwrap_or("");
+    let is_trailer_block = message.contains("\n\n") && last_paragraph.lines().all(|line| {
+        line.split_once(": ")
+            .map(|(key, _)| !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'))
+            .unwrap_or(false)
+            || line.starts_with("(cherry picked from commit ")
+    });
+
+    if is_trailer_block && last_paragraph.lines().last() == Some(trailer.as_str()) {
+        format!("{}\n", message)
+    } else if is_trailer_block {
+        format!("{}\n{}\n", message, trailer)
+    } else {
+        format!("{}\n\n{}\n", message, trailer)
+    }
+}
+
+/// The active CodeGit user profile, if any.
+fn active_user(app: &tauri::AppHandle) -> Option<UserInfo> {
+    let db_state = app.try_state::<DatabaseState>()?;
+
+    tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.get_user().await
+    })
+    .ok()
+    .flatten()
+}
+
+/// user.name and user.email set in the repository's own config, not inherited.
+fn local_identity(repo: &Repository) -> (Option<String>, Option<String>) {
+    let config = match repo.config().and_then(|config| config.open_level(ConfigLevel::Local)) {
+        Ok(config) => config,
+        Err(_) => return (None, None),
+    };
+    (config.get_string("user.name").ok(), config.get_string("user.email").ok())
+}
+
+/// Signature for commits created by CodeGit. Explicit name/email win, then the
+/// repository's identity profile, then user.name/user.email from the repository's own
+/// config, then the active user profile, then the rest of git config.
+pub fn resolve_signature(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    author_name: Option<String>,
+    author_email: Option<String>,
+) -> Result<Signature<'static>, String> {
+    let (name, email) = match (author_name, author_email) {
+        (Some(name), Some(email)) => (name, email),
+        (name, email) => {
+            let (default_name, default_email) = match repository_profile(app, repo_path) {
+                Some(profile) => (Some(profile.author_name), Some(profile.author_email)),
+                None => {
+                    let (local_name, local_email) = local_identity(repo);
+                    let (fallback_name, fallback_email) = match active_user(app) {
+                        Some(user) => (Some(user.name), Some(user.email)),
+                        None => {
+                            let config_signature = repo.signature().ok();
+                            (
+                                config_signature.as_ref().and_then(|s| s.name().map(|n| n.to_string())),
+                                config_signature.as_ref().and_then(|s| s.email().map(|e| e.to_string())),
+                            )
+                        }
+                    };
+                    (local_name.or(fallback_name), local_email.or(fallback_email))
+                }
+            };
+
+            let name = name.or(default_name)
+                .ok_or_else(|| "No author name configured; assign an identity profile or set user.name".to_string())?;
+            let email = email.or(default_email)
+                .ok_or_else(|| "No author email configured; assign an identity profile or set user.email".to_string())?;
+            (name, email)
+        }
+    };
+
+    Signature::now(&name, &email)
+        .map_err(|e| format!("Failed to create signature: {}", e))
+}
+
+#[derive(Debug, Clone)]
+pub struct SigningConfig {
+    pub format: String, // 'openpgp' | 'ssh'
+    pub key: String,
+}
+
+/// Whether and how to sign. A profile with a signing key decides on its own;
+/// otherwise git config applies (`config_key` is commit.gpgSign or tag.gpgSign,
+/// plus user.signingKey and gpg.format).
+pub fn signing_config(app: &tauri::AppHandle, repo: &Repository, repo_path: &str, config_key: &str) -> Option<SigningConfig> {
+    if let Some(profile) = repository_profile(app, repo_path) {
+        if let Some(key) = profile.signing_key {
+            return profile.sign_by_default.then(|| SigningConfig {
+                format: profile.signing_format.unwrap_or_else(|| "openpgp".to_string()),
+                key,
+            });
+        }
+    }
+
+    let config = repo.config().ok()?;
+    if !config.get_bool(config_key).unwrap_or(false) {
+        return None;
+    }
+
+    Some(SigningConfig {
+        format: config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string()),
+        key: config.get_string("user.signingkey").ok()?,
+    })
+}
+
+/// The public key in a literal user.signingKey ("ssh-ed25519 AAAA…" or "key::…"), which
+/// git signs with through the agent instead of reading a key file.
+fn literal_ssh_key(key: &str) -> Option<&str> {
+    key.strip_prefix("key::").or_else(|| key.starts_with("ssh-").then_some(key))
+}
+
+/// A signing key path, with a leading "~/" expanded to the home directory.
+fn expand_home(path: &str) -> PathBuf {
+    path.strip_prefix("~/")
+        .and_then(|rest| crate::user_home_dir().map(|home| home.join(rest)))
+        .unwrap_or_else(|| PathBuf::from(path))
+}
+
+/// Produces an armored detached signature for a commit or tag buffer with gpg or ssh-keygen.
+pub fn sign_buffer(signing: &SigningConfig, buffer: &str) -> Result<String, String> {
+    // A literal ssh key is handed to ssh-keygen through a temporary file
+    let mut key_file = None;
+    let command = match signing.format.as_str() {
+        "openpgp" => {
+            let mut command = Command::new("gpg");
+            command.args(["--status-fd=2", "-bsau", &signing.key]);
+            command
+        }
+        "ssh" => {
+            let mut command = Command::new("ssh-keygen");
+            command.args(["-Y", "sign", "-n", "git"]);
+            match literal_ssh_key(&signing.key) {
+                Some(public_key) => {
+                    let path = std::env::temp_dir().join(format!(
+                        "codegit-signing-key-{}-{}.pub",
+                        std::process::id(),
+                        chrono::Utc::now().timestamp_nanos_opt().unwrap_or(0)
+                    ));
+                    std::fs::write(&path, format!("{}\n", public_key.trim()))
+                        .map_err(|e| format!("Failed to prepare the signing key: {}", e))?;
+                    command.arg("-U").arg("-f").arg(&path);
+                    key_file = Some(path);
+                }
+                None => {
+                    command.arg("-f").arg(expand_home(&signing.key));
+                }
+            }
+            command
+        }
+        other => return Err(format!("Unsupported signing format: {}", other)),
+    };
+
+    let signature = run_signer(command, &signing.format, buffer);
+    if let Some(path) = key_file {
+        let _ = std::fs::remove_file(path);
+    }
+    signature
+}
+
+fn run_signer(mut command: Command, format: &str, buffer: &str) -> Result<String, String> {
+    let mut child = command
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run {} signer: {}", format, e))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(buffer.as_bytes())
+            .map_err(|e| format!("Failed to pass data to signer: {}", e))?;
+    }
+
+    let output = child.wait_with_output()
+        .map_err(|e| format!("Failed to run {} signer: {}", format, e))?;
+
+    if !output.status.success() {
+        return Err(format!("Signing failed: {}", String::from_utf8_lossy(&output.stderr).trim()));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Creates a commit, signed when `signing` is set, and moves `update_ref` to it.
+/// "HEAD" updates the checked-out branch (or the detached HEAD).
+#[allow(clippy::too_many_arguments)]
+pub fn create_commit(
+    repo: &Repository,
+    signing: Option<&SigningConfig>,
+    update_ref: Option<&str>,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+    tree: &git2::Tree,
+    parents: &[&git2::Commit],
+) -> Result<Oid, String> {
+    let signing = match signing {
+        Some(signing) => signing,
+        None => return repo.commit(update_ref, author, committer, message, tree, parents)
+            .map_err(|e| e.to_string()),
+    };
+
+    let buffer = repo.commit_create_buffer(author, committer, message, tree, parents)
+        .map_err(|e| e.to_string())?;
+    let buffer = buffer.as_str()
+        .ok_or_else(|| "Commit content is not valid UTF-8".to_string())?;
+    let signature = sign_buffer(signing, buffer)?;
+
+    let commit_id = repo.commit_signed(buffer, &signature, Some("gpgsig"))
+        .map_err(|e| e.to_string())?;
+
+    if let Some(update_ref) = update_ref {
+        let log_message = format!("commit: {}", message.lines().next().unwrap_or(""));
+        let ref_name = if update_ref == "HEAD" { crate::head_ref_name(repo) } else { update_ref.to_string() };
+
+        if ref_name == "HEAD" {
+            repo.set_head_detached(commit_id)
+                .map_err(|e| e.to_string())?;
+        } else {
+            repo.reference(&ref_name, commit_id, true, &log_message)
+                .map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(commit_id)
+}
+
+/// "Name <email> seconds +hhmm", as signatures appear in object headers.
+fn signature_header(signature: &Signature) -> String {
+    let offset = signature.when().offset_minutes();
+    format!(
+        "{} <{}> {} {}{:02}{:02}",
+        signature.name().unwrap_or(""),
+        signature.email().unwrap_or(""),
+        signature.when().seconds(),
+        if offset < 0 { '-' } else { '+' },
+        offset.abs() / 60,
+        offset.abs() % 60
+    )
+}
+
+/// Creates an annotated tag, signed when `signing` is set. Fails if the tag exists.
+pub fn create_annotated_tag(
+    repo: &Repository,
+    signing: Option<&SigningConfig>,
+    name: &str,
+    target: &git2::Object,
+    tagger: &Signature,
+    message: &str,
+) -> Result<Oid, String> {
+    let signing = match signing {
+        Some(signing) => signing,
+        None => return repo.tag(name, target, tagger, message, false)
+            .map_err(|e| e.to_string()),
+    };
+
+    let ref_name = format!("refs/tags/{}", name);
+    if repo.find_reference(&ref_name).is_ok() {
+        return Err(format!("Tag '{}' already exists", name));
+    }
+
+    // git appends the armored signature to the tag message
+    let message = if message.ends_with('\n') { message.to_string() } else { format!("{}\n", message) };
+    let buffer = format!(
+        "object {}\ntype {}\ntag {}\ntagger {}\n\n{}",
+        target.id(),
+        target.kind().map(|kind| kind.str()).unwrap_or("commit"),
+        name,
+        signature_header(tagger),
+        message
+    );
+    let signature = sign_buffer(signing, &buffer)?;
+
+    let tag_id = repo.odb()
+        .and_then(|odb| odb.write(git2::ObjectType::Tag, format!("{}{}", buffer, signature).as_bytes()))
+        .map_err(|e| e.to_string())?;
+    repo.reference(&ref_name, tag_id, false, &format!("tag: {}", name))
+        .map_err(|e| e.to_string())?;
+
+    Ok(tag_id)
+}
+
+fn check_identity(app: &tauri::AppHandle, repo_path: &str) -> Result<IdentityCheck, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+    let config = repo.config()
+        .map_err(|e| format!("Failed to open repository config: {}", e))?;
+
+    let effective_name = config.get_string("user.name").ok();
+    let effective_email = config.get_string("user.email").ok();
+    let profile = repository_profile(app, repo_path);
+
+    let warning = profile.as_ref().and_then(|profile| {
+        let matches = effective_email
+            .as_ref()
+            .map(|email| email.eq_ignore_ascii_case(&profile.author_email))
+            .unwrap_or(false);
+        if matches {
+            return None;
+        }
+        Some(format!(
+            "git would commit as {} but the assigned profile '{}' uses {}",
+            effective_email.as_deref().unwrap_or("<no email>"),
+            profile.name,
+            profile.author_email
+        ))
+    });
+
+    Ok(IdentityCheck {
+        effective_name,
+        effective_email,
+        profile,
+        is_mismatch: warning.is_some(),
+        warning,
+    })
+}
+
+/// Warning shown in repository info when git config disagrees with the assigned profile.
+pub fn identity_warning(app: &tauri::AppHandle, repo_path: &str) -> Option<String> {
+    check_identity(app, repo_path).ok().and_then(|check| check.warning)
+}
+
+#[tauri::command]
+pub fn check_repository_identity(app: tauri::AppHandle, repo_path: String) -> Result<IdentityCheck, AppError> {
+    check_identity(&app, &repo_path).map_err(AppError::from)
+}
+
+fn write_identity(config: &mut Config, profile: &IdentityProfile) -> Result<(), String> {
+    config.set_str("user.name", &profile.author_name)
+        .map_err(|e| format!("Failed to set user.name: {}", e))?;
+    config.set_str("user.email", &profile.author_email)
+        .map_err(|e| format!("Failed to set user.email: {}", e))?;
+    if let Some(signing_key) = &profile.signing_key {
+        config.set_str("user.signingkey", signing_key)
+            .map_err(|e| format!("Failed to set user.signingkey: {}", e))?;
+    }
+    Ok(())
+}
+
+/// Makes git use the assigned profile for this repository.
+/// `mode` is "local" (write user.* to the repository config) or "include_if"
+/// (write the profile to its own file and include it from the global config for
+/// every repository under `directory`, which defaults to the repository's parent).
+#[tauri::command]
+pub fn fix_repository_identity(
+    app: tauri::AppHandle,
+    repo_path: String,
+    mode: String,
+    directory: Option<String>,
+) -> Result<String, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let profile = repository_profile(&app, &repo_path)
+        .ok_or_else(|| "No identity profile is assigned to this repository".to_string())?;
+
+    match mode.as_str() {
+        "local" => {
+            let mut config = repo.config()
+                .and_then(|config| config.open_level(git2::ConfigLevel::Local))
+                .map_err(|e| AppError::wrap(e, "Failed to open repository config"))?;
+            write_identity(&mut config, &profile)?;
+            Ok(format!("Repository now commits as {}", profile.author_email))
+        }
+        "include_if" => {
+            let directory = match directory {
+                Some(directory) => std::path::PathBuf::from(directory),
+                None => repo.workdir()
+                    .unwrap_or_else(|| repo.path())
+                    .parent()
+                    .map(|parent| parent.to_path_buf())
+                    .ok_or_else(|| "Failed to determine the repository's parent directory".to_string())?,
+            };
+
+            let global_path = crate::global_git_config_path()?;
+            let include_path = global_path
+                .with_file_name(format!(".gitconfig-codegit-{}", profile.id));
+
+            let mut include_config = Config::open(&include_path)
+                .map_err(|e| AppError::wrap(e, &format!("Failed to create {}", include_path.display())))?;
+            write_identity(&mut include_config, &profile)?;
+
+            // gitdir patterns use forward slashes and a trailing slash to match everything below
+            let gitdir = format!("{}/", directory.to_string_lossy().replace('\\', "/").trim_end_matches('/'));
+            let mut global = crate::global_git_config()?;
+            global.set_str(&format!("includeIf.gitdir:{}.path", gitdir), &include_path.to_string_lossy())
+                .map_err(|e| AppError::wrap(e, "Failed to write includeIf"))?;
+
+            Ok(format!("Repositories under {} now commit as {}", gitdir, profile.author_email))
+        }
+        _ => Err(AppError::invalid_input(format!("Invalid fix mode: {}. Use 'local' or 'include_if'", mode))),
+    }
+}
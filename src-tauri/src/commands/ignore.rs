@@ -0,0 +1,244 @@
+use crate::commands::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IgnoreMatch {
+    pub path: String,
+    pub is_ignored: bool,
+    pub source: Option<String>,
+    pub line_number: Option<usize>,
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct GlobalExcludes {
+    pub path: String,
+    pub exists: bool,
+    pub is_configured: bool,
+    pub content: String,
+}
+
+/// Appends `pattern` to an ignore file unless an identical line already exists.
+/// Returns false when the pattern was already present.
+pub fn append_ignore_pattern(file: &Path, pattern: &str) -> Result<bool, String> {
+    let content = fs::read_to_string(file).unwrap_or_default();
+    if content.lines().any(|line| line.trim() == pattern) {
+        return Ok(false);
+    }
+
+    let mut updated = content;
+    if !updated.is_empty() && !updated.ends_with('\n') {
+        updated.push('\n');
+    }
+    updated.push_str(pattern);
+    updated.push('\n');
+
+    fs::write(file, updated)
+        .map_err(|e| format!("Failed to write {}: {}", file.display(), e))?;
+    Ok(true)
+}
+
+fn gitignore_path(repo_path: &str) -> Result<PathBuf, String> {
+    let repo = crate::open_worktree_repository(repo_path)?;
+    let workdir = repo.workdir()
+        .ok_or_else(|| "Repository has no working tree".to_string())?;
+    Ok(workdir.join(".gitignore"))
+}
+
+#[tauri::command]
+pub fn get_gitignore(repo_path: String) -> Result<String, AppError> {
+    let path = gitignore_path(&repo_path)?;
+
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(String::new()),
+        Err(e) => Err(AppError::wrap(e, "Failed to read .gitignore")),
+    }
+}
+
+#[tauri::command]
+pub fn save_gitignore(repo_path: String, content: String) -> Result<String, AppError> {
+    let path = gitignore_path(&repo_path)?;
+
+    fs::write(&path, content)
+        .map_err(|e| AppError::wrap(e, "Failed to write .gitignore"))?;
+
+    Ok("Saved .gitignore".to_string())
+}
+
+/// Builds the pattern for "ignore this file/folder/extension" from a repository-relative path.
+fn ignore_pattern(target: &str, kind: &str) -> Result<String, AppError> {
+    let target = target.trim().trim_start_matches("./").trim_matches('/');
+    if target.is_empty() {
+        return Err(AppError::invalid_input("Nothing to ignore"));
+    }
+
+    match kind {
+        "file" => Ok(format!("/{}", target)),
+        "folder" => Ok(format!("/{}/", target)),
+        "extension" => {
+            let extension = Path::new(target)
+                .extension()
+                .map(|extension| extension.to_string_lossy().to_string())
+                .unwrap_or_else(|| target.trim_start_matches("*.").trim_start_matches('.').to_string());
+            Ok(format!("*.{}", extension))
+        }
+        _ => Err(AppError::invalid_input(format!("Invalid ignore kind: {}. Use 'file', 'folder' or 'extension'", kind))),
+    }
+}
+
+/// Adds an ignore rule for a file, folder or extension to the repository's .gitignore.
+/// Returns the pattern that was added.
+#[tauri::command]
+pub fn add_ignore_pattern(repo_path: String, target: String, kind: String) -> Result<String, AppError> {
+    let path = gitignore_path(&repo_path)?;
+    let pattern = ignore_pattern(&target, &kind)?;
+
+    if !append_ignore_pattern(&path, &pattern)? {
+        return Err(AppError::conflict(format!("{} is already in .gitignore", pattern), Vec::new()));
+    }
+
+    Ok(pattern)
+}
+
+/// Explains for each path whether it is ignored and which rule (file, line, pattern) decides it.
+#[tauri::command]
+pub fn check_ignored(repo_path: String, paths: Vec<String>) -> Result<Vec<IgnoreMatch>, AppError> {
+    crate::open_worktree_repository(&repo_path)?;
+
+    if paths.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut child = crate::git_cli(Some(&repo_path))
+        .args(["check-ignore", "--verbose", "--non-matching", "-z", "--stdin"])
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| AppError::wrap(e, "Failed to run git"))?;
+
+    // -z requires the paths on stdin, NUL separated. It is written from its own thread
+    // because git answers as it reads, and a full stdout pipe would block both sides.
+    let writer = child.stdin.take().map(|mut stdin| {
+        let input: Vec<u8> = paths.iter().flat_map(|path| path.bytes().chain(std::iter::once(0))).collect();
+        std::thread::spawn(move || stdin.write_all(&input))
+    });
+
+    let output = child.wait_with_output()
+        .map_err(|e| AppError::wrap(e, "Failed to run git"))?;
+    if let Some(writer) = writer {
+        writer.join()
+            .map_err(|_| AppError::from("Failed to pass paths to git"))?
+            .map_err(|e| AppError::wrap(e, "Failed to pass paths to git"))?;
+    }
+
+    // Exit code 1 just means that none of the paths are ignored
+    if !matches!(output.status.code(), Some(0) | Some(1)) {
+        return Err(format!("Failed to check ignored paths: {}", String::from_utf8_lossy(&output.stderr).trim()).into());
+    }
+
+    // With -z every path produces four fields: source, line number, pattern, path
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout.split('\0').collect();
+
+    Ok(fields
+        .chunks_exact(4)
+        .map(|record| {
+            let (source, line_number, pattern, path) = (record[0], record[1], record[2], record[3]);
+            let matched = !pattern.is_empty();
+
+            IgnoreMatch {
+                path: path.to_string(),
+                // A matching negated rule ("!pattern") re-includes the path
+                is_ignored: matched && !pattern.starts_with('!'),
+                source: Some(source.to_string()).filter(|source| !source.is_empty()),
+                line_number: line_number.parse().ok(),
+                pattern: Some(pattern.to_string()).filter(|_| matched),
+            }
+        })
+        .collect())
+}
+
+/// core.excludesFile, or git's default $XDG_CONFIG_HOME/git/ignore when it is not set.
+/// The flag tells whether the path came from config.
+fn global_excludes_path() -> Result<(PathBuf, bool), String> {
+    let configured = git2::Config::open_default()
+        .and_then(|config| config.get_path("core.excludesFile"))
+        .ok();
+    if let Some(path) = configured {
+        return Ok((path, true));
+    }
+
+    let config_home = std::env::var("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|_| {
+            std::env::var("HOME")
+                .or_else(|_| std::env::var("USERPROFILE"))
+                .map(|home| PathBuf::from(home).join(".config"))
+        })
+        .map_err(|_| "Failed to locate the home directory".to_string())?;
+
+    Ok((config_home.join("git").join("ignore"), false))
+}
+
+fn ensure_parent_dir(path: &Path) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub fn get_global_excludes() -> Result<GlobalExcludes, AppError> {
+    let (path, is_configured) = global_excludes_path()?;
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(AppError::wrap(e, &format!("Failed to read {}", path.display()))),
+    };
+
+    Ok(GlobalExcludes {
+        path: path.to_string_lossy().to_string(),
+        exists: path.exists(),
+        is_configured,
+        content,
+    })
+}
+
+#[tauri::command]
+pub fn save_global_excludes(content: String) -> Result<String, AppError> {
+    let (path, _) = global_excludes_path()?;
+    ensure_parent_dir(&path)?;
+
+    fs::write(&path, content)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to write {}", path.display())))?;
+
+    Ok(format!("Saved {}", path.display()))
+}
+
+/// Adds patterns (e.g. .DS_Store, .idea/) to the global excludes file, creating it if
+/// needed. Returns the patterns that were not already present.
+#[tauri::command]
+pub fn add_global_exclude_patterns(patterns: Vec<String>) -> Result<Vec<String>, AppError> {
+    let (path, _) = global_excludes_path()?;
+    ensure_parent_dir(&path)?;
+
+    let mut added = Vec::new();
+    for pattern in patterns {
+        let pattern = pattern.trim();
+        if pattern.is_empty() || pattern.starts_with('#') {
+            continue;
+        }
+        if append_ignore_pattern(&path, pattern)? {
+            added.push(pattern.to_string());
+        }
+    }
+
+    Ok(added)
+}
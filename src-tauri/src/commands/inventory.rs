@@ -0,0 +1,112 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::database::InventoryEntry;
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use tauri::State;
+
+const INVENTORY_FORMATS: [&str; 2] = ["json", "csv"];
+const CSV_COLUMNS: [&str; 9] = [
+    "name", "path", "remote_url", "organization", "tags", "current_branch", "last_commit", "last_commit_at", "last_accessed",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryRepository {
+    pub name: String,
+    pub path: String,
+    pub remote_url: Option<String>,
+    pub organization: Option<String>,
+    pub tags: Vec<String>,
+    pub current_branch: String,
+    pub last_commit: String,
+    pub last_commit_at: Option<String>, // Committer date of HEAD; None when the repository can't be read
+    pub last_accessed: String,
+}
+
+fn inventory_repository(entry: InventoryEntry) -> InventoryRepository {
+    let repository = entry.repository;
+    let last_commit_at = git2::Repository::open(&repository.path)
+        .ok()
+        .and_then(|repo| repo.head().ok()?.peel_to_commit().ok().map(|commit| commit.time().seconds()))
+        .and_then(|seconds| Utc.timestamp_opt(seconds, 0).single())
+        .map(|time| time.to_rfc3339());
+
+    InventoryRepository {
+        name: repository.name,
+        path: repository.path,
+        remote_url: repository.remote_url,
+        organization: entry.organization,
+        tags: repository.tags
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.as_str().map(|tag| tag.to_string()))
+            .collect(),
+        current_branch: repository.current_branch,
+        last_commit: repository.last_commit,
+        last_commit_at,
+        last_accessed: repository.last_accessed.to_rfc3339(),
+    }
+}
+
+/// RFC 4180 field: quoted when it holds a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn to_csv(repositories: &[InventoryRepository]) -> String {
+    let mut csv = CSV_COLUMNS.join(",");
+    csv.push_str("\r\n");
+
+    for repository in repositories {
+        let fields = [
+            repository.name.clone(),
+            repository.path.clone(),
+            repository.remote_url.clone().unwrap_or_default(),
+            repository.organization.clone().unwrap_or_default(),
+            repository.tags.join(";"),
+            repository.current_branch.clone(),
+            repository.last_commit.clone(),
+            repository.last_commit_at.clone().unwrap_or_default(),
+            repository.last_accessed.clone(),
+        ];
+        let row: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&row.join(","));
+        csv.push_str("\r\n");
+    }
+    csv
+}
+
+/// Every registered repository (all profiles and workspaces) with its path, remote,
+/// organization, tags and last activity, as "json" or "csv" text for team inventories
+/// and backups. Tags are separated by ';' in CSV.
+#[tauri::command]
+pub async fn export_repositories(
+    db_state: State<'_, DatabaseState>,
+    format: String,
+) -> Result<String, AppError> {
+    if !INVENTORY_FORMATS.contains(&format.as_str()) {
+        return Err(AppError::invalid_input(format!("Unsupported inventory format: {}", format)));
+    }
+
+    let entries = {
+        let db = db_state.lock().await;
+        db.get_repository_inventory()
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to get repositories"))?
+    };
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let repositories: Vec<InventoryRepository> = entries.into_iter().map(inventory_repository).collect();
+        match format.as_str() {
+            "csv" => Ok(to_csv(&repositories)),
+            _ => serde_json::to_string_pretty(&repositories)
+                .map_err(|e| AppError::wrap(e, "Failed to serialize repositories")),
+        }
+    })
+    .await?
+}
@@ -0,0 +1,347 @@
+use crate::commands::database::repo_settings_for;
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_request, hosted_repository, parse_remote_url, HostedRepository, Provider};
+use git2::Repository;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::Manager;
+
+const ISSUE_PAGE_SIZE: usize = 50;
+// Recent commits scanned for references already in use, e.g. tracker keys with no provider API
+const HISTORY_SCAN_LIMIT: usize = 500;
+const SUGGESTION_LIMIT: usize = 20;
+// Suggestions are asked for as the user types, so the issue list is reused this long
+const ISSUE_CACHE_TTL: Duration = Duration::from_secs(60);
+// "#123" not preceded by a word character, so "abc#1" and URL fragments don't match
+const DEFAULT_ISSUE_PATTERN: &str = r"\B#\d+\b";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Issue {
+    pub provider: Provider,
+    pub number: u64,
+    pub url: String,
+    pub title: String,
+    pub state: String, // open, closed
+    pub author: Option<String>,
+    pub labels: Vec<String>,
+}
+
+/// An issue reference as written in a commit message: "#123" or a key matched by one of
+/// the repository's issue patterns, such as "PROJ-42".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IssueReference {
+    pub reference: String,
+    pub title: Option<String>,
+    pub url: Option<String>,
+}
+
+/// Open issues fetched for suggestions, by repository path, with when they were fetched.
+#[derive(Default)]
+pub struct IssueSuggestionCache(Mutex<HashMap<String, (Instant, Vec<Issue>)>>);
+
+fn github_issue(value: &serde_json::Value) -> Issue {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+
+    Issue {
+        provider: Provider::Github,
+        number: value["number"].as_u64().unwrap_or(0),
+        url: text("/html_url"),
+        title: text("/title"),
+        state: text("/state"),
+        author: value.pointer("/user/login").and_then(|v| v.as_str()).map(|login| login.to_string()),
+        labels: value["labels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|label| label["name"].as_str().map(|name| name.to_string()))
+            .collect(),
+    }
+}
+
+fn bitbucket_issue(value: &serde_json::Value) -> Issue {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let state = match text("/state").as_str() {
+        "new" | "open" | "on hold" => "open",
+        _ => "closed", // resolved, closed, invalid, duplicate, wontfix
+    };
+
+    Issue {
+        provider: Provider::Bitbucket,
+        number: value["id"].as_u64().unwrap_or(0),
+        url: text("/links/html/href"),
+        title: text("/title"),
+        state: state.to_string(),
+        author: value.pointer("/reporter/display_name").and_then(|v| v.as_str()).map(|name| name.to_string()),
+        // Bitbucket has no labels; the kind (bug, enhancement, ...) plays that role
+        labels: value["kind"].as_str().map(|kind| vec![kind.to_string()]).unwrap_or_default(),
+    }
+}
+
+fn gitlab_issue(value: &serde_json::Value) -> Issue {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let state = if text("/state") == "opened" { "open" } else { "closed" };
+
+    Issue {
+        provider: Provider::Gitlab,
+        number: value["iid"].as_u64().unwrap_or(0),
+        url: text("/web_url"),
+        title: text("/title"),
+        state: state.to_string(),
+        author: value.pointer("/author/username").and_then(|v| v.as_str()).map(|name| name.to_string()),
+        labels: value["labels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|label| label.as_str().map(|name| name.to_string()))
+            .collect(),
+    }
+}
+
+/// Issues in `state` ("open", "closed" or "all"), most recently updated first, first page only.
+fn fetch_issues(hosted: &HostedRepository, state: &str) -> Result<Vec<Issue>, AppError> {
+    let auth = hosted.authorization()?;
+
+    let issues = match hosted.provider {
+        Provider::Github => {
+            let url = format!(
+                "{}/repos/{}/{}/issues?state={}&sort=updated&per_page={}",
+                hosted.api_base(), hosted.owner, hosted.name, state, ISSUE_PAGE_SIZE
+            );
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to list issues").with_host(Some(hosted.host.clone())))?;
+            // The issues endpoint includes pull requests, which carry a "pull_request" key
+            response.as_array()
+                .into_iter()
+                .flatten()
+                .filter(|item| item.get("pull_request").is_none())
+                .map(github_issue)
+                .collect::<Vec<_>>()
+        }
+        Provider::Bitbucket => {
+            let url = format!(
+                "{}/repositories/{}/{}/issues?sort=-updated_on&pagelen={}",
+                hosted.api_base(), hosted.owner, hosted.name, ISSUE_PAGE_SIZE
+            );
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to list issues").with_host(Some(hosted.host.clone())))?;
+            response["values"].as_array().into_iter().flatten().map(bitbucket_issue).collect()
+        }
+        Provider::Gitlab => {
+            let gitlab_state = match state {
+                "open" => "opened",
+                other => other,
+            };
+            let url = format!(
+                "{}/projects/{}/issues?state={}&order_by=updated_at&per_page={}",
+                hosted.api_base(), hosted.gitlab_project(), gitlab_state, ISSUE_PAGE_SIZE
+            );
+            let response = api_request("GET", &url, &auth, None)
+                .map_err(|e| AppError::wrap(e, "Failed to list issues").with_host(Some(hosted.host.clone())))?;
+            response.as_array().into_iter().flatten().map(gitlab_issue).collect()
+        }
+        Provider::AzureDevops => {
+            return Err(AppError::invalid_input("Azure DevOps tracks work items, which aren't listed as issues"));
+        }
+    };
+
+    Ok(issues
+        .into_iter()
+        .filter(|issue| state == "all" || issue.state == state)
+        .collect())
+}
+
+/// The repository's issue patterns, or "#123" when none are configured. Patterns are
+/// validated when settings are saved, so one that fails to compile here is skipped.
+fn issue_patterns(app: &tauri::AppHandle, repo_path: &str) -> Vec<Regex> {
+    let configured = repo_settings_for(app, repo_path).issue_patterns;
+    let patterns = if configured.is_empty() {
+        vec![DEFAULT_ISSUE_PATTERN.to_string()]
+    } else {
+        configured
+    };
+
+    patterns.iter().filter_map(|pattern| Regex::new(pattern).ok()).collect()
+}
+
+/// References in `message` in order of first appearance, without duplicates.
+fn find_references(patterns: &[Regex], message: &str) -> Vec<String> {
+    let mut references: Vec<(usize, String)> = Vec::new();
+    for pattern in patterns {
+        for found in pattern.find_iter(message) {
+            if !references.iter().any(|(_, reference)| reference == found.as_str()) {
+                references.push((found.start(), found.as_str().to_string()));
+            }
+        }
+    }
+
+    references.sort_by_key(|(start, _)| *start);
+    references.into_iter().map(|(_, reference)| reference).collect()
+}
+
+/// Web page for a "#123" reference; other keys belong to trackers the app doesn't know.
+fn issue_url(hosted: Option<&HostedRepository>, reference: &str) -> Option<String> {
+    let hosted = hosted?;
+    let number = reference.strip_prefix('#')?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+
+    match hosted.provider {
+        // "#N" is a work item on Azure DevOps
+        Provider::AzureDevops => Some(format!("https://{}/{}/_workitems/edit/{}", hosted.host, hosted.owner, number)),
+        Provider::Gitlab => Some(format!("{}/-/issues/{}", hosted.web_url(), number)),
+        // GitHub redirects /issues/N to the pull request when N is one
+        _ => Some(format!("{}/issues/{}", hosted.web_url(), number)),
+    }
+}
+
+/// The hosted repository behind the default remote, if it is on a known provider.
+fn default_hosted_repository(app: &tauri::AppHandle, repo: &Repository, repo_path: &str) -> Option<HostedRepository> {
+    let remote_name = crate::default_remote_name(app, repo_path);
+    let remote = repo.find_remote(&remote_name).ok()?;
+    parse_remote_url(remote.url()?)
+}
+
+/// Open issues of `hosted`, fetched at most once per ISSUE_CACHE_TTL for each repository.
+/// A failed fetch is remembered as an empty list, so typing offline doesn't retry it.
+fn cached_open_issues(app: &tauri::AppHandle, repo_path: &str, hosted: &HostedRepository) -> Vec<Issue> {
+    let cache = app.state::<IssueSuggestionCache>();
+    let cached = cache.0.lock().ok().and_then(|entries| entries.get(repo_path).cloned());
+    if let Some((fetched_at, issues)) = cached {
+        if fetched_at.elapsed() < ISSUE_CACHE_TTL {
+            return issues;
+        }
+    }
+
+    let issues = fetch_issues(hosted, "open").unwrap_or_else(|e| {
+        println!("⚠️  Issue suggestions limited to history: {}", e.message);
+        Vec::new()
+    });
+    if let Ok(mut entries) = cache.0.lock() {
+        entries.insert(repo_path.to_string(), (Instant::now(), issues.clone()));
+    }
+    issues
+}
+
+/// References used in the most recent commit messages reachable from HEAD.
+fn references_in_history(repo: &Repository, patterns: &[Regex]) -> Vec<String> {
+    let mut references: Vec<String> = Vec::new();
+    let mut revwalk = match repo.revwalk() {
+        Ok(revwalk) => revwalk,
+        Err(_) => return references,
+    };
+    if revwalk.push_head().is_err() {
+        return references; // Unborn HEAD
+    }
+
+    for oid in revwalk.flatten().take(HISTORY_SCAN_LIMIT) {
+        if let Ok(commit) = repo.find_commit(oid) {
+            for reference in find_references(patterns, commit.message().unwrap_or("")) {
+                if !references.contains(&reference) {
+                    references.push(reference);
+                }
+            }
+        }
+    }
+
+    references
+}
+
+#[tauri::command]
+pub async fn list_issues(
+    app: tauri::AppHandle,
+    repo_path: String,
+    state: Option<String>,
+    remote_name: Option<String>,
+) -> Result<Vec<Issue>, AppError> {
+    let state = state.unwrap_or_else(|| "open".to_string());
+    if !matches!(state.as_str(), "open" | "closed" | "all") {
+        return Err(AppError::invalid_input(format!("Unknown issue state: {}", state)));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&app, &repo_path));
+        fetch_issues(&hosted_repository(&repo, &remote_name)?, &state)
+    })
+    .await?
+}
+
+/// Autocomplete for the commit message box: open provider issues plus references already
+/// used in recent history, matched against `query` by reference or title. The issue list
+/// is cached briefly per repository and filtered locally. Works offline and without a
+/// token, in which case only history is searched.
+#[tauri::command]
+pub async fn suggest_issue_references(
+    app: tauri::AppHandle,
+    repo_path: String,
+    query: String,
+) -> Result<Vec<IssueReference>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let hosted = default_hosted_repository(&app, &repo, &repo_path);
+        let patterns = issue_patterns(&app, &repo_path);
+
+        let mut suggestions: Vec<IssueReference> = Vec::new();
+        if let Some(hosted) = &hosted {
+            suggestions.extend(cached_open_issues(&app, &repo_path, hosted).into_iter().map(|issue| IssueReference {
+                reference: format!("#{}", issue.number),
+                title: Some(issue.title),
+                url: Some(issue.url),
+            }));
+        }
+        for reference in references_in_history(&repo, &patterns) {
+            if !suggestions.iter().any(|suggestion| suggestion.reference == reference) {
+                suggestions.push(IssueReference {
+                    url: issue_url(hosted.as_ref(), &reference),
+                    reference,
+                    title: None,
+                });
+            }
+        }
+
+        let query = query.trim().to_lowercase();
+        let query = query.trim_start_matches('#');
+        Ok(suggestions
+            .into_iter()
+            .filter(|suggestion| {
+                query.is_empty()
+                    || suggestion.reference.to_lowercase().contains(query)
+                    || suggestion.title.as_deref().map(|title| title.to_lowercase().contains(query)).unwrap_or(false)
+            })
+            .take(SUGGESTION_LIMIT)
+            .collect())
+    })
+    .await?
+}
+
+/// Issue references in a commit message, for linking them in the commit details. Needs
+/// no network: "#123" links to the provider's issue page, other keys come back unlinked.
+#[tauri::command]
+pub async fn parse_issue_references(
+    app: tauri::AppHandle,
+    repo_path: String,
+    message: String,
+) -> Result<Vec<IssueReference>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let hosted = default_hosted_repository(&app, &repo, &repo_path);
+        let patterns = issue_patterns(&app, &repo_path);
+
+        Ok(find_references(&patterns, &message)
+            .into_iter()
+            .map(|reference| IssueReference {
+                url: issue_url(hosted.as_ref(), &reference),
+                reference,
+                title: None,
+            })
+            .collect())
+    })
+    .await?
+}
@@ -0,0 +1,184 @@
+use crate::commands::audit::audited;
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::database::{OperationEntry, RefChange};
+use git2::Repository;
+use tauri::{Manager, State};
+
+/// Records a mutating git operation in the undo journal.
+/// Journaling is best effort: a missing or failing database never fails the git operation itself.
+pub fn journal_operation(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    operation: &str,
+    description: String,
+    undo_mode: &str,
+    ref_changes: Vec<RefChange>,
+) {
+    let db_state = match app.try_state::<DatabaseState>() {
+        Some(db_state) => db_state,
+        None => return,
+    };
+
+    let entry = OperationEntry {
+        id: None,
+        repo_path: repo_path.to_string(),
+        operation: operation.to_string(),
+        description,
+        ref_changes,
+        undo_mode: undo_mode.to_string(),
+        created_at: chrono::Utc::now(),
+        undone_at: None,
+    };
+
+    let result = tauri::async_runtime::block_on(async {
+        let db = db_state.lock().await;
+        db.record_operation(&entry).await
+    });
+
+    if let Err(e) = result {
+        println!("⚠️  Failed to record operation in journal: {}", e);
+    }
+}
+
+pub fn ref_change(repo: &Repository, ref_name: &str, old_target: Option<String>) -> RefChange {
+    RefChange {
+        ref_name: ref_name.to_string(),
+        old_target,
+        new_target: crate::ref_target(repo, ref_name),
+    }
+}
+
+#[tauri::command]
+pub async fn undo_last_operation(
+    app: tauri::AppHandle,
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+) -> Result<String, AppError> {
+    let entry = {
+        let db = db_state.lock().await;
+        db.get_last_operation(&repo_path)
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to read operation journal"))?
+            .ok_or_else(|| "Nothing to undo".to_string())?
+    };
+
+    // The audit log locks the database itself, and blocks, so undo off the async runtime
+    let undo_app = app.clone();
+    let undo_entry = entry.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        audited(&undo_app, &undo_entry.repo_path, "undo_last_operation", || {
+            undo_operation(&undo_entry).map_err(AppError::from)
+        })
+    })
+    .await??;
+
+    if let Some(id) = entry.id {
+        let db = db_state.lock().await;
+        db.mark_operation_undone(id)
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to update operation journal"))?;
+    }
+
+    Ok(format!("Undid {}", entry.description))
+}
+
+fn undo_operation(entry: &OperationEntry) -> Result<(), String> {
+    let repo = Repository::open(&entry.repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+    // Only undo when every ref is still where the operation left it
+    for change in &entry.ref_changes {
+        if crate::ref_target(&repo, &change.ref_name) != change.new_target {
+            return Err(format!(
+                "Cannot undo {}: {} has changed since",
+                entry.description, change.ref_name
+            ));
+        }
+    }
+
+    if entry.undo_mode == "stash" {
+        return restore_dropped_stash(&repo, entry);
+    }
+
+    let head_ref = crate::head_ref_name(&repo);
+
+    for change in &entry.ref_changes {
+        if change.ref_name == head_ref {
+            let old_target = change.old_target.as_ref()
+                .ok_or_else(|| format!("Cannot undo {}: there is no previous commit", entry.description))?;
+            reset_head_to(&repo, old_target, &entry.undo_mode)?;
+        } else {
+            match &change.old_target {
+                Some(old_target) => {
+                    let oid = git2::Oid::from_str(old_target)
+                        .map_err(|e| format!("Invalid commit ID: {}", e))?;
+                    repo.reference(&change.ref_name, oid, true, &format!("codegit: undo {}", entry.operation))
+                        .map_err(|e| format!("Failed to restore {}: {}", change.ref_name, e))?;
+                }
+                None => {
+                    repo.find_reference(&change.ref_name)
+                        .and_then(|mut reference| reference.delete())
+                        .map_err(|e| format!("Failed to remove {}: {}", change.ref_name, e))?;
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn reset_head_to(repo: &Repository, target: &str, undo_mode: &str) -> Result<(), String> {
+    let reset_type = match undo_mode {
+        "soft" => git2::ResetType::Soft,
+        "mixed" => git2::ResetType::Mixed,
+        _ => git2::ResetType::Hard,
+    };
+
+    if reset_type == git2::ResetType::Hard && has_uncommitted_changes(repo)? {
+        return Err("Cannot undo: commit or stash your local changes first".to_string());
+    }
+
+    let oid = git2::Oid::from_str(target)
+        .map_err(|e| format!("Invalid commit ID: {}", e))?;
+    let commit = repo.find_commit(oid)
+        .map_err(|e| format!("Failed to find commit: {}", e))?;
+
+    crate::create_backup_ref(repo, "undo")?;
+
+    repo.reset(commit.as_object(), reset_type, None)
+        .map_err(|e| format!("Failed to reset: {}", e))?;
+
+    Ok(())
+}
+
+pub fn has_uncommitted_changes(repo: &Repository) -> Result<bool, String> {
+    let mut options = git2::StatusOptions::new();
+    options.include_untracked(false).include_ignored(false);
+
+    let statuses = repo.statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+
+    Ok(!statuses.is_empty())
+}
+
+fn restore_dropped_stash(repo: &Repository, entry: &OperationEntry) -> Result<(), String> {
+    let stash_id = entry.ref_changes.first()
+        .and_then(|change| change.old_target.as_ref())
+        .ok_or_else(|| "Cannot undo: the dropped stash was not recorded".to_string())?;
+
+    let oid = git2::Oid::from_str(stash_id)
+        .map_err(|e| format!("Invalid stash ID: {}", e))?;
+    let stash_commit = repo.find_commit(oid)
+        .map_err(|e| format!("Dropped stash is no longer available: {}", e))?;
+
+    // Same as `git stash store`: push the commit back onto refs/stash with a reflog entry
+    let message = stash_commit.message().unwrap_or("Restored stash").to_string();
+
+    repo.reference_ensure_log("refs/stash")
+        .map_err(|e| format!("Failed to prepare stash reflog: {}", e))?;
+    repo.reference("refs/stash", oid, true, &message)
+        .map_err(|e| format!("Failed to restore stash: {}", e))?;
+
+    Ok(())
+}
@@ -0,0 +1,272 @@
+use crate::commands::error::AppError;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+const LFS_POINTER_PREFIX: &[u8] = b"version https://git-lfs.github.com/spec/v1";
+// Pointer files are tiny; anything larger is real content
+const LFS_POINTER_MAX_SIZE: usize = 1024;
+const LFS_HOOKS: [&str; 4] = ["pre-push", "post-checkout", "post-commit", "post-merge"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LfsPointer {
+    pub oid: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LfsPointerFile {
+    pub path: String,
+    pub oid: String,
+    pub size: u64,
+    pub is_downloaded: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LfsInfo {
+    pub uses_lfs: bool,
+    pub has_lfs_hooks: bool,
+    pub tracked_patterns: Vec<String>,
+    pub pointer_files: Vec<LfsPointerFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LfsProgress {
+    pub repo_path: String,
+    pub message: String,
+    pub percent: Option<u8>,
+}
+
+/// Parses the content of a Git LFS pointer file, returning None for regular content.
+pub fn parse_lfs_pointer(content: &[u8]) -> Option<LfsPointer> {
+    if content.len() > LFS_POINTER_MAX_SIZE || !content.starts_with(LFS_POINTER_PREFIX) {
+        return None;
+    }
+
+    let text = std::str::from_utf8(content).ok()?;
+    let mut oid = None;
+    let mut size = None;
+
+    for line in text.lines() {
+        if let Some(value) = line.strip_prefix("oid sha256:") {
+            oid = Some(value.trim().to_string());
+        } else if let Some(value) = line.strip_prefix("size ") {
+            size = value.trim().parse::<u64>().ok();
+        }
+    }
+
+    Some(LfsPointer { oid: oid?, size: size? })
+}
+
+/// Location of a downloaded LFS object inside .git/lfs/objects.
+pub fn lfs_object_path(repo: &Repository, oid: &str) -> Option<std::path::PathBuf> {
+    if oid.len() < 5 {
+        return None;
+    }
+    Some(repo.path().join("lfs").join("objects").join(&oid[0..2]).join(&oid[2..4]).join(oid))
+}
+
+/// Patterns configured with `filter=lfs` in every .gitattributes file of the index.
+pub fn lfs_tracked_patterns(repo: &Repository) -> Result<Vec<String>, String> {
+    let index = repo.index()
+        .map_err(|e| format!("Failed to get index: {}", e))?;
+
+    let mut patterns = Vec::new();
+
+    for entry in index.iter() {
+        let path = String::from_utf8_lossy(&entry.path).to_string();
+        if path != ".gitattributes" && !path.ends_with("/.gitattributes") {
+            continue;
+        }
+
+        let blob = match repo.find_blob(entry.id) {
+            Ok(blob) => blob,
+            Err(_) => continue,
+        };
+        let directory = path.trim_end_matches(".gitattributes");
+
+        for line in String::from_utf8_lossy(blob.content()).lines() {
+            let line = line.trim();
+            if line.starts_with('#') {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            if let Some(pattern) = parts.next() {
+                if parts.any(|attribute| attribute == "filter=lfs") {
+                    patterns.push(format!("{}{}", directory, pattern));
+                }
+            }
+        }
+    }
+
+    Ok(patterns)
+}
+
+fn has_lfs_hooks(repo: &Repository) -> bool {
+    let hooks_dir = repo.path().join("hooks");
+
+    LFS_HOOKS.iter().any(|hook| {
+        fs::read_to_string(hooks_dir.join(hook))
+            .map(|script| script.contains("git lfs") || script.contains("git-lfs"))
+            .unwrap_or(false)
+    })
+}
+
+/// LFS pointer files committed in HEAD.
+fn lfs_pointer_files(repo: &Repository) -> Result<Vec<LfsPointerFile>, String> {
+    let tree = match repo.head().and_then(|head| head.peel_to_tree()) {
+        Ok(tree) => tree,
+        Err(_) => return Ok(Vec::new()), // No commits yet
+    };
+
+    let odb = repo.odb()
+        .map_err(|e| format!("Failed to open object database: {}", e))?;
+
+    let mut pointer_files = Vec::new();
+
+    tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+        if entry.kind() != Some(git2::ObjectType::Blob) {
+            return git2::TreeWalkResult::Ok;
+        }
+        // Check the size from the object header before loading any content
+        let is_small = odb.read_header(entry.id())
+            .map(|(size, _)| size <= LFS_POINTER_MAX_SIZE)
+            .unwrap_or(false);
+        if !is_small {
+            return git2::TreeWalkResult::Ok;
+        }
+
+        if let Ok(blob) = repo.find_blob(entry.id()) {
+            if let Some(pointer) = parse_lfs_pointer(blob.content()) {
+                let path = format!("{}{}", root, entry.name().unwrap_or(""));
+                let is_downloaded = lfs_object_path(repo, &pointer.oid)
+                    .map(|object_path| object_path.exists())
+                    .unwrap_or(false);
+
+                pointer_files.push(LfsPointerFile {
+                    path,
+                    oid: pointer.oid,
+                    size: pointer.size,
+                    is_downloaded,
+                });
+            }
+        }
+        git2::TreeWalkResult::Ok
+    }).map_err(|e| format!("Failed to walk tree: {}", e))?;
+
+    Ok(pointer_files)
+}
+
+#[tauri::command]
+pub fn get_lfs_info(repo_path: String) -> Result<LfsInfo, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+    let tracked_patterns = lfs_tracked_patterns(&repo)?;
+    let has_lfs_hooks = has_lfs_hooks(&repo);
+    let pointer_files = lfs_pointer_files(&repo)?;
+
+    Ok(LfsInfo {
+        uses_lfs: !tracked_patterns.is_empty() || has_lfs_hooks || !pointer_files.is_empty(),
+        has_lfs_hooks,
+        tracked_patterns,
+        pointer_files,
+    })
+}
+
+pub fn is_git_lfs_available() -> bool {
+    crate::git_cli(None)
+        .args(["lfs", "version"])
+        .output()
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+/// Runs `git lfs pull` (fetch + checkout of the current ref), forwarding git-lfs
+/// progress lines to the frontend as `lfs-progress` events.
+pub fn pull_lfs_objects(app: &tauri::AppHandle, repo_path: &str) -> Result<(), String> {
+    use std::io::Read;
+    use tauri::Manager;
+
+    if !is_git_lfs_available() {
+        return Err("git-lfs is not installed; install it to download LFS files".to_string());
+    }
+
+    let mut child = crate::git_cli(Some(repo_path))
+        .args(["lfs", "pull"])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to run git lfs: {}", e))?;
+
+    let mut stderr = child.stderr.take()
+        .ok_or_else(|| "Failed to capture git lfs output".to_string())?;
+
+    // git-lfs redraws its progress line with '\r', so split on both line endings
+    let mut output = String::new();
+    let mut line = Vec::new();
+    let mut buffer = [0u8; 1024];
+    loop {
+        let read = stderr.read(&mut buffer)
+            .map_err(|e| format!("Failed to read git lfs output: {}", e))?;
+        if read == 0 {
+            break;
+        }
+        for &byte in &buffer[..read] {
+            if byte != b'\r' && byte != b'\n' {
+                line.push(byte);
+                continue;
+            }
+            if line.is_empty() {
+                continue;
+            }
+            let message = String::from_utf8_lossy(&line).trim().to_string();
+            let _ = app.emit_all("lfs-progress", LfsProgress {
+                repo_path: repo_path.to_string(),
+                percent: parse_progress_percent(&message),
+                message: message.clone(),
+            });
+            output.push_str(&message);
+            output.push('\n');
+            line.clear();
+        }
+    }
+
+    let status = child.wait()
+        .map_err(|e| format!("Failed to wait for git lfs: {}", e))?;
+
+    if !status.success() {
+        return Err(format!("git lfs pull failed: {}", output.trim()));
+    }
+
+    Ok(())
+}
+
+fn parse_progress_percent(message: &str) -> Option<u8> {
+    let percent_index = message.find('%')?;
+    let digits: String = message[..percent_index]
+        .chars()
+        .rev()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.chars().rev().collect::<String>().parse().ok()
+}
+
+#[tauri::command]
+pub fn download_lfs_objects(app: tauri::AppHandle, repo_path: String) -> Result<String, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+    let missing = lfs_pointer_files(&repo)?
+        .into_iter()
+        .filter(|file| !file.is_downloaded)
+        .count();
+
+    if missing == 0 && lfs_tracked_patterns(&repo)?.is_empty() {
+        return Ok("Repository does not use Git LFS".to_string());
+    }
+
+    pull_lfs_objects(&app, &repo_path)?;
+
+    Ok(format!("Downloaded LFS objects ({} were missing)", missing))
+}
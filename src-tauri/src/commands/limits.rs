@@ -0,0 +1,73 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, State};
+
+/// Size limits for payloads sent to the webview, stored as "payload_limits" in the
+/// settings JSON. Anything above them is truncated and flagged so the frontend can
+/// fetch the rest on demand.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PayloadLimits {
+    pub max_file_bytes: usize,
+    pub max_diff_bytes: usize,
+    pub max_log_entries: usize,
+    pub max_commit_message_bytes: usize,
+}
+
+impl Default for PayloadLimits {
+    fn default() -> Self {
+        PayloadLimits {
+            max_file_bytes: 5 * 1024 * 1024,
+            max_diff_bytes: 2 * 1024 * 1024,
+            max_log_entries: 5000,
+            max_commit_message_bytes: 64 * 1024,
+        }
+    }
+}
+
+impl PayloadLimits {
+    fn from_settings(settings_json: &serde_json::Value) -> Self {
+        settings_json
+            .get("payload_limits")
+            .and_then(|limits| serde_json::from_value(limits.clone()).ok())
+            .unwrap_or_default()
+    }
+}
+
+/// Limits from the app settings, or the defaults when the database is unavailable.
+pub fn payload_limits(app: &tauri::AppHandle) -> PayloadLimits {
+    let settings = app.try_state::<DatabaseState>().and_then(|db_state| {
+        tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.get_settings().await
+        })
+        .ok()
+    });
+
+    settings
+        .map(|settings| PayloadLimits::from_settings(&settings.settings_json))
+        .unwrap_or_default()
+}
+
+/// Cuts `text` to at most `max_bytes` on a character boundary; true when something was cut.
+pub fn truncate_text(text: &str, max_bytes: usize) -> (String, bool) {
+    if text.len() <= max_bytes {
+        return (text.to_string(), false);
+    }
+
+    let mut end = max_bytes;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    (text[..end].to_string(), true)
+}
+
+#[tauri::command]
+pub async fn get_payload_limits(db_state: State<'_, DatabaseState>) -> Result<PayloadLimits, AppError> {
+    let db = db_state.lock().await;
+    let settings = db.get_settings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get settings"))?;
+    Ok(PayloadLimits::from_settings(&settings.settings_json))
+}
@@ -0,0 +1,129 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use git2::{Config, ConfigLevel, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+const AUTOCRLF_VALUES: [&str; 3] = ["true", "false", "input"];
+const EOL_VALUES: [&str; 3] = ["lf", "crlf", "native"];
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineEndingSettings {
+    pub autocrlf: Option<String>,
+    pub eol: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LineEndingConfig {
+    pub local: Option<LineEndingSettings>,
+    pub global: LineEndingSettings,
+    pub effective: LineEndingSettings,
+}
+
+fn read_settings(config: &Config) -> LineEndingSettings {
+    LineEndingSettings {
+        autocrlf: config.get_string("core.autocrlf").ok(),
+        eol: config.get_string("core.eol").ok(),
+    }
+}
+
+fn local_config(repo_path: &str) -> Result<Config, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+    repo.config()
+        .and_then(|config| config.open_level(ConfigLevel::Local))
+        .map_err(|e| format!("Failed to open repository config: {}", e))
+}
+
+fn apply_setting(config: &mut Config, key: &str, value: Option<&str>, allowed: &[&str]) -> Result<(), String> {
+    match value {
+        Some(value) => {
+            if !allowed.contains(&value) {
+                return Err(format!("Invalid value '{}' for {}. Use one of: {}", value, key, allowed.join(", ")));
+            }
+            config.set_str(key, value)
+                .map_err(|e| format!("Failed to set {}: {}", key, e))
+        }
+        None => match config.remove(key) {
+            Err(e) if e.code() != git2::ErrorCode::NotFound => Err(format!("Failed to unset {}: {}", key, e)),
+            _ => Ok(()),
+        },
+    }
+}
+
+/// Reads core.autocrlf and core.eol from the repository, the global config, and
+/// the effective value git will use.
+#[tauri::command]
+pub fn get_line_ending_config(repo_path: Option<String>) -> Result<LineEndingConfig, AppError> {
+    let global = crate::global_git_config()
+        .map(|config| read_settings(&config))
+        .unwrap_or(LineEndingSettings { autocrlf: None, eol: None });
+
+    let (local, effective) = match repo_path {
+        Some(repo_path) => {
+            let repo = Repository::open(&repo_path)
+                .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+            let config = repo.config()
+                .map_err(|e| AppError::wrap(e, "Failed to open repository config"))?;
+            let local = config.open_level(ConfigLevel::Local)
+                .map(|local| read_settings(&local))
+                .ok();
+            (local, read_settings(&config))
+        }
+        None => {
+            let config = Config::open_default()
+                .map_err(|e| AppError::wrap(e, "Failed to open git config"))?;
+            (None, read_settings(&config))
+        }
+    };
+
+    Ok(LineEndingConfig { local, global, effective })
+}
+
+/// Sets core.autocrlf/core.eol in the repository (or globally when no repository
+/// is given). A `None` value removes the setting.
+#[tauri::command]
+pub fn set_line_ending_config(
+    repo_path: Option<String>,
+    autocrlf: Option<String>,
+    eol: Option<String>,
+) -> Result<String, AppError> {
+    let mut config = match &repo_path {
+        Some(repo_path) => local_config(repo_path)?,
+        None => crate::global_git_config()?,
+    };
+
+    apply_setting(&mut config, "core.autocrlf", autocrlf.as_deref(), &AUTOCRLF_VALUES)?;
+    apply_setting(&mut config, "core.eol", eol.as_deref(), &EOL_VALUES)?;
+
+    let scope = if repo_path.is_some() { "repository" } else { "global" };
+    Ok(format!("Updated {} line ending settings", scope))
+}
+
+fn staged_paths(repo_path: &str) -> Result<HashSet<String>, String> {
+    let output = crate::run_git(repo_path, &["diff", "--cached", "--name-only", "-z"], "list staged files")?;
+    Ok(output.split('\0').filter(|path| !path.is_empty()).map(|path| path.to_string()).collect())
+}
+
+/// Re-adds every tracked file so the index matches the current line ending settings
+/// (`git add --renormalize .`). Returns the files that were newly staged by it.
+#[tauri::command]
+pub fn renormalize_line_endings(app: tauri::AppHandle, repo_path: String) -> Result<Vec<String>, AppError> {
+    audited(&app, &repo_path, "renormalize_line_endings", || {
+        crate::open_worktree_repository(&repo_path)?;
+
+        let before = staged_paths(&repo_path)?;
+
+        crate::run_git(&repo_path, &["add", "--renormalize", "."], "renormalize line endings")?;
+
+        let mut renormalized: Vec<String> = staged_paths(&repo_path)?
+            .into_iter()
+            .filter(|path| !before.contains(path))
+            .collect();
+        renormalized.sort();
+
+        println!("🔁 Renormalized {} files in {}", renormalized.len(), repo_path);
+        Ok(renormalized)
+    })
+}
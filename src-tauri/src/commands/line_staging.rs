@@ -0,0 +1,187 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use git2::{ApplyLocation, Delta, Diff, DiffOptions, Patch, Repository};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+
+/// A line picked in get_unstaged_diff or get_staged_diff, identified as they report it:
+/// removed lines by `old_lineno`, added lines by `new_lineno`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectedLine {
+    pub old_lineno: Option<u32>,
+    pub new_lineno: Option<u32>,
+}
+
+impl SelectedLine {
+    fn matches(&self, line: &git2::DiffLine) -> bool {
+        match line.origin() {
+            '+' => self.new_lineno.is_some() && self.new_lineno == line.new_lineno(),
+            '-' => self.old_lineno.is_some() && self.old_lineno == line.old_lineno(),
+            _ => false,
+        }
+    }
+
+    fn reversed(&self) -> SelectedLine {
+        SelectedLine { old_lineno: self.new_lineno, new_lineno: self.old_lineno }
+    }
+}
+
+fn write_line(patch: &mut Vec<u8>, origin: char, content: &[u8]) {
+    patch.push(origin as u8);
+    patch.extend_from_slice(content);
+    if !content.ends_with(b"\n") {
+        patch.extend_from_slice(b"\n\\ No newline at end of file\n");
+    }
+}
+
+/// Builds a patch for `file_path` holding only the selected changes of `diff`: unselected
+/// additions are dropped and unselected removals become context, so the result applies
+/// to the diff's old side. Returns None when no selected line is a change.
+fn partial_patch(diff: &Diff, file_path: &str, selection: &[SelectedLine]) -> Result<Option<Vec<u8>>, AppError> {
+    let patch = match Patch::from_diff(diff, 0).map_err(|e| AppError::wrap(e, "Failed to read diff"))? {
+        Some(patch) if patch.num_hunks() > 0 => patch,
+        _ => return Ok(None), // Unchanged or binary
+    };
+    let delta = patch.delta();
+
+    let mut hunks = Vec::new();
+    let mut offset: i64 = 0;
+    let mut all_selected = true;
+    for hunk_index in 0..patch.num_hunks() {
+        let (hunk, line_count) = patch.hunk(hunk_index)
+            .map_err(|e| AppError::wrap(e, "Failed to read diff"))?;
+
+        let mut body = Vec::new();
+        let (mut old_count, mut new_count, mut changes) = (0i64, 0i64, 0);
+        for line_index in 0..line_count {
+            let line = patch.line_in_hunk(hunk_index, line_index)
+                .map_err(|e| AppError::wrap(e, "Failed to read diff"))?;
+            let selected = selection.iter().any(|selected| selected.matches(&line));
+            match line.origin() {
+                ' ' => {
+                    write_line(&mut body, ' ', line.content());
+                    old_count += 1;
+                    new_count += 1;
+                }
+                '+' if selected => {
+                    write_line(&mut body, '+', line.content());
+                    new_count += 1;
+                    changes += 1;
+                }
+                '-' if selected => {
+                    write_line(&mut body, '-', line.content());
+                    old_count += 1;
+                    changes += 1;
+                }
+                '-' => {
+                    write_line(&mut body, ' ', line.content());
+                    old_count += 1;
+                    new_count += 1;
+                    all_selected = false;
+                }
+                '+' => all_selected = false,
+                _ => {} // End-of-file newline markers; write_line adds them where they belong
+            }
+        }
+        if changes == 0 {
+            continue;
+        }
+
+        // Every old line is kept as a removal or context, so the old range is unchanged.
+        // A zero-length range names the line before it, hence the shifts by one.
+        let old_start = hunk.old_start() as i64;
+        let first_line = if old_count == 0 { old_start + 1 } else { old_start } + offset;
+        let new_start = if new_count == 0 { first_line - 1 } else { first_line };
+        offset += new_count - old_count;
+
+        let _ = writeln!(hunks, "@@ -{},{} +{},{} @@", old_start, old_count, new_start, new_count);
+        hunks.extend_from_slice(&body);
+    }
+    if hunks.is_empty() {
+        return Ok(None);
+    }
+
+    let mode = |file: git2::DiffFile| format!("{:o}", u32::from(file.mode()));
+    let mut text = Vec::new();
+    let _ = writeln!(text, "diff --git a/{0} b/{0}", file_path);
+    match delta.status() {
+        Delta::Added | Delta::Untracked => {
+            let _ = writeln!(text, "new file mode {}", mode(delta.new_file()));
+            let _ = writeln!(text, "--- /dev/null\n+++ b/{}", file_path);
+        }
+        // Keeping some of the removed lines leaves the file in place
+        Delta::Deleted if all_selected => {
+            let _ = writeln!(text, "deleted file mode {}", mode(delta.old_file()));
+            let _ = writeln!(text, "--- a/{}\n+++ /dev/null", file_path);
+        }
+        _ => {
+            let _ = writeln!(text, "--- a/{0}\n+++ b/{0}", file_path);
+        }
+    }
+    text.extend_from_slice(&hunks);
+    Ok(Some(text))
+}
+
+fn apply_to_index(repo: &Repository, patch: &[u8]) -> Result<(), AppError> {
+    let diff = Diff::from_buffer(patch)
+        .map_err(|e| AppError::wrap(e, "Failed to build patch"))?;
+    repo.apply(&diff, ApplyLocation::Index, None)
+        .map_err(|e| AppError::wrap(e, "Failed to apply patch to the index"))
+}
+
+fn file_diff_options(file_path: &str) -> DiffOptions {
+    let mut diff_opts = DiffOptions::new();
+    diff_opts
+        .pathspec(file_path)
+        .disable_pathspec_match(true)
+        .include_untracked(true)
+        .show_untracked_content(true);
+    diff_opts
+}
+
+/// Stages only the selected lines of a file's unstaged changes (index → working tree),
+/// the way `git add -p` does when a hunk is edited.
+#[tauri::command]
+pub fn stage_lines(app: tauri::AppHandle, repo_path: String, file_path: String, lines: Vec<SelectedLine>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "stage_lines", || {
+        let repo = crate::open_worktree_repository(&repo_path)?;
+        let index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        let diff = repo.diff_index_to_workdir(Some(&index), Some(&mut file_diff_options(&file_path)))
+            .map_err(|e| AppError::wrap(e, "Failed to get unstaged changes"))?;
+
+        let patch = partial_patch(&diff, &file_path, &lines)?
+            .ok_or_else(|| AppError::invalid_input("No unstaged changes are selected"))?;
+        apply_to_index(&repo, &patch)?;
+
+        Ok(format!("Staged {} line(s) of {}", lines.len(), file_path))
+    })
+}
+
+/// Unstages only the selected lines of a file's staged changes (HEAD → index), leaving
+/// the working tree alone.
+#[tauri::command]
+pub fn unstage_lines(app: tauri::AppHandle, repo_path: String, file_path: String, lines: Vec<SelectedLine>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "unstage_lines", || {
+        let repo = crate::open_worktree_repository(&repo_path)?;
+        let head_tree = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(|e| AppError::wrap(e, "Failed to get HEAD tree"))?),
+            Err(_) => None,
+        };
+        let index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+
+        // Unstaging applies the selected part of index → HEAD to the index
+        let mut diff_opts = file_diff_options(&file_path);
+        diff_opts.reverse(true);
+        let diff = repo.diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut diff_opts))
+            .map_err(|e| AppError::wrap(e, "Failed to get staged changes"))?;
+
+        let selection: Vec<SelectedLine> = lines.iter().map(SelectedLine::reversed).collect();
+        let patch = partial_patch(&diff, &file_path, &selection)?
+            .ok_or_else(|| AppError::invalid_input("No staged changes are selected"))?;
+        apply_to_index(&repo, &patch)?;
+
+        Ok(format!("Unstaged {} line(s) of {}", lines.len(), file_path))
+    })
+}
@@ -0,0 +1,895 @@
+use crate::commands::audit::audited;
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::commands::operations::run_operation;
+use chrono::{DateTime, Utc};
+use git2::{ObjectType, Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use tauri::State;
+
+// Keep reports of badly broken repositories to a reasonable IPC payload
+const MAX_REPORTED_ISSUES: usize = 500;
+const LARGEST_BLOBS_LIMIT: usize = 50;
+// Blobs above this size are worth moving to Git LFS
+const LFS_CANDIDATE_MIN_SIZE: u64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityIssue {
+    pub kind: String, // 'open_failed' | 'broken_ref' | 'corrupt_object' | 'missing_object' | 'index'
+    pub object_id: Option<String>,
+    pub reference: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub repo_path: String,
+    pub can_open: bool,
+    pub is_valid: bool,
+    pub object_count: usize,
+    pub checked_refs: usize,
+    pub checked_commits: usize,
+    pub issues: Vec<IntegrityIssue>,
+    pub truncated: bool,
+    pub duration_ms: u128,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PackFileInfo {
+    pub name: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LargeBlob {
+    pub object_id: String,
+    pub path: String,
+    pub size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LfsCandidate {
+    pub pattern: String,
+    pub file_count: usize,
+    pub total_size: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RepositorySizeReport {
+    pub repo_path: String,
+    pub fingerprint: String,
+    pub pack_count: usize,
+    pub total_pack_size: u64,
+    pub packs: Vec<PackFileInfo>,
+    pub loose_object_count: usize,
+    pub loose_objects_size: u64,
+    pub blob_count: usize,
+    pub total_blob_size: u64,
+    pub largest_blobs: Vec<LargeBlob>,
+    pub lfs_candidates: Vec<LfsCandidate>,
+    pub analyzed_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeCommit {
+    pub id: String,
+    pub summary: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PurgeReport {
+    pub dry_run: bool,
+    pub rewritten_commit_count: usize,
+    pub rewritten_commits: Vec<PurgeCommit>,
+    pub affected_refs: Vec<String>,
+    pub removed_paths: Vec<String>,
+    pub removed_size: u64,
+    pub backup_branches: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommitGraphInfo {
+    pub repo_path: String,
+    pub exists: bool,
+    pub is_split: bool, // Written as a chain of incremental layers
+    pub layer_count: usize,
+    pub size: u64,
+    pub written_at: Option<DateTime<Utc>>,
+}
+
+struct IssueCollector {
+    issues: Vec<IntegrityIssue>,
+    truncated: bool,
+}
+
+impl IssueCollector {
+    fn push(&mut self, kind: &str, object_id: Option<Oid>, reference: Option<&str>, message: String) {
+        if self.issues.len() >= MAX_REPORTED_ISSUES {
+            self.truncated = true;
+            return;
+        }
+        self.issues.push(IntegrityIssue {
+            kind: kind.to_string(),
+            object_id: object_id.map(|oid| oid.to_string()),
+            reference: reference.map(|name| name.to_string()),
+            message,
+        });
+    }
+}
+
+#[tauri::command]
+pub fn verify_repository(repo_path: String) -> Result<IntegrityReport, AppError> {
+    let started = std::time::Instant::now();
+    let mut collector = IssueCollector { issues: Vec::new(), truncated: false };
+
+    let repo = match Repository::open(&repo_path) {
+        Ok(repo) => repo,
+        Err(e) => {
+            // Report instead of failing: this is exactly the case users need diagnosed
+            collector.push("open_failed", None, None, e.message().to_string());
+            return Ok(IntegrityReport {
+                repo_path,
+                can_open: false,
+                is_valid: false,
+                object_count: 0,
+                checked_refs: 0,
+                checked_commits: 0,
+                issues: collector.issues,
+                truncated: false,
+                duration_ms: started.elapsed().as_millis(),
+            });
+        }
+    };
+
+    let odb = repo.odb()
+        .map_err(|e| AppError::wrap(e, "Failed to open object database"))?;
+
+    // Every stored object must decompress and hash correctly
+    let mut object_ids = Vec::new();
+    odb.foreach(|oid| {
+        object_ids.push(*oid);
+        true
+    }).map_err(|e| AppError::wrap(e, "Failed to enumerate objects"))?;
+
+    for oid in &object_ids {
+        if let Err(e) = odb.read(*oid) {
+            collector.push("corrupt_object", Some(*oid), None, e.message().to_string());
+        }
+    }
+
+    // Every ref must resolve to an existing object
+    let mut checked_refs = 0;
+    let mut tips = Vec::new();
+    let references = repo.references()
+        .map_err(|e| AppError::wrap(e, "Failed to list references"))?;
+
+    for reference in references {
+        checked_refs += 1;
+        let reference = match reference {
+            Ok(reference) => reference,
+            Err(e) => {
+                collector.push("broken_ref", None, None, e.message().to_string());
+                continue;
+            }
+        };
+        let name = reference.name().unwrap_or("<invalid utf-8>").to_string();
+
+        match reference.resolve() {
+            Ok(resolved) => match resolved.target() {
+                Some(oid) if odb.exists(oid) => tips.push(oid),
+                Some(oid) => collector.push("broken_ref", Some(oid), Some(&name), "Ref points to a missing object".to_string()),
+                None => collector.push("broken_ref", None, Some(&name), "Ref has no target".to_string()),
+            },
+            Err(e) => collector.push("broken_ref", None, Some(&name), e.message().to_string()),
+        }
+    }
+
+    if let Err(e) = repo.head() {
+        if e.code() != git2::ErrorCode::UnbornBranch {
+            collector.push("broken_ref", None, Some("HEAD"), e.message().to_string());
+        }
+    }
+
+    // Connectivity: everything reachable from the refs must exist
+    let mut checked_commits = 0;
+    let mut seen_commits = HashSet::new();
+    let mut seen_trees = HashSet::new();
+    let mut pending: Vec<Oid> = tips;
+
+    while let Some(oid) = pending.pop() {
+        if !seen_commits.insert(oid) {
+            continue;
+        }
+
+        let object = match repo.find_object(oid, None) {
+            Ok(object) => object,
+            Err(e) => {
+                collector.push("missing_object", Some(oid), None, e.message().to_string());
+                continue;
+            }
+        };
+
+        // Refs may point at annotated tags or directly at trees/blobs
+        let commit = match object.kind() {
+            Some(ObjectType::Commit) => object.peel_to_commit(),
+            Some(ObjectType::Tag) => {
+                match object.as_tag().map(|tag| tag.target_id()) {
+                    Some(target) if odb.exists(target) => pending.push(target),
+                    Some(target) => collector.push("missing_object", Some(target), None, format!("Target of tag {} is missing", oid)),
+                    None => {}
+                }
+                continue;
+            }
+            _ => continue,
+        };
+
+        let commit = match commit {
+            Ok(commit) => commit,
+            Err(e) => {
+                collector.push("corrupt_object", Some(oid), None, e.message().to_string());
+                continue;
+            }
+        };
+        checked_commits += 1;
+
+        for parent_id in commit.parent_ids() {
+            if odb.exists(parent_id) {
+                pending.push(parent_id);
+            } else {
+                collector.push("missing_object", Some(parent_id), None, format!("Parent of commit {} is missing", oid));
+            }
+        }
+
+        check_tree(&repo, &odb, commit.tree_id(), &mut seen_trees, &mut collector);
+    }
+
+    // The index must only reference existing blobs
+    match repo.index() {
+        Ok(index) => {
+            for entry in index.iter() {
+                // Gitlinks point into submodules, not into this object database
+                if entry.mode == 0o160000 || odb.exists(entry.id) {
+                    continue;
+                }
+                let path = String::from_utf8_lossy(&entry.path).to_string();
+                collector.push("index", Some(entry.id), None, format!("Index entry '{}' points to a missing blob", path));
+            }
+        }
+        Err(e) => collector.push("index", None, None, e.message().to_string()),
+    }
+
+    Ok(IntegrityReport {
+        repo_path,
+        can_open: true,
+        is_valid: collector.issues.is_empty(),
+        object_count: object_ids.len(),
+        checked_refs,
+        checked_commits,
+        issues: collector.issues,
+        truncated: collector.truncated,
+        duration_ms: started.elapsed().as_millis(),
+    })
+}
+
+fn check_tree(
+    repo: &Repository,
+    odb: &git2::Odb,
+    tree_id: Oid,
+    seen_trees: &mut HashSet<Oid>,
+    collector: &mut IssueCollector,
+) {
+    if !seen_trees.insert(tree_id) {
+        return;
+    }
+
+    let tree = match repo.find_tree(tree_id) {
+        Ok(tree) => tree,
+        Err(e) => {
+            collector.push("missing_object", Some(tree_id), None, e.message().to_string());
+            return;
+        }
+    };
+
+    for entry in tree.iter() {
+        match entry.kind() {
+            Some(ObjectType::Tree) => check_tree(repo, odb, entry.id(), seen_trees, collector),
+            Some(ObjectType::Blob) if !odb.exists(entry.id()) => {
+                let name = entry.name().unwrap_or("");
+                collector.push("missing_object", Some(entry.id()), None, format!("Blob '{}' in tree {} is missing", name, tree_id));
+            }
+            _ => {} // Gitlinks (submodule commits) live in another repository
+        }
+    }
+}
+
+/// Cheap identity of the repository state: changes whenever any ref moves.
+fn refs_fingerprint(repo: &Repository) -> Result<String, String> {
+    let references = repo.references()
+        .map_err(|e| format!("Failed to list references: {}", e))?;
+
+    let mut targets: Vec<(String, String)> = references
+        .flatten()
+        .filter_map(|reference| {
+            let name = reference.name()?.to_string();
+            let target = reference.target()?.to_string();
+            Some((name, target))
+        })
+        .collect();
+    targets.sort();
+
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    targets.hash(&mut hasher);
+    Ok(format!("{:016x}", hasher.finish()))
+}
+
+fn pack_files(repo: &Repository) -> Vec<PackFileInfo> {
+    let pack_dir = repo.path().join("objects").join("pack");
+
+    let mut packs: Vec<PackFileInfo> = fs::read_dir(pack_dir)
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|entry| entry.path().extension().map(|ext| ext == "pack").unwrap_or(false))
+                .map(|entry| PackFileInfo {
+                    name: entry.file_name().to_string_lossy().to_string(),
+                    size: entry.metadata().map(|meta| meta.len()).unwrap_or(0),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    packs.sort_by_key(|pack| std::cmp::Reverse(pack.size));
+    packs
+}
+
+fn loose_objects(repo: &Repository) -> (usize, u64) {
+    let objects_dir = repo.path().join("objects");
+    let mut count = 0;
+    let mut size = 0;
+
+    if let Ok(entries) = fs::read_dir(objects_dir) {
+        for entry in entries.flatten() {
+            // Loose objects live in two-hex-digit fan-out directories
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name.len() != 2 || !name.chars().all(|c| c.is_ascii_hexdigit()) {
+                continue;
+            }
+            if let Ok(objects) = fs::read_dir(entry.path()) {
+                for object in objects.flatten() {
+                    count += 1;
+                    size += object.metadata().map(|meta| meta.len()).unwrap_or(0);
+                }
+            }
+        }
+    }
+
+    (count, size)
+}
+
+fn lfs_candidate_pattern(path: &str) -> String {
+    let file_name = path.rsplit('/').next().unwrap_or(path);
+    match file_name.rsplit_once('.') {
+        Some((stem, extension)) if !stem.is_empty() => format!("*.{}", extension.to_lowercase()),
+        _ => path.to_string(),
+    }
+}
+
+fn compute_size_report(repo_path: &str) -> Result<RepositorySizeReport, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+
+    let fingerprint = refs_fingerprint(&repo)?;
+    let odb = repo.odb()
+        .map_err(|e| format!("Failed to open object database: {}", e))?;
+
+    // Walk every tree reachable from any ref, remembering the first path each blob was seen at
+    let mut revwalk = repo.revwalk()
+        .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+    revwalk.push_glob("*")
+        .map_err(|e| format!("Failed to push refs: {}", e))?;
+
+    let mut seen_trees = HashSet::new();
+    let mut blobs: HashMap<Oid, (String, u64)> = HashMap::new();
+
+    for oid in revwalk {
+        let oid = oid.map_err(|e| format!("Failed to get OID: {}", e))?;
+        let commit = repo.find_commit(oid)
+            .map_err(|e| format!("Failed to find commit: {}", e))?;
+        let tree = commit.tree()
+            .map_err(|e| format!("Failed to get tree: {}", e))?;
+
+        if !seen_trees.insert(tree.id()) {
+            continue;
+        }
+
+        tree.walk(git2::TreeWalkMode::PreOrder, |root, entry| {
+            match entry.kind() {
+                // Subtrees already seen in another commit contain nothing new
+                Some(ObjectType::Tree) if !seen_trees.insert(entry.id()) => git2::TreeWalkResult::Skip,
+                Some(ObjectType::Blob) => {
+                    if let std::collections::hash_map::Entry::Vacant(slot) = blobs.entry(entry.id()) {
+                        let size = odb.read_header(entry.id()).map(|(size, _)| size as u64).unwrap_or(0);
+                        slot.insert((format!("{}{}", root, entry.name().unwrap_or("")), size));
+                    }
+                    git2::TreeWalkResult::Ok
+                }
+                _ => git2::TreeWalkResult::Ok,
+            }
+        }).map_err(|e| format!("Failed to walk tree: {}", e))?;
+    }
+
+    let blob_count = blobs.len();
+    let total_blob_size = blobs.values().map(|(_, size)| size).sum();
+
+    let mut candidates: HashMap<String, LfsCandidate> = HashMap::new();
+    for (path, size) in blobs.values() {
+        if *size < LFS_CANDIDATE_MIN_SIZE {
+            continue;
+        }
+        let pattern = lfs_candidate_pattern(path);
+        let candidate = candidates.entry(pattern.clone()).or_insert(LfsCandidate {
+            pattern,
+            file_count: 0,
+            total_size: 0,
+        });
+        candidate.file_count += 1;
+        candidate.total_size += size;
+    }
+    let mut lfs_candidates: Vec<LfsCandidate> = candidates.into_values().collect();
+    lfs_candidates.sort_by_key(|candidate| std::cmp::Reverse(candidate.total_size));
+
+    let mut largest_blobs: Vec<LargeBlob> = blobs
+        .into_iter()
+        .map(|(oid, (path, size))| LargeBlob { object_id: oid.to_string(), path, size })
+        .collect();
+    largest_blobs.sort_by_key(|blob| std::cmp::Reverse(blob.size));
+    largest_blobs.truncate(LARGEST_BLOBS_LIMIT);
+
+    let packs = pack_files(&repo);
+    let (loose_object_count, loose_objects_size) = loose_objects(&repo);
+
+    Ok(RepositorySizeReport {
+        repo_path: repo_path.to_string(),
+        fingerprint,
+        pack_count: packs.len(),
+        total_pack_size: packs.iter().map(|pack| pack.size).sum(),
+        packs,
+        loose_object_count,
+        loose_objects_size,
+        blob_count,
+        total_blob_size,
+        largest_blobs,
+        lfs_candidates,
+        analyzed_at: Utc::now(),
+    })
+}
+
+#[tauri::command]
+pub async fn analyze_repository_size(
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+    force_refresh: Option<bool>,
+) -> Result<RepositorySizeReport, AppError> {
+    let fingerprint = {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        refs_fingerprint(&repo)?
+    };
+
+    if !force_refresh.unwrap_or(false) {
+        let db = db_state.lock().await;
+        let cached = db.get_size_report(&repo_path)
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to read cached size report"))?;
+
+        if let Some((cached_fingerprint, report)) = cached {
+            if cached_fingerprint == fingerprint {
+                if let Ok(report) = serde_json::from_value(report) {
+                    return Ok(report);
+                }
+            }
+        }
+    }
+
+    // Walking the whole history can take a while on big repositories
+    let path = repo_path.clone();
+    let report = tauri::async_runtime::spawn_blocking(move || compute_size_report(&path))
+        .await
+        .map_err(|e| AppError::wrap(e, "Size analysis failed"))??;
+
+    let report_json = serde_json::to_value(&report)
+        .map_err(|e| AppError::wrap(e, "Failed to serialize size report"))?;
+
+    let db = db_state.lock().await;
+    db.save_size_report(&repo_path, &report.fingerprint, &report_json)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to cache size report"))?;
+
+    Ok(report)
+}
+
+/// Decides which tree entries get removed by a history purge.
+struct PurgeMatcher {
+    paths: Vec<String>,
+    blob_ids: HashSet<Oid>,
+}
+
+impl PurgeMatcher {
+    fn matches(&self, path: &str, oid: Oid) -> bool {
+        self.blob_ids.contains(&oid)
+            || self.paths.iter().any(|purged| {
+                path == purged || (path.starts_with(purged.as_str()) && path[purged.len()..].starts_with('/'))
+            })
+    }
+}
+
+/// Tree rewriting state shared across all commits of a purge, so each tree is filtered once.
+struct PurgeState<'a> {
+    repo: &'a Repository,
+    matcher: PurgeMatcher,
+    dry_run: bool,
+    trees: HashMap<(Oid, String), Option<Oid>>,
+    removed: HashMap<String, u64>,
+    removed_blobs: HashSet<Oid>,
+}
+
+impl PurgeState<'_> {
+    /// Returns Some(new tree id) when the tree changed, None when it is untouched.
+    /// In dry-run mode the original id stands in for the new one since nothing is written.
+    fn filter_tree(&mut self, tree_id: Oid, prefix: &str) -> Result<Option<Oid>, String> {
+        let key = (tree_id, prefix.to_string());
+        if let Some(result) = self.trees.get(&key) {
+            return Ok(*result);
+        }
+
+        let tree = self.repo.find_tree(tree_id)
+            .map_err(|e| format!("Failed to find tree: {}", e))?;
+        let mut builder = if self.dry_run {
+            None
+        } else {
+            Some(self.repo.treebuilder(Some(&tree)).map_err(|e| format!("Failed to create tree builder: {}", e))?)
+        };
+        let mut changed = false;
+
+        for entry in tree.iter() {
+            let name = entry.name().unwrap_or("").to_string();
+            let path = format!("{}{}", prefix, name);
+
+            if entry.kind() != Some(ObjectType::Commit) && self.matcher.matches(&path, entry.id()) {
+                changed = true;
+                self.record_removed(&path, entry.id(), entry.kind())?;
+                if let Some(builder) = builder.as_mut() {
+                    builder.remove(&name).map_err(|e| format!("Failed to remove '{}': {}", path, e))?;
+                }
+                continue;
+            }
+
+            if entry.kind() == Some(ObjectType::Tree) {
+                if let Some(new_subtree) = self.filter_tree(entry.id(), &format!("{}/", path))? {
+                    changed = true;
+                    if let Some(builder) = builder.as_mut() {
+                        builder.insert(&name, new_subtree, entry.filemode())
+                            .map_err(|e| format!("Failed to update '{}': {}", path, e))?;
+                    }
+                }
+            }
+        }
+
+        let result = match (changed, builder) {
+            (false, _) => None,
+            (true, Some(builder)) => Some(builder.write().map_err(|e| format!("Failed to write tree: {}", e))?),
+            (true, None) => Some(tree_id),
+        };
+
+        self.trees.insert(key, result);
+        Ok(result)
+    }
+
+    fn record_removed(&mut self, path: &str, oid: Oid, kind: Option<ObjectType>) -> Result<(), String> {
+        let odb = self.repo.odb()
+            .map_err(|e| format!("Failed to open object database: {}", e))?;
+
+        if kind == Some(ObjectType::Blob) {
+            if self.removed_blobs.insert(oid) {
+                let size = odb.read_header(oid).map(|(size, _)| size as u64).unwrap_or(0);
+                *self.removed.entry(path.to_string()).or_insert(0) += size;
+            }
+            return Ok(());
+        }
+
+        // A whole directory matched: account for every blob below it
+        let tree = self.repo.find_tree(oid)
+            .map_err(|e| format!("Failed to find tree: {}", e))?;
+        let mut blobs = Vec::new();
+        tree.walk(git2::TreeWalkMode::PreOrder, |_, entry| {
+            if entry.kind() == Some(ObjectType::Blob) {
+                blobs.push(entry.id());
+            }
+            git2::TreeWalkResult::Ok
+        }).map_err(|e| format!("Failed to walk tree: {}", e))?;
+
+        for blob in blobs {
+            if self.removed_blobs.insert(blob) {
+                let size = odb.read_header(blob).map(|(size, _)| size as u64).unwrap_or(0);
+                *self.removed.entry(path.to_string()).or_insert(0) += size;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Removes paths and/or blobs from the history of every local branch and tag.
+/// With `dry_run` only reports which commits would be rewritten; otherwise every
+/// rewritten branch is first preserved as a `backup/purge-<timestamp>/<branch>` branch.
+#[tauri::command]
+pub fn purge_history(
+    app: tauri::AppHandle,
+    repo_path: String,
+    paths: Vec<String>,
+    blob_ids: Vec<String>,
+    dry_run: bool,
+) -> Result<PurgeReport, AppError> {
+    audited(&app, &repo_path, "purge_history", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+        if paths.is_empty() && blob_ids.is_empty() {
+            return Err(AppError::invalid_input("Select at least one path or blob to purge"));
+        }
+
+        let blob_ids = blob_ids
+            .iter()
+            .map(|id| Oid::from_str(id).map_err(|e| AppError::wrap(e, &format!("Invalid blob ID '{}'", id))))
+            .collect::<Result<HashSet<Oid>, AppError>>()?;
+        let paths = paths
+            .iter()
+            .map(|path| path.trim_matches('/').to_string())
+            .filter(|path| !path.is_empty())
+            .collect();
+
+        if !dry_run && !repo.is_bare() {
+            let mut options = git2::StatusOptions::new();
+            options.include_untracked(false);
+            let statuses = repo.statuses(Some(&mut options))
+                .map_err(|e| AppError::wrap(e, "Failed to get status"))?;
+            if !statuses.is_empty() {
+                return Err(AppError::conflict("Commit or stash your local changes before rewriting history", Vec::new()));
+            }
+        }
+
+        // Local branches and tags are rewritten; remote-tracking refs are left alone
+        let mut refs_to_rewrite = Vec::new();
+        for glob in ["refs/heads/*", "refs/tags/*"] {
+            let references = repo.references_glob(glob)
+                .map_err(|e| AppError::wrap(e, "Failed to list references"))?;
+            for reference in references.flatten() {
+                if let (Some(name), Ok(commit)) = (reference.name(), reference.peel_to_commit()) {
+                    refs_to_rewrite.push((name.to_string(), reference.target(), commit.id()));
+                }
+            }
+        }
+
+        let mut revwalk = repo.revwalk()
+            .map_err(|e| AppError::wrap(e, "Failed to create revwalk"))?;
+        revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::REVERSE)
+            .map_err(|e| AppError::wrap(e, "Failed to sort revwalk"))?;
+        for (_, _, commit_id) in &refs_to_rewrite {
+            revwalk.push(*commit_id)
+                .map_err(|e| AppError::wrap(e, "Failed to push commit"))?;
+        }
+
+        let mut state = PurgeState {
+            repo: &repo,
+            matcher: PurgeMatcher { paths, blob_ids },
+            dry_run,
+            trees: HashMap::new(),
+            removed: HashMap::new(),
+            removed_blobs: HashSet::new(),
+        };
+        let mut rewritten: HashMap<Oid, Oid> = HashMap::new();
+        let mut rewritten_commits = Vec::new();
+        let mut rewritten_commit_count = 0;
+
+        // Parents always come first, so their replacement is known when a child is rewritten
+        for oid in revwalk {
+            let oid = oid.map_err(|e| AppError::wrap(e, "Failed to get OID"))?;
+            let commit = repo.find_commit(oid)
+                .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
+
+            let new_tree = state.filter_tree(commit.tree_id(), "")?;
+            let parents_changed = commit.parent_ids().any(|parent| rewritten.contains_key(&parent));
+
+            if new_tree.is_none() && !parents_changed {
+                continue;
+            }
+
+            rewritten_commit_count += 1;
+            if rewritten_commits.len() < MAX_REPORTED_ISSUES {
+                rewritten_commits.push(PurgeCommit {
+                    id: oid.to_string(),
+                    summary: commit.summary().unwrap_or("").to_string(),
+                });
+            }
+
+            if dry_run {
+                rewritten.insert(oid, oid);
+                continue;
+            }
+
+            let tree = repo.find_tree(new_tree.unwrap_or_else(|| commit.tree_id()))
+                .map_err(|e| AppError::wrap(e, "Failed to find tree"))?;
+            let parents = commit.parent_ids()
+                .map(|parent| repo.find_commit(*rewritten.get(&parent).unwrap_or(&parent)))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| AppError::wrap(e, "Failed to find parent commit"))?;
+            let parent_refs: Vec<&git2::Commit> = parents.iter().collect();
+
+            let new_commit = repo.commit(
+                None,
+                &commit.author(),
+                &commit.committer(),
+                commit.message().unwrap_or(""),
+                &tree,
+                &parent_refs,
+            ).map_err(|e| AppError::wrap(e, &format!("Failed to rewrite commit {}", oid)))?;
+
+            rewritten.insert(oid, new_commit);
+        }
+
+        let affected: Vec<&(String, Option<Oid>, Oid)> = refs_to_rewrite
+            .iter()
+            .filter(|(_, _, commit_id)| rewritten.contains_key(commit_id))
+            .collect();
+
+        let mut backup_branches = Vec::new();
+
+        if !dry_run && !affected.is_empty() {
+            crate::create_backup_ref(&repo, "purge")?;
+
+            let timestamp = Utc::now().format("%Y%m%d-%H%M%S");
+
+            for (name, target, commit_id) in &affected {
+                // Mandatory backup of the original history before any ref moves
+                let short_name = name.trim_start_matches("refs/heads/").trim_start_matches("refs/");
+                let backup_name = format!("refs/heads/backup/purge-{}/{}", timestamp, short_name);
+                repo.reference(&backup_name, *commit_id, false, "codegit: backup before history purge")
+                    .map_err(|e| AppError::wrap(e, "Failed to create backup branch"))?;
+                backup_branches.push(backup_name.trim_start_matches("refs/heads/").to_string());
+
+                let new_commit_id = rewritten[commit_id];
+                let annotated_tag = target
+                    .and_then(|target| repo.find_tag(target).ok());
+
+                match annotated_tag {
+                    Some(tag) => {
+                        let new_commit = repo.find_object(new_commit_id, None)
+                            .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
+                        let tagger = tag.tagger()
+                            .map(|tagger| tagger.to_owned())
+                            .or_else(|| repo.signature().ok())
+                            .ok_or_else(|| "Failed to determine tagger".to_string())?;
+                        repo.tag(tag.name().unwrap_or(short_name), &new_commit, &tagger, tag.message().unwrap_or(""), true)
+                            .map_err(|e| AppError::wrap(e, &format!("Failed to rewrite tag {}", name)))?;
+                    }
+                    None => {
+                        repo.reference(name, new_commit_id, true, "codegit: history purge")
+                            .map_err(|e| AppError::wrap(e, &format!("Failed to update {}", name)))?;
+                    }
+                }
+            }
+
+            // Bring the index and worktree in line with the rewritten HEAD
+            let head_commit = repo.head().and_then(|head| head.peel_to_commit());
+            if let (false, Ok(head_commit)) = (repo.is_bare(), head_commit) {
+                repo.reset(head_commit.as_object(), git2::ResetType::Hard, None)
+                    .map_err(|e| AppError::wrap(e, "Failed to update working tree"))?;
+            }
+        }
+
+        let mut removed_paths: Vec<String> = state.removed.keys().cloned().collect();
+        removed_paths.sort();
+
+        Ok(PurgeReport {
+            dry_run,
+            rewritten_commit_count,
+            rewritten_commits,
+            affected_refs: affected.iter().map(|(name, _, _)| name.clone()).collect(),
+            removed_paths,
+            removed_size: state.removed.values().sum(),
+            backup_branches,
+        })
+    })
+}
+
+/// objects/ of the repository, shared with the main worktree for linked worktrees.
+fn objects_dir(repo: &Repository) -> PathBuf {
+    let git_dir = repo.path();
+    let common_dir = fs::read_to_string(git_dir.join("commondir"))
+        .map(|content| git_dir.join(content.trim()))
+        .unwrap_or_else(|_| git_dir.to_path_buf());
+    common_dir.join("objects")
+}
+
+fn commit_graph_info(repo: &Repository, repo_path: &str) -> CommitGraphInfo {
+    let info_dir = objects_dir(repo).join("info");
+    let chain_dir = info_dir.join("commit-graphs");
+
+    let mut files: Vec<PathBuf> = fs::read_to_string(chain_dir.join("commit-graph-chain"))
+        .map(|chain| {
+            chain
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|hash| chain_dir.join(format!("graph-{}.graph", hash.trim())))
+                .collect()
+        })
+        .unwrap_or_default();
+    let is_split = !files.is_empty();
+    if !is_split && info_dir.join("commit-graph").is_file() {
+        files.push(info_dir.join("commit-graph"));
+    }
+
+    let metadata: Vec<fs::Metadata> = files.iter().filter_map(|file| fs::metadata(file).ok()).collect();
+
+    CommitGraphInfo {
+        repo_path: repo_path.to_string(),
+        exists: !metadata.is_empty(),
+        is_split,
+        layer_count: metadata.len(),
+        size: metadata.iter().map(|meta| meta.len()).sum(),
+        written_at: metadata
+            .iter()
+            .filter_map(|meta| meta.modified().ok())
+            .max()
+            .map(DateTime::<Utc>::from),
+    }
+}
+
+#[tauri::command]
+pub fn get_commit_graph_info(repo_path: String) -> Result<CommitGraphInfo, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    Ok(commit_graph_info(&repo, &repo_path))
+}
+
+/// Writes the commit-graph file for everything reachable from the refs. libgit2 reads it
+/// on open, so revwalks, ahead/behind and merge-base stop parsing every commit object.
+/// `incremental` only adds a layer for new commits instead of rewriting the whole file.
+#[tauri::command]
+pub async fn write_commit_graph(
+    app: tauri::AppHandle,
+    repo_path: String,
+    incremental: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<CommitGraphInfo, AppError> {
+    run_operation(&app, "maintenance", operation_id, move |token| {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+
+        // Changed-path Bloom filters also speed up path-limited log and blame in git itself
+        let mut args = vec!["commit-graph", "write", "--reachable", "--changed-paths"];
+        if incremental.unwrap_or(false) {
+            args.push("--split");
+        }
+        token.check()?;
+        crate::run_git(&repo_path, &args, "write commit-graph")?;
+
+        Ok::<_, AppError>(commit_graph_info(&repo, &repo_path))
+    })
+    .await
+}
+
+/// Adds the commits of a fetch to an existing commit-graph, like git's fetch.writeCommitGraph.
+/// Repositories without one are left alone until maintenance writes the first.
+pub fn refresh_commit_graph(repo: &Repository, repo_path: &str) {
+    if !commit_graph_info(repo, repo_path).exists {
+        return;
+    }
+
+    if let Err(e) = crate::run_git(repo_path, &["commit-graph", "write", "--reachable", "--split"], "update commit-graph") {
+        println!("⚠️  {}", e);
+    }
+}
@@ -1 +1,54 @@
-pub mod database;
\ No newline at end of file
+pub mod ahead_behind;
+pub mod archive;
+pub mod attributes;
+pub mod audit;
+pub mod avatars;
+pub mod batch;
+pub mod blame;
+pub mod branching;
+pub mod bundle;
+pub mod checks;
+pub mod clean;
+pub mod cli;
+pub mod client_import;
+pub mod commit_cache;
+pub mod commit_message;
+pub mod database;
+pub mod deep_links;
+pub mod discovery;
+pub mod encoding;
+pub mod error;
+pub mod external_tools;
+pub mod forks;
+pub mod gerrit;
+pub mod git_command;
+pub mod health;
+pub mod hooks;
+pub mod hosting;
+pub mod identity;
+pub mod ignore;
+pub mod inventory;
+pub mod issues;
+pub mod journal;
+pub mod lfs;
+pub mod limits;
+pub mod line_endings;
+pub mod line_staging;
+pub mod maintenance;
+pub mod operations;
+pub mod pipelines;
+pub mod profiling;
+pub mod provider_repositories;
+pub mod pull_requests;
+pub mod rebase;
+pub mod reflog;
+pub mod releases;
+pub mod scopes;
+pub mod secrets;
+pub mod send_email;
+pub mod session;
+pub mod status;
+pub mod tags;
+pub mod time_machine;
+pub mod watcher;
+pub mod web_urls;
\ No newline at end of file
@@ -0,0 +1,159 @@
+use crate::commands::error::AppError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{Manager, State};
+
+static NEXT_OPERATION: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationInfo {
+    pub operation_id: String,
+    pub kind: String, // clone, fetch, pull, push, log, rebase, ...
+    pub started_at: i64,
+}
+
+/// Emitted as `operation-started` and `operation-finished`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationEvent {
+    pub operation_id: String,
+    pub kind: String,
+    pub status: String, // running, succeeded, failed, cancelled
+    pub error: Option<String>,
+}
+
+struct RunningOperation {
+    info: OperationInfo,
+    cancelled: Arc<AtomicBool>,
+}
+
+/// Operations currently running on the blocking thread pool, by operation id.
+#[derive(Default)]
+pub struct Operations(Mutex<HashMap<String, RunningOperation>>);
+
+/// Handed to every background task. Long loops call `check` and remote transfers
+/// return `!is_cancelled()` from their progress callbacks to honor cancel_operation.
+/// A default token is never cancelled, for internal work the user can't cancel.
+#[derive(Debug, Clone, Default)]
+pub struct OperationToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl OperationToken {
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            return Err(AppError::cancelled());
+        }
+        Ok(())
+    }
+}
+
+pub fn new_operation_id(kind: &str) -> String {
+    format!(
+        "{}-{}-{}",
+        kind,
+        chrono::Utc::now().timestamp_millis(),
+        NEXT_OPERATION.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Runs a blocking git task on the blocking thread pool so IPC handling stays responsive.
+/// `operation_id` lets the frontend correlate events before the command returns; a new
+/// one is generated when it is not given.
+pub async fn run_operation<T, E, F>(
+    app: &tauri::AppHandle,
+    kind: &str,
+    operation_id: Option<String>,
+    task: F,
+) -> Result<T, AppError>
+where
+    T: Send + 'static,
+    E: Into<AppError> + Send + 'static,
+    F: FnOnce(OperationToken) -> Result<T, E> + Send + 'static,
+{
+    let operation_id = operation_id.unwrap_or_else(|| new_operation_id(kind));
+    let operations = app.state::<Operations>();
+    let cancelled = Arc::new(AtomicBool::new(false));
+
+    {
+        let mut running = operations.0.lock()
+            .map_err(|_| "Operation registry is unavailable".to_string())?;
+        if running.contains_key(&operation_id) {
+            return Err(AppError::invalid_input(format!("Operation {} is already running", operation_id)));
+        }
+        running.insert(operation_id.clone(), RunningOperation {
+            info: OperationInfo {
+                operation_id: operation_id.clone(),
+                kind: kind.to_string(),
+                started_at: chrono::Utc::now().timestamp(),
+            },
+            cancelled: cancelled.clone(),
+        });
+    }
+
+    let _ = app.emit_all("operation-started", OperationEvent {
+        operation_id: operation_id.clone(),
+        kind: kind.to_string(),
+        status: "running".to_string(),
+        error: None,
+    });
+
+    let token = OperationToken { cancelled: cancelled.clone() };
+    let mut result = tauri::async_runtime::spawn_blocking(move || task(token).map_err(Into::into))
+        .await
+        .unwrap_or_else(|e| Err(AppError::from(format!("Operation {} failed: {}", operation_id, e))));
+
+    // Aborted transfers surface as libgit2 errors; report them as the cancellation they are
+    let was_cancelled = cancelled.load(Ordering::Relaxed) && result.is_err();
+    if was_cancelled {
+        result = Err(AppError::cancelled());
+    }
+
+    if let Ok(mut running) = operations.0.lock() {
+        running.remove(&operation_id);
+    }
+
+    let _ = app.emit_all("operation-finished", OperationEvent {
+        operation_id,
+        kind: kind.to_string(),
+        status: match (&result, was_cancelled) {
+            (Ok(_), _) => "succeeded",
+            (Err(_), true) => "cancelled",
+            (Err(_), false) => "failed",
+        }
+        .to_string(),
+        error: result.as_ref().err().map(|error| error.message.clone()),
+    });
+
+    result
+}
+
+#[tauri::command]
+pub fn list_operations(operations: State<'_, Operations>) -> Result<Vec<OperationInfo>, AppError> {
+    let running = operations.0.lock()
+        .map_err(|_| "Operation registry is unavailable".to_string())?;
+
+    let mut list: Vec<OperationInfo> = running.values().map(|operation| operation.info.clone()).collect();
+    list.sort_by_key(|operation| operation.started_at);
+    Ok(list)
+}
+
+/// Requests cancellation; the task stops at its next check. Returns false for unknown ids.
+#[tauri::command]
+pub fn cancel_operation(operations: State<'_, Operations>, operation_id: String) -> Result<bool, AppError> {
+    let running = operations.0.lock()
+        .map_err(|_| "Operation registry is unavailable".to_string())?;
+
+    match running.get(&operation_id) {
+        Some(operation) => {
+            operation.cancelled.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
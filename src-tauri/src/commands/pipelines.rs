@@ -0,0 +1,234 @@
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_request, hosted_repository, percent_encode, HostedRepository, Provider};
+use chrono::DateTime;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+const PIPELINE_PAGE_SIZE: usize = 20;
+const DEFAULT_LOG_TAIL_LINES: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PipelineRun {
+    pub id: u64,
+    pub name: String,
+    pub status: String, // pass, fail, pending, cancelled
+    pub branch: String,
+    pub sha: String,
+    pub url: String,
+    pub started_at: Option<String>,
+    pub duration_secs: Option<i64>, // Set once the run has finished
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FailedJobLog {
+    pub job_id: u64,
+    pub job_name: String,
+    pub url: String,
+    pub log_tail: String,
+}
+
+fn text(value: &serde_json::Value, key: &str) -> String {
+    value[key].as_str().unwrap_or("").to_string()
+}
+
+/// Seconds between two RFC 3339 timestamps.
+fn seconds_between(start: &str, end: &str) -> Option<i64> {
+    let start = DateTime::parse_from_rfc3339(start).ok()?;
+    let end = DateTime::parse_from_rfc3339(end).ok()?;
+    Some((end - start).num_seconds().max(0))
+}
+
+fn github_run(value: &serde_json::Value) -> PipelineRun {
+    let status = match (value["status"].as_str(), value["conclusion"].as_str()) {
+        (Some("completed"), Some("success")) | (Some("completed"), Some("neutral")) | (Some("completed"), Some("skipped")) => "pass",
+        (Some("completed"), Some("cancelled")) => "cancelled",
+        (Some("completed"), _) => "fail",
+        _ => "pending", // queued, in_progress, waiting
+    };
+    let started_at = value["run_started_at"].as_str().map(|started| started.to_string());
+    let duration_secs = match (status, &started_at, value["updated_at"].as_str()) {
+        ("pending", _, _) => None,
+        (_, Some(started), Some(updated)) => seconds_between(started, updated),
+        _ => None,
+    };
+
+    PipelineRun {
+        id: value["id"].as_u64().unwrap_or(0),
+        name: text(value, "name"),
+        status: status.to_string(),
+        branch: text(value, "head_branch"),
+        sha: text(value, "head_sha"),
+        url: text(value, "html_url"),
+        started_at,
+        duration_secs,
+    }
+}
+
+fn gitlab_pipeline(value: &serde_json::Value) -> PipelineRun {
+    let status = match value["status"].as_str() {
+        Some("success") | Some("skipped") => "pass",
+        Some("failed") => "fail",
+        Some("canceled") => "cancelled",
+        _ => "pending", // created, waiting_for_resource, preparing, pending, running, manual, scheduled
+    };
+    let started_at = value["created_at"].as_str().map(|created| created.to_string());
+    let duration_secs = match (status, &started_at, value["updated_at"].as_str()) {
+        ("pending", _, _) => None,
+        (_, Some(started), Some(updated)) => seconds_between(started, updated),
+        _ => None,
+    };
+    let id = value["id"].as_u64().unwrap_or(0);
+
+    PipelineRun {
+        id,
+        // Pipelines are only named when the CI config sets workflow:name
+        name: value["name"].as_str().map(|name| name.to_string()).unwrap_or_else(|| format!("Pipeline #{}", id)),
+        status: status.to_string(),
+        branch: text(value, "ref"),
+        sha: text(value, "sha"),
+        url: text(value, "web_url"),
+        started_at,
+        duration_secs,
+    }
+}
+
+fn fetch_pipeline_runs(hosted: &HostedRepository, branch: &str) -> Result<Vec<PipelineRun>, AppError> {
+    let auth = hosted.authorization()?;
+
+    match hosted.provider {
+        Provider::Github => {
+            let url = format!(
+                "{}/repos/{}/{}/actions/runs?branch={}&per_page={}",
+                hosted.api_base(), hosted.owner, hosted.name, percent_encode(branch, false), PIPELINE_PAGE_SIZE
+            );
+            let response = api_request("GET", &url, &auth, None)?;
+            Ok(response["workflow_runs"].as_array().into_iter().flatten().map(github_run).collect())
+        }
+        Provider::Gitlab => {
+            let url = format!(
+                "{}/projects/{}/pipelines?ref={}&per_page={}",
+                hosted.api_base(), hosted.gitlab_project(), percent_encode(branch, false), PIPELINE_PAGE_SIZE
+            );
+            let response = api_request("GET", &url, &auth, None)?;
+            Ok(response.as_array().into_iter().flatten().map(gitlab_pipeline).collect())
+        }
+        _ => Err(AppError::invalid_input("Pipeline runs are available for GitHub Actions and GitLab CI")),
+    }
+}
+
+/// Last `lines` lines of a job log.
+fn log_tail(log: &str, lines: usize) -> String {
+    let all: Vec<&str> = log.lines().collect();
+    all[all.len().saturating_sub(lines)..].join("\n")
+}
+
+fn fetch_failed_job_log(hosted: &HostedRepository, run_id: u64, lines: usize) -> Result<FailedJobLog, AppError> {
+    let auth = hosted.authorization()?;
+
+    let (job, log_url) = match hosted.provider {
+        Provider::Github => {
+            let url = format!(
+                "{}/repos/{}/{}/actions/runs/{}/jobs?filter=latest&per_page=100",
+                hosted.api_base(), hosted.owner, hosted.name, run_id
+            );
+            let response = api_request("GET", &url, &auth, None)?;
+            let job = response["jobs"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|job| matches!(job["conclusion"].as_str(), Some("failure") | Some("timed_out")))
+                .cloned()
+                .ok_or_else(|| AppError::not_found(format!("Run {} has no failed job", run_id)))?;
+            let log_url = format!(
+                "{}/repos/{}/{}/actions/jobs/{}/logs",
+                hosted.api_base(), hosted.owner, hosted.name, job["id"].as_u64().unwrap_or(0)
+            );
+            (FailedJobLog {
+                job_id: job["id"].as_u64().unwrap_or(0),
+                job_name: text(&job, "name"),
+                url: text(&job, "html_url"),
+                log_tail: String::new(),
+            }, log_url)
+        }
+        Provider::Gitlab => {
+            let url = format!(
+                "{}/projects/{}/pipelines/{}/jobs?scope%5B%5D=failed&per_page=100",
+                hosted.api_base(), hosted.gitlab_project(), run_id
+            );
+            let response = api_request("GET", &url, &auth, None)?;
+            let job = response
+                .as_array()
+                .and_then(|jobs| jobs.first())
+                .cloned()
+                .ok_or_else(|| AppError::not_found(format!("Pipeline {} has no failed job", run_id)))?;
+            let log_url = format!(
+                "{}/projects/{}/jobs/{}/trace",
+                hosted.api_base(), hosted.gitlab_project(), job["id"].as_u64().unwrap_or(0)
+            );
+            (FailedJobLog {
+                job_id: job["id"].as_u64().unwrap_or(0),
+                job_name: text(&job, "name"),
+                url: text(&job, "web_url"),
+                log_tail: String::new(),
+            }, log_url)
+        }
+        _ => return Err(AppError::invalid_input("Pipeline runs are available for GitHub Actions and GitLab CI")),
+    };
+
+    // Logs are plain text, which api_request hands back as a JSON string
+    let log = api_request("GET", &log_url, &auth, None)?;
+    Ok(FailedJobLog {
+        log_tail: log_tail(log.as_str().unwrap_or(""), lines),
+        ..job
+    })
+}
+
+/// Recent CI runs (GitHub Actions workflow runs or GitLab pipelines) for `branch`, the
+/// current branch by default, newest first.
+#[tauri::command]
+pub async fn list_pipeline_runs(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branch: Option<String>,
+    remote_name: Option<String>,
+) -> Result<Vec<PipelineRun>, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let branch = match branch {
+            Some(branch) => branch,
+            None => repo.head()
+                .ok()
+                .filter(|head| head.is_branch())
+                .and_then(|head| head.shorthand().map(|name| name.to_string()))
+                .ok_or_else(|| AppError::invalid_input("Check out a branch to see its pipeline runs"))?,
+        };
+
+        let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&app, &repo_path));
+        let hosted = hosted_repository(&repo, &remote_name)?;
+        fetch_pipeline_runs(&hosted, &branch)
+            .map_err(|e| AppError::wrap(e, "Failed to list pipeline runs").with_host(Some(hosted.host.clone())))
+    })
+    .await?
+}
+
+/// The end of the log of the first failed job in a run, to show why it went red.
+#[tauri::command]
+pub async fn get_failed_job_log(
+    app: tauri::AppHandle,
+    repo_path: String,
+    run_id: u64,
+    lines: Option<usize>,
+    remote_name: Option<String>,
+) -> Result<FailedJobLog, AppError> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&app, &repo_path));
+        let hosted = hosted_repository(&repo, &remote_name)?;
+
+        fetch_failed_job_log(&hosted, run_id, lines.unwrap_or(DEFAULT_LOG_TAIL_LINES))
+            .map_err(|e| AppError::wrap(e, "Failed to get job log").with_host(Some(hosted.host.clone())))
+    })
+    .await?
+}
@@ -0,0 +1,142 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::database::{CommandTiming, RepositoryTiming};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+use tauri::{Manager, State};
+
+const DEFAULT_REPORT_HOURS: i64 = 24 * 7;
+const SLOWEST_REPOSITORIES: i64 = 10;
+
+/// Whether command timings are being recorded; mirrors "profiling_enabled" in the
+/// settings JSON so the hot path never touches the database to find out.
+#[derive(Default)]
+pub struct Profiler {
+    enabled: AtomicBool,
+}
+
+impl Profiler {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Relaxed)
+    }
+
+    pub fn set_enabled(&self, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+    }
+
+    pub fn sync_with_settings(&self, settings_json: &serde_json::Value) {
+        self.set_enabled(profiling_setting(settings_json));
+    }
+}
+
+fn profiling_setting(settings_json: &serde_json::Value) -> bool {
+    settings_json
+        .get("profiling_enabled")
+        .and_then(|enabled| enabled.as_bool())
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PerformanceReport {
+    pub enabled: bool,
+    pub since: chrono::DateTime<chrono::Utc>,
+    pub commands: Vec<CommandTiming>,
+    pub slowest_repositories: Vec<RepositoryTiming>,
+}
+
+/// Stores one timing without making the caller wait for the database.
+pub fn record_timing(app: &tauri::AppHandle, command: &str, repo_path: Option<&str>, started: Instant) {
+    if !app.try_state::<Profiler>().map(|profiler| profiler.is_enabled()).unwrap_or(false) {
+        return;
+    }
+
+    let duration_ms = started.elapsed().as_millis() as i64;
+    let (app, command, repo_path) = (app.clone(), command.to_string(), repo_path.map(|path| path.to_string()));
+    tauri::async_runtime::spawn(async move {
+        if let Some(db_state) = app.try_state::<DatabaseState>() {
+            let db = db_state.lock().await;
+            if let Err(e) = db.record_command_timing(&command, repo_path.as_deref(), duration_ms).await {
+                println!("⚠️  Failed to record timing for {}: {}", command, e);
+            }
+        }
+    });
+}
+
+/// Runs `task` and records how long it took when profiling is enabled.
+pub fn timed<T, F>(app: &tauri::AppHandle, command: &str, repo_path: &str, task: F) -> T
+where
+    F: FnOnce() -> T,
+{
+    let started = Instant::now();
+    let result = task();
+    record_timing(app, command, Some(repo_path), started);
+    result
+}
+
+/// Reads the setting once the database is up.
+pub async fn load_profiling_setting(app: &tauri::AppHandle) {
+    let settings = match app.try_state::<DatabaseState>() {
+        Some(db_state) => db_state.lock().await.get_settings().await,
+        None => return,
+    };
+    if let Ok(settings) = settings {
+        app.state::<Profiler>().sync_with_settings(&settings.settings_json);
+    }
+}
+
+#[tauri::command]
+pub async fn set_profiling_enabled(
+    db_state: State<'_, DatabaseState>,
+    profiler: State<'_, Profiler>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    let mut settings = db.get_settings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get settings"))?;
+
+    if let Some(settings_json) = settings.settings_json.as_object_mut() {
+        settings_json.insert("profiling_enabled".to_string(), serde_json::Value::Bool(enabled));
+    }
+    db.update_settings(&settings)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to update settings"))?;
+
+    profiler.set_enabled(enabled);
+    Ok(())
+}
+
+/// Per-command durations and the repositories where the most time went, over the last
+/// `hours` (a week by default).
+#[tauri::command]
+pub async fn get_performance_report(
+    db_state: State<'_, DatabaseState>,
+    profiler: State<'_, Profiler>,
+    hours: Option<i64>,
+) -> Result<PerformanceReport, AppError> {
+    let since = chrono::Utc::now() - chrono::Duration::hours(hours.unwrap_or(DEFAULT_REPORT_HOURS));
+    let db = db_state.lock().await;
+
+    let commands = db.get_command_timings(since)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get command timings"))?;
+    let slowest_repositories = db.get_slowest_repositories(since, SLOWEST_REPOSITORIES)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get repository timings"))?;
+
+    Ok(PerformanceReport {
+        enabled: profiler.is_enabled(),
+        since,
+        commands,
+        slowest_repositories,
+    })
+}
+
+#[tauri::command]
+pub async fn clear_performance_data(db_state: State<'_, DatabaseState>) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.clear_command_timings()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to clear command timings"))
+}
@@ -0,0 +1,148 @@
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_base, api_request, authorization, detect_provider, percent_encode, Provider};
+use serde::{Deserialize, Serialize};
+
+const REPOSITORY_PAGE_SIZE: usize = 50;
+// GitHub can't search by name across everything a user can access, so a query filters
+// the user's repositories client-side, reading at most this many pages of 100
+const GITHUB_SEARCH_PAGES: u32 = 10;
+
+/// A repository the signed-in account can clone.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderRepository {
+    pub provider: Provider,
+    pub full_name: String,
+    pub description: Option<String>,
+    pub clone_url: String,
+    pub ssh_url: String,
+    pub web_url: String,
+    pub private: bool,
+    pub default_branch: Option<String>,
+    pub updated_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProviderRepositoryPage {
+    pub repositories: Vec<ProviderRepository>,
+    pub page: u32,
+    pub has_more: bool,
+}
+
+fn optional_text(value: &serde_json::Value, key: &str) -> Option<String> {
+    value[key].as_str().filter(|text| !text.is_empty()).map(|text| text.to_string())
+}
+
+fn github_repository(value: &serde_json::Value) -> ProviderRepository {
+    ProviderRepository {
+        provider: Provider::Github,
+        full_name: optional_text(value, "full_name").unwrap_or_default(),
+        description: optional_text(value, "description"),
+        clone_url: optional_text(value, "clone_url").unwrap_or_default(),
+        ssh_url: optional_text(value, "ssh_url").unwrap_or_default(),
+        web_url: optional_text(value, "html_url").unwrap_or_default(),
+        private: value["private"].as_bool().unwrap_or(false),
+        default_branch: optional_text(value, "default_branch"),
+        updated_at: optional_text(value, "updated_at"),
+    }
+}
+
+fn gitlab_repository(value: &serde_json::Value) -> ProviderRepository {
+    ProviderRepository {
+        provider: Provider::Gitlab,
+        full_name: optional_text(value, "path_with_namespace").unwrap_or_default(),
+        description: optional_text(value, "description"),
+        clone_url: optional_text(value, "http_url_to_repo").unwrap_or_default(),
+        ssh_url: optional_text(value, "ssh_url_to_repo").unwrap_or_default(),
+        web_url: optional_text(value, "web_url").unwrap_or_default(),
+        private: value["visibility"].as_str() != Some("public"),
+        default_branch: optional_text(value, "default_branch"),
+        updated_at: optional_text(value, "last_activity_at"),
+    }
+}
+
+fn github_repositories(host: &str, auth: &str, query: &str, page: u32) -> Result<ProviderRepositoryPage, AppError> {
+    let base = api_base(Provider::Github, host);
+
+    if query.is_empty() {
+        let url = format!(
+            "{}/user/repos?sort=updated&per_page={}&page={}",
+            base, REPOSITORY_PAGE_SIZE, page
+        );
+        let response = api_request("GET", &url, auth, None)?;
+        let repositories: Vec<ProviderRepository> = response.as_array().into_iter().flatten().map(github_repository).collect();
+        return Ok(ProviderRepositoryPage {
+            has_more: repositories.len() == REPOSITORY_PAGE_SIZE,
+            repositories,
+            page,
+        });
+    }
+
+    let query = query.to_lowercase();
+    let mut matches = Vec::new();
+    for scan_page in 1..=GITHUB_SEARCH_PAGES {
+        let url = format!("{}/user/repos?sort=updated&per_page=100&page={}", base, scan_page);
+        let response = api_request("GET", &url, auth, None)?;
+        let items = response.as_array().cloned().unwrap_or_default();
+
+        matches.extend(
+            items.iter()
+                .map(github_repository)
+                .filter(|repository| repository.full_name.to_lowercase().contains(&query)),
+        );
+        if items.len() < 100 {
+            break;
+        }
+    }
+
+    let start = (page as usize - 1) * REPOSITORY_PAGE_SIZE;
+    Ok(ProviderRepositoryPage {
+        has_more: matches.len() > start + REPOSITORY_PAGE_SIZE,
+        repositories: matches.into_iter().skip(start).take(REPOSITORY_PAGE_SIZE).collect(),
+        page,
+    })
+}
+
+fn gitlab_repositories(host: &str, auth: &str, query: &str, page: u32) -> Result<ProviderRepositoryPage, AppError> {
+    let mut url = format!(
+        "{}/projects?membership=true&order_by=last_activity_at&per_page={}&page={}",
+        api_base(Provider::Gitlab, host), REPOSITORY_PAGE_SIZE, page
+    );
+    if !query.is_empty() {
+        url.push_str(&format!("&search={}", percent_encode(query, false)));
+    }
+
+    let response = api_request("GET", &url, auth, None)?;
+    let repositories: Vec<ProviderRepository> = response.as_array().into_iter().flatten().map(gitlab_repository).collect();
+    Ok(ProviderRepositoryPage {
+        has_more: repositories.len() == REPOSITORY_PAGE_SIZE,
+        repositories,
+        page,
+    })
+}
+
+/// Repositories the account on `account` (a host such as "github.com" or a GitLab
+/// instance) can access, most recently updated first, for the clone dialog. `query`
+/// narrows by name; `page` starts at 1.
+#[tauri::command]
+pub async fn list_provider_repositories(
+    account: String,
+    query: Option<String>,
+    page: Option<u32>,
+) -> Result<ProviderRepositoryPage, AppError> {
+    let host = account.trim().to_lowercase();
+    let provider = detect_provider(&host)
+        .ok_or_else(|| AppError::invalid_input(format!("Unknown hosting provider: {}", account)))?;
+    let query = query.unwrap_or_default().trim().to_string();
+    let page = page.unwrap_or(1).max(1);
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let auth = authorization(provider, &host)?;
+        match provider {
+            Provider::Github => github_repositories(&host, &auth, &query, page),
+            Provider::Gitlab => gitlab_repositories(&host, &auth, &query, page),
+            _ => Err(AppError::invalid_input("Browsing repositories is available for GitHub and GitLab accounts")),
+        }
+        .map_err(|e| AppError::wrap(e, "Failed to list repositories").with_host(Some(host.clone())))
+    })
+    .await?
+}
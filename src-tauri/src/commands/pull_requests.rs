@@ -0,0 +1,336 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_request, hosted_repository, HostedRepository, Provider, AZURE_API_VERSION};
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+// GitHub caps a page at 100 and Bitbucket at 50; one page covers the common case
+const PULL_REQUEST_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequest {
+    pub provider: Provider,
+    pub number: u64,
+    pub url: String,
+    pub title: String,
+    pub state: String, // open, closed, merged
+    pub draft: bool,
+    pub head: String,
+    pub base: String,
+    pub author: Option<String>,
+}
+
+fn github_pull_request(value: &serde_json::Value) -> PullRequest {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let state = if value["merged_at"].is_string() { "merged".to_string() } else { text("/state") };
+
+    PullRequest {
+        provider: Provider::Github,
+        number: value["number"].as_u64().unwrap_or(0),
+        url: text("/html_url"),
+        title: text("/title"),
+        state,
+        draft: value["draft"].as_bool().unwrap_or(false),
+        head: text("/head/ref"),
+        base: text("/base/ref"),
+        author: value.pointer("/user/login").and_then(|v| v.as_str()).map(|login| login.to_string()),
+    }
+}
+
+fn bitbucket_pull_request(value: &serde_json::Value) -> PullRequest {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let state = match text("/state").as_str() {
+        "OPEN" => "open",
+        "MERGED" => "merged",
+        _ => "closed", // DECLINED, SUPERSEDED
+    };
+
+    PullRequest {
+        provider: Provider::Bitbucket,
+        number: value["id"].as_u64().unwrap_or(0),
+        url: text("/links/html/href"),
+        title: text("/title"),
+        state: state.to_string(),
+        draft: value["draft"].as_bool().unwrap_or(false),
+        head: text("/source/branch/name"),
+        base: text("/destination/branch/name"),
+        author: value.pointer("/author/display_name").and_then(|v| v.as_str()).map(|name| name.to_string()),
+    }
+}
+
+fn azure_pull_request(hosted: &HostedRepository, value: &serde_json::Value) -> PullRequest {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let branch = |pointer: &str| text(pointer).trim_start_matches("refs/heads/").to_string();
+    let number = value["pullRequestId"].as_u64().unwrap_or(0);
+    let state = match text("/status").as_str() {
+        "active" => "open",
+        "completed" => "merged",
+        _ => "closed", // abandoned
+    };
+
+    PullRequest {
+        provider: Provider::AzureDevops,
+        number,
+        // The response only links the API resource
+        url: format!("{}/pullrequest/{}", hosted.web_url(), number),
+        title: text("/title"),
+        state: state.to_string(),
+        draft: value["isDraft"].as_bool().unwrap_or(false),
+        head: branch("/sourceRefName"),
+        base: branch("/targetRefName"),
+        author: value.pointer("/createdBy/displayName").and_then(|v| v.as_str()).map(|name| name.to_string()),
+    }
+}
+
+/// GitLab calls them merge requests and numbers them per project with "iid".
+fn gitlab_pull_request(value: &serde_json::Value) -> PullRequest {
+    let text = |pointer: &str| value.pointer(pointer).and_then(|v| v.as_str()).unwrap_or("").to_string();
+    let state = match text("/state").as_str() {
+        "opened" => "open",
+        "merged" => "merged",
+        _ => "closed", // closed, locked
+    };
+
+    PullRequest {
+        provider: Provider::Gitlab,
+        number: value["iid"].as_u64().unwrap_or(0),
+        url: text("/web_url"),
+        title: text("/title"),
+        state: state.to_string(),
+        draft: value["draft"].as_bool().unwrap_or(false),
+        head: text("/source_branch"),
+        base: text("/target_branch"),
+        author: value.pointer("/author/username").and_then(|v| v.as_str()).map(|name| name.to_string()),
+    }
+}
+
+fn azure_pull_requests_url(hosted: &HostedRepository) -> String {
+    format!("{}/{}/_apis/git/repositories/{}/pullrequests", hosted.api_base(), hosted.owner, hosted.name)
+}
+
+/// True when the remote-tracking branch already points at the local tip.
+fn is_pushed(repo: &Repository, remote_name: &str, branch: &str) -> bool {
+    let local = repo.refname_to_id(&format!("refs/heads/{}", branch));
+    let remote = repo.refname_to_id(&format!("refs/remotes/{}/{}", remote_name, branch));
+    matches!((local, remote), (Ok(local), Ok(remote)) if local == remote)
+}
+
+fn open_pull_request(
+    hosted: &HostedRepository,
+    base: &str,
+    head: &str,
+    title: &str,
+    body: &str,
+    draft: bool,
+) -> Result<PullRequest, AppError> {
+    let auth = hosted.authorization()?;
+
+    match hosted.provider {
+        Provider::Github => {
+            let url = format!("{}/repos/{}/{}/pulls", hosted.api_base(), hosted.owner, hosted.name);
+            let request = serde_json::json!({
+                "title": title,
+                "body": body,
+                "head": head,
+                "base": base,
+                "draft": draft,
+            });
+            let response = api_request("POST", &url, &auth, Some(&request))
+                .map_err(|e| AppError::wrap(e, "Failed to create pull request").with_host(Some(hosted.host.clone())))?;
+            Ok(github_pull_request(&response))
+        }
+        Provider::Bitbucket => {
+            let url = format!("{}/repositories/{}/{}/pullrequests", hosted.api_base(), hosted.owner, hosted.name);
+            let request = serde_json::json!({
+                "title": title,
+                "description": body,
+                "source": { "branch": { "name": head } },
+                "destination": { "branch": { "name": base } },
+                "draft": draft,
+            });
+            let response = api_request("POST", &url, &auth, Some(&request))
+                .map_err(|e| AppError::wrap(e, "Failed to create pull request").with_host(Some(hosted.host.clone())))?;
+            Ok(bitbucket_pull_request(&response))
+        }
+        Provider::AzureDevops => {
+            let url = format!("{}?api-version={}", azure_pull_requests_url(hosted), AZURE_API_VERSION);
+            let request = serde_json::json!({
+                "title": title,
+                "description": body,
+                "sourceRefName": format!("refs/heads/{}", head),
+                "targetRefName": format!("refs/heads/{}", base),
+                "isDraft": draft,
+            });
+            let response = api_request("POST", &url, &auth, Some(&request))
+                .map_err(|e| AppError::wrap(e, "Failed to create pull request").with_host(Some(hosted.host.clone())))?;
+            Ok(azure_pull_request(hosted, &response))
+        }
+        Provider::Gitlab => {
+            let url = format!("{}/projects/{}/merge_requests", hosted.api_base(), hosted.gitlab_project());
+            // GitLab marks drafts by title prefix
+            let title = if draft { format!("Draft: {}", title) } else { title.to_string() };
+            let request = serde_json::json!({
+                "title": title,
+                "description": body,
+                "source_branch": head,
+                "target_branch": base,
+            });
+            let response = api_request("POST", &url, &auth, Some(&request))
+                .map_err(|e| AppError::wrap(e, "Failed to create pull request").with_host(Some(hosted.host.clone())))?;
+            Ok(gitlab_pull_request(&response))
+        }
+    }
+}
+
+/// Pull requests in `state` ("open", "closed" or "all"), newest first, first page only.
+fn fetch_pull_requests(hosted: &HostedRepository, state: &str) -> Result<Vec<PullRequest>, AppError> {
+    let auth = hosted.authorization()?;
+
+    let url = match hosted.provider {
+        Provider::Github => format!(
+            "{}/repos/{}/{}/pulls?state={}&per_page={}",
+            hosted.api_base(), hosted.owner, hosted.name, state, PULL_REQUEST_PAGE_SIZE
+        ),
+        Provider::Bitbucket => {
+            let states = match state {
+                "open" => "state=OPEN",
+                "closed" => "state=MERGED&state=DECLINED&state=SUPERSEDED",
+                _ => "state=OPEN&state=MERGED&state=DECLINED&state=SUPERSEDED",
+            };
+            format!(
+                "{}/repositories/{}/{}/pullrequests?{}&pagelen={}",
+                hosted.api_base(), hosted.owner, hosted.name, states, PULL_REQUEST_PAGE_SIZE
+            )
+        }
+        Provider::AzureDevops => {
+            // "closed" covers two statuses, so it is filtered after fetching all
+            let status = if state == "open" { "active" } else { "all" };
+            format!(
+                "{}?searchCriteria.status={}&$top={}&api-version={}",
+                azure_pull_requests_url(hosted), status, PULL_REQUEST_PAGE_SIZE, AZURE_API_VERSION
+            )
+        }
+        Provider::Gitlab => {
+            // Likewise closed and merged are separate states on GitLab
+            let gitlab_state = if state == "open" { "opened" } else { "all" };
+            format!(
+                "{}/projects/{}/merge_requests?state={}&order_by=updated_at&per_page={}",
+                hosted.api_base(), hosted.gitlab_project(), gitlab_state, PULL_REQUEST_PAGE_SIZE
+            )
+        }
+    };
+
+    let response = api_request("GET", &url, &auth, None)
+        .map_err(|e| AppError::wrap(e, "Failed to list pull requests").with_host(Some(hosted.host.clone())))?;
+    // GitHub and GitLab return an array, Bitbucket a page object with the items in "values",
+    // Azure in "value"
+    let items = response.as_array()
+        .or_else(|| response["values"].as_array())
+        .or_else(|| response["value"].as_array())
+        .cloned()
+        .unwrap_or_default();
+
+    let pull_requests = items.iter().map(|item| match hosted.provider {
+        Provider::Github => github_pull_request(item),
+        Provider::Bitbucket => bitbucket_pull_request(item),
+        Provider::AzureDevops => azure_pull_request(hosted, item),
+        Provider::Gitlab => gitlab_pull_request(item),
+    });
+    Ok(pull_requests
+        .filter(|pull_request| state != "closed" || pull_request.state != "open")
+        .collect())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_pull_request_blocking(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: String,
+    base: String,
+    head: Option<String>,
+    title: String,
+    body: String,
+    draft: bool,
+    token: OperationToken,
+) -> Result<PullRequest, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let hosted = hosted_repository(&repo, &remote_name)?;
+
+    let head = match head {
+        Some(head) => head,
+        None => repo.head()
+            .ok()
+            .filter(|head| head.is_branch())
+            .and_then(|head| head.shorthand().map(|name| name.to_string()))
+            .ok_or_else(|| AppError::invalid_input("Check out a branch to open a pull request from"))?,
+    };
+    if title.trim().is_empty() {
+        return Err(AppError::invalid_input("A pull request needs a title"));
+    }
+
+    if !is_pushed(&repo, &remote_name, &head) {
+        crate::push_to_remote_blocking(app, repo_path, Some(remote_name), head.clone(), token.clone())?;
+    }
+    token.check()?;
+
+    open_pull_request(&hosted, &base, &head, &title, &body, draft)
+}
+
+/// Opens a pull request from `head` (the current branch by default) into `base`, pushing
+/// the branch first when the remote doesn't have its latest commit.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn create_pull_request(
+    app: tauri::AppHandle,
+    repo_path: String,
+    base: String,
+    head: Option<String>,
+    title: String,
+    body: Option<String>,
+    draft: Option<bool>,
+    remote_name: Option<String>,
+    operation_id: Option<String>,
+) -> Result<PullRequest, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "pull_request", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "create_pull_request", || {
+            let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&task_app, &repo_path));
+            create_pull_request_blocking(
+                task_app.clone(),
+                repo_path,
+                remote_name,
+                base,
+                head,
+                title,
+                body.unwrap_or_default(),
+                draft.unwrap_or(false),
+                token,
+            )
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn list_pull_requests(
+    app: tauri::AppHandle,
+    repo_path: String,
+    state: Option<String>,
+    remote_name: Option<String>,
+) -> Result<Vec<PullRequest>, AppError> {
+    let state = state.unwrap_or_else(|| "open".to_string());
+    if !matches!(state.as_str(), "open" | "closed" | "all") {
+        return Err(AppError::invalid_input(format!("Unknown pull request state: {}", state)));
+    }
+
+    tauri::async_runtime::spawn_blocking(move || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&app, &repo_path));
+        fetch_pull_requests(&hosted_repository(&repo, &remote_name)?, &state)
+    })
+    .await?
+}
@@ -0,0 +1,450 @@
+use crate::commands::audit::audited;
+use crate::commands::commit_cache::extend_commit_cache;
+use crate::commands::error::AppError;
+use crate::commands::identity::{create_commit, resolve_signature, signing_config};
+use crate::commands::journal::{has_uncommitted_changes, journal_operation, ref_change};
+use crate::commands::operations::{run_operation, OperationToken};
+use crate::{RebaseAction, RebaseCommit, RebasePlan};
+use git2::{Commit, Oid, Repository, ResetType, Signature};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+const STATE_FILE: &str = "codegit-rebase.json";
+
+/// Progress of an interactive rebase, kept in the git directory so it survives between
+/// the command that pauses it and the one that resumes it.
+#[derive(Debug, Serialize, Deserialize)]
+struct RebaseState {
+    onto_branch: String,
+    branch_ref: Option<String>, // None when the rebase started on a detached HEAD
+    orig_head: String,
+    current: Option<RebaseCommit>, // Applied but not committed: stopped on conflicts or for editing
+    remaining: Vec<RebaseCommit>,
+    applied: usize,
+}
+
+fn state_path(repo: &Repository) -> PathBuf {
+    repo.path().join(STATE_FILE)
+}
+
+fn load_state(repo: &Repository) -> Result<Option<RebaseState>, AppError> {
+    let path = state_path(repo);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::wrap(e, "Failed to read rebase state"))?;
+    serde_json::from_str(&content)
+        .map(Some)
+        .map_err(|e| AppError::wrap(e, "Failed to parse rebase state"))
+}
+
+fn save_state(repo: &Repository, state: &RebaseState) -> Result<(), AppError> {
+    let content = serde_json::to_string_pretty(state)
+        .map_err(|e| AppError::wrap(e, "Failed to serialize rebase state"))?;
+    std::fs::write(state_path(repo), content)
+        .map_err(|e| AppError::wrap(e, "Failed to write rebase state"))
+}
+
+fn clear_state(repo: &Repository) -> Result<(), AppError> {
+    match std::fs::remove_file(state_path(repo)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(AppError::wrap(e, "Failed to remove rebase state")),
+        _ => Ok(()),
+    }
+}
+
+fn find_commit<'r>(repo: &'r Repository, id: &str) -> Result<Commit<'r>, AppError> {
+    let oid = Oid::from_str(id)
+        .map_err(|_| AppError::invalid_input(format!("Invalid commit ID: {}", id)))?;
+    repo.find_commit(oid)
+        .map_err(|_| AppError::not_found(format!("Commit {} does not exist", id)))
+}
+
+fn head_commit(repo: &Repository) -> Result<Commit<'_>, AppError> {
+    repo.head()
+        .and_then(|head| head.peel_to_commit())
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))
+}
+
+fn short_id(id: &str) -> &str {
+    &id[..id.len().min(7)]
+}
+
+/// Applies `commit` to the index and working tree on top of HEAD. Each commit gets its own
+/// single-step git2 rebase, so the plan's order is kept however it was rearranged. Returns
+/// the conflicted paths, if any.
+fn apply_commit(repo: &Repository, commit: &Commit) -> Result<Vec<String>, AppError> {
+    let branch = repo.find_annotated_commit(commit.id())
+        .map_err(|e| AppError::wrap(e, "Failed to read commit"))?;
+    let upstream = match commit.parent_id(0) {
+        Ok(parent) => Some(repo.find_annotated_commit(parent).map_err(|e| AppError::wrap(e, "Failed to read parent commit"))?),
+        Err(_) => None,
+    };
+    let onto = repo.find_annotated_commit(head_commit(repo)?.id())
+        .map_err(|e| AppError::wrap(e, "Failed to read HEAD commit"))?;
+
+    let mut rebase = repo.rebase(Some(&branch), upstream.as_ref(), Some(&onto), None)
+        .map_err(|e| AppError::wrap(e, "Failed to start rebase"))?;
+    if let Some(operation) = rebase.next() {
+        operation.map_err(|e| AppError::wrap(e, &format!("Failed to apply {}", short_id(&commit.id().to_string()))))?;
+    }
+
+    let index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+    if index.has_conflicts() {
+        return Ok(crate::conflicted_paths(&index)?);
+    }
+    Ok(Vec::new())
+}
+
+/// Clears the state of the single-step rebase opened by apply_commit. HEAD is detached
+/// throughout, so finishing it moves no branch.
+fn finish_step(repo: &Repository) -> Result<(), AppError> {
+    if let Ok(mut rebase) = repo.open_rebase(None) {
+        rebase.finish(None)
+            .map_err(|e| AppError::wrap(e, "Failed to finish rebase step"))?;
+    }
+    Ok(())
+}
+
+/// Commits what apply_commit left in the index, keeping the original author. Squash and
+/// Fixup entries amend HEAD instead when `melds_into_head`, i.e. HEAD was made by this
+/// rebase. Returns None when the changes were already on HEAD and there was nothing to commit.
+fn commit_step(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    entry: &RebaseCommit,
+    committer: &Signature,
+    melds_into_head: bool,
+) -> Result<Option<Oid>, AppError> {
+    let original = find_commit(repo, &entry.id)?;
+    let mut index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+    if index.has_conflicts() {
+        let conflicts = crate::conflicted_paths(&index)?;
+        return Err(AppError::conflict(format!("Resolve the remaining conflicts first: {}", conflicts.join(", ")), conflicts));
+    }
+
+    let tree_id = index.write_tree()
+        .map_err(|e| AppError::wrap(e, "Failed to write tree"))?;
+    let head = head_commit(repo)?;
+    if tree_id == head.tree_id() {
+        finish_step(repo)?;
+        return Ok(None);
+    }
+    let tree = repo.find_tree(tree_id)
+        .map_err(|e| AppError::wrap(e, "Failed to find tree"))?;
+
+    let planned_message = Some(entry.message.as_str()).filter(|message| !message.trim().is_empty());
+    let original_message = original.message().unwrap_or("<no message>");
+    let signing = signing_config(app, repo, repo_path, "commit.gpgsign");
+    let commit_id = match entry.action {
+        // HEAD is the previous commit of this rebase; it is replaced by one holding both changes
+        RebaseAction::Squash | RebaseAction::Fixup if melds_into_head => {
+            let previous_message = head.message().unwrap_or("").trim_end();
+            let message = match entry.action {
+                RebaseAction::Squash => format!("{}\n\n{}", previous_message, planned_message.unwrap_or(original_message).trim_end()),
+                _ => previous_message.to_string(),
+            };
+            let parents: Vec<Commit> = head.parents().collect();
+            let parents: Vec<&Commit> = parents.iter().collect();
+            let commit_id = create_commit(repo, signing.as_ref(), None, &head.author(), committer, &format!("{}\n", message), &tree, &parents)
+                .map_err(|e| AppError::wrap(e, "Failed to create squashed commit"))?;
+            repo.set_head_detached(commit_id)
+                .map_err(|e| AppError::wrap(e, "Failed to update HEAD"))?;
+            commit_id
+        }
+        _ => {
+            let message = match entry.action {
+                RebaseAction::Reword => planned_message.unwrap_or(original_message),
+                _ => original_message,
+            };
+            create_commit(repo, signing.as_ref(), Some("HEAD"), &original.author(), committer, message, &tree, &[&head])
+                .map_err(|e| AppError::wrap(e, "Failed to create commit"))?
+        }
+    };
+
+    finish_step(repo)?;
+    Ok(Some(commit_id))
+}
+
+/// Replays the remaining commits of the plan, stopping on conflicts and at Edit entries.
+fn run_plan(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    repo_path: &str,
+    state: &mut RebaseState,
+    committer: &Signature,
+    token: &OperationToken,
+) -> Result<String, AppError> {
+    while !state.remaining.is_empty() {
+        token.check()?;
+        let entry = state.remaining.remove(0);
+        let commit = find_commit(repo, &entry.id)?;
+        // Like git, merges are flattened: the commits they brought in are in the plan already
+        if matches!(entry.action, RebaseAction::Drop) || commit.parent_count() > 1 {
+            save_state(repo, state)?;
+            continue;
+        }
+
+        let conflicts = apply_commit(repo, &commit)?;
+        if !conflicts.is_empty() || matches!(entry.action, RebaseAction::Edit) {
+            let short = short_id(&entry.id).to_string();
+            state.current = Some(entry);
+            save_state(repo, state)?;
+            if conflicts.is_empty() {
+                return Ok(format!("Stopped at {} for editing; stage your changes and continue the rebase", short));
+            }
+            return Err(AppError::conflict(
+                format!("Rebase stopped at {} with conflicts in: {}", short, conflicts.join(", ")),
+                conflicts,
+            ));
+        }
+
+        if commit_step(app, repo, repo_path, &entry, committer, state.applied > 0)?.is_some() {
+            state.applied += 1;
+        }
+        save_state(repo, state)?;
+    }
+
+    finish_rebase(app, repo, repo_path, state)
+}
+
+/// Points the rebased branch at the result and checks it out again.
+fn finish_rebase(app: &tauri::AppHandle, repo: &Repository, repo_path: &str, state: &RebaseState) -> Result<String, AppError> {
+    let new_head = head_commit(repo)?.id();
+    let updated_ref = state.branch_ref.clone().unwrap_or_else(|| "HEAD".to_string());
+    if let Some(branch_ref) = &state.branch_ref {
+        repo.reference(branch_ref, new_head, true, &format!("rebase finished: {} onto {}", branch_ref, state.onto_branch))
+            .map_err(|e| AppError::wrap(e, "Failed to update branch"))?;
+        repo.set_head(branch_ref)
+            .map_err(|e| AppError::wrap(e, "Failed to check out the rebased branch"))?;
+    }
+    // Every step's git2 rebase moved ORIG_HEAD to the commit it replayed
+    if let Ok(orig_head) = Oid::from_str(&state.orig_head) {
+        let _ = repo.reference("ORIG_HEAD", orig_head, true, "rebase finished");
+    }
+    clear_state(repo)?;
+
+    journal_operation(
+        app,
+        repo_path,
+        "rebase",
+        format!("interactive rebase onto '{}'", state.onto_branch),
+        "hard",
+        vec![ref_change(repo, &updated_ref, Some(state.orig_head.clone()))],
+    );
+    extend_commit_cache(app, repo, repo_path);
+
+    Ok(format!("Interactive rebase completed successfully. {} commits processed.", state.applied))
+}
+
+/// The action and target subject of a `fixup! <subject>` or `squash! <subject>` commit.
+/// Repeated prefixes ("fixup! fixup! ...") all point at the same target.
+fn autosquash_target(message: &str) -> Option<(RebaseAction, &str)> {
+    let mut subject = message.lines().next().unwrap_or("");
+    let action = if subject.starts_with("fixup! ") {
+        RebaseAction::Fixup
+    } else if subject.starts_with("squash! ") {
+        RebaseAction::Squash
+    } else {
+        return None;
+    };
+    while let Some(rest) = subject.strip_prefix("fixup! ").or_else(|| subject.strip_prefix("squash! ")) {
+        subject = rest.trim_start();
+    }
+    Some((action, subject)).filter(|(_, subject)| !subject.is_empty())
+}
+
+/// Moves `fixup!` and `squash!` commits right after the commit they name, by subject,
+/// commit ID prefix or subject prefix, like `git rebase --autosquash`. Commits whose
+/// target is not earlier in the plan stay where they are.
+pub fn autosquash(commits: Vec<RebaseCommit>) -> Vec<RebaseCommit> {
+    let mut ordered: Vec<RebaseCommit> = Vec::with_capacity(commits.len());
+    for mut commit in commits {
+        let target = autosquash_target(&commit.message).and_then(|(action, subject)| {
+            let subject_of = |entry: &RebaseCommit| entry.message.lines().next().unwrap_or("").to_string();
+            // fixup!/squash! commits are never targets themselves
+            let candidates = || ordered.iter().enumerate().filter(|(_, entry)| autosquash_target(&entry.message).is_none());
+            let (index, _) = candidates().find(|(_, entry)| subject_of(entry) == subject)
+                .or_else(|| candidates().find(|(_, entry)| subject.len() >= 4 && entry.id.starts_with(subject)))
+                .or_else(|| candidates().find(|(_, entry)| subject_of(entry).starts_with(subject)))?;
+            Some((action, index))
+        });
+
+        match target {
+            Some((action, index)) => {
+                // After the target and whatever was already attached to it
+                let mut insert_at = index + 1;
+                while matches!(ordered.get(insert_at).map(|entry| &entry.action), Some(RebaseAction::Fixup | RebaseAction::Squash)) {
+                    insert_at += 1;
+                }
+                commit.action = action;
+                ordered.insert(insert_at, commit);
+            }
+            None => ordered.push(commit),
+        }
+    }
+    ordered
+}
+
+/// Starts an interactive rebase of the current branch onto `plan.onto_branch`. Commits are
+/// replayed on a detached HEAD; the branch only moves once the whole plan has applied.
+pub fn start_interactive_rebase(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    plan: RebasePlan,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    token: &OperationToken,
+) -> Result<String, AppError> {
+    let repo = crate::open_worktree_repository(repo_path)?;
+    crate::ensure_no_operation_in_progress(&repo)?;
+    if load_state(&repo)?.is_some() {
+        return Err(AppError::conflict("An interactive rebase is already in progress; continue or abort it first", Vec::new()));
+    }
+    if has_uncommitted_changes(&repo)? {
+        return Err(AppError::conflict("Commit or stash your changes before rebasing", Vec::new()));
+    }
+
+    let first = plan.commits.iter().find(|entry| !matches!(entry.action, RebaseAction::Drop));
+    if let Some(entry) = first.filter(|entry| matches!(entry.action, RebaseAction::Squash | RebaseAction::Fixup)) {
+        return Err(AppError::invalid_input(format!("Cannot squash {} without a previous commit", short_id(&entry.id))));
+    }
+
+    let committer = resolve_signature(app, &repo, repo_path, author_name, author_email)?;
+    let onto = repo.revparse_single(&plan.onto_branch)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| AppError::wrap(e, &format!("Failed to resolve '{}'", plan.onto_branch)))?;
+    let orig_head = head_commit(&repo)?.id();
+    let branch_ref = Some(crate::head_ref_name(&repo)).filter(|name| name != "HEAD");
+
+    crate::create_backup_ref(&repo, "rebase")?;
+    repo.checkout_tree(onto.as_object(), None)
+        .map_err(|e| AppError::wrap(e, "Failed to check out rebase target"))?;
+    repo.set_head_detached(onto.id())
+        .map_err(|e| AppError::wrap(e, "Failed to detach HEAD"))?;
+
+    let mut state = RebaseState {
+        onto_branch: plan.onto_branch,
+        branch_ref,
+        orig_head: orig_head.to_string(),
+        current: None,
+        remaining: plan.commits,
+        applied: 0,
+    };
+    save_state(&repo, &state)?;
+    run_plan(app, &repo, repo_path, &mut state, &committer, token)
+}
+
+fn load_active_state(repo: &Repository) -> Result<RebaseState, AppError> {
+    load_state(repo)?.ok_or_else(|| AppError::conflict("No interactive rebase in progress", Vec::new()))
+}
+
+/// Resumes a stopped rebase: the resolved (or edited) changes in the index are committed
+/// and the rest of the plan is replayed.
+#[tauri::command]
+pub async fn rebase_continue(
+    app: tauri::AppHandle,
+    repo_path: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "rebase", operation_id, move |token| {
+        audited(&task_app, &repo_path, "rebase_continue", || {
+            let repo = crate::open_worktree_repository(&repo_path)?;
+            let mut state = load_active_state(&repo)?;
+            let committer = resolve_signature(&task_app, &repo, &repo_path, author_name, author_email)?;
+
+            if let Some(entry) = &state.current {
+                if commit_step(&task_app, &repo, &repo_path, entry, &committer, state.applied > 0)?.is_some() {
+                    state.applied += 1;
+                }
+                state.current = None;
+                save_state(&repo, &state)?;
+            }
+            run_plan(&task_app, &repo, &repo_path, &mut state, &committer, &token)
+        })
+    })
+    .await
+}
+
+/// Drops the commit the rebase stopped at, discarding its changes and any resolution, and
+/// replays the rest of the plan.
+#[tauri::command]
+pub async fn rebase_skip(
+    app: tauri::AppHandle,
+    repo_path: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "rebase", operation_id, move |token| {
+        audited(&task_app, &repo_path, "rebase_skip", || {
+            let repo = crate::open_worktree_repository(&repo_path)?;
+            let mut state = load_active_state(&repo)?;
+            let committer = resolve_signature(&task_app, &repo, &repo_path, author_name, author_email)?;
+
+            if state.current.take().is_none() {
+                return Err(AppError::invalid_input("The rebase is not stopped at a commit"));
+            }
+            // Not Rebase::abort: that would return to the skipped commit
+            repo.reset(head_commit(&repo)?.as_object(), ResetType::Hard, None)
+                .map_err(|e| AppError::wrap(e, "Failed to reset working tree"))?;
+            finish_step(&repo)?;
+            save_state(&repo, &state)?;
+            run_plan(&task_app, &repo, &repo_path, &mut state, &committer, &token)
+        })
+    })
+    .await
+}
+
+/// Backs out of a rebase in progress: the branch, index and working tree return to where
+/// they were before it started. Rebases started from the command line are aborted with git.
+pub fn abort_rebase_in_progress(repo: &Repository, repo_path: &str) -> Result<String, AppError> {
+    if let Some(state) = load_state(repo)? {
+        let orig_head = find_commit(repo, &state.orig_head)?;
+        match &state.branch_ref {
+            Some(branch_ref) => repo.set_head(branch_ref),
+            None => repo.set_head_detached(orig_head.id()),
+        }
+        .map_err(|e| AppError::wrap(e, "Failed to restore HEAD"))?;
+        repo.reset(orig_head.as_object(), ResetType::Hard, None)
+            .map_err(|e| AppError::wrap(e, "Failed to reset working tree"))?;
+        finish_step(repo)?;
+        clear_state(repo)?;
+        return Ok("Rebase aborted".to_string());
+    }
+
+    if !matches!(
+        repo.state(),
+        git2::RepositoryState::Rebase
+            | git2::RepositoryState::RebaseInteractive
+            | git2::RepositoryState::RebaseMerge
+            | git2::RepositoryState::ApplyMailboxOrRebase
+    ) {
+        return Err(AppError::conflict("No rebase in progress", Vec::new()));
+    }
+
+    // libgit2 only understands the rebase-merge state it writes itself
+    match repo.open_rebase(None) {
+        Ok(mut rebase) => rebase.abort()
+            .map_err(|e| AppError::wrap(e, "Failed to abort rebase"))?,
+        Err(_) => {
+            crate::run_git(repo_path, &["rebase", "--abort"], "abort rebase")?;
+        }
+    }
+    Ok("Rebase aborted".to_string())
+}
+
+#[tauri::command]
+pub fn rebase_abort(app: tauri::AppHandle, repo_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "rebase_abort", || {
+        let repo = crate::open_worktree_repository(&repo_path)?;
+        abort_rebase_in_progress(&repo, &repo_path)
+    })
+}
@@ -0,0 +1,89 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use crate::commands::journal::{journal_operation, ref_change};
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+
+/// One reflog entry, newest first. `selector` is the `HEAD@{n}` form git accepts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReflogEntry {
+    pub selector: String,
+    pub old_id: String,
+    pub new_id: String,
+    pub message: String, // e.g. "reset: moving to HEAD~3"
+    pub committer: String,
+    pub email: String,
+    pub timestamp: i64,
+    pub summary: Option<String>, // Summary of the `new_id` commit, None when it is gone
+}
+
+/// Where `ref_name` (HEAD by default) has pointed, newest first, as far back as the
+/// reflog goes.
+#[tauri::command]
+pub fn get_reflog(repo_path: String, ref_name: Option<String>) -> Result<Vec<ReflogEntry>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let ref_name = ref_name.filter(|name| !name.trim().is_empty()).unwrap_or_else(|| "HEAD".to_string());
+    // Short branch names are accepted too ("main" for refs/heads/main)
+    let full_name = match repo.resolve_reference_from_short_name(&ref_name) {
+        Ok(reference) if ref_name != "HEAD" => reference.name().unwrap_or(&ref_name).to_string(),
+        _ => ref_name.clone(),
+    };
+    let reflog = repo.reflog(&full_name)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to read the reflog of '{}'", ref_name)))?;
+
+    Ok(reflog
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let committer = entry.committer();
+            ReflogEntry {
+                selector: format!("{}@{{{}}}", ref_name, index),
+                old_id: entry.id_old().to_string(),
+                new_id: entry.id_new().to_string(),
+                message: entry.message().unwrap_or("").to_string(),
+                committer: committer.name().unwrap_or("Unknown").to_string(),
+                email: committer.email().unwrap_or("").to_string(),
+                timestamp: committer.when().seconds(),
+                summary: repo.find_commit(entry.id_new()).ok().map(|commit| commit.summary().unwrap_or("").to_string()),
+            }
+        })
+        .collect())
+}
+
+/// Recovers a commit found in the reflog by creating `new_branch_name` on it. HEAD and
+/// the working tree are left alone.
+#[tauri::command]
+pub fn restore_from_reflog(app: tauri::AppHandle, repo_path: String, oid: String, new_branch_name: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "restore_from_reflog", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let oid = Oid::from_str(oid.trim())
+            .map_err(|_| AppError::invalid_input(format!("Invalid commit ID: {}", oid)))?;
+        // Entries can outlive their commits once gc prunes unreachable objects
+        let commit = repo.find_commit(oid)
+            .map_err(|_| AppError::not_found(format!("Commit {} no longer exists", oid)))?;
+
+        let branch_name = new_branch_name.trim();
+        let ref_name = format!("refs/heads/{}", branch_name);
+        if branch_name.is_empty() || !git2::Reference::is_valid_name(&ref_name) {
+            return Err(AppError::invalid_input(format!("Invalid branch name: {}", new_branch_name)));
+        }
+        if repo.find_reference(&ref_name).is_ok() {
+            return Err(AppError::conflict(format!("Branch '{}' already exists", branch_name), Vec::new()));
+        }
+
+        repo.branch(branch_name, &commit, false)
+            .map_err(|e| AppError::wrap(e, "Failed to create branch"))?;
+        journal_operation(
+            &app,
+            &repo_path,
+            "restore",
+            format!("restore of {} as '{}'", &oid.to_string()[..7], branch_name),
+            "hard",
+            vec![ref_change(&repo, &ref_name, None)],
+        );
+
+        Ok(format!("Restored {} as branch '{}'", &oid.to_string()[..7], branch_name))
+    })
+}
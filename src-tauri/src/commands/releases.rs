@@ -0,0 +1,282 @@
+use crate::commands::audit::audited;
+use crate::commands::error::AppError;
+use crate::commands::hosting::{api_request, api_upload, hosted_repository, percent_encode, HostedRepository, Provider};
+use crate::commands::identity::{create_annotated_tag, resolve_signature, signing_config};
+use crate::commands::operations::{run_operation, OperationToken};
+use crate::commands::tags::push_tag_ref;
+use git2::{Oid, Repository};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+// Generated notes list at most this many commits
+const RELEASE_NOTES_LIMIT: usize = 200;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Release {
+    pub provider: Provider,
+    pub tag: String,
+    pub name: String,
+    pub url: String,
+    pub draft: bool,
+    pub prerelease: bool,
+    pub assets: Vec<String>, // Uploaded file names
+}
+
+/// Commit each tag points at, tags on the same commit grouped.
+fn tags_by_commit(repo: &Repository) -> HashMap<Oid, Vec<String>> {
+    let mut tags: HashMap<Oid, Vec<String>> = HashMap::new();
+    if let Ok(references) = repo.references_glob("refs/tags/*") {
+        for reference in references.flatten() {
+            if let (Some(name), Ok(commit)) = (reference.shorthand(), reference.peel_to_commit()) {
+                tags.entry(commit.id()).or_default().push(name.to_string());
+            }
+        }
+    }
+    tags
+}
+
+/// The nearest tag in the history of `commit_id`, other than `tag` itself.
+fn previous_tag(repo: &Repository, commit_id: Oid, tag: &str) -> Option<(String, Oid)> {
+    let tags = tags_by_commit(repo);
+    let mut revwalk = repo.revwalk().ok()?;
+    revwalk.set_sorting(git2::Sort::TOPOLOGICAL | git2::Sort::TIME).ok()?;
+    revwalk.push(commit_id).ok()?;
+
+    revwalk.flatten().find_map(|oid| {
+        tags.get(&oid)
+            .and_then(|names| names.iter().find(|name| name.as_str() != tag))
+            .map(|name| (name.clone(), oid))
+    })
+}
+
+/// Release notes listing the commits since the previous tag, merges left out.
+fn generate_notes(repo: &Repository, commit_id: Oid, tag: &str) -> Result<String, AppError> {
+    let previous = previous_tag(repo, commit_id, tag);
+
+    let mut revwalk = repo.revwalk()
+        .map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+    revwalk.push(commit_id)
+        .map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+    if let Some((_, previous_id)) = &previous {
+        revwalk.hide(*previous_id)
+            .map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+    }
+
+    let mut lines = Vec::new();
+    let mut omitted = 0;
+    for oid in revwalk.flatten() {
+        let commit = match repo.find_commit(oid) {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+        if commit.parent_count() > 1 {
+            continue;
+        }
+        if lines.len() == RELEASE_NOTES_LIMIT {
+            omitted += 1;
+            continue;
+        }
+        let short_id = oid.to_string()[..7].to_string();
+        lines.push(format!("- {} ({})", commit.summary().unwrap_or(""), short_id));
+    }
+
+    let mut notes = match &previous {
+        Some((previous_name, _)) => format!("## Changes since {}\n\n", previous_name),
+        None => "## Changes\n\n".to_string(),
+    };
+    notes.push_str(&lines.join("\n"));
+    if omitted > 0 {
+        notes.push_str(&format!("\n- …and {} more", omitted));
+    }
+    Ok(notes)
+}
+
+fn file_name(path: &str) -> String {
+    Path::new(path)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string())
+}
+
+/// What to publish, once the tag exists on the remote.
+struct NewRelease {
+    tag: String,
+    name: String,
+    notes: String,
+    draft: bool,
+    prerelease: bool,
+    assets: Vec<String>,
+}
+
+fn publish_github_release(hosted: &HostedRepository, auth: &str, release: NewRelease) -> Result<Release, AppError> {
+    let url = format!("{}/repos/{}/{}/releases", hosted.api_base(), hosted.owner, hosted.name);
+    let request = serde_json::json!({
+        "tag_name": release.tag,
+        "name": release.name,
+        "body": release.notes,
+        "draft": release.draft,
+        "prerelease": release.prerelease,
+    });
+    let response = api_request("POST", &url, auth, Some(&request))?;
+
+    // "https://uploads.github.com/repos/o/r/releases/1/assets{?name,label}"
+    let upload_url = response["upload_url"].as_str().unwrap_or("");
+    let upload_url = upload_url.split('{').next().unwrap_or(upload_url);
+    let mut uploaded = Vec::new();
+    for asset in &release.assets {
+        let asset_name = file_name(asset);
+        let asset_url = format!("{}?name={}", upload_url, percent_encode(&asset_name, false));
+        api_upload(&asset_url, auth, Path::new(asset), None)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to upload {}", asset_name)))?;
+        uploaded.push(asset_name);
+    }
+
+    Ok(Release {
+        provider: Provider::Github,
+        tag: release.tag,
+        name: release.name,
+        url: response["html_url"].as_str().unwrap_or("").to_string(),
+        draft: release.draft,
+        prerelease: release.prerelease,
+        assets: uploaded,
+    })
+}
+
+/// GitLab has no drafts or prereleases; assets are uploaded to the project first and
+/// attached to the release as links.
+fn publish_gitlab_release(hosted: &HostedRepository, auth: &str, release: NewRelease) -> Result<Release, AppError> {
+    let project_url = format!("{}/projects/{}", hosted.api_base(), hosted.gitlab_project());
+
+    let mut links = Vec::new();
+    let mut uploaded = Vec::new();
+    for asset in &release.assets {
+        let asset_name = file_name(asset);
+        let upload = api_upload(&format!("{}/uploads", project_url), auth, Path::new(asset), Some("file"))
+            .map_err(|e| AppError::wrap(e, &format!("Failed to upload {}", asset_name)))?;
+        let path = upload["full_path"].as_str().or_else(|| upload["url"].as_str()).unwrap_or("");
+        links.push(serde_json::json!({
+            "name": asset_name,
+            "url": format!("https://{}{}", hosted.host, path),
+        }));
+        uploaded.push(asset_name);
+    }
+
+    let request = serde_json::json!({
+        "tag_name": release.tag,
+        "name": release.name,
+        "description": release.notes,
+        "assets": { "links": links },
+    });
+    api_request("POST", &format!("{}/releases", project_url), auth, Some(&request))?;
+
+    Ok(Release {
+        provider: Provider::Gitlab,
+        url: format!("{}/-/releases/{}", hosted.web_url(), percent_encode(&release.tag, false)),
+        tag: release.tag,
+        name: release.name,
+        draft: false,
+        prerelease: false,
+        assets: uploaded,
+    })
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create_release_blocking(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: String,
+    tag: String,
+    title: Option<String>,
+    notes: Option<String>,
+    target: Option<String>,
+    assets: Vec<String>,
+    draft: bool,
+    prerelease: bool,
+    token: OperationToken,
+) -> Result<Release, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let hosted = hosted_repository(&repo, &remote_name)?;
+    if !matches!(hosted.provider, Provider::Github | Provider::Gitlab) {
+        return Err(AppError::invalid_input("Releases can be published on GitHub and GitLab"));
+    }
+    if let Some(missing) = assets.iter().find(|asset| !Path::new(asset.as_str()).is_file()) {
+        return Err(AppError::not_found(format!("Asset not found: {}", missing)));
+    }
+    let name = title.filter(|title| !title.trim().is_empty()).unwrap_or_else(|| tag.clone());
+    // Without a token the release can't be published, so don't leave a pushed tag behind
+    let auth = hosted.authorization()?;
+
+    // An existing tag is released as is; otherwise it is created on `target` (HEAD by default)
+    let commit_id = match repo.find_reference(&format!("refs/tags/{}", tag)) {
+        Ok(reference) => reference.peel_to_commit()
+            .map_err(|e| AppError::wrap(e, &format!("Failed to resolve tag '{}'", tag)))?
+            .id(),
+        Err(_) => {
+            let target = repo.revparse_single(target.as_deref().unwrap_or("HEAD"))
+                .and_then(|object| object.peel(git2::ObjectType::Commit))
+                .map_err(|e| AppError::wrap(e, "Failed to resolve release target"))?;
+            let tagger = resolve_signature(&app, &repo, &repo_path, None, None)?;
+            let signing = signing_config(&app, &repo, &repo_path, "tag.gpgsign");
+            create_annotated_tag(&repo, signing.as_ref(), &tag, &target, &tagger, &name)?;
+            target.id()
+        }
+    };
+
+    let notes = match notes.filter(|notes| !notes.trim().is_empty()) {
+        Some(notes) => notes,
+        None => generate_notes(&repo, commit_id, &tag)?,
+    };
+
+    push_tag_ref(&repo, &remote_name, &tag, &token)?;
+    token.check()?;
+
+    let release = NewRelease { tag, name, notes, draft, prerelease, assets };
+    match hosted.provider {
+        Provider::Gitlab => publish_gitlab_release(&hosted, &auth, release),
+        _ => publish_github_release(&hosted, &auth, release),
+    }
+    .map_err(|e| AppError::wrap(e, "Failed to create release").with_host(Some(hosted.host.clone())))
+}
+
+/// Publishes a GitHub or GitLab release for `tag`, creating and pushing the tag first
+/// when needed. Without `notes`, the notes list the commits since the previous tag.
+/// `assets` are local file paths uploaded with the release.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn create_release(
+    app: tauri::AppHandle,
+    repo_path: String,
+    tag: String,
+    title: Option<String>,
+    notes: Option<String>,
+    target: Option<String>,
+    assets: Option<Vec<String>>,
+    draft: Option<bool>,
+    prerelease: Option<bool>,
+    remote_name: Option<String>,
+    operation_id: Option<String>,
+) -> Result<Release, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "release", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "create_release", || {
+            let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&task_app, &repo_path));
+            create_release_blocking(
+                task_app.clone(),
+                repo_path,
+                remote_name,
+                tag,
+                title,
+                notes,
+                target,
+                assets.unwrap_or_default(),
+                draft.unwrap_or(false),
+                prerelease.unwrap_or(false),
+                token,
+            )
+        })
+    })
+    .await
+}
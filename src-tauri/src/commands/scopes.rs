@@ -0,0 +1,150 @@
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::database::RepoScope;
+use git2::{Commit, DiffOptions, Pathspec, PathspecFlags, Repository};
+use std::path::Path;
+use tauri::{Manager, State};
+
+/// The active scope of a repository, compiled for matching. Without an active scope every
+/// path matches.
+pub struct ActiveScope {
+    pathspecs: Vec<String>,
+    pathspec: Option<Pathspec>,
+}
+
+impl ActiveScope {
+    fn new(pathspecs: Vec<String>) -> Result<Self, AppError> {
+        let pathspec = if pathspecs.is_empty() {
+            None
+        } else {
+            Some(Pathspec::new(pathspecs.iter()).map_err(|e| AppError::wrap(e, "Invalid scope pathspec"))?)
+        };
+        Ok(ActiveScope { pathspecs, pathspec })
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.pathspec.is_some()
+    }
+
+    pub fn pathspecs(&self) -> &[String] {
+        &self.pathspecs
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        match &self.pathspec {
+            Some(pathspec) => pathspec.matches_path(Path::new(path), PathspecFlags::DEFAULT),
+            None => true,
+        }
+    }
+
+    /// Whether `commit` changes a path in the scope, compared with its first parent like
+    /// `git log -- <pathspec>` does.
+    pub fn touches(&self, repo: &Repository, commit: &Commit) -> Result<bool, String> {
+        if !self.is_active() {
+            return Ok(true);
+        }
+        let tree = commit.tree()
+            .map_err(|e| format!("Failed to get commit tree: {}", e))?;
+        let parent_tree = match commit.parent(0) {
+            Ok(parent) => Some(parent.tree().map_err(|e| format!("Failed to get parent tree: {}", e))?),
+            Err(_) => None,
+        };
+
+        let mut diff_opts = DiffOptions::new();
+        for pathspec in &self.pathspecs {
+            diff_opts.pathspec(pathspec);
+        }
+        let diff = repo.diff_tree_to_tree(parent_tree.as_ref(), Some(&tree), Some(&mut diff_opts))
+            .map_err(|e| format!("Failed to diff commit: {}", e))?;
+        Ok(diff.deltas().len() > 0)
+    }
+}
+
+/// The repository's active scope, read from the database. Blocks on the database, so call
+/// it from a blocking command or thread.
+pub fn active_scope(app: &tauri::AppHandle, repo_path: &str) -> Result<ActiveScope, AppError> {
+    let scope = app.try_state::<DatabaseState>().and_then(|db_state| {
+        tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.get_active_repo_scope(repo_path).await
+        })
+        .ok()
+        .flatten()
+    });
+
+    ActiveScope::new(scope.map(|scope| scope.pathspecs).unwrap_or_default())
+}
+
+#[tauri::command]
+pub async fn list_scopes(
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+) -> Result<Vec<RepoScope>, AppError> {
+    let db = db_state.lock().await;
+    db.get_repo_scopes(&repo_path)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get scopes"))
+}
+
+/// Creates or renames a scope and replaces its pathspecs; it keeps its active state.
+#[tauri::command]
+pub async fn save_scope(
+    db_state: State<'_, DatabaseState>,
+    mut scope: RepoScope,
+) -> Result<(), AppError> {
+    scope.name = scope.name.trim().to_string();
+    scope.pathspecs = scope.pathspecs
+        .iter()
+        .map(|pathspec| pathspec.trim().to_string())
+        .filter(|pathspec| !pathspec.is_empty())
+        .collect();
+    if scope.id.trim().is_empty() || scope.name.is_empty() {
+        return Err(AppError::invalid_input("Scope ID and name are required"));
+    }
+    if scope.pathspecs.is_empty() {
+        return Err(AppError::invalid_input("A scope needs at least one path"));
+    }
+    ActiveScope::new(scope.pathspecs.clone())?;
+
+    let db = db_state.lock().await;
+    db.save_repo_scope(&scope)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to save scope"))
+}
+
+#[tauri::command]
+pub async fn delete_scope(
+    db_state: State<'_, DatabaseState>,
+    id: String,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.delete_repo_scope(&id)
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to delete scope"))
+}
+
+/// Activates a scope of the repository, or shows the whole repository again when
+/// `scope_id` is None.
+#[tauri::command]
+pub async fn set_active_scope(
+    db_state: State<'_, DatabaseState>,
+    repo_path: String,
+    scope_id: Option<String>,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+
+    if let Some(scope_id) = &scope_id {
+        let exists = db.get_repo_scopes(&repo_path)
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to get scopes"))?
+            .iter()
+            .any(|scope| &scope.id == scope_id);
+        if !exists {
+            return Err(AppError::not_found(format!("Scope not found: {}", scope_id)));
+        }
+    }
+
+    db.set_active_repo_scope(&repo_path, scope_id.as_deref())
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to set active scope"))
+}
@@ -0,0 +1,266 @@
+use crate::commands::error::AppError;
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+
+const KEYCHAIN_SERVICE: &str = "codegit";
+const SECRETS_FILE: &str = "secrets.json";
+// Passphrase for the encrypted file store, which is only used without an OS keychain
+const PASSPHRASE_ENV: &str = "CODEGIT_SECRETS_PASSPHRASE";
+
+/// Secret names used by the git credential callbacks.
+pub const GIT_USERNAME_SECRET: &str = "git-username";
+pub const GIT_PASSWORD_SECRET: &str = "git-password";
+pub const SSH_PASSPHRASE_SECRET: &str = "ssh-key-passphrase";
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SecretInfo {
+    pub name: String,
+    pub backend: String, // keychain, encrypted-file
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct EncryptedEntry {
+    nonce: String,
+    ciphertext: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SecretsFile {
+    salt: String,
+    entries: BTreeMap<String, EncryptedEntry>,
+}
+
+fn secrets_file_path() -> PathBuf {
+    crate::database::codegit_data_dir().join(SECRETS_FILE)
+}
+
+fn keychain_entry(name: &str) -> Option<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, name).ok()
+}
+
+fn validate_secret_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.len() <= 128
+        && name.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':' | '@' | '/'));
+
+    if !valid {
+        return Err(format!("Invalid secret name: '{}'", name));
+    }
+    Ok(())
+}
+
+fn read_secrets_file() -> Result<SecretsFile, String> {
+    let path = secrets_file_path();
+    match fs::read_to_string(&path) {
+        Ok(content) => serde_json::from_str(&content)
+            .map_err(|e| format!("Failed to parse {}: {}", path.display(), e)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(SecretsFile::default()),
+        Err(e) => Err(format!("Failed to read {}: {}", path.display(), e)),
+    }
+}
+
+fn write_private_file(path: &PathBuf, content: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    fs::write(path, content)
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| format!("Failed to restrict {}: {}", path.display(), e))?;
+    }
+
+    Ok(())
+}
+
+fn write_secrets_file(secrets: &SecretsFile) -> Result<(), String> {
+    let content = serde_json::to_string_pretty(secrets)
+        .map_err(|e| format!("Failed to serialize secrets: {}", e))?;
+    write_private_file(&secrets_file_path(), content.as_bytes())
+}
+
+/// Passphrase for the encrypted file store. It has to come from the user: a key stored
+/// on the same disk as secrets.json would let anyone who can read the file decrypt it.
+/// The store therefore protects secrets against copies of the data directory (backups,
+/// synced folders, another user reading it), not against code running as this user,
+/// which can read the environment too.
+fn file_store_passphrase() -> Result<Vec<u8>, String> {
+    match std::env::var(PASSPHRASE_ENV) {
+        Ok(passphrase) if !passphrase.is_empty() => Ok(passphrase.into_bytes()),
+        _ => Err(format!(
+            "No OS keychain is available; set {} to keep secrets in the encrypted file store",
+            PASSPHRASE_ENV
+        )),
+    }
+}
+
+fn derive_cipher(passphrase: &[u8], salt: &[u8]) -> Result<ChaCha20Poly1305, String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(passphrase, salt, &mut key)
+        .map_err(|e| format!("Failed to derive secrets key: {}", e))?;
+
+    Ok(ChaCha20Poly1305::new(Key::from_slice(&key)))
+}
+
+fn decrypt_entry(cipher: &ChaCha20Poly1305, name: &str, entry: &EncryptedEntry) -> Result<Vec<u8>, String> {
+    let nonce = BASE64.decode(&entry.nonce)
+        .map_err(|e| format!("Invalid secret nonce: {}", e))?;
+    let ciphertext = BASE64.decode(&entry.ciphertext)
+        .map_err(|e| format!("Invalid secret data: {}", e))?;
+    if nonce.len() != 12 {
+        return Err("Invalid secret nonce length".to_string());
+    }
+
+    cipher.decrypt(Nonce::from_slice(&nonce), ciphertext.as_ref())
+        .map_err(|_| format!("Failed to decrypt secret '{}': wrong passphrase or corrupted store", name))
+}
+
+fn encrypt_entry(cipher: &ChaCha20Poly1305, value: &[u8]) -> Result<EncryptedEntry, String> {
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher.encrypt(&nonce, value)
+        .map_err(|e| format!("Failed to encrypt secret: {}", e))?;
+
+    Ok(EncryptedEntry {
+        nonce: BASE64.encode(nonce),
+        ciphertext: BASE64.encode(ciphertext),
+    })
+}
+
+fn file_store_cipher(secrets: &mut SecretsFile) -> Result<ChaCha20Poly1305, String> {
+    let passphrase = file_store_passphrase()?;
+    if secrets.salt.is_empty() {
+        let mut salt = [0u8; 16];
+        OsRng.fill_bytes(&mut salt);
+        secrets.salt = BASE64.encode(salt);
+    }
+
+    let salt = BASE64.decode(&secrets.salt)
+        .map_err(|e| format!("Invalid secrets salt: {}", e))?;
+    derive_cipher(&passphrase, &salt)
+}
+
+fn file_store_save(name: &str, value: &str) -> Result<(), String> {
+    let mut secrets = read_secrets_file()?;
+    let cipher = file_store_cipher(&mut secrets)?;
+
+    let entry = encrypt_entry(&cipher, value.as_bytes())?;
+    secrets.entries.insert(name.to_string(), entry);
+    write_secrets_file(&secrets)
+}
+
+fn file_store_get(name: &str) -> Result<Option<String>, String> {
+    let mut secrets = read_secrets_file()?;
+    if !secrets.entries.contains_key(name) {
+        return Ok(None);
+    }
+    let cipher = file_store_cipher(&mut secrets)?;
+
+    let entry = secrets.entries.get(name)
+        .ok_or_else(|| format!("Secret '{}' disappeared from the store", name))?;
+    let plaintext = decrypt_entry(&cipher, name, entry)?;
+
+    String::from_utf8(plaintext)
+        .map(Some)
+        .map_err(|e| format!("Secret '{}' is not valid UTF-8: {}", name, e))
+}
+
+fn file_store_delete(name: &str) -> Result<bool, String> {
+    let mut secrets = read_secrets_file()?;
+    if secrets.entries.remove(name).is_none() {
+        return Ok(false);
+    }
+    write_secrets_file(&secrets)?;
+    Ok(true)
+}
+
+/// Stores a secret in the OS keychain, falling back to the encrypted file store (which
+/// needs CODEGIT_SECRETS_PASSPHRASE) when no keychain is available. Returns the backend
+/// that holds the secret.
+pub fn store_secret(name: &str, value: &str) -> Result<String, String> {
+    validate_secret_name(name)?;
+
+    if let Some(entry) = keychain_entry(name) {
+        if entry.set_password(value).is_ok() {
+            // Don't leave a stale copy behind from a time the keychain was unavailable
+            let _ = file_store_delete(name);
+            return Ok("keychain".to_string());
+        }
+    }
+
+    file_store_save(name, value)?;
+    Ok("encrypted-file".to_string())
+}
+
+/// Reads a secret from whichever backend holds it.
+pub fn load_secret(name: &str) -> Result<Option<String>, String> {
+    validate_secret_name(name)?;
+
+    if let Some(entry) = keychain_entry(name) {
+        if let Ok(value) = entry.get_password() {
+            return Ok(Some(value));
+        }
+    }
+
+    file_store_get(name)
+}
+
+pub fn remove_secret(name: &str) -> Result<bool, String> {
+    validate_secret_name(name)?;
+
+    let removed_from_keychain = keychain_entry(name)
+        .map(|entry| entry.delete_credential().is_ok())
+        .unwrap_or(false);
+    let removed_from_file = file_store_delete(name)?;
+
+    Ok(removed_from_keychain || removed_from_file)
+}
+
+/// Moves GIT_USERNAME/GIT_PASSWORD from the environment into the secrets store so
+/// the credential callbacks no longer depend on them.
+pub fn migrate_environment_credentials() {
+    for (variable, name) in [("GIT_USERNAME", GIT_USERNAME_SECRET), ("GIT_PASSWORD", GIT_PASSWORD_SECRET)] {
+        let value = match std::env::var(variable) {
+            Ok(value) if !value.is_empty() => value,
+            _ => continue,
+        };
+
+        if matches!(load_secret(name), Ok(Some(ref stored)) if *stored == value) {
+            continue;
+        }
+
+        match store_secret(name, &value) {
+            Ok(backend) => println!("🔐 Migrated {} into the secrets store ({})", variable, backend),
+            Err(e) => println!("⚠️  Failed to migrate {} into the secrets store: {}", variable, e),
+        }
+    }
+}
+
+#[tauri::command]
+pub fn save_secret(name: String, value: String) -> Result<SecretInfo, AppError> {
+    let backend = store_secret(&name, &value)?;
+    Ok(SecretInfo { name, backend })
+}
+
+#[tauri::command]
+pub fn get_secret(name: String) -> Result<Option<String>, AppError> {
+    load_secret(&name).map_err(AppError::from)
+}
+
+#[tauri::command]
+pub fn delete_secret(name: String) -> Result<bool, AppError> {
+    remove_secret(&name).map_err(AppError::from)
+}
@@ -0,0 +1,383 @@
+use crate::commands::error::{AppError, ErrorKind};
+use crate::commands::operations::{run_operation, OperationToken};
+use crate::commands::secrets::{load_secret, store_secret};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::io::Write;
+use std::process::Stdio;
+
+const SMTP_ACCOUNT_SECRET: &str = "smtp-account";
+const SMTP_TIMEOUT_SECS: &str = "60";
+const COVER_SUBJECT_PLACEHOLDER: &str = "*** SUBJECT HERE ***";
+const COVER_BLURB_PLACEHOLDER: &str = "*** BLURB HERE ***";
+
+/// SMTP server patches are sent through, kept in the secrets store as one entry.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmtpAccount {
+    pub host: String,
+    pub port: u16,
+    pub security: String, // tls (implicit, usually 465), starttls (usually 587), none
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>, // Never returned by get_smtp_account; None on save keeps the stored one
+    pub from: String,             // "Name <address>" or an address
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverLetter {
+    pub subject: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PatchEmail {
+    pub subject: String,
+    pub message_id: Option<String>,
+    pub message: Option<String>, // Full message, only for dry runs
+}
+
+/// One message of a `git format-patch --stdout` series.
+struct PatchMessage {
+    headers: Vec<(String, String)>,
+    body: String,
+}
+
+impl PatchMessage {
+    fn parse(text: &str) -> Self {
+        let (head, body) = text.split_once("\n\n").unwrap_or((text, ""));
+        let mut headers: Vec<(String, String)> = Vec::new();
+        for line in head.lines() {
+            if line.starts_with(' ') || line.starts_with('\t') {
+                // Folded continuation of the previous header
+                if let Some((_, value)) = headers.last_mut() {
+                    value.push('\n');
+                    value.push_str(line);
+                }
+            } else if let Some((name, value)) = line.split_once(':') {
+                headers.push((name.to_string(), value.trim_start().to_string()));
+            }
+        }
+        PatchMessage { headers, body: body.to_string() }
+    }
+
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn set_header(&mut self, name: &str, value: String) {
+        match self.headers.iter_mut().find(|(header, _)| header.eq_ignore_ascii_case(name)) {
+            Some(header) => header.1 = value,
+            None => self.headers.push((name.to_string(), value)),
+        }
+    }
+
+    /// The message with CRLF line endings, as SMTP expects.
+    fn render(&self) -> String {
+        let mut message = String::new();
+        for (name, value) in &self.headers {
+            message.push_str(&format!("{}: {}\n", name, value));
+        }
+        message.push('\n');
+        message.push_str(&self.body);
+        message.replace("\r\n", "\n").replace('\n', "\r\n")
+    }
+}
+
+/// Encodes a header value as RFC 2047 when it isn't plain ASCII.
+fn encode_header(value: &str) -> String {
+    if value.is_ascii() {
+        value.to_string()
+    } else {
+        format!("=?UTF-8?B?{}?=", BASE64.encode(value.as_bytes()))
+    }
+}
+
+/// Address part of "Name <address>".
+fn bare_address(mailbox: &str) -> String {
+    match (mailbox.find('<'), mailbox.rfind('>')) {
+        (Some(start), Some(end)) if start < end => mailbox[start + 1..end].trim().to_string(),
+        _ => mailbox.trim().to_string(),
+    }
+}
+
+fn validate_address(address: &str) -> Result<(), AppError> {
+    if !bare_address(address).contains('@') || address.contains(['\r', '\n']) {
+        return Err(AppError::invalid_input(format!("Invalid email address: {}", address)));
+    }
+    Ok(())
+}
+
+fn load_account() -> Result<SmtpAccount, AppError> {
+    let stored = load_secret(SMTP_ACCOUNT_SECRET)?
+        .ok_or_else(|| AppError::new(ErrorKind::AuthRequired, "smtp.not_configured", "Set up an SMTP account to send patches"))?;
+    serde_json::from_str(&stored).map_err(|e| AppError::wrap(e, "Failed to read the SMTP account"))
+}
+
+fn smtp_url(account: &SmtpAccount) -> Result<String, AppError> {
+    let scheme = match account.security.as_str() {
+        "tls" => "smtps",
+        "starttls" | "none" => "smtp",
+        other => return Err(AppError::invalid_input(format!("Unknown SMTP security: {}", other))),
+    };
+    Ok(format!("{}://{}:{}", scheme, account.host, account.port))
+}
+
+/// Hands one message to the SMTP server through curl. The message goes through a temporary
+/// file and the credentials through curl's config on stdin, so the password never shows up
+/// in the process list.
+fn send_message(account: &SmtpAccount, recipients: &[String], message: &str) -> Result<(), AppError> {
+    let message_path = std::env::temp_dir().join(format!("codegit-patch-{}-{}.eml", std::process::id(), Utc::now().timestamp_nanos_opt().unwrap_or(0)));
+    std::fs::write(&message_path, message)
+        .map_err(|e| AppError::wrap(e, "Failed to prepare the message"))?;
+
+    let quote = |value: &str| format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut config = vec![
+        format!("url = {}", quote(&smtp_url(account)?)),
+        format!("mail-from = {}", quote(&bare_address(&account.from))),
+        format!("upload-file = {}", quote(&message_path.to_string_lossy())),
+    ];
+    for recipient in recipients {
+        config.push(format!("mail-rcpt = {}", quote(&bare_address(recipient))));
+    }
+    if account.security == "starttls" {
+        config.push("ssl-reqd".to_string());
+    }
+    if let Some(username) = account.username.as_deref().filter(|username| !username.is_empty()) {
+        config.push(format!("user = {}", quote(&format!("{}:{}", username, account.password.as_deref().unwrap_or("")))));
+    }
+
+    let mut command = std::process::Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--max-time", SMTP_TIMEOUT_SECS, "--config", "-"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console window from the GUI
+        command.creation_flags(0x08000000);
+    }
+
+    let result = command.spawn()
+        .map_err(|e| AppError::wrap(e, "Failed to run curl"))
+        .and_then(|mut child| {
+            if let Some(mut stdin) = child.stdin.take() {
+                stdin.write_all(config.join("\n").as_bytes())
+                    .map_err(|e| AppError::wrap(e, "Failed to send the message to curl"))?;
+            }
+            child.wait_with_output().map_err(|e| AppError::wrap(e, "Failed to run curl"))
+        });
+    let _ = std::fs::remove_file(&message_path);
+
+    let output = result?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+        // curl exits with 67 when the server rejects the login
+        let (kind, code) = match output.status.code() {
+            Some(67) => (ErrorKind::AuthRequired, "smtp.unauthorized"),
+            _ => (ErrorKind::Network, "smtp.failed"),
+        };
+        return Err(AppError::new(kind, code, format!("Failed to send mail via {}: {}", account.host, stderr))
+            .with_host(Some(account.host.clone())));
+    }
+    Ok(())
+}
+
+/// The series as format-patch writes it, one message per patch (and the cover letter
+/// first), threaded with Message-Id/In-Reply-To/References headers.
+fn format_series(
+    repo_path: &str,
+    revision_range: &str,
+    to: &[String],
+    cc: &[String],
+    cover_letter: bool,
+    in_reply_to: Option<&str>,
+    subject_prefix: Option<&str>,
+) -> Result<Vec<PatchMessage>, AppError> {
+    let mut args: Vec<String> = vec!["format-patch".to_string(), "--stdout".to_string(), "--thread=shallow".to_string()];
+    if cover_letter {
+        args.push("--cover-letter".to_string());
+    }
+    if let Some(prefix) = subject_prefix.filter(|prefix| !prefix.trim().is_empty()) {
+        args.push(format!("--subject-prefix={}", prefix.trim()));
+    }
+    if let Some(message_id) = in_reply_to.filter(|message_id| !message_id.trim().is_empty()) {
+        args.push(format!("--in-reply-to={}", message_id.trim()));
+    }
+    args.extend(to.iter().map(|address| format!("--to={}", address)));
+    args.extend(cc.iter().map(|address| format!("--cc={}", address)));
+    // "-3" is a commit count; anything else is a revision, never an option
+    if !revision_range.starts_with('-') {
+        args.push("--end-of-options".to_string());
+    }
+    args.push(revision_range.to_string());
+
+    let arg_refs: Vec<&str> = args.iter().map(|arg| arg.as_str()).collect();
+    let output = crate::run_git(repo_path, &arg_refs, "format patches")?;
+
+    // Every message starts with an mbox separator line naming the commit
+    let separator = regex::Regex::new(r"(?m)^From [0-9a-f]{40} Mon Sep 17 00:00:00 2001\n")
+        .map_err(|e| AppError::from(format!("Failed to read patches: {}", e)))?;
+    let messages: Vec<PatchMessage> = separator
+        .split(&output)
+        .filter(|message| !message.trim().is_empty())
+        .map(PatchMessage::parse)
+        .collect();
+
+    if messages.is_empty() {
+        return Err(AppError::invalid_input(format!("No commits in {}", revision_range)));
+    }
+    Ok(messages)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn send_patch_series_blocking(
+    repo_path: String,
+    revision_range: String,
+    to: Vec<String>,
+    cc: Vec<String>,
+    cover_letter: Option<CoverLetter>,
+    in_reply_to: Option<String>,
+    subject_prefix: Option<String>,
+    dry_run: bool,
+    token: OperationToken,
+) -> Result<Vec<PatchEmail>, AppError> {
+    let account = load_account()?;
+    let mut messages = format_series(
+        &repo_path,
+        &revision_range,
+        &to,
+        &cc,
+        cover_letter.is_some(),
+        in_reply_to.as_deref(),
+        subject_prefix.as_deref(),
+    )?;
+
+    if let (Some(cover), Some(first)) = (&cover_letter, messages.first_mut()) {
+        if let Some(subject) = first.header("Subject").map(|subject| subject.replace(COVER_SUBJECT_PLACEHOLDER, &encode_header(&cover.subject))) {
+            first.set_header("Subject", subject);
+        }
+        first.body = first.body.replace(COVER_BLURB_PLACEHOLDER, &cover.body);
+    }
+
+    let sender = bare_address(&account.from);
+    let recipients: Vec<String> = to.iter().chain(cc.iter()).cloned().collect();
+    let started = Utc::now();
+    let mut sent = Vec::new();
+
+    for (index, message) in messages.iter_mut().enumerate() {
+        token.check()?;
+
+        // Like send-email: the sender's From goes in the header and the patch author's moves
+        // into the body, so `git am` still credits the author
+        if let Some(author) = message.header("From").map(|author| author.to_string()) {
+            if !bare_address(&author).eq_ignore_ascii_case(&sender) {
+                message.body = format!("From: {}\n\n{}", author, message.body);
+            }
+        }
+        message.set_header("From", account.from.clone());
+        // One second apart, so mail clients keep the series in order
+        message.set_header("Date", (started + Duration::seconds(index as i64)).to_rfc2822());
+
+        let rendered = message.render();
+        if !dry_run {
+            send_message(&account, &recipients, &rendered)?;
+        }
+        sent.push(PatchEmail {
+            subject: message.header("Subject").unwrap_or("").to_string(),
+            message_id: message.header("Message-Id").map(|message_id| message_id.to_string()),
+            message: if dry_run { Some(rendered) } else { None },
+        });
+    }
+
+    println!("📧 {} {} message(s) for {}", if dry_run { "Prepared" } else { "Sent" }, sent.len(), revision_range);
+    Ok(sent)
+}
+
+#[tauri::command]
+pub fn get_smtp_account() -> Result<Option<SmtpAccount>, AppError> {
+    match load_secret(SMTP_ACCOUNT_SECRET)? {
+        Some(_) => {
+            let mut account = load_account()?;
+            account.password = None;
+            Ok(Some(account))
+        }
+        None => Ok(None),
+    }
+}
+
+#[tauri::command]
+pub fn save_smtp_account(mut account: SmtpAccount) -> Result<(), AppError> {
+    validate_address(&account.from)?;
+    smtp_url(&account)?;
+    if account.host.trim().is_empty() {
+        return Err(AppError::invalid_input("The SMTP host cannot be empty"));
+    }
+    if account.password.is_none() {
+        account.password = load_account().ok().and_then(|stored| stored.password);
+    }
+
+    let value = serde_json::to_string(&account)
+        .map_err(|e| AppError::wrap(e, "Failed to save the SMTP account"))?;
+    store_secret(SMTP_ACCOUNT_SECRET, &value)?;
+    Ok(())
+}
+
+/// Mails the commits in `revision_range` ("origin/main..HEAD", "-3", ...) as a patch
+/// series through the saved SMTP account, like `git send-email`. With `cover_letter` a
+/// "[PATCH 0/n]" message introduces the series; `in_reply_to` threads it under an earlier
+/// message (for a v2). `dry_run` returns the messages without sending them.
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn send_patch_series(
+    app: tauri::AppHandle,
+    repo_path: String,
+    revision_range: String,
+    to: Vec<String>,
+    cc: Option<Vec<String>>,
+    cover_letter: Option<CoverLetter>,
+    in_reply_to: Option<String>,
+    subject_prefix: Option<String>,
+    dry_run: Option<bool>,
+    operation_id: Option<String>,
+) -> Result<Vec<PatchEmail>, AppError> {
+    let cc = cc.unwrap_or_default();
+    if to.is_empty() {
+        return Err(AppError::invalid_input("Add at least one recipient"));
+    }
+    for address in to.iter().chain(cc.iter()) {
+        validate_address(address)?;
+    }
+    let range = revision_range.trim().to_string();
+    let is_count = range.len() > 1 && range.starts_with('-') && range[1..].chars().all(|c| c.is_ascii_digit());
+    if range.is_empty() || (range.starts_with('-') && !is_count) {
+        return Err(AppError::invalid_input(format!("Invalid revision range: {}", revision_range)));
+    }
+    // These end up in headers
+    let header_values = [in_reply_to.as_deref(), subject_prefix.as_deref(), cover_letter.as_ref().map(|cover| cover.subject.as_str())];
+    if header_values.iter().flatten().any(|value| value.contains(['\r', '\n'])) {
+        return Err(AppError::invalid_input("Subjects and Message-Ids must be a single line"));
+    }
+
+    run_operation(&app, "send_email", operation_id, move |token| {
+        send_patch_series_blocking(
+            repo_path,
+            range,
+            to,
+            cc,
+            cover_letter,
+            in_reply_to,
+            subject_prefix,
+            dry_run.unwrap_or(false),
+            token,
+        )
+    })
+    .await
+}
@@ -0,0 +1,101 @@
+use crate::commands::commit_cache::cached_history;
+use crate::commands::database::DatabaseState;
+use crate::commands::error::AppError;
+use crate::commands::operations::OperationToken;
+use crate::commands::status::StatusCache;
+use crate::commands::watcher::{watch_repository, RepositoryWatchers};
+use crate::database::SessionState;
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+use tauri::{Manager, State};
+
+// Matches the first page the history view asks for
+const WARM_LOG_ENTRIES: usize = 100;
+
+/// Emitted as `session-restored` once the last repository has been pre-opened.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionRestored {
+    pub session: SessionState,
+    pub status_ready: bool,
+    pub log_ready: bool,
+}
+
+#[tauri::command]
+pub async fn save_session(
+    db_state: State<'_, DatabaseState>,
+    repo_path: Option<String>,
+    branch: Option<String>,
+    open_panels: Vec<String>,
+) -> Result<(), AppError> {
+    let db = db_state.lock().await;
+    db.save_session_state(&SessionState {
+        repo_path,
+        branch,
+        open_panels,
+        updated_at: chrono::Utc::now(),
+    })
+    .await
+    .map_err(|e| AppError::wrap(e, "Failed to save session"))
+}
+
+#[tauri::command]
+pub async fn get_session(db_state: State<'_, DatabaseState>) -> Result<Option<SessionState>, AppError> {
+    let db = db_state.lock().await;
+    db.get_session_state()
+        .await
+        .map_err(|e| AppError::wrap(e, "Failed to get session"))
+}
+
+/// Pre-opens the repository from the last session: starts its watcher and fills the
+/// status and commit caches, so the first get_repository_status and get_log_graph
+/// calls are answered from memory and SQLite.
+pub fn warm_start(app: &tauri::AppHandle) {
+    let session = match app.try_state::<DatabaseState>() {
+        Some(db_state) => tauri::async_runtime::block_on(async {
+            let db = db_state.lock().await;
+            db.get_session_state().await
+        }),
+        None => return,
+    };
+
+    let session = match session {
+        Ok(Some(session)) => session,
+        Ok(None) => return,
+        Err(e) => {
+            println!("⚠️  Failed to load session: {}", e);
+            return;
+        }
+    };
+    let repo_path = match &session.repo_path {
+        Some(repo_path) => repo_path.clone(),
+        None => return,
+    };
+    // The repository may have been moved or deleted since
+    let repo = match Repository::open(&repo_path) {
+        Ok(repo) => repo,
+        Err(_) => return,
+    };
+
+    let mut status_ready = false;
+    if !repo.is_bare() {
+        if let Err(e) = watch_repository(app.clone(), app.state(), app.state(), repo_path.clone(), None) {
+            println!("⚠️  Failed to watch {}: {}", repo_path, e);
+        }
+        let is_watched = app.state::<RepositoryWatchers>().is_watching(&repo_path);
+        status_ready = app.state::<StatusCache>()
+            .statuses(&repo, &repo_path, is_watched)
+            .is_ok();
+    }
+
+    let log_ready = matches!(
+        cached_history(app, &repo, &repo_path, WARM_LOG_ENTRIES, &OperationToken::default()),
+        Ok(Some(_))
+    );
+
+    println!("🔥 Restored session for {}", repo_path);
+    let _ = app.emit_all("session-restored", SessionRestored {
+        session,
+        status_ready,
+        log_ready,
+    });
+}
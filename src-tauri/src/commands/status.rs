@@ -0,0 +1,202 @@
+use git2::{Repository, Status};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{Read, Seek, SeekFrom};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+pub type StatusMap = BTreeMap<String, Status>;
+
+struct CachedStatus {
+    fingerprint: String,
+    entries: StatusMap,
+    last_returned: HashMap<String, StatusMap>, // What the previous since-last call reported, per filter
+    dirty_paths: BTreeSet<String>,
+    is_watched: bool, // Only a watched repository can be refreshed path by path
+    needs_rescan: bool,
+}
+
+/// Per-repository status results. With a watcher running, refreshes only re-check the
+/// paths it reported; otherwise every call rescans, relying on libgit2's stat cache.
+#[derive(Clone, Default)]
+pub struct StatusCache(Arc<Mutex<HashMap<String, CachedStatus>>>);
+
+/// Changes since the previous call: entries whose status changed, and paths now clean.
+pub struct StatusDelta {
+    pub changed: StatusMap,
+    pub cleared: Vec<String>,
+}
+
+/// HEAD target plus the index checksum (the trailing SHA-1 of .git/index). Any staging,
+/// commit or checkout changes it and invalidates the cached entries.
+fn status_fingerprint(repo: &Repository) -> String {
+    let head = repo.head().ok()
+        .and_then(|head| head.target())
+        .map(|oid| oid.to_string())
+        .unwrap_or_default();
+
+    let mut checksum = [0u8; 20];
+    let index_checksum = std::fs::File::open(repo.path().join("index"))
+        .and_then(|mut file| {
+            file.seek(SeekFrom::End(-20))?;
+            file.read_exact(&mut checksum)
+        })
+        .map(|_| checksum.iter().map(|byte| format!("{:02x}", byte)).collect::<String>())
+        .unwrap_or_default();
+
+    format!("{}:{}", head, index_checksum)
+}
+
+fn scan_statuses(repo: &Repository) -> Result<StatusMap, String> {
+    let mut options = git2::StatusOptions::new();
+    options
+        .include_untracked(true)
+        .recurse_untracked_dirs(true)
+        .include_ignored(false)
+        // Refresh stale stat data in the index so the next scan can skip unchanged files
+        .update_index(true);
+
+    let statuses = repo.statuses(Some(&mut options))
+        .map_err(|e| format!("Failed to get status: {}", e))?;
+
+    Ok(statuses
+        .iter()
+        .filter_map(|entry| entry.path().map(|path| (path.to_string(), entry.status())))
+        .filter(|(_, status)| !status.is_empty())
+        .collect())
+}
+
+/// Re-checks single paths; None when a path can't be checked alone (e.g. a directory).
+fn refresh_paths(repo: &Repository, entries: &mut StatusMap, paths: &BTreeSet<String>) -> Option<()> {
+    let workdir = repo.workdir()?;
+
+    for path in paths {
+        let full_path = workdir.join(path);
+        if full_path.is_dir() {
+            return None;
+        }
+        // A changed ignore file can hide or reveal paths anywhere below it
+        if path.rsplit('/').next() == Some(".gitignore") {
+            return None;
+        }
+        // A moved or deleted directory may be reported once for all of its files
+        let prefix = format!("{}/", path);
+        if !full_path.exists() && entries.keys().any(|entry| entry.starts_with(&prefix)) {
+            return None;
+        }
+
+        match repo.status_file(Path::new(path)) {
+            Ok(status) if status.is_empty() || status.contains(Status::IGNORED) => {
+                entries.remove(path);
+            }
+            Ok(status) => {
+                entries.insert(path.clone(), status);
+            }
+            Err(e) if e.code() == git2::ErrorCode::NotFound => {
+                entries.remove(path);
+            }
+            Err(_) => return None,
+        }
+    }
+
+    Some(())
+}
+
+impl StatusCache {
+    /// Called when a watcher starts, so later refreshes can be incremental.
+    pub fn track(&self, repo_path: &str) {
+        if let Ok(mut cache) = self.0.lock() {
+            if let Some(cached) = cache.get_mut(repo_path) {
+                // Changes before the watcher started were not recorded
+                cached.is_watched = true;
+                cached.needs_rescan = true;
+            }
+        }
+    }
+
+    pub fn untrack(&self, repo_path: &str) {
+        if let Ok(mut cache) = self.0.lock() {
+            cache.remove(repo_path);
+        }
+    }
+
+    /// Makes the next call rescan everything, e.g. after .git/info/exclude changed.
+    pub fn invalidate(&self, repo_path: &str) {
+        if let Ok(mut cache) = self.0.lock() {
+            if let Some(cached) = cache.get_mut(repo_path) {
+                cached.needs_rescan = true;
+            }
+        }
+    }
+
+    /// Records a worktree path (relative to the workdir) reported by the watcher.
+    pub fn mark_dirty(&self, repo_path: &str, relative_path: &str) {
+        if let Ok(mut cache) = self.0.lock() {
+            if let Some(cached) = cache.get_mut(repo_path) {
+                cached.dirty_paths.insert(relative_path.replace('\\', "/"));
+            }
+        }
+    }
+
+    /// Current statuses of every changed path.
+    pub fn statuses(&self, repo: &Repository, repo_path: &str, is_watched: bool) -> Result<StatusMap, String> {
+        let mut cache = self.0.lock()
+            .map_err(|_| "Status cache is unavailable".to_string())?;
+        let fingerprint = status_fingerprint(repo);
+
+        if let Some(cached) = cache.get_mut(repo_path) {
+            let can_refresh = cached.is_watched
+                && !cached.needs_rescan
+                && cached.fingerprint == fingerprint;
+
+            if can_refresh {
+                let dirty_paths = std::mem::take(&mut cached.dirty_paths);
+                if refresh_paths(repo, &mut cached.entries, &dirty_paths).is_some() {
+                    return Ok(cached.entries.clone());
+                }
+            }
+        }
+
+        let last_returned = cache.get_mut(repo_path)
+            .map(|cached| std::mem::take(&mut cached.last_returned))
+            .unwrap_or_default();
+        let entries = scan_statuses(repo)?;
+
+        cache.insert(repo_path.to_string(), CachedStatus {
+            // Scanning may rewrite the index, so fingerprint the state the scan left behind
+            fingerprint: status_fingerprint(repo),
+            entries: entries.clone(),
+            last_returned,
+            dirty_paths: BTreeSet::new(),
+            is_watched,
+            needs_rescan: false,
+        });
+
+        Ok(entries)
+    }
+
+    /// Difference between `current` and what the previous call with the same `filter`
+    /// returned for this repository. `current` must already be narrowed by that filter.
+    pub fn changes_since_last(&self, repo_path: &str, filter: &str, current: &StatusMap) -> StatusDelta {
+        let mut cache = match self.0.lock() {
+            Ok(cache) => cache,
+            Err(_) => return StatusDelta { changed: current.clone(), cleared: Vec::new() },
+        };
+
+        let previous = cache.get_mut(repo_path)
+            .and_then(|cached| cached.last_returned.insert(filter.to_string(), current.clone()))
+            .unwrap_or_default();
+
+        StatusDelta {
+            changed: current
+                .iter()
+                .filter(|(path, status)| previous.get(*path) != Some(*status))
+                .map(|(path, status)| (path.clone(), *status))
+                .collect(),
+            cleared: previous
+                .keys()
+                .filter(|path| !current.contains_key(*path))
+                .cloned()
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+use crate::commands::audit::audited;
+use crate::commands::error::{remote_host, AppError};
+use crate::commands::identity::{create_annotated_tag, resolve_signature, signing_config, SigningConfig};
+use crate::commands::journal::{journal_operation, ref_change};
+use crate::commands::operations::{run_operation, OperationToken};
+use git2::{ObjectType, PushOptions, Reference, Repository};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+const SIGNATURE_MARKERS: [&str; 3] = [
+    "-----BEGIN PGP SIGNATURE-----",
+    "-----BEGIN SSH SIGNATURE-----",
+    "-----BEGIN SIGNED MESSAGE-----",
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitTag {
+    pub name: String,
+    pub target: String, // Object the tag resolves to, usually a commit
+    pub is_annotated: bool,
+    pub tagger: Option<String>, // Annotated tags only
+    pub tagger_email: Option<String>,
+    pub message: Option<String>, // Without the signature block
+    pub date: Option<i64>, // Tagger date; commit date for lightweight tags
+    pub is_signed: bool,
+}
+
+fn tag_ref_name(name: &str) -> Result<String, AppError> {
+    let ref_name = format!("refs/tags/{}", name.trim());
+    if name.trim().is_empty() || !Reference::is_valid_name(&ref_name) {
+        return Err(AppError::invalid_input(format!("Invalid tag name: {}", name)));
+    }
+    Ok(ref_name)
+}
+
+fn git_tag(repo: &Repository, reference: &Reference) -> Option<GitTag> {
+    let name = reference.name()?.strip_prefix("refs/tags/")?.to_string();
+    let target = reference.peel(ObjectType::Any).ok()?;
+
+    match reference.target().and_then(|oid| repo.find_tag(oid).ok()) {
+        Some(tag) => {
+            let message = tag.message().unwrap_or("");
+            let signature_start = SIGNATURE_MARKERS.iter().filter_map(|marker| message.find(marker)).min();
+            let tagger = tag.tagger();
+            Some(GitTag {
+                name,
+                target: target.id().to_string(),
+                is_annotated: true,
+                tagger: tagger.as_ref().and_then(|tagger| tagger.name().map(|name| name.to_string())),
+                tagger_email: tagger.as_ref().and_then(|tagger| tagger.email().map(|email| email.to_string())),
+                message: Some(message[..signature_start.unwrap_or(message.len())].trim_end().to_string()),
+                date: tagger.as_ref().map(|tagger| tagger.when().seconds()),
+                is_signed: signature_start.is_some(),
+            })
+        }
+        None => Some(GitTag {
+            name,
+            target: target.id().to_string(),
+            is_annotated: false,
+            tagger: None,
+            tagger_email: None,
+            message: None,
+            date: target.as_commit().map(|commit| commit.time().seconds()),
+            is_signed: false,
+        }),
+    }
+}
+
+/// All tags, newest first.
+#[tauri::command]
+pub fn get_tags(repo_path: String) -> Result<Vec<GitTag>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let references = repo.references_glob("refs/tags/*")
+        .map_err(|e| AppError::wrap(e, "Failed to list tags"))?;
+
+    let mut tags: Vec<GitTag> = references
+        .flatten()
+        .filter_map(|reference| git_tag(&repo, &reference))
+        .collect();
+    tags.sort_by(|a, b| b.date.cmp(&a.date).then_with(|| a.name.cmp(&b.name)));
+    Ok(tags)
+}
+
+/// The key to sign with when signing is asked for explicitly, even if tag.gpgsign is off.
+fn configured_signing_key(repo: &Repository) -> Option<SigningConfig> {
+    let config = repo.config().ok()?;
+    Some(SigningConfig {
+        format: config.get_string("gpg.format").unwrap_or_else(|_| "openpgp".to_string()),
+        key: config.get_string("user.signingkey").ok()?,
+    })
+}
+
+/// Creates a tag on `target` (HEAD by default). With a message, or when signing, the tag
+/// is annotated; otherwise it is lightweight. `sign` overrides tag.gpgsign and the
+/// profile's signing default.
+#[tauri::command]
+pub fn create_tag(
+    app: tauri::AppHandle,
+    repo_path: String,
+    name: String,
+    target: Option<String>,
+    message: Option<String>,
+    sign: Option<bool>,
+) -> Result<GitTag, AppError> {
+    audited(&app, &repo_path, "create_tag", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let ref_name = tag_ref_name(&name)?;
+        let name = name.trim();
+        if repo.find_reference(&ref_name).is_ok() {
+            return Err(AppError::conflict(format!("Tag '{}' already exists", name), Vec::new()));
+        }
+
+        let target_object = repo.revparse_single(target.as_deref().unwrap_or("HEAD"))
+            .map_err(|e| AppError::wrap(e, "Failed to resolve tag target"))?;
+        let signing = match sign {
+            Some(false) => None,
+            Some(true) => Some(
+                signing_config(&app, &repo, &repo_path, "tag.gpgsign")
+                    .or_else(|| configured_signing_key(&repo))
+                    .ok_or_else(|| AppError::invalid_input("No signing key is configured (user.signingkey)"))?,
+            ),
+            None => signing_config(&app, &repo, &repo_path, "tag.gpgsign"),
+        };
+        let message = message.map(|message| message.trim().to_string()).filter(|message| !message.is_empty());
+
+        match (&message, &signing) {
+            (None, None) => {
+                repo.tag_lightweight(name, &target_object, false)
+                    .map_err(|e| AppError::wrap(e, &format!("Failed to create tag '{}'", name)))?;
+            }
+            _ => {
+                let tagger = resolve_signature(&app, &repo, &repo_path, None, None)?;
+                create_annotated_tag(&repo, signing.as_ref(), name, &target_object, &tagger, message.as_deref().unwrap_or(name))
+                    .map_err(|e| AppError::wrap(e, &format!("Failed to create tag '{}'", name)))?;
+            }
+        }
+
+        journal_operation(&app, &repo_path, "tag", format!("tag '{}'", name), "hard", vec![ref_change(&repo, &ref_name, None)]);
+        println!("🏷️  Created tag {}", name);
+
+        let reference = repo.find_reference(&ref_name)
+            .map_err(|e| AppError::wrap(e, "Failed to read the new tag"))?;
+        git_tag(&repo, &reference).ok_or_else(|| AppError::from("Failed to read the new tag"))
+    })
+}
+
+/// Deletes a local tag. Tags already pushed stay on the remote.
+#[tauri::command]
+pub fn delete_tag(app: tauri::AppHandle, repo_path: String, name: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "delete_tag", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        let ref_name = tag_ref_name(&name)?;
+        let mut reference = repo.find_reference(&ref_name)
+            .map_err(|_| AppError::not_found(format!("Tag '{}' does not exist", name)))?;
+        let old_target = reference.target().map(|oid| oid.to_string());
+
+        reference.delete()
+            .map_err(|e| AppError::wrap(e, &format!("Failed to delete tag '{}'", name)))?;
+        journal_operation(&app, &repo_path, "delete_tag", format!("deletion of tag '{}'", name), "hard", vec![ref_change(&repo, &ref_name, old_target)]);
+
+        Ok(format!("Deleted tag '{}'", name))
+    })
+}
+
+pub fn push_tag_ref(repo: &Repository, remote_name: &str, tag: &str, token: &OperationToken) -> Result<(), AppError> {
+    let mut remote = repo.find_remote(remote_name)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
+    let host = remote.url().and_then(remote_host);
+
+    // A remote that already has a different tag rejects it through the ref status, which
+    // libgit2 reports without failing the push
+    let rejection: Arc<Mutex<Option<String>>> = Arc::new(Mutex::new(None));
+    let mut callbacks = crate::cancellable_callbacks(token);
+    let update_rejection = rejection.clone();
+    callbacks.push_update_reference(move |_, status| {
+        if let (Some(status), Ok(mut rejection)) = (status, update_rejection.lock()) {
+            *rejection = Some(status.to_string());
+        }
+        Ok(())
+    });
+
+    let mut push_options = PushOptions::new();
+    push_options.remote_callbacks(callbacks);
+    let refspec = format!("refs/tags/{}:refs/tags/{}", tag, tag);
+
+    token.check()?;
+    remote.push(&[&refspec], Some(&mut push_options))
+        .map_err(|e| AppError::wrap(e, &format!("Failed to push tag '{}' to '{}'", tag, remote_name)).with_host(host.clone()))?;
+
+    if let Some(reason) = rejection.lock().ok().and_then(|rejection| rejection.clone()) {
+        return Err(AppError::conflict(format!("'{}' rejected tag '{}': {}", remote_name, tag, reason), Vec::new()).with_host(host));
+    }
+    Ok(())
+}
+
+/// Pushes one tag to `remote_name` (the default remote when not given).
+#[tauri::command]
+pub async fn push_tag(
+    app: tauri::AppHandle,
+    repo_path: String,
+    name: String,
+    remote_name: Option<String>,
+    operation_id: Option<String>,
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "push", operation_id, move |token| {
+        audited(&task_app, &repo_path, "push_tag", || {
+            let repo = Repository::open(&repo_path)
+                .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+            tag_ref_name(&name)?;
+            let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&task_app, &repo_path));
+            push_tag_ref(&repo, &remote_name, &name, &token)?;
+            Ok(format!("Pushed tag '{}' to '{}'", name, remote_name))
+        })
+    })
+    .await
+}
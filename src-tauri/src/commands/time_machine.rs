@@ -0,0 +1,177 @@
+use crate::commands::encoding::{detect_encoding, read_text_lines, TextEncoding};
+use crate::commands::error::AppError;
+use crate::commands::limits::payload_limits;
+use chrono::{DateTime, NaiveDate, Utc};
+use git2::{ObjectType, Repository, Sort};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// The commit a branch was at on a given date. Pass `commit_id` to list_tree_at,
+/// read_file_at or export_archive to browse or export that state.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionAtTime {
+    pub commit_id: String,
+    pub summary: String,
+    pub author: String,
+    pub timestamp: i64, // Commit time, at or before the requested date
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionTreeEntry {
+    pub name: String,
+    pub path: String,
+    pub kind: String, // 'file' | 'directory' | 'symlink' | 'submodule'
+    pub size: Option<u64>, // Files and symlinks only
+    pub object_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RevisionFile {
+    pub path: String,
+    pub content: String,
+    pub is_binary: bool,
+    pub size: u64,
+    pub truncated: bool, // Content was cut at max_file_bytes
+    pub encoding: String,
+}
+
+/// RFC 3339 ("2024-03-01T12:00:00+01:00"), or a date alone meaning the end of that day UTC.
+fn parse_datetime(value: &str) -> Result<DateTime<Utc>, AppError> {
+    let value = value.trim();
+    if let Ok(datetime) = DateTime::parse_from_rfc3339(value) {
+        return Ok(datetime.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(23, 59, 59))
+        .map(|datetime| DateTime::<Utc>::from_naive_utc_and_offset(datetime, Utc))
+        .ok_or_else(|| AppError::invalid_input(format!("Invalid date: {}", value)))
+}
+
+fn revision_tree<'r>(repo: &'r Repository, revision: &str) -> Result<git2::Tree<'r>, AppError> {
+    repo.revparse_single(revision)
+        .and_then(|object| object.peel_to_tree())
+        .map_err(|e| AppError::wrap(e, &format!("Failed to resolve '{}'", revision)))
+}
+
+/// The last commit on `branch`'s first-parent history committed at or before `datetime`,
+/// i.e. what the branch pointed to at that moment as far as history can tell (the reflog
+/// only covers this clone, and expires).
+#[tauri::command]
+pub fn resolve_revision_at(repo_path: String, branch: String, datetime: String) -> Result<RevisionAtTime, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let at = parse_datetime(&datetime)?.timestamp();
+
+    let tip = repo.revparse_single(&branch)
+        .and_then(|object| object.peel_to_commit())
+        .map_err(|e| AppError::wrap(e, &format!("Failed to resolve '{}'", branch)))?;
+    let mut revwalk = repo.revwalk()
+        .map_err(|e| AppError::wrap(e, "Failed to create revwalk"))?;
+    revwalk.push(tip.id())
+        .map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+    revwalk.simplify_first_parent()
+        .map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+    revwalk.set_sorting(Sort::TOPOLOGICAL | Sort::TIME)
+        .map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+
+    for oid in revwalk {
+        let oid = oid.map_err(|e| AppError::wrap(e, "Failed to walk history"))?;
+        let commit = repo.find_commit(oid)
+            .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
+        if commit.time().seconds() <= at {
+            return Ok(RevisionAtTime {
+                commit_id: oid.to_string(),
+                summary: commit.summary().unwrap_or("").to_string(),
+                author: commit.author().name().unwrap_or("Unknown").to_string(),
+                timestamp: commit.time().seconds(),
+            });
+        }
+    }
+
+    Err(AppError::not_found(format!("'{}' has no commits before {}", branch, datetime)))
+}
+
+/// One directory of the tree at `revision` (the root when `path` is None), directories first.
+#[tauri::command]
+pub fn list_tree_at(repo_path: String, revision: String, path: Option<String>) -> Result<Vec<RevisionTreeEntry>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let root = revision_tree(&repo, &revision)?;
+    let directory = path.as_deref().map(|path| path.trim_matches('/')).unwrap_or("");
+
+    let tree = if directory.is_empty() {
+        root
+    } else {
+        root.get_path(Path::new(directory))
+            .and_then(|entry| entry.to_object(&repo))
+            .and_then(|object| object.peel_to_tree())
+            .map_err(|_| AppError::not_found(format!("{} is not a directory at {}", directory, revision)))?
+    };
+    let odb = repo.odb()
+        .map_err(|e| AppError::wrap(e, "Failed to open object database"))?;
+
+    let mut entries: Vec<RevisionTreeEntry> = tree
+        .iter()
+        .map(|entry| {
+            let name = entry.name().unwrap_or("").to_string();
+            let kind = match (entry.kind(), entry.filemode()) {
+                (Some(ObjectType::Tree), _) => "directory",
+                (Some(ObjectType::Commit), _) => "submodule",
+                (_, 0o120000) => "symlink",
+                _ => "file",
+            };
+            let size = if kind == "file" || kind == "symlink" {
+                odb.read_header(entry.id()).ok().map(|(size, _)| size as u64)
+            } else {
+                None
+            };
+            RevisionTreeEntry {
+                path: if directory.is_empty() { name.clone() } else { format!("{}/{}", directory, name) },
+                name,
+                kind: kind.to_string(),
+                size,
+                object_id: entry.id().to_string(),
+            }
+        })
+        .collect();
+
+    entries.sort_by_key(|entry| (entry.kind != "directory", entry.name.to_lowercase()));
+    Ok(entries)
+}
+
+/// A file's content at `revision`, cut at max_file_bytes.
+#[tauri::command]
+pub fn read_file_at(app: tauri::AppHandle, repo_path: String, revision: String, file_path: String) -> Result<RevisionFile, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let tree = revision_tree(&repo, &revision)?;
+    let blob = tree.get_path(Path::new(&file_path))
+        .and_then(|entry| entry.to_object(&repo))
+        .and_then(|object| object.peel_to_blob())
+        .map_err(|_| AppError::not_found(format!("{} does not exist at {}", file_path, revision)))?;
+
+    let bytes = blob.content();
+    let size = bytes.len() as u64;
+    let encoding = detect_encoding(&bytes[..bytes.len().min(8000)]);
+    if encoding == TextEncoding::Binary {
+        return Ok(RevisionFile {
+            path: file_path,
+            content: format!("Binary file ({} bytes)", size),
+            is_binary: true,
+            size,
+            truncated: false,
+            encoding: encoding.name().to_string(),
+        });
+    }
+
+    let page = read_text_lines(bytes, encoding, 0, None, payload_limits(&app).max_file_bytes)?;
+    Ok(RevisionFile {
+        path: file_path,
+        content: page.content,
+        is_binary: false,
+        size,
+        truncated: page.has_more,
+        encoding: encoding.name().to_string(),
+    })
+}
@@ -0,0 +1,398 @@
+use crate::commands::error::AppError;
+use crate::commands::status::StatusCache;
+use git2::Repository;
+use notify::{RecommendedWatcher, RecursiveMode, Watcher};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{Manager, State};
+
+// Bursts like a checkout touch thousands of files; wait for this much quiet before emitting
+const DEBOUNCE_INTERVAL: Duration = Duration::from_millis(300);
+// Default for watch_repository's min_interval_ms
+const DEFAULT_MIN_INTERVAL: Duration = Duration::from_millis(1000);
+
+/// Active watchers by repository path. Dropping a watcher stops its debounce thread.
+#[derive(Default)]
+pub struct RepositoryWatchers(Mutex<HashMap<String, RecommendedWatcher>>);
+
+impl RepositoryWatchers {
+    pub fn is_watching(&self, repo_path: &str) -> bool {
+        self.0.lock()
+            .map(|watchers| watchers.contains_key(repo_path))
+            .unwrap_or(false)
+    }
+}
+
+/// Payload of status-changed, sent after the status cache has been recomputed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatusChangedEvent {
+    pub repo_path: String,
+    pub total_entries: Option<usize>, // None when the status could not be computed
+}
+
+/// Payload of head-changed: what HEAD points at after a checkout, commit, reset, ...
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HeadState {
+    pub repo_path: String,
+    pub branch: Option<String>, // None when detached or unborn
+    pub commit: Option<String>,
+    pub is_detached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefUpdate {
+    pub name: String,
+    pub old_target: Option<String>,
+    pub new_target: Option<String>,
+}
+
+/// Payload of refs-changed: every ref created, moved or deleted since the last event.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefsChangedEvent {
+    pub repo_path: String,
+    pub updates: Vec<RefUpdate>,
+}
+
+#[derive(Debug, Default)]
+struct PendingChanges {
+    status: bool,
+    head: bool,
+    refs: bool,
+}
+
+impl PendingChanges {
+    fn is_empty(&self) -> bool {
+        !self.status && !self.head && !self.refs
+    }
+}
+
+struct ChangeClassifier {
+    repo: Repository,
+    repo_path: String,
+    status_cache: StatusCache,
+    workdir: Option<PathBuf>,
+    git_dir: PathBuf,
+    common_dir: Option<PathBuf>, // Shared git dir holding refs, for linked worktrees
+}
+
+/// The shared git dir of a linked worktree, from its `commondir` file.
+fn common_git_dir(git_dir: &Path) -> Option<PathBuf> {
+    let content = std::fs::read_to_string(git_dir.join("commondir")).ok()?;
+    git_dir.join(content.trim()).canonicalize().ok()
+}
+
+fn head_state(repo: &Repository, repo_path: &str) -> HeadState {
+    let head = repo.head().ok();
+    let is_detached = repo.head_detached().unwrap_or(false);
+
+    HeadState {
+        repo_path: repo_path.to_string(),
+        branch: head.as_ref()
+            .filter(|_| !is_detached)
+            .and_then(|head| head.shorthand().map(|name| name.to_string()))
+            .or_else(|| {
+                // Unborn branch: HEAD names a branch that has no commits yet
+                repo.find_reference("HEAD").ok()
+                    .and_then(|head| head.symbolic_target().map(|target| target.trim_start_matches("refs/heads/").to_string()))
+            }),
+        commit: head.and_then(|head| head.target()).map(|oid| oid.to_string()),
+        is_detached,
+    }
+}
+
+fn ref_snapshot(repo: &Repository) -> BTreeMap<String, String> {
+    let mut snapshot = BTreeMap::new();
+    if let Ok(references) = repo.references() {
+        for reference in references.flatten() {
+            if let (Some(name), Some(target)) = (reference.name(), reference.target()) {
+                snapshot.insert(name.to_string(), target.to_string());
+            }
+        }
+    }
+    snapshot
+}
+
+fn diff_refs(old: &BTreeMap<String, String>, new: &BTreeMap<String, String>) -> Vec<RefUpdate> {
+    let mut updates = Vec::new();
+
+    for (name, new_target) in new {
+        if old.get(name) != Some(new_target) {
+            updates.push(RefUpdate {
+                name: name.clone(),
+                old_target: old.get(name).cloned(),
+                new_target: Some(new_target.clone()),
+            });
+        }
+    }
+    for (name, old_target) in old {
+        if !new.contains_key(name) {
+            updates.push(RefUpdate {
+                name: name.clone(),
+                old_target: Some(old_target.clone()),
+                new_target: None,
+            });
+        }
+    }
+
+    updates
+}
+
+impl ChangeClassifier {
+    fn classify(&self, path: &Path, changes: &mut PendingChanges) {
+        if let Ok(relative) = path.strip_prefix(&self.git_dir) {
+            self.classify_git_path(relative, changes);
+            return;
+        }
+        if let Some(relative) = self.common_dir.as_ref().and_then(|common_dir| path.strip_prefix(common_dir).ok()) {
+            // Only the shared refs matter; HEAD and index there belong to the main worktree
+            let name = relative.to_string_lossy().replace('\\', "/");
+            if !name.ends_with(".lock") && (name.starts_with("refs/") || name == "packed-refs") {
+                changes.refs = true;
+            }
+            if name == "info/exclude" {
+                self.status_cache.invalidate(&self.repo_path);
+                changes.status = true;
+            }
+            return;
+        }
+
+        if let Some(workdir) = &self.workdir {
+            if let Ok(relative) = path.strip_prefix(workdir) {
+                // .git of a submodule or nested repository is not part of our status
+                if relative.components().any(|component| component.as_os_str() == ".git") {
+                    return;
+                }
+                if !self.repo.status_should_ignore(relative).unwrap_or(false) {
+                    self.status_cache.mark_dirty(&self.repo_path, &relative.to_string_lossy());
+                    changes.status = true;
+                }
+            }
+        }
+    }
+
+    fn classify_git_path(&self, relative: &Path, changes: &mut PendingChanges) {
+        let name = relative.to_string_lossy().replace('\\', "/");
+
+        // Lock files come and go while git writes; the final rename is reported separately
+        if name.ends_with(".lock") {
+            return;
+        }
+
+        if name == "HEAD" || name == "ORIG_HEAD" {
+            changes.head = true;
+            changes.status = true;
+        } else if name.starts_with("refs/") || name == "packed-refs" || name == "FETCH_HEAD" {
+            changes.refs = true;
+        } else if name == "index" || name == "MERGE_HEAD" || name == "REBASE_HEAD" || name == "CHERRY_PICK_HEAD" {
+            changes.status = true;
+        } else if name == "info/exclude" {
+            // Exclude rules apply to the whole worktree, so single paths can't be refreshed
+            self.status_cache.invalidate(&self.repo_path);
+            changes.status = true;
+        }
+    }
+}
+
+/// Last state reported to the frontend, so events only fire on real changes.
+struct ReportedState {
+    head: HeadState,
+    refs: BTreeMap<String, String>,
+}
+
+fn emit_changes(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    repo: &Repository,
+    status_cache: &StatusCache,
+    changes: &PendingChanges,
+    reported: &mut ReportedState,
+) {
+    if changes.status {
+        // Recompute here so the frontend's follow-up get_repository_status is served from the cache
+        let total_entries = status_cache.statuses(repo, repo_path, true)
+            .map(|entries| entries.len())
+            .ok();
+        let _ = app.emit_all("status-changed", StatusChangedEvent {
+            repo_path: repo_path.to_string(),
+            total_entries,
+        });
+    }
+
+    // A commit from the terminal only moves the branch ref, so refs changes can move HEAD too
+    if changes.head || changes.refs {
+        let head = head_state(repo, repo_path);
+        if head != reported.head {
+            let _ = app.emit_all("head-changed", head.clone());
+            reported.head = head;
+        }
+    }
+
+    if changes.refs {
+        let refs = ref_snapshot(repo);
+        let updates = diff_refs(&reported.refs, &refs);
+        if !updates.is_empty() {
+            let _ = app.emit_all("refs-changed", RefsChangedEvent {
+                repo_path: repo_path.to_string(),
+                updates,
+            });
+        }
+        reported.refs = refs;
+    }
+}
+
+/// When the pending changes should be emitted: after a quiet period, but no later than
+/// `min_interval` after the first change so a never-ending burst (npm install) still
+/// reports progress, and no sooner than `min_interval` after the last status recompute.
+fn flush_deadline(
+    changes: &PendingChanges,
+    first_change: Instant,
+    last_event: Instant,
+    last_status_refresh: Option<Instant>,
+    min_interval: Duration,
+) -> Instant {
+    let deadline = (last_event + DEBOUNCE_INTERVAL).min(first_change + min_interval);
+
+    match last_status_refresh {
+        Some(last_refresh) if changes.status => deadline.max(last_refresh + min_interval),
+        _ => deadline,
+    }
+}
+
+/// Collects filesystem events and emits one consolidated event per kind of change,
+/// recomputing the status at most once per `min_interval`.
+fn run_debouncer(
+    app: tauri::AppHandle,
+    repo_path: String,
+    classifier: ChangeClassifier,
+    receiver: mpsc::Receiver<notify::Event>,
+    min_interval: Duration,
+) {
+    let mut changes = PendingChanges::default();
+    let mut reported = ReportedState {
+        head: head_state(&classifier.repo, &repo_path),
+        refs: ref_snapshot(&classifier.repo),
+    };
+    let mut first_change = Instant::now();
+    let mut last_event = Instant::now();
+    let mut last_status_refresh = None;
+
+    loop {
+        let event = if changes.is_empty() {
+            match receiver.recv() {
+                Ok(event) => event,
+                Err(_) => return, // Watcher dropped
+            }
+        } else {
+            let deadline = flush_deadline(&changes, first_change, last_event, last_status_refresh, min_interval);
+            match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => {
+                    emit_changes(&app, &repo_path, &classifier.repo, &classifier.status_cache, &changes, &mut reported);
+                    if changes.status {
+                        last_status_refresh = Some(Instant::now());
+                    }
+                    changes = PendingChanges::default();
+                    continue;
+                }
+                Err(RecvTimeoutError::Disconnected) => return,
+            }
+        };
+
+        if matches!(event.kind, notify::EventKind::Access(_)) {
+            continue;
+        }
+        let was_empty = changes.is_empty();
+        for path in &event.paths {
+            classifier.classify(path, &mut changes);
+        }
+        if !changes.is_empty() {
+            last_event = Instant::now();
+            if was_empty {
+                first_change = last_event;
+            }
+        }
+    }
+}
+
+#[tauri::command]
+pub fn watch_repository(
+    app: tauri::AppHandle,
+    watchers: State<'_, RepositoryWatchers>,
+    status_cache: State<'_, StatusCache>,
+    repo_path: String,
+    min_interval_ms: Option<u64>,
+) -> Result<(), AppError> {
+    let mut watchers = watchers.0.lock()
+        .map_err(|_| "Repository watchers are unavailable".to_string())?;
+    if watchers.contains_key(&repo_path) {
+        return Ok(());
+    }
+
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let git_dir = repo.path().canonicalize()
+        .map_err(|e| AppError::wrap(e, "Failed to resolve git directory"))?;
+    let workdir = repo.workdir().and_then(|workdir| workdir.canonicalize().ok());
+
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(move |result: notify::Result<notify::Event>| {
+        if let Ok(event) = result {
+            let _ = sender.send(event);
+        }
+    })
+    .map_err(|e| AppError::wrap(e, "Failed to create watcher"))?;
+
+    // The git dir lives inside the worktree except for linked worktrees and bare repositories
+    let mut watched = Vec::new();
+    if let Some(workdir) = &workdir {
+        watched.push(workdir.clone());
+    }
+    if !matches!(&workdir, Some(workdir) if git_dir.starts_with(workdir)) {
+        watched.push(git_dir.clone());
+    }
+    let common_dir = common_git_dir(&git_dir);
+    if let Some(common_dir) = &common_dir {
+        watched.push(common_dir.join("refs"));
+        // packed-refs sits at the top of the shared dir; watch it without recursing into objects
+        watcher.watch(common_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to watch {}", common_dir.display())))?;
+    }
+    for path in &watched {
+        watcher.watch(path, RecursiveMode::Recursive)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to watch {}", path.display())))?;
+    }
+
+    let classifier = ChangeClassifier {
+        repo,
+        repo_path: repo_path.clone(),
+        status_cache: status_cache.inner().clone(),
+        workdir,
+        git_dir,
+        common_dir,
+    };
+    status_cache.track(&repo_path);
+    let thread_app = app.clone();
+    let thread_repo_path = repo_path.clone();
+    let min_interval = min_interval_ms.map(Duration::from_millis).unwrap_or(DEFAULT_MIN_INTERVAL);
+    std::thread::spawn(move || run_debouncer(thread_app, thread_repo_path, classifier, receiver, min_interval));
+
+    watchers.insert(repo_path, watcher);
+    Ok(())
+}
+
+#[tauri::command]
+pub fn unwatch_repository(
+    watchers: State<'_, RepositoryWatchers>,
+    status_cache: State<'_, StatusCache>,
+    repo_path: String,
+) -> Result<bool, AppError> {
+    let mut watchers = watchers.0.lock()
+        .map_err(|_| "Repository watchers are unavailable".to_string())?;
+    // Without the watcher, cached statuses would silently go stale
+    status_cache.untrack(&repo_path);
+    Ok(watchers.remove(&repo_path).is_some())
+}
@@ -0,0 +1,150 @@
+use crate::commands::error::AppError;
+use crate::commands::hosting::{parse_remote_url, percent_encode, Provider};
+use git2::Repository;
+use serde::{Deserialize, Serialize};
+
+/// What to open on the provider's website.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum WebTarget {
+    Repository,
+    File {
+        path: String,
+        rev: Option<String>, // Defaults to the pushed branch, else the HEAD commit
+        line: Option<u32>,
+        end_line: Option<u32>,
+    },
+    Commit { sha: String },
+    Branch { name: String },
+    Compare { base: String, head: String },
+}
+
+fn is_commit_id(rev: &str) -> bool {
+    rev.len() == 40 && rev.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+/// Revision a file link points at: the current branch when `remote_name` has it,
+/// otherwise the HEAD commit, so the link doesn't depend on unpushed work.
+fn default_file_rev(repo: &Repository, remote_name: &str) -> Result<String, AppError> {
+    let head = repo.head()
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD"))?;
+
+    if let Some(branch) = head.shorthand().filter(|_| head.is_branch()) {
+        if repo.find_reference(&format!("refs/remotes/{}/{}", remote_name, branch)).is_ok() {
+            return Ok(branch.to_string());
+        }
+    }
+    head.peel_to_commit()
+        .map(|commit| commit.id().to_string())
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))
+}
+
+fn line_anchor(provider: Provider, line: Option<u32>, end_line: Option<u32>) -> String {
+    let line = match line {
+        Some(line) => line,
+        None => return String::new(),
+    };
+    let end_line = end_line.filter(|end_line| *end_line > line);
+
+    match (provider, end_line) {
+        (Provider::Github, Some(end)) => format!("#L{}-L{}", line, end),
+        (Provider::Github, None) => format!("#L{}", line),
+        (Provider::Gitlab, Some(end)) => format!("#L{}-{}", line, end),
+        (Provider::Gitlab, None) => format!("#L{}", line),
+        (Provider::Bitbucket, Some(end)) => format!("#lines-{}:{}", line, end),
+        (Provider::Bitbucket, None) => format!("#lines-{}", line),
+        // Azure selects lines with query parameters instead
+        (Provider::AzureDevops, _) => String::new(),
+    }
+}
+
+fn azure_version(rev: &str) -> String {
+    let prefix = if is_commit_id(rev) { "GC" } else { "GB" };
+    format!("{}{}", prefix, percent_encode(rev, false))
+}
+
+fn build_web_url(provider: Provider, base: &str, target: &WebTarget, file_rev: Option<&str>) -> String {
+    match (provider, target) {
+        (_, WebTarget::Repository) => base.to_string(),
+
+        (Provider::AzureDevops, WebTarget::File { path, line, end_line, .. }) => {
+            let mut url = format!(
+                "{}?path=/{}&version={}",
+                base, percent_encode(path, true), azure_version(file_rev.unwrap_or_default())
+            );
+            if let Some(line) = line {
+                let end_line = end_line.filter(|end_line| *end_line > *line).unwrap_or(*line);
+                url.push_str(&format!(
+                    "&line={}&lineEnd={}&lineStartColumn=1&lineEndColumn=1&lineStyle=plain",
+                    line, end_line + 1
+                ));
+            }
+            url
+        }
+        (_, WebTarget::File { path, line, end_line, .. }) => {
+            let section = match provider {
+                Provider::Gitlab => "-/blob",
+                Provider::Bitbucket => "src",
+                _ => "blob",
+            };
+            format!(
+                "{}/{}/{}/{}{}",
+                base, section, percent_encode(file_rev.unwrap_or_default(), true), percent_encode(path, true),
+                line_anchor(provider, *line, *end_line)
+            )
+        }
+
+        (Provider::Github, WebTarget::Commit { sha }) | (Provider::AzureDevops, WebTarget::Commit { sha }) => format!("{}/commit/{}", base, sha),
+        (Provider::Gitlab, WebTarget::Commit { sha }) => format!("{}/-/commit/{}", base, sha),
+        (Provider::Bitbucket, WebTarget::Commit { sha }) => format!("{}/commits/{}", base, sha),
+
+        (Provider::Github, WebTarget::Branch { name }) => format!("{}/tree/{}", base, percent_encode(name, true)),
+        (Provider::Gitlab, WebTarget::Branch { name }) => format!("{}/-/tree/{}", base, percent_encode(name, true)),
+        (Provider::Bitbucket, WebTarget::Branch { name }) => format!("{}/branch/{}", base, percent_encode(name, true)),
+        (Provider::AzureDevops, WebTarget::Branch { name }) => format!("{}?version={}", base, azure_version(name)),
+
+        (Provider::Github, WebTarget::Compare { base: from, head }) => {
+            format!("{}/compare/{}...{}", base, percent_encode(from, true), percent_encode(head, true))
+        }
+        (Provider::Gitlab, WebTarget::Compare { base: from, head }) => {
+            format!("{}/-/compare/{}...{}", base, percent_encode(from, true), percent_encode(head, true))
+        }
+        // Bitbucket lists the source first, separated by a carriage return
+        (Provider::Bitbucket, WebTarget::Compare { base: from, head }) => {
+            format!("{}/branches/compare/{}%0D{}", base, percent_encode(head, true), percent_encode(from, true))
+        }
+        (Provider::AzureDevops, WebTarget::Compare { base: from, head }) => {
+            format!("{}/branchCompare?baseVersion={}&targetVersion={}", base, azure_version(from), azure_version(head))
+        }
+    }
+}
+
+/// Page on the hosting provider (GitHub, GitLab, Bitbucket or Azure DevOps) for a file
+/// and line range, a commit, a branch or a comparison, from the default remote or
+/// `remote_name`. Both HTTPS and SSH remotes are understood.
+#[tauri::command]
+pub fn get_remote_web_url(
+    app: tauri::AppHandle,
+    repo_path: String,
+    target: WebTarget,
+    remote_name: Option<String>,
+) -> Result<String, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    let remote_name = remote_name.unwrap_or_else(|| crate::default_remote_name(&app, &repo_path));
+    let remote = repo.find_remote(&remote_name)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
+    let url = remote.url().unwrap_or("");
+
+    let hosted = parse_remote_url(url).ok_or_else(|| {
+        AppError::invalid_input(format!("Remote '{}' is not on a supported hosting provider: {}", remote_name, url))
+    })?;
+
+    let file_rev = match &target {
+        WebTarget::File { rev: Some(rev), .. } => Some(rev.clone()),
+        WebTarget::File { rev: None, .. } => Some(default_file_rev(&repo, &remote_name)?),
+        _ => None,
+    };
+
+    Ok(build_web_url(hosted.provider, &hosted.web_url(), &target, file_rev.as_deref()))
+}
@@ -1,7 +1,7 @@
-use sqlx::{sqlite::SqlitePool, Row};
+use sqlx::{sqlite::SqlitePool, QueryBuilder, Row, Sqlite};
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::api::path::data_dir;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -10,6 +10,8 @@ pub struct UserInfo {
     pub name: String,
     pub email: String,
     pub workspace_name: Option<String>,
+    #[serde(default)]
+    pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
 }
@@ -21,6 +23,23 @@ pub struct Organization {
     pub color: String,
     pub description: Option<String>,
     pub avatar: Option<String>,
+    #[serde(default)]
+    pub user_id: Option<i64>, // Owning user profile; None means shared by all profiles
+    #[serde(default)]
+    pub workspace_id: Option<String>, // None assigns the active workspace on save
+    #[serde(default)]
+    pub sign_off: bool, // Add Signed-off-by to commits in its repositories unless a repository overrides it
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Workspace {
+    pub id: String,
+    pub name: String,
+    pub color: Option<String>,
+    #[serde(default)]
+    pub is_active: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -36,12 +55,31 @@ pub struct Repository {
     pub last_commit: String,
     pub is_dirty: bool,
     pub is_favorite: bool,
+    #[serde(default)]
+    pub is_pinned: bool, // Managed by set_repository_pinned; save_repository keeps the stored value
+    #[serde(default)]
+    pub position: Option<i64>, // Manual sort order; managed by reorder_repositories
     pub tags: serde_json::Value, // JSON array of strings
+    #[serde(default)]
+    pub workspace_id: Option<String>, // None inherits the organization's or the active workspace
     pub last_accessed: DateTime<Utc>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryTag {
+    pub tag: String,
+    pub repository_count: i64,
+}
+
+/// A registered repository with its organization's name, for inventory exports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InventoryEntry {
+    pub repository: Repository,
+    pub organization: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub id: Option<i64>,
@@ -51,13 +89,712 @@ pub struct AppSettings {
     pub font_family: String,
     pub language: String,
     pub settings_json: serde_json::Value, // Complete settings as JSON
+    #[serde(default)]
+    pub active_theme_id: Option<String>, // Custom theme from the themes table, set via set_active_theme
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefChange {
+    pub ref_name: String,
+    pub old_target: Option<String>,
+    pub new_target: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationEntry {
+    pub id: Option<i64>,
+    pub repo_path: String,
+    pub operation: String, // 'commit' | 'merge' | 'rebase' | 'reset' | 'branch_delete' | 'stash_drop'
+    pub description: String,
+    pub ref_changes: Vec<RefChange>,
+    pub undo_mode: String, // 'soft' | 'mixed' | 'hard' | 'ref' | 'stash'
+    pub created_at: DateTime<Utc>,
+    pub undone_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuditEntry {
+    pub id: Option<i64>,
+    pub repo_path: String,
+    pub command: String,
+    pub ref_changes: Vec<RefChange>, // Only the refs the command moved, created or deleted
+    pub outcome: String, // 'success' | 'failed' | 'cancelled'
+    pub error: Option<String>,
+    pub started_at: DateTime<Utc>,
+    pub finished_at: DateTime<Utc>,
+}
+
+/// Aggregated timings of one command, for the performance report.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandTiming {
+    pub command: String,
+    pub calls: i64,
+    pub avg_ms: f64,
+    pub max_ms: i64,
+    pub total_ms: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryTiming {
+    pub repo_path: String,
+    pub calls: i64,
+    pub avg_ms: f64,
+    pub max_ms: i64,
+    pub total_ms: i64,
+    pub slowest_command: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdentityProfile {
+    pub id: String,
+    pub name: String,
+    pub author_name: String,
+    pub author_email: String,
+    pub signing_key: Option<String>,
+    pub signing_format: Option<String>, // 'openpgp' | 'ssh'
+    pub sign_by_default: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomTheme {
+    pub id: String,
+    pub name: String,
+    pub base_mode: String, // 'dark' | 'light'
+    pub tokens: serde_json::Value, // JSON object of color token -> CSS color
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoSettings {
+    pub repo_path: String,
+    pub default_remote: Option<String>,
+    pub push_behavior: String, // 'current' | 'upstream' | 'gerrit' (refs/for/<branch>)
+    pub pull_strategy: String, // 'merge' | 'rebase' | 'ff-only'
+    pub auto_fetch_interval: Option<i64>, // minutes, None disables auto-fetch
+    pub diff_whitespace: String, // 'none' | 'all' | 'change' | 'eol'
+    #[serde(default)]
+    pub issue_patterns: Vec<String>, // Regexes for issue references in commit messages; empty means "#123"
+    #[serde(default)]
+    pub branching_model: BranchingModel,
+    #[serde(default)]
+    pub sign_off: Option<bool>, // Signed-off-by trailer on commits; None follows the organization
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Git-flow style branch layout: where feature, release and hotfix branches start and
+/// which branches they are merged into when finished.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BranchingModel {
+    pub main_branch: String,    // Production history; hotfixes start here, releases and hotfixes are tagged here
+    pub develop_branch: String, // Integration branch; features and releases start here
+    pub feature_prefix: String,
+    pub release_prefix: String,
+    pub hotfix_prefix: String,
+    pub version_tag_prefix: String, // Prepended to release/hotfix names for the tag, e.g. "v"
+    pub merge_strategy: String,     // 'no-ff' | 'ff' | 'squash'
+}
+
+impl Default for BranchingModel {
+    fn default() -> Self {
+        BranchingModel {
+            main_branch: "main".to_string(),
+            develop_branch: "develop".to_string(),
+            feature_prefix: "feature/".to_string(),
+            release_prefix: "release/".to_string(),
+            hotfix_prefix: "hotfix/".to_string(),
+            version_tag_prefix: String::new(),
+            merge_strategy: "no-ff".to_string(),
+        }
+    }
+}
+
+impl RepoSettings {
+    pub fn defaults(repo_path: &str) -> Self {
+        RepoSettings {
+            repo_path: repo_path.to_string(),
+            default_remote: None,
+            push_behavior: "current".to_string(),
+            pull_strategy: "merge".to_string(),
+            auto_fetch_interval: None,
+            diff_whitespace: "none".to_string(),
+            issue_patterns: Vec::new(),
+            branching_model: BranchingModel::default(),
+            sign_off: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// A saved set of pathspecs within a repository (e.g. one package of a monorepo). While a
+/// scope is active, status, log and diff only show paths it matches.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepoScope {
+    pub id: String,
+    pub repo_path: String,
+    pub name: String,
+    pub pathspecs: Vec<String>, // Same syntax as `git log -- <pathspec>`: directories and globs
+    pub is_active: bool, // At most one active scope per repository
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Keybinding {
+    pub action: String,
+    pub keys: String,
+    pub is_default: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryExclusion {
+    pub id: Option<i64>,
+    pub kind: String, // 'glob' | 'path' | 'tombstone'
+    pub pattern: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Commit metadata kept per repository so the history renders without reading the ODB.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CachedCommit {
+    pub id: String,
+    pub parents: Vec<String>,
+    pub author: String,
+    pub email: String,
+    pub message: String,
+    pub timestamp: i64,
+}
+
+/// What was open when the app last closed, restored on the next start.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionState {
+    pub repo_path: Option<String>,
+    pub branch: Option<String>,
+    pub open_panels: Vec<String>,
     pub updated_at: DateTime<Utc>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PanelLayout {
+    pub name: String,
+    pub layout: serde_json::Value,
+    pub is_default: bool,
+}
+
+// Built-in shortcuts; rows in the keybindings table override them per action
+const DEFAULT_KEYBINDINGS: [(&str, &str); 6] = [
+    ("git_pull", "Ctrl+P"),
+    ("git_push", "Ctrl+Shift+P"),
+    ("git_fetch", "Ctrl+F"),
+    ("selection_invert", "Ctrl+I"),
+    ("selection_clear", "Ctrl+D"),
+    ("bulk_operations", "Ctrl+B"),
+];
+
+fn default_panel_layout(name: &str) -> Option<serde_json::Value> {
+    match name {
+        "sidebar" => Some(serde_json::json!({ "width": 300, "is_collapsed": false })),
+        "details_panel" => Some(serde_json::json!({ "width": 350, "is_collapsed": false })),
+        "header" => Some(serde_json::json!({ "height": 48, "show_breadcrumb": true })),
+        "main_view" => Some(serde_json::json!({ "mode": "history" })),
+        _ => None,
+    }
+}
+
+const DEFAULT_PANEL_LAYOUTS: [&str; 4] = ["sidebar", "details_panel", "header", "main_view"];
+
+// Layout version stored as "schema_version" inside settings_json.
+// Bump it and append a step to SETTINGS_MIGRATIONS whenever the layout changes.
+pub const SETTINGS_SCHEMA_VERSION: u64 = 1;
+
+type SettingsMigration = fn(&mut serde_json::Map<String, serde_json::Value>);
+
+// SETTINGS_MIGRATIONS[n] upgrades settings_json from version n to n + 1
+const SETTINGS_MIGRATIONS: [SettingsMigration; SETTINGS_SCHEMA_VERSION as usize] = [
+    migrate_settings_v0_to_v1,
+];
+
+fn migrate_settings_v0_to_v1(_settings: &mut serde_json::Map<String, serde_json::Value>) {
+    // Version 0 is the original unversioned layout; its keys carry over unchanged
+}
+
+/// Parses stored settings_json without losing content that fails to parse.
+fn parse_settings_json(raw: &str) -> serde_json::Value {
+    serde_json::from_str(raw).unwrap_or_else(|_| serde_json::json!({ "recovered_settings": raw }))
+}
+
+/// Upgrades settings_json to SETTINGS_SCHEMA_VERSION, returning the result and whether it changed.
+/// Settings written by a newer version of the app are returned untouched.
+pub fn migrate_settings_json(value: serde_json::Value) -> (serde_json::Value, bool) {
+    let mut settings = match value {
+        serde_json::Value::Object(settings) => settings,
+        // Anything other than an object is kept rather than dropped
+        other => {
+            let mut settings = serde_json::Map::new();
+            settings.insert("legacy_settings".to_string(), other);
+            settings
+        }
+    };
+
+    let version = settings.get("schema_version").and_then(|v| v.as_u64()).unwrap_or(0);
+    if version >= SETTINGS_SCHEMA_VERSION {
+        let changed = !settings.contains_key("schema_version");
+        return (serde_json::Value::Object(settings), changed);
+    }
+
+    for migration in &SETTINGS_MIGRATIONS[version as usize..] {
+        migration(&mut settings);
+    }
+    settings.insert("schema_version".to_string(), serde_json::json!(SETTINGS_SCHEMA_VERSION));
+
+    (serde_json::Value::Object(settings), true)
+}
+
 pub struct Database {
     pool: SqlitePool,
 }
 
+const REPOSITORY_COLUMNS: &str = "id, name, path, organization_id, remote_url, current_branch, last_commit, \
+    is_dirty, is_favorite, is_pinned, position, tags, workspace_id, last_accessed, created_at, updated_at";
+
+// Pinned first, then the manual order, then most recently used
+const REPOSITORY_ORDER: &str = "is_pinned DESC, position IS NULL, position ASC, last_accessed DESC";
+
+// Repositories of the active user profile and workspace
+const VISIBLE_REPOSITORIES: &str = r#"
+    (organization_id IS NULL OR organization_id IN (
+        SELECT id FROM organizations
+        WHERE user_id IS NULL OR user_id = (SELECT id FROM users WHERE is_active = TRUE LIMIT 1)
+    ))
+    AND (workspace_id IS NULL OR workspace_id = (SELECT id FROM workspaces WHERE is_active = TRUE LIMIT 1))
+"#;
+
+// Rotating snapshots kept in <data dir>/backups, oldest removed first
+const AUTOMATIC_BACKUP_COUNT: usize = 5;
+
+/// CodeGit's directory inside the platform's app data directory.
+pub fn codegit_data_dir() -> PathBuf {
+    // Use app data directory for better cross-platform compatibility
+    let app_data_dir = data_dir().unwrap_or_else(|| {
+        // Fallback for different platforms
+        #[cfg(target_os = "macos")]
+        { PathBuf::from(std::env::var("HOME").unwrap_or_default()).join("Library/Application Support") }
+        #[cfg(target_os = "windows")]
+        { PathBuf::from(std::env::var("APPDATA").unwrap_or_default()) }
+        #[cfg(target_os = "linux")]
+        { PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".local/share") }
+        #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
+        { PathBuf::from(".") }
+    });
+
+    app_data_dir.join("codegit")
+}
+
+pub fn database_path() -> PathBuf {
+    codegit_data_dir().join("database.sqlite")
+}
+
+/// Checks that `path` is an intact CodeGit database before it replaces the current one.
+pub async fn validate_database_file(path: &Path) -> Result<(), sqlx::Error> {
+    let database_url = format!("sqlite:{}?mode=ro", path.to_string_lossy());
+    let pool = SqlitePool::connect(&database_url).await?;
+
+    let integrity: String = sqlx::query_scalar("PRAGMA integrity_check")
+        .fetch_one(&pool)
+        .await?;
+    let has_settings: Option<String> = sqlx::query_scalar(
+        "SELECT name FROM sqlite_master WHERE type = 'table' AND name = 'app_settings'"
+    )
+    .fetch_optional(&pool)
+    .await?;
+    pool.close().await;
+
+    if integrity != "ok" {
+        return Err(sqlx::Error::Protocol(format!("integrity check failed: {}", integrity)));
+    }
+    if has_settings.is_none() {
+        return Err(sqlx::Error::Protocol("not a CodeGit database".to_string()));
+    }
+    Ok(())
+}
+
+/// One step of a schema migration.
+enum MigrationStep {
+    Sql(&'static str),
+    /// (table, column, definition): ADD COLUMN that is skipped when the column exists,
+    /// since databases created before versioned migrations may already have it.
+    AddColumn(&'static str, &'static str, &'static str),
+}
+
+struct SchemaMigration {
+    version: i64,
+    description: &'static str,
+    steps: &'static [MigrationStep],
+}
+
+/// Ordered schema migrations. Never edit a released step: append a new version instead.
+const SCHEMA_MIGRATIONS: &[SchemaMigration] = &[
+    SchemaMigration {
+        version: 1,
+        description: "initial schema",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS users (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    name TEXT NOT NULL,
+                    email TEXT NOT NULL UNIQUE,
+                    workspace_name TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    updated_at DATETIME
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS organizations (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    color TEXT NOT NULL,
+                    description TEXT,
+                    avatar TEXT,
+                    user_id INTEGER,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS repositories (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    path TEXT NOT NULL UNIQUE,
+                    organization_id TEXT,
+                    remote_url TEXT,
+                    current_branch TEXT NOT NULL,
+                    last_commit TEXT NOT NULL,
+                    is_dirty BOOLEAN NOT NULL DEFAULT FALSE,
+                    is_favorite BOOLEAN NOT NULL DEFAULT FALSE,
+                    tags TEXT NOT NULL DEFAULT '[]',
+                    last_accessed DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE SET NULL
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS app_settings (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    is_first_time BOOLEAN NOT NULL DEFAULT TRUE,
+                    theme_mode TEXT NOT NULL DEFAULT 'dark',
+                    font_size INTEGER NOT NULL DEFAULT 14,
+                    font_family TEXT NOT NULL DEFAULT 'Inter',
+                    language TEXT NOT NULL DEFAULT 'en',
+                    settings_json TEXT NOT NULL DEFAULT '{}',
+                    active_theme_id TEXT,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS operation_journal (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    repo_path TEXT NOT NULL,
+                    operation TEXT NOT NULL,
+                    description TEXT NOT NULL,
+                    ref_changes TEXT NOT NULL DEFAULT '[]',
+                    undo_mode TEXT NOT NULL,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    undone_at DATETIME
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS repository_size_reports (
+                    repo_path TEXT PRIMARY KEY,
+                    fingerprint TEXT NOT NULL,
+                    report_json TEXT NOT NULL,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS identity_profiles (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    author_name TEXT NOT NULL,
+                    author_email TEXT NOT NULL,
+                    signing_key TEXT,
+                    signing_format TEXT,
+                    sign_by_default BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            // target_type is 'organization' (target_id = organization id) or 'repository' (target_id = repo path)
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS identity_assignments (
+                    target_type TEXT NOT NULL,
+                    target_id TEXT NOT NULL,
+                    profile_id TEXT NOT NULL,
+                    PRIMARY KEY (target_type, target_id),
+                    FOREIGN KEY (profile_id) REFERENCES identity_profiles (id) ON DELETE CASCADE
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS keybindings (
+                    action TEXT PRIMARY KEY,
+                    keys TEXT NOT NULL,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS panel_layouts (
+                    name TEXT PRIMARY KEY,
+                    layout_json TEXT NOT NULL,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS repo_settings (
+                    repo_path TEXT PRIMARY KEY,
+                    default_remote TEXT,
+                    push_behavior TEXT NOT NULL DEFAULT 'current',
+                    pull_strategy TEXT NOT NULL DEFAULT 'merge',
+                    auto_fetch_interval INTEGER,
+                    diff_whitespace TEXT NOT NULL DEFAULT 'none',
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS themes (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    base_mode TEXT NOT NULL DEFAULT 'dark',
+                    tokens TEXT NOT NULL DEFAULT '{}',
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                INSERT OR IGNORE INTO app_settings (id, is_first_time, settings_json, updated_at)
+                VALUES (1, TRUE, '{}', CURRENT_TIMESTAMP)
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 2,
+        description: "columns added before versioned migrations",
+        steps: &[
+            MigrationStep::AddColumn("users", "is_active", "BOOLEAN NOT NULL DEFAULT FALSE"),
+            MigrationStep::AddColumn("organizations", "user_id", "INTEGER"),
+            MigrationStep::AddColumn("app_settings", "active_theme_id", "TEXT"),
+            MigrationStep::AddColumn("identity_profiles", "signing_format", "TEXT"),
+            MigrationStep::AddColumn("identity_profiles", "sign_by_default", "BOOLEAN NOT NULL DEFAULT FALSE"),
+        ],
+    },
+    SchemaMigration {
+        version: 3,
+        description: "workspaces",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS workspaces (
+                    id TEXT PRIMARY KEY,
+                    name TEXT NOT NULL,
+                    color TEXT,
+                    is_active BOOLEAN NOT NULL DEFAULT FALSE,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+                )
+            "#),
+            MigrationStep::AddColumn("organizations", "workspace_id", "TEXT"),
+            MigrationStep::AddColumn("repositories", "workspace_id", "TEXT"),
+            // Everything that existed before workspaces lands in a default one
+            MigrationStep::Sql(r#"
+                INSERT OR IGNORE INTO workspaces (id, name, is_active)
+                VALUES ('default', 'Default', TRUE)
+            "#),
+            MigrationStep::Sql("UPDATE organizations SET workspace_id = 'default' WHERE workspace_id IS NULL"),
+            MigrationStep::Sql(r#"
+                UPDATE repositories SET workspace_id = COALESCE(
+                    (SELECT workspace_id FROM organizations WHERE organizations.id = repositories.organization_id),
+                    'default'
+                )
+                WHERE workspace_id IS NULL
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 4,
+        description: "repository tags join table",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS repository_tags (
+                    repository_id TEXT NOT NULL,
+                    tag TEXT NOT NULL,
+                    PRIMARY KEY (repository_id, tag),
+                    FOREIGN KEY (repository_id) REFERENCES repositories (id) ON DELETE CASCADE
+                )
+            "#),
+            MigrationStep::Sql("CREATE INDEX IF NOT EXISTS idx_repository_tags_tag ON repository_tags (tag)"),
+            MigrationStep::Sql(r#"
+                INSERT OR IGNORE INTO repository_tags (repository_id, tag)
+                SELECT repositories.id, json_each.value
+                FROM repositories, json_each(repositories.tags)
+                WHERE json_valid(repositories.tags) AND json_each.type = 'text'
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 5,
+        description: "pinned repositories and manual ordering",
+        steps: &[
+            MigrationStep::AddColumn("repositories", "is_pinned", "BOOLEAN NOT NULL DEFAULT FALSE"),
+            MigrationStep::AddColumn("repositories", "position", "INTEGER"),
+        ],
+    },
+    SchemaMigration {
+        version: 6,
+        description: "discovery exclusions",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS discovery_exclusions (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    kind TEXT NOT NULL,
+                    pattern TEXT NOT NULL,
+                    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+                    UNIQUE (kind, pattern)
+                )
+            "#),
+            // The directories discovery used to skip unconditionally
+            MigrationStep::Sql(r#"
+                INSERT OR IGNORE INTO discovery_exclusions (kind, pattern)
+                VALUES ('glob', 'node_modules'), ('glob', 'target'), ('glob', 'dist'), ('glob', 'build')
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 7,
+        description: "commit metadata cache",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS commit_cache (
+                    repo_path TEXT NOT NULL,
+                    id TEXT NOT NULL,
+                    parents TEXT NOT NULL,
+                    author TEXT NOT NULL,
+                    email TEXT NOT NULL,
+                    message TEXT NOT NULL,
+                    timestamp INTEGER NOT NULL,
+                    PRIMARY KEY (repo_path, id)
+                )
+            "#),
+            // Commits whose whole ancestry is cached; the next update stops walking at them
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS commit_cache_tips (
+                    repo_path TEXT NOT NULL,
+                    id TEXT NOT NULL,
+                    PRIMARY KEY (repo_path, id)
+                )
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 8,
+        description: "session state",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS session_state (
+                    id INTEGER PRIMARY KEY CHECK (id = 1),
+                    repo_path TEXT,
+                    branch TEXT,
+                    open_panels TEXT NOT NULL DEFAULT '[]',
+                    updated_at DATETIME NOT NULL
+                )
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 9,
+        description: "operation audit log",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS operation_history (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    repo_path TEXT NOT NULL,
+                    command TEXT NOT NULL,
+                    ref_changes TEXT NOT NULL DEFAULT '[]',
+                    outcome TEXT NOT NULL,
+                    error TEXT,
+                    started_at DATETIME NOT NULL,
+                    finished_at DATETIME NOT NULL
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE INDEX IF NOT EXISTS idx_operation_history_repo ON operation_history(repo_path, id)
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 10,
+        description: "command timings",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS command_timings (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    command TEXT NOT NULL,
+                    repo_path TEXT,
+                    duration_ms INTEGER NOT NULL,
+                    recorded_at DATETIME NOT NULL
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE INDEX IF NOT EXISTS idx_command_timings_recorded ON command_timings(recorded_at)
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 11,
+        description: "issue reference patterns",
+        steps: &[
+            MigrationStep::AddColumn("repo_settings", "issue_patterns", "TEXT NOT NULL DEFAULT '[]'"),
+        ],
+    },
+    SchemaMigration {
+        version: 12,
+        description: "repository path scopes",
+        steps: &[
+            MigrationStep::Sql(r#"
+                CREATE TABLE IF NOT EXISTS repo_scopes (
+                    id TEXT PRIMARY KEY,
+                    repo_path TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    pathspecs TEXT NOT NULL DEFAULT '[]',
+                    is_active BOOLEAN NOT NULL DEFAULT 0,
+                    created_at DATETIME NOT NULL,
+                    updated_at DATETIME NOT NULL
+                )
+            "#),
+            MigrationStep::Sql(r#"
+                CREATE INDEX IF NOT EXISTS idx_repo_scopes_repo ON repo_scopes(repo_path)
+            "#),
+        ],
+    },
+    SchemaMigration {
+        version: 13,
+        description: "branching model",
+        steps: &[
+            MigrationStep::AddColumn("repo_settings", "branching_model", "TEXT NOT NULL DEFAULT '{}'"),
+        ],
+    },
+    SchemaMigration {
+        version: 14,
+        description: "Signed-off-by settings",
+        steps: &[
+            MigrationStep::AddColumn("repo_settings", "sign_off", "BOOLEAN"),
+            MigrationStep::AddColumn("organizations", "sign_off", "BOOLEAN NOT NULL DEFAULT 0"),
+        ],
+    },
+];
+
 impl Database {
     // Public getter for the pool
     pub fn pool(&self) -> &SqlitePool {
@@ -65,21 +802,9 @@ impl Database {
     }
 
     pub async fn new() -> Result<Self, sqlx::Error> {
-        // Use app data directory for better cross-platform compatibility
-        let app_data_dir = data_dir().unwrap_or_else(|| {
-            // Fallback for different platforms
-            #[cfg(target_os = "macos")]
-            { PathBuf::from(std::env::var("HOME").unwrap_or_default()).join("Library/Application Support") }
-            #[cfg(target_os = "windows")]
-            { PathBuf::from(std::env::var("APPDATA").unwrap_or_default()) }
-            #[cfg(target_os = "linux")]
-            { PathBuf::from(std::env::var("HOME").unwrap_or_default()).join(".local/share") }
-            #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
-            { PathBuf::from(".") }
-        });
-        
-        let codegit_dir = app_data_dir.join("codegit");
-        let db_path = codegit_dir.join("database.sqlite");
+        let codegit_dir = codegit_data_dir();
+        let db_path = database_path();
+        let is_existing = std::fs::metadata(&db_path).map(|m| m.len() > 0).unwrap_or(false);
         
         // Create directory if it doesn't exist
         std::fs::create_dir_all(&codegit_dir).map_err(|e| {
@@ -94,103 +819,171 @@ impl Database {
         let pool = SqlitePool::connect(&database_url).await?;
 
         let db = Database { pool };
+        if is_existing && db.has_pending_migrations().await? {
+            // Schema migrations run in initialize(); keep a copy to roll back to
+            if let Err(e) = db.automatic_backup("pre-migration").await {
+                println!("⚠️  Failed to back up database before migrations: {}", e);
+            }
+        }
         db.initialize().await?;
         Ok(db)
     }
 
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    /// Writes a consistent snapshot of the live database to `path` (which must not exist).
+    pub async fn backup_to(&self, path: &Path) -> Result<(), sqlx::Error> {
+        sqlx::query("VACUUM INTO ?1")
+            .bind(path.to_string_lossy().to_string())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Snapshots the database into the rotating backups directory.
+    pub async fn automatic_backup(&self, reason: &str) -> Result<PathBuf, sqlx::Error> {
+        let backups_dir = codegit_data_dir().join("backups");
+        std::fs::create_dir_all(&backups_dir)?;
+
+        let path = backups_dir.join(format!(
+            "database-{}-{}.sqlite",
+            Utc::now().format("%Y%m%d-%H%M%S"),
+            reason
+        ));
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+        }
+        self.backup_to(&path).await?;
+
+        let mut backups: Vec<PathBuf> = std::fs::read_dir(&backups_dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("");
+                name.starts_with("database-") && name.ends_with(".sqlite")
+            })
+            .collect();
+        backups.sort();
+        while backups.len() > AUTOMATIC_BACKUP_COUNT {
+            let _ = std::fs::remove_file(backups.remove(0));
+        }
+
+        Ok(path)
+    }
+
     async fn initialize(&self) -> Result<(), sqlx::Error> {
-        // Create tables
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS users (
-                id INTEGER PRIMARY KEY AUTOINCREMENT,
-                name TEXT NOT NULL,
-                email TEXT NOT NULL UNIQUE,
-                workspace_name TEXT,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+        self.run_schema_migrations().await?;
+        self.migrate_settings().await?;
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS organizations (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                color TEXT NOT NULL,
-                description TEXT,
-                avatar TEXT,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+        Ok(())
+    }
 
+    async fn ensure_schema_version_table(&self) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            CREATE TABLE IF NOT EXISTS repositories (
-                id TEXT PRIMARY KEY,
-                name TEXT NOT NULL,
-                path TEXT NOT NULL UNIQUE,
-                organization_id TEXT,
-                remote_url TEXT,
-                current_branch TEXT NOT NULL,
-                last_commit TEXT NOT NULL,
-                is_dirty BOOLEAN NOT NULL DEFAULT FALSE,
-                is_favorite BOOLEAN NOT NULL DEFAULT FALSE,
-                tags TEXT NOT NULL DEFAULT '[]',
-                last_accessed DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                FOREIGN KEY (organization_id) REFERENCES organizations(id) ON DELETE SET NULL
+            CREATE TABLE IF NOT EXISTS schema_version (
+                version INTEGER PRIMARY KEY,
+                description TEXT NOT NULL,
+                applied_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
             )
             "#,
         )
         .execute(&self.pool)
         .await?;
+        Ok(())
+    }
 
-        sqlx::query(
-            r#"
-            CREATE TABLE IF NOT EXISTS app_settings (
-                id INTEGER PRIMARY KEY CHECK (id = 1),
-                is_first_time BOOLEAN NOT NULL DEFAULT TRUE,
-                theme_mode TEXT NOT NULL DEFAULT 'dark',
-                font_size INTEGER NOT NULL DEFAULT 14,
-                font_family TEXT NOT NULL DEFAULT 'Inter',
-                language TEXT NOT NULL DEFAULT 'en',
-                settings_json TEXT NOT NULL DEFAULT '{}',
-                updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-            )
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+    /// Highest applied schema migration, 0 for a new database.
+    pub async fn schema_version(&self) -> Result<i64, sqlx::Error> {
+        self.ensure_schema_version_table().await?;
 
-        // Insert default settings if not exists
-        sqlx::query(
-            r#"
-            INSERT OR IGNORE INTO app_settings (id, is_first_time, settings_json, updated_at)
-            VALUES (1, TRUE, '{}', CURRENT_TIMESTAMP)
-            "#,
-        )
-        .execute(&self.pool)
-        .await?;
+        let version: Option<i64> = sqlx::query_scalar("SELECT MAX(version) FROM schema_version")
+            .fetch_one(&self.pool)
+            .await?;
+        Ok(version.unwrap_or(0))
+    }
+
+    async fn has_pending_migrations(&self) -> Result<bool, sqlx::Error> {
+        let version = self.schema_version().await?;
+        Ok(SCHEMA_MIGRATIONS.iter().any(|migration| migration.version > version))
+    }
+
+    /// Applies every migration newer than the recorded schema version, each in its own transaction.
+    async fn run_schema_migrations(&self) -> Result<(), sqlx::Error> {
+        let current_version = self.schema_version().await?;
+
+        for migration in SCHEMA_MIGRATIONS.iter().filter(|m| m.version > current_version) {
+            let mut tx = self.pool.begin().await?;
+
+            for step in migration.steps {
+                match step {
+                    MigrationStep::Sql(sql) => {
+                        sqlx::query(sql).execute(&mut *tx).await?;
+                    }
+                    MigrationStep::AddColumn(table, column, definition) => {
+                        let exists: i64 = sqlx::query_scalar(
+                            "SELECT COUNT(*) FROM pragma_table_info(?1) WHERE name = ?2"
+                        )
+                        .bind(table)
+                        .bind(column)
+                        .fetch_one(&mut *tx)
+                        .await?;
+
+                        if exists == 0 {
+                            sqlx::query(&format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition))
+                                .execute(&mut *tx)
+                                .await?;
+                        }
+                    }
+                }
+            }
+
+            sqlx::query("INSERT INTO schema_version (version, description) VALUES (?1, ?2)")
+                .bind(migration.version)
+                .bind(migration.description)
+                .execute(&mut *tx)
+                .await?;
+
+            tx.commit().await?;
+            println!("🗄️  Applied schema migration {}: {}", migration.version, migration.description);
+        }
+
+        Ok(())
+    }
+
+    /// Rewrites the stored settings_json in the current layout version.
+    async fn migrate_settings(&self) -> Result<(), sqlx::Error> {
+        let row = sqlx::query("SELECT settings_json FROM app_settings WHERE id = 1")
+            .fetch_one(&self.pool)
+            .await?;
+
+        let settings_str: String = row.get("settings_json");
+        let (settings_json, changed) = migrate_settings_json(parse_settings_json(&settings_str));
+
+        if changed {
+            println!("🔄 Migrated settings to schema version {}", SETTINGS_SCHEMA_VERSION);
+            sqlx::query("UPDATE app_settings SET settings_json = ?1 WHERE id = 1")
+                .bind(settings_json.to_string())
+                .execute(&self.pool)
+                .await?;
+        }
 
         Ok(())
     }
 
     // User operations
+    /// Creates or updates a user profile (matched by email) and makes it the active one.
     pub async fn save_user(&self, user: &UserInfo) -> Result<i64, sqlx::Error> {
         let now = Utc::now();
-        let result = sqlx::query(
+        sqlx::query(
             r#"
-            INSERT OR REPLACE INTO users (name, email, workspace_name, created_at, updated_at)
-            VALUES (?1, ?2, ?3, COALESCE((SELECT created_at FROM users WHERE email = ?2), ?4), ?4)
+            INSERT INTO users (name, email, workspace_name, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?4)
+            ON CONFLICT(email) DO UPDATE SET
+                name = excluded.name,
+                workspace_name = excluded.workspace_name,
+                updated_at = excluded.updated_at
             "#,
         )
         .bind(&user.name)
@@ -200,24 +993,78 @@ impl Database {
         .execute(&self.pool)
         .await?;
 
-        Ok(result.last_insert_rowid())
+        let id: i64 = sqlx::query("SELECT id FROM users WHERE email = ?1")
+            .bind(&user.email)
+            .fetch_one(&self.pool)
+            .await?
+            .get("id");
+
+        self.set_active_user(id).await?;
+
+        Ok(id)
     }
 
+    /// The active user profile; before profiles could be switched this was the latest row.
     pub async fn get_user(&self) -> Result<Option<UserInfo>, sqlx::Error> {
         let row = sqlx::query(
-            "SELECT id, name, email, workspace_name, created_at, updated_at FROM users ORDER BY id DESC LIMIT 1"
+            "SELECT id, name, email, workspace_name, is_active, created_at, updated_at FROM users ORDER BY is_active DESC, id DESC LIMIT 1"
         )
         .fetch_optional(&self.pool)
         .await?;
 
-        Ok(row.map(|r| UserInfo {
+        Ok(row.map(|r| Self::user_from_row(&r)))
+    }
+
+    pub async fn list_users(&self) -> Result<Vec<UserInfo>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, email, workspace_name, is_active, created_at, updated_at FROM users ORDER BY name ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| Self::user_from_row(&r)).collect())
+    }
+
+    pub async fn set_active_user(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE users SET is_active = (id = ?1)")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes a user profile; if it was active, the most recently created profile takes over.
+    pub async fn delete_user(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE organizations SET user_id = NULL WHERE user_id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM users WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query(
+            r#"
+            UPDATE users SET is_active = TRUE
+            WHERE id = (SELECT id FROM users ORDER BY id DESC LIMIT 1)
+              AND NOT EXISTS (SELECT 1 FROM users WHERE is_active = TRUE)
+            "#
+        )
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    fn user_from_row(r: &sqlx::sqlite::SqliteRow) -> UserInfo {
+        UserInfo {
             id: Some(r.get("id")),
             name: r.get("name"),
             email: r.get("email"),
             workspace_name: r.get("workspace_name"),
+            is_active: r.get("is_active"),
             created_at: r.get("created_at"),
             updated_at: r.get("updated_at"),
-        }))
+        }
     }
 
     // Organization operations
@@ -225,8 +1072,14 @@ impl Database {
         sqlx::query(
             r#"
             INSERT OR REPLACE INTO organizations 
-            (id, name, color, description, avatar, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, COALESCE((SELECT created_at FROM organizations WHERE id = ?1), ?6), ?6)
+            (id, name, color, description, avatar, user_id, workspace_id, sign_off, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5,
+                    COALESCE(?7, (SELECT user_id FROM organizations WHERE id = ?1),
+                             (SELECT id FROM users WHERE is_active = TRUE LIMIT 1)),
+                    COALESCE(?8, (SELECT workspace_id FROM organizations WHERE id = ?1),
+                             (SELECT id FROM workspaces WHERE is_active = TRUE LIMIT 1)),
+                    ?9,
+                    COALESCE((SELECT created_at FROM organizations WHERE id = ?1), ?6), ?6)
             "#,
         )
         .bind(&org.id)
@@ -235,6 +1088,9 @@ impl Database {
         .bind(&org.description)
         .bind(&org.avatar)
         .bind(&org.updated_at)
+        .bind(org.user_id)
+        .bind(&org.workspace_id)
+        .bind(org.sign_off)
         .execute(&self.pool)
         .await?;
 
@@ -243,7 +1099,12 @@ impl Database {
 
     pub async fn get_organizations(&self) -> Result<Vec<Organization>, sqlx::Error> {
         let rows = sqlx::query(
-            "SELECT id, name, color, description, avatar, created_at, updated_at FROM organizations ORDER BY created_at ASC"
+            r#"
+            SELECT id, name, color, description, avatar, user_id, workspace_id, sign_off, created_at, updated_at FROM organizations
+            WHERE (user_id IS NULL OR user_id = (SELECT id FROM users WHERE is_active = TRUE LIMIT 1))
+              AND (workspace_id IS NULL OR workspace_id = (SELECT id FROM workspaces WHERE is_active = TRUE LIMIT 1))
+            ORDER BY created_at ASC
+            "#
         )
         .fetch_all(&self.pool)
         .await?;
@@ -256,6 +1117,9 @@ impl Database {
                 color: r.get("color"),
                 description: r.get("description"),
                 avatar: r.get("avatar"),
+                user_id: r.get("user_id"),
+                workspace_id: r.get("workspace_id"),
+                sign_off: r.get("sign_off"),
                 created_at: r.get("created_at"),
                 updated_at: r.get("updated_at"),
             })
@@ -272,73 +1136,338 @@ impl Database {
         Ok(())
     }
 
-    // Repository operations
-    pub async fn save_repository(&self, repo: &Repository) -> Result<(), sqlx::Error> {
-        let tags_json = serde_json::to_string(&repo.tags).unwrap_or_else(|_| "[]".to_string());
-        
+    // Workspace operations
+    pub async fn save_workspace(&self, workspace: &Workspace) -> Result<(), sqlx::Error> {
         sqlx::query(
             r#"
-            INSERT OR REPLACE INTO repositories 
-            (id, name, path, organization_id, remote_url, current_branch, last_commit, 
-             is_dirty, is_favorite, tags, last_accessed, created_at, updated_at)
-            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, 
-                    COALESCE((SELECT created_at FROM repositories WHERE id = ?1), ?12), ?12)
+            INSERT OR REPLACE INTO workspaces (id, name, color, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3,
+                    COALESCE((SELECT is_active FROM workspaces WHERE id = ?1), FALSE),
+                    COALESCE((SELECT created_at FROM workspaces WHERE id = ?1), ?4), ?4)
             "#,
         )
-        .bind(&repo.id)
-        .bind(&repo.name)
-        .bind(&repo.path)
-        .bind(&repo.organization_id)
-        .bind(&repo.remote_url)
-        .bind(&repo.current_branch)
-        .bind(&repo.last_commit)
-        .bind(repo.is_dirty)
-        .bind(repo.is_favorite)
-        .bind(tags_json)
-        .bind(&repo.last_accessed)
-        .bind(&repo.updated_at)
+        .bind(&workspace.id)
+        .bind(&workspace.name)
+        .bind(&workspace.color)
+        .bind(workspace.updated_at)
         .execute(&self.pool)
         .await?;
 
+        if workspace.is_active {
+            self.set_active_workspace(&workspace.id).await?;
+        }
         Ok(())
     }
 
-    pub async fn get_repositories(&self) -> Result<Vec<Repository>, sqlx::Error> {
+    pub async fn get_workspaces(&self) -> Result<Vec<Workspace>, sqlx::Error> {
         let rows = sqlx::query(
-            r#"
-            SELECT id, name, path, organization_id, remote_url, current_branch, last_commit,
-                   is_dirty, is_favorite, tags, last_accessed, created_at, updated_at 
-            FROM repositories ORDER BY last_accessed DESC
-            "#
+            "SELECT id, name, color, is_active, created_at, updated_at FROM workspaces ORDER BY created_at ASC"
         )
         .fetch_all(&self.pool)
         .await?;
 
-        let repositories = rows
+        let workspaces = rows
             .into_iter()
-            .map(|r| {
-                let tags_str: String = r.get("tags");
-                let tags = serde_json::from_str(&tags_str).unwrap_or_else(|_| serde_json::json!([]));
-                
-                Repository {
-                    id: r.get("id"),
-                    name: r.get("name"),
-                    path: r.get("path"),
-                    organization_id: r.get("organization_id"),
-                    remote_url: r.get("remote_url"),
-                    current_branch: r.get("current_branch"),
-                    last_commit: r.get("last_commit"),
-                    is_dirty: r.get("is_dirty"),
-                    is_favorite: r.get("is_favorite"),
-                    tags,
-                    last_accessed: r.get("last_accessed"),
-                    created_at: r.get("created_at"),
-                    updated_at: r.get("updated_at"),
-                }
+            .map(|r| Workspace {
+                id: r.get("id"),
+                name: r.get("name"),
+                color: r.get("color"),
+                is_active: r.get("is_active"),
+                created_at: r.get("created_at"),
+                updated_at: r.get("updated_at"),
             })
             .collect();
 
-        Ok(repositories)
+        Ok(workspaces)
+    }
+
+    pub async fn set_active_workspace(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE workspaces SET is_active = (id = ?1)")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Deletes a workspace together with its organization and repository records
+    /// (the repositories on disk are untouched).
+    pub async fn delete_workspace(&self, id: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for statement in [
+            "DELETE FROM repositories WHERE workspace_id = ?1",
+            "DELETE FROM organizations WHERE workspace_id = ?1",
+            "DELETE FROM workspaces WHERE id = ?1",
+        ] {
+            sqlx::query(statement).bind(id).execute(&mut *tx).await?;
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE workspaces SET is_active = TRUE
+            WHERE id = (SELECT id FROM workspaces ORDER BY created_at ASC LIMIT 1)
+              AND NOT EXISTS (SELECT 1 FROM workspaces WHERE is_active = TRUE)
+            "#
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Repository operations
+    pub async fn save_repository(&self, repo: &Repository) -> Result<(), sqlx::Error> {
+        let tags_json = serde_json::to_string(&repo.tags).unwrap_or_else(|_| "[]".to_string());
+        
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO repositories 
+            (id, name, path, organization_id, remote_url, current_branch, last_commit, 
+             is_dirty, is_favorite, is_pinned, position, tags, workspace_id, last_accessed, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9,
+                    COALESCE((SELECT is_pinned FROM repositories WHERE id = ?1), FALSE),
+                    (SELECT position FROM repositories WHERE id = ?1),
+                    ?10,
+                    COALESCE(?13, (SELECT workspace_id FROM repositories WHERE id = ?1),
+                             (SELECT workspace_id FROM organizations WHERE id = ?4),
+                             (SELECT id FROM workspaces WHERE is_active = TRUE LIMIT 1)),
+                    ?11, COALESCE((SELECT created_at FROM repositories WHERE id = ?1), ?12), ?12)
+            "#,
+        )
+        .bind(&repo.id)
+        .bind(&repo.name)
+        .bind(&repo.path)
+        .bind(&repo.organization_id)
+        .bind(&repo.remote_url)
+        .bind(&repo.current_branch)
+        .bind(&repo.last_commit)
+        .bind(repo.is_dirty)
+        .bind(repo.is_favorite)
+        .bind(tags_json)
+        .bind(&repo.last_accessed)
+        .bind(&repo.updated_at)
+        .bind(&repo.workspace_id)
+        .execute(&self.pool)
+        .await?;
+
+        // repository_tags is the queryable copy of the tags column
+        sqlx::query("DELETE FROM repository_tags WHERE repository_id = ?1")
+            .bind(&repo.id)
+            .execute(&self.pool)
+            .await?;
+        for tag in repo.tags.as_array().into_iter().flatten().filter_map(|tag| tag.as_str()) {
+            sqlx::query("INSERT OR IGNORE INTO repository_tags (repository_id, tag) VALUES (?1, ?2)")
+                .bind(&repo.id)
+                .bind(tag)
+                .execute(&self.pool)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_repositories(&self) -> Result<Vec<Repository>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM repositories
+            WHERE {}
+            ORDER BY {}
+            "#,
+            REPOSITORY_COLUMNS, VISIBLE_REPOSITORIES, REPOSITORY_ORDER
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::repository_from_row).collect())
+    }
+
+    /// Path of every registered repository, regardless of workspace and user, for
+    /// checking whether a path is already registered.
+    pub async fn get_all_repository_paths(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT path FROM repositories")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn get_repositories_by_tag(&self, tag: &str) -> Result<Vec<Repository>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {} FROM repositories
+            WHERE {}
+              AND id IN (SELECT repository_id FROM repository_tags WHERE tag = ?1)
+            ORDER BY {}
+            "#,
+            REPOSITORY_COLUMNS, VISIBLE_REPOSITORIES, REPOSITORY_ORDER
+        ))
+        .bind(tag)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::repository_from_row).collect())
+    }
+
+    pub async fn get_repositories_by_organization(&self, organization_id: &str) -> Result<Vec<Repository>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            "SELECT {} FROM repositories WHERE organization_id = ?1 ORDER BY {}",
+            REPOSITORY_COLUMNS, REPOSITORY_ORDER
+        ))
+        .bind(organization_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(Self::repository_from_row).collect())
+    }
+
+    fn repository_from_row(r: &sqlx::sqlite::SqliteRow) -> Repository {
+        let tags_str: String = r.get("tags");
+        let tags = serde_json::from_str(&tags_str).unwrap_or_else(|_| serde_json::json!([]));
+
+        Repository {
+            id: r.get("id"),
+            name: r.get("name"),
+            path: r.get("path"),
+            organization_id: r.get("organization_id"),
+            remote_url: r.get("remote_url"),
+            current_branch: r.get("current_branch"),
+            last_commit: r.get("last_commit"),
+            is_dirty: r.get("is_dirty"),
+            is_favorite: r.get("is_favorite"),
+            is_pinned: r.get("is_pinned"),
+            position: r.get("position"),
+            tags,
+            workspace_id: r.get("workspace_id"),
+            last_accessed: r.get("last_accessed"),
+            created_at: r.get("created_at"),
+            updated_at: r.get("updated_at"),
+        }
+    }
+
+    /// Flips is_favorite and returns the new value, None when the repository is unknown.
+    pub async fn toggle_repository_favorite(&self, id: &str) -> Result<Option<bool>, sqlx::Error> {
+        sqlx::query("UPDATE repositories SET is_favorite = NOT is_favorite, updated_at = ?2 WHERE id = ?1")
+            .bind(id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query_scalar("SELECT is_favorite FROM repositories WHERE id = ?1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn set_repository_pinned(&self, id: &str, pinned: bool) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("UPDATE repositories SET is_pinned = ?2, updated_at = ?3 WHERE id = ?1")
+            .bind(id)
+            .bind(pinned)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Stores `ids` as the manual order; repositories not listed keep no position.
+    pub async fn reorder_repositories(&self, ids: &[String]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(&format!("UPDATE repositories SET position = NULL WHERE {}", VISIBLE_REPOSITORIES))
+            .execute(&mut *tx)
+            .await?;
+        for (position, id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE repositories SET position = ?2 WHERE id = ?1")
+                .bind(id)
+                .bind(position as i64)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Repository tag operations
+    pub async fn add_repository_tag(&self, repository_id: &str, tag: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT OR IGNORE INTO repository_tags (repository_id, tag) VALUES (?1, ?2)")
+            .bind(repository_id)
+            .bind(tag)
+            .execute(&self.pool)
+            .await?;
+        self.sync_repository_tags_column(repository_id).await
+    }
+
+    pub async fn remove_repository_tag(&self, repository_id: &str, tag: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM repository_tags WHERE repository_id = ?1 AND tag = ?2")
+            .bind(repository_id)
+            .bind(tag)
+            .execute(&self.pool)
+            .await?;
+        self.sync_repository_tags_column(repository_id).await
+    }
+
+    /// Tags in use by visible repositories (or by one repository) with their usage counts.
+    pub async fn list_repository_tags(&self, repository_id: Option<&str>) -> Result<Vec<RepositoryTag>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT tag, COUNT(*) AS repository_count FROM repository_tags
+            WHERE repository_id IN (SELECT id FROM repositories WHERE {})
+              AND (?1 IS NULL OR repository_id = ?1)
+            GROUP BY tag
+            ORDER BY tag ASC
+            "#,
+            VISIBLE_REPOSITORIES
+        ))
+        .bind(repository_id)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| RepositoryTag {
+                tag: r.get("tag"),
+                repository_count: r.get("repository_count"),
+            })
+            .collect())
+    }
+
+    /// Every registered repository, across user profiles and workspaces, by name.
+    pub async fn get_repository_inventory(&self) -> Result<Vec<InventoryEntry>, sqlx::Error> {
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}, (SELECT name FROM organizations WHERE id = repositories.organization_id) AS organization_name
+            FROM repositories
+            ORDER BY name COLLATE NOCASE ASC, path ASC
+            "#,
+            REPOSITORY_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|r| InventoryEntry {
+                repository: Self::repository_from_row(r),
+                organization: r.get("organization_name"),
+            })
+            .collect())
+    }
+
+    /// Rewrites the legacy JSON tags column from repository_tags.
+    async fn sync_repository_tags_column(&self, repository_id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            UPDATE repositories SET
+                tags = COALESCE((SELECT json_group_array(tag) FROM
+                                 (SELECT tag FROM repository_tags WHERE repository_id = ?1 ORDER BY tag)), '[]'),
+                updated_at = ?2
+            WHERE id = ?1
+            "#
+        )
+        .bind(repository_id)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
     }
 
     pub async fn delete_repository(&self, id: &str) -> Result<(), sqlx::Error> {
@@ -352,13 +1481,13 @@ impl Database {
     // Settings operations
     pub async fn get_settings(&self) -> Result<AppSettings, sqlx::Error> {
         let row = sqlx::query(
-            "SELECT id, is_first_time, theme_mode, font_size, font_family, language, settings_json, updated_at FROM app_settings WHERE id = 1"
+            "SELECT id, is_first_time, theme_mode, font_size, font_family, language, settings_json, active_theme_id, updated_at FROM app_settings WHERE id = 1"
         )
         .fetch_one(&self.pool)
         .await?;
 
         let settings_str: String = row.get("settings_json");
-        let settings_json = serde_json::from_str(&settings_str).unwrap_or_else(|_| serde_json::json!({}));
+        let (settings_json, _) = migrate_settings_json(parse_settings_json(&settings_str));
 
         Ok(AppSettings {
             id: Some(row.get("id")),
@@ -368,13 +1497,15 @@ impl Database {
             font_family: row.get("font_family"),
             language: row.get("language"),
             settings_json,
+            active_theme_id: row.get("active_theme_id"),
             updated_at: row.get("updated_at"),
         })
     }
 
     pub async fn update_settings(&self, settings: &AppSettings) -> Result<(), sqlx::Error> {
-        let settings_json_str = serde_json::to_string(&settings.settings_json)
-            .unwrap_or_else(|_| "{}".to_string());
+        // Payloads from older frontends are upgraded before they are stored
+        let (settings_json, _) = migrate_settings_json(settings.settings_json.clone());
+        let settings_json_str = settings_json.to_string();
 
         sqlx::query(
             r#"
@@ -402,6 +1533,15 @@ impl Database {
         Ok(())
     }
 
+    pub async fn set_active_theme(&self, theme_id: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE app_settings SET active_theme_id = ?1, updated_at = ?2 WHERE id = 1")
+            .bind(theme_id)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn complete_onboarding(&self) -> Result<(), sqlx::Error> {
         sqlx::query(
             "UPDATE app_settings SET is_first_time = FALSE, updated_at = CURRENT_TIMESTAMP WHERE id = 1"
@@ -410,4 +1550,854 @@ impl Database {
         .await?;
         Ok(())
     }
+
+    // Operation journal
+    pub async fn record_operation(&self, entry: &OperationEntry) -> Result<i64, sqlx::Error> {
+        let ref_changes_json = serde_json::to_string(&entry.ref_changes).unwrap_or_else(|_| "[]".to_string());
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO operation_journal (repo_path, operation, description, ref_changes, undo_mode, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+        )
+        .bind(&entry.repo_path)
+        .bind(&entry.operation)
+        .bind(&entry.description)
+        .bind(ref_changes_json)
+        .bind(&entry.undo_mode)
+        .bind(entry.created_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn get_last_operation(&self, repo_path: &str) -> Result<Option<OperationEntry>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, repo_path, operation, description, ref_changes, undo_mode, created_at, undone_at
+            FROM operation_journal WHERE repo_path = ?1 AND undone_at IS NULL
+            ORDER BY id DESC LIMIT 1
+            "#
+        )
+        .bind(repo_path)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            let ref_changes_str: String = r.get("ref_changes");
+
+            OperationEntry {
+                id: Some(r.get("id")),
+                repo_path: r.get("repo_path"),
+                operation: r.get("operation"),
+                description: r.get("description"),
+                ref_changes: serde_json::from_str(&ref_changes_str).unwrap_or_default(),
+                undo_mode: r.get("undo_mode"),
+                created_at: r.get("created_at"),
+                undone_at: r.get("undone_at"),
+            }
+        }))
+    }
+
+    pub async fn mark_operation_undone(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE operation_journal SET undone_at = ?1 WHERE id = ?2")
+            .bind(Utc::now())
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Repository size analysis cache
+    pub async fn get_size_report(&self, repo_path: &str) -> Result<Option<(String, serde_json::Value)>, sqlx::Error> {
+        let row = sqlx::query("SELECT fingerprint, report_json FROM repository_size_reports WHERE repo_path = ?1")
+            .bind(repo_path)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.and_then(|r| {
+            let report_str: String = r.get("report_json");
+            serde_json::from_str(&report_str)
+                .ok()
+                .map(|report| (r.get("fingerprint"), report))
+        }))
+    }
+
+    pub async fn save_size_report(&self, repo_path: &str, fingerprint: &str, report: &serde_json::Value) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO repository_size_reports (repo_path, fingerprint, report_json, created_at)
+            VALUES (?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(repo_path)
+        .bind(fingerprint)
+        .bind(report.to_string())
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Identity profile operations
+    pub async fn save_identity_profile(&self, profile: &IdentityProfile) -> Result<(), sqlx::Error> {
+        // An upsert, not INSERT OR REPLACE: replacing deletes the row, and the foreign key
+        // cascade would delete every assignment of the profile with it
+        sqlx::query(
+            r#"
+            INSERT INTO identity_profiles
+            (id, name, author_name, author_email, signing_key, signing_format, sign_by_default, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?8)
+            ON CONFLICT(id) DO UPDATE SET
+                name = excluded.name,
+                author_name = excluded.author_name,
+                author_email = excluded.author_email,
+                signing_key = excluded.signing_key,
+                signing_format = excluded.signing_format,
+                sign_by_default = excluded.sign_by_default,
+                updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&profile.id)
+        .bind(&profile.name)
+        .bind(&profile.author_name)
+        .bind(&profile.author_email)
+        .bind(&profile.signing_key)
+        .bind(&profile.signing_format)
+        .bind(profile.sign_by_default)
+        .bind(profile.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_identity_profiles(&self) -> Result<Vec<IdentityProfile>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, author_name, author_email, signing_key, signing_format, sign_by_default, created_at, updated_at FROM identity_profiles ORDER BY name ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| Self::identity_profile_from_row(&r)).collect())
+    }
+
+    pub async fn delete_identity_profile(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM identity_assignments WHERE profile_id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM identity_profiles WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Assigns a profile to an organization or repository; `None` removes the assignment.
+    pub async fn assign_identity_profile(&self, target_type: &str, target_id: &str, profile_id: Option<&str>) -> Result<(), sqlx::Error> {
+        match profile_id {
+            Some(profile_id) => {
+                sqlx::query(
+                    "INSERT OR REPLACE INTO identity_assignments (target_type, target_id, profile_id) VALUES (?1, ?2, ?3)"
+                )
+                .bind(target_type)
+                .bind(target_id)
+                .bind(profile_id)
+                .execute(&self.pool)
+                .await?;
+            }
+            None => {
+                sqlx::query("DELETE FROM identity_assignments WHERE target_type = ?1 AND target_id = ?2")
+                    .bind(target_type)
+                    .bind(target_id)
+                    .execute(&self.pool)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// The profile for a repository: its own assignment first, then its organization's.
+    pub async fn resolve_identity_profile(&self, repo_path: &str) -> Result<Option<IdentityProfile>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT p.id, p.name, p.author_name, p.author_email, p.signing_key, p.signing_format, p.sign_by_default,
+                   p.created_at, p.updated_at
+            FROM identity_profiles p
+            JOIN identity_assignments a ON a.profile_id = p.id
+            WHERE (a.target_type = 'repository' AND a.target_id = ?1)
+               OR (a.target_type = 'organization' AND a.target_id =
+                   (SELECT organization_id FROM repositories WHERE path = ?1 LIMIT 1))
+            ORDER BY CASE a.target_type WHEN 'repository' THEN 0 ELSE 1 END
+            LIMIT 1
+            "#
+        )
+        .bind(repo_path)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| Self::identity_profile_from_row(&r)))
+    }
+
+    /// Whether commits in the repository get a Signed-off-by trailer: its own setting, else
+    /// its organization's.
+    pub async fn resolve_sign_off(&self, repo_path: &str) -> Result<bool, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(
+                (SELECT sign_off FROM repo_settings WHERE repo_path = ?1),
+                (SELECT o.sign_off FROM organizations o JOIN repositories r ON r.organization_id = o.id
+                 WHERE r.path = ?1 LIMIT 1),
+                FALSE
+            )
+            "#
+        )
+        .bind(repo_path)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    fn identity_profile_from_row(r: &sqlx::sqlite::SqliteRow) -> IdentityProfile {
+        IdentityProfile {
+            id: r.get("id"),
+            name: r.get("name"),
+            author_name: r.get("author_name"),
+            author_email: r.get("author_email"),
+            signing_key: r.get("signing_key"),
+            signing_format: r.get("signing_format"),
+            sign_by_default: r.get("sign_by_default"),
+            created_at: r.get("created_at"),
+            updated_at: r.get("updated_at"),
+        }
+    }
+
+    // Keybinding operations
+    /// All shortcuts: the built-in defaults with user overrides applied, plus custom actions.
+    pub async fn get_keybindings(&self) -> Result<Vec<Keybinding>, sqlx::Error> {
+        let rows = sqlx::query("SELECT action, keys FROM keybindings ORDER BY action ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let overrides: Vec<(String, String)> = rows
+            .into_iter()
+            .map(|r| (r.get("action"), r.get("keys")))
+            .collect();
+
+        let mut keybindings: Vec<Keybinding> = DEFAULT_KEYBINDINGS
+            .iter()
+            .map(|(action, keys)| {
+                let custom = overrides.iter().find(|(custom_action, _)| custom_action == action);
+                Keybinding {
+                    action: action.to_string(),
+                    keys: custom.map(|(_, keys)| keys.clone()).unwrap_or_else(|| keys.to_string()),
+                    is_default: custom.is_none(),
+                }
+            })
+            .collect();
+
+        keybindings.extend(
+            overrides
+                .into_iter()
+                .filter(|(action, _)| !DEFAULT_KEYBINDINGS.iter().any(|(default_action, _)| default_action == action))
+                .map(|(action, keys)| Keybinding { action, keys, is_default: false }),
+        );
+
+        Ok(keybindings)
+    }
+
+    pub async fn save_keybinding(&self, action: &str, keys: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT OR REPLACE INTO keybindings (action, keys, updated_at) VALUES (?1, ?2, ?3)")
+            .bind(action)
+            .bind(keys)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Drops the override for one action, or for all actions when `action` is None.
+    pub async fn reset_keybindings(&self, action: Option<&str>) -> Result<(), sqlx::Error> {
+        match action {
+            Some(action) => sqlx::query("DELETE FROM keybindings WHERE action = ?1").bind(action),
+            None => sqlx::query("DELETE FROM keybindings"),
+        }
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Panel layout operations
+    pub async fn get_panel_layouts(&self) -> Result<Vec<PanelLayout>, sqlx::Error> {
+        let rows = sqlx::query("SELECT name, layout_json FROM panel_layouts ORDER BY name ASC")
+            .fetch_all(&self.pool)
+            .await?;
+
+        let mut layouts: Vec<PanelLayout> = rows
+            .into_iter()
+            .map(|r| {
+                let layout_str: String = r.get("layout_json");
+                PanelLayout {
+                    name: r.get("name"),
+                    layout: serde_json::from_str(&layout_str).unwrap_or_else(|_| serde_json::json!({})),
+                    is_default: false,
+                }
+            })
+            .collect();
+
+        for name in DEFAULT_PANEL_LAYOUTS {
+            if !layouts.iter().any(|layout| layout.name == name) {
+                layouts.push(PanelLayout {
+                    name: name.to_string(),
+                    layout: default_panel_layout(name).unwrap_or_else(|| serde_json::json!({})),
+                    is_default: true,
+                });
+            }
+        }
+
+        Ok(layouts)
+    }
+
+    pub async fn save_panel_layout(&self, name: &str, layout: &serde_json::Value) -> Result<(), sqlx::Error> {
+        sqlx::query("INSERT OR REPLACE INTO panel_layouts (name, layout_json, updated_at) VALUES (?1, ?2, ?3)")
+            .bind(name)
+            .bind(layout.to_string())
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Restores the default layout for one panel, or for all panels when `name` is None.
+    pub async fn reset_panel_layouts(&self, name: Option<&str>) -> Result<(), sqlx::Error> {
+        match name {
+            Some(name) => sqlx::query("DELETE FROM panel_layouts WHERE name = ?1").bind(name),
+            None => sqlx::query("DELETE FROM panel_layouts"),
+        }
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    // Repository settings operations
+    pub async fn get_repo_settings(&self, repo_path: &str) -> Result<Option<RepoSettings>, sqlx::Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT repo_path, default_remote, push_behavior, pull_strategy, auto_fetch_interval, diff_whitespace, issue_patterns, branching_model, sign_off, updated_at
+            FROM repo_settings WHERE repo_path = ?1
+            "#
+        )
+        .bind(repo_path)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            let patterns_str: String = r.get("issue_patterns");
+            let model_str: String = r.get("branching_model");
+
+            RepoSettings {
+                repo_path: r.get("repo_path"),
+                default_remote: r.get("default_remote"),
+                push_behavior: r.get("push_behavior"),
+                pull_strategy: r.get("pull_strategy"),
+                auto_fetch_interval: r.get("auto_fetch_interval"),
+                diff_whitespace: r.get("diff_whitespace"),
+                issue_patterns: serde_json::from_str(&patterns_str).unwrap_or_default(),
+                branching_model: serde_json::from_str(&model_str).unwrap_or_default(),
+                sign_off: r.get("sign_off"),
+                updated_at: r.get("updated_at"),
+            }
+        }))
+    }
+
+    /// Repositories with auto-fetch enabled, with their interval in minutes.
+    pub async fn get_auto_fetch_intervals(&self) -> Result<Vec<(String, i64)>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT repo_path, auto_fetch_interval FROM repo_settings WHERE auto_fetch_interval IS NOT NULL"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(|r| (r.get("repo_path"), r.get("auto_fetch_interval"))).collect())
+    }
+
+    pub async fn save_repo_settings(&self, settings: &RepoSettings) -> Result<(), sqlx::Error> {
+        let patterns_json = serde_json::to_string(&settings.issue_patterns).unwrap_or_else(|_| "[]".to_string());
+        let model_json = serde_json::to_string(&settings.branching_model).unwrap_or_else(|_| "{}".to_string());
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO repo_settings
+            (repo_path, default_remote, push_behavior, pull_strategy, auto_fetch_interval, diff_whitespace, issue_patterns, branching_model, sign_off, updated_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)
+            "#,
+        )
+        .bind(&settings.repo_path)
+        .bind(&settings.default_remote)
+        .bind(&settings.push_behavior)
+        .bind(&settings.pull_strategy)
+        .bind(settings.auto_fetch_interval)
+        .bind(&settings.diff_whitespace)
+        .bind(patterns_json)
+        .bind(model_json)
+        .bind(settings.sign_off)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Path scope operations
+    pub async fn get_repo_scopes(&self, repo_path: &str) -> Result<Vec<RepoScope>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, repo_path, name, pathspecs, is_active, created_at, updated_at
+            FROM repo_scopes WHERE repo_path = ?1 ORDER BY name ASC
+            "#
+        )
+        .bind(repo_path)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let scopes = rows
+            .into_iter()
+            .map(|r| {
+                let pathspecs_str: String = r.get("pathspecs");
+
+                RepoScope {
+                    id: r.get("id"),
+                    repo_path: r.get("repo_path"),
+                    name: r.get("name"),
+                    pathspecs: serde_json::from_str(&pathspecs_str).unwrap_or_default(),
+                    is_active: r.get("is_active"),
+                    created_at: r.get("created_at"),
+                    updated_at: r.get("updated_at"),
+                }
+            })
+            .collect();
+
+        Ok(scopes)
+    }
+
+    /// Saves a scope's name and pathspecs; whether it is active is only changed by
+    /// set_active_repo_scope.
+    pub async fn save_repo_scope(&self, scope: &RepoScope) -> Result<(), sqlx::Error> {
+        let pathspecs_json = serde_json::to_string(&scope.pathspecs).unwrap_or_else(|_| "[]".to_string());
+
+        sqlx::query(
+            r#"
+            INSERT INTO repo_scopes (id, repo_path, name, pathspecs, is_active, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, 0, ?5, ?5)
+            ON CONFLICT(id) DO UPDATE SET name = excluded.name, pathspecs = excluded.pathspecs, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&scope.id)
+        .bind(&scope.repo_path)
+        .bind(&scope.name)
+        .bind(pathspecs_json)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_repo_scope(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM repo_scopes WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Makes `scope_id` the repository's only active scope, or clears it when None.
+    pub async fn set_active_repo_scope(&self, repo_path: &str, scope_id: Option<&str>) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE repo_scopes SET is_active = COALESCE(id = ?2, 0) WHERE repo_path = ?1")
+            .bind(repo_path)
+            .bind(scope_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn get_active_repo_scope(&self, repo_path: &str) -> Result<Option<RepoScope>, sqlx::Error> {
+        Ok(self.get_repo_scopes(repo_path)
+            .await?
+            .into_iter()
+            .find(|scope| scope.is_active))
+    }
+
+    // Theme operations
+    pub async fn save_theme(&self, theme: &CustomTheme) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO themes (id, name, base_mode, tokens, created_at, updated_at)
+            VALUES (?1, ?2, ?3, ?4, COALESCE((SELECT created_at FROM themes WHERE id = ?1), ?5), ?5)
+            "#,
+        )
+        .bind(&theme.id)
+        .bind(&theme.name)
+        .bind(&theme.base_mode)
+        .bind(theme.tokens.to_string())
+        .bind(theme.updated_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    pub async fn get_themes(&self) -> Result<Vec<CustomTheme>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, name, base_mode, tokens, created_at, updated_at FROM themes ORDER BY name ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let themes = rows
+            .into_iter()
+            .map(|r| {
+                let tokens_str: String = r.get("tokens");
+
+                CustomTheme {
+                    id: r.get("id"),
+                    name: r.get("name"),
+                    base_mode: r.get("base_mode"),
+                    tokens: serde_json::from_str(&tokens_str).unwrap_or_else(|_| serde_json::json!({})),
+                    created_at: r.get("created_at"),
+                    updated_at: r.get("updated_at"),
+                }
+            })
+            .collect();
+
+        Ok(themes)
+    }
+
+    /// Deletes a theme, falling back to the built-in theme if it was active.
+    pub async fn delete_theme(&self, id: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE app_settings SET active_theme_id = NULL WHERE active_theme_id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        sqlx::query("DELETE FROM themes WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Discovery exclusion operations
+    pub async fn get_discovery_exclusions(&self) -> Result<Vec<DiscoveryExclusion>, sqlx::Error> {
+        let rows = sqlx::query(
+            "SELECT id, kind, pattern, created_at FROM discovery_exclusions ORDER BY kind ASC, pattern ASC"
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        let exclusions = rows
+            .into_iter()
+            .map(|r| DiscoveryExclusion {
+                id: Some(r.get("id")),
+                kind: r.get("kind"),
+                pattern: r.get("pattern"),
+                created_at: r.get("created_at"),
+            })
+            .collect();
+
+        Ok(exclusions)
+    }
+
+    pub async fn add_discovery_exclusion(&self, kind: &str, pattern: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query("INSERT OR IGNORE INTO discovery_exclusions (kind, pattern) VALUES (?1, ?2)")
+            .bind(kind)
+            .bind(pattern)
+            .execute(&self.pool)
+            .await?;
+
+        sqlx::query_scalar("SELECT id FROM discovery_exclusions WHERE kind = ?1 AND pattern = ?2")
+            .bind(kind)
+            .bind(pattern)
+            .fetch_one(&self.pool)
+            .await
+    }
+
+    pub async fn delete_discovery_exclusion(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM discovery_exclusions WHERE id = ?1")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn delete_repository_by_path(&self, path: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM repositories WHERE path = ?1")
+            .bind(path)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    // Commit cache operations
+    pub async fn get_commit_cache_tips(&self, repo_path: &str) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar("SELECT id FROM commit_cache_tips WHERE repo_path = ?1")
+            .bind(repo_path)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn get_cached_commit(&self, repo_path: &str, id: &str) -> Result<Option<CachedCommit>, sqlx::Error> {
+        let row = sqlx::query(
+            "SELECT id, parents, author, email, message, timestamp FROM commit_cache WHERE repo_path = ?1 AND id = ?2"
+        )
+        .bind(repo_path)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|r| {
+            let parents: String = r.get("parents");
+            CachedCommit {
+                id: r.get("id"),
+                parents: parents.split_whitespace().map(|parent| parent.to_string()).collect(),
+                author: r.get("author"),
+                email: r.get("email"),
+                message: r.get("message"),
+                timestamp: r.get("timestamp"),
+            }
+        }))
+    }
+
+    /// Adds newly walked commits and replaces the tips in one transaction, so the tips
+    /// never claim an ancestry that isn't stored.
+    pub async fn save_commit_cache(&self, repo_path: &str, commits: &[CachedCommit], tips: &[String]) -> Result<(), sqlx::Error> {
+        // Seven values per row; SQLite allows 999 bound values per statement
+        const ROWS_PER_INSERT: usize = 140;
+
+        let mut tx = self.pool.begin().await?;
+
+        for chunk in commits.chunks(ROWS_PER_INSERT) {
+            let mut query: QueryBuilder<Sqlite> = QueryBuilder::new(
+                "INSERT OR IGNORE INTO commit_cache (repo_path, id, parents, author, email, message, timestamp) ",
+            );
+            query.push_values(chunk, |mut row, commit| {
+                row.push_bind(repo_path)
+                    .push_bind(&commit.id)
+                    .push_bind(commit.parents.join(" "))
+                    .push_bind(&commit.author)
+                    .push_bind(&commit.email)
+                    .push_bind(&commit.message)
+                    .push_bind(commit.timestamp);
+            });
+            query.build().execute(&mut *tx).await?;
+        }
+
+        sqlx::query("DELETE FROM commit_cache_tips WHERE repo_path = ?1")
+            .bind(repo_path)
+            .execute(&mut *tx)
+            .await?;
+        for tip in tips {
+            sqlx::query("INSERT OR IGNORE INTO commit_cache_tips (repo_path, id) VALUES (?1, ?2)")
+                .bind(repo_path)
+                .bind(tip)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn clear_commit_cache(&self, repo_path: &str) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for statement in [
+            "DELETE FROM commit_cache WHERE repo_path = ?1",
+            "DELETE FROM commit_cache_tips WHERE repo_path = ?1",
+        ] {
+            sqlx::query(statement).bind(repo_path).execute(&mut *tx).await?;
+        }
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    // Session operations
+    pub async fn get_session_state(&self) -> Result<Option<SessionState>, sqlx::Error> {
+        let row = sqlx::query("SELECT repo_path, branch, open_panels, updated_at FROM session_state WHERE id = 1")
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|r| {
+            let panels_str: String = r.get("open_panels");
+            SessionState {
+                repo_path: r.get("repo_path"),
+                branch: r.get("branch"),
+                open_panels: serde_json::from_str(&panels_str).unwrap_or_default(),
+                updated_at: r.get("updated_at"),
+            }
+        }))
+    }
+
+    pub async fn save_session_state(&self, session: &SessionState) -> Result<(), sqlx::Error> {
+        let panels_json = serde_json::to_string(&session.open_panels).unwrap_or_else(|_| "[]".to_string());
+
+        sqlx::query(
+            r#"
+            INSERT OR REPLACE INTO session_state (id, repo_path, branch, open_panels, updated_at)
+            VALUES (1, ?1, ?2, ?3, ?4)
+            "#,
+        )
+        .bind(&session.repo_path)
+        .bind(&session.branch)
+        .bind(panels_json)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    // Operation audit log
+    pub async fn record_audit_entry(&self, entry: &AuditEntry) -> Result<i64, sqlx::Error> {
+        let ref_changes_json = serde_json::to_string(&entry.ref_changes).unwrap_or_else(|_| "[]".to_string());
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO operation_history (repo_path, command, ref_changes, outcome, error, started_at, finished_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)
+            "#,
+        )
+        .bind(&entry.repo_path)
+        .bind(&entry.command)
+        .bind(ref_changes_json)
+        .bind(&entry.outcome)
+        .bind(&entry.error)
+        .bind(entry.started_at)
+        .bind(entry.finished_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Newest first; every repository when `repo_path` is None.
+    pub async fn get_audit_entries(
+        &self,
+        repo_path: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<AuditEntry>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, repo_path, command, ref_changes, outcome, error, started_at, finished_at
+            FROM operation_history WHERE ?1 IS NULL OR repo_path = ?1
+            ORDER BY id DESC LIMIT ?2 OFFSET ?3
+            "#
+        )
+        .bind(repo_path)
+        .bind(limit)
+        .bind(offset)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| {
+            let ref_changes_str: String = r.get("ref_changes");
+
+            AuditEntry {
+                id: Some(r.get("id")),
+                repo_path: r.get("repo_path"),
+                command: r.get("command"),
+                ref_changes: serde_json::from_str(&ref_changes_str).unwrap_or_default(),
+                outcome: r.get("outcome"),
+                error: r.get("error"),
+                started_at: r.get("started_at"),
+                finished_at: r.get("finished_at"),
+            }
+        }).collect())
+    }
+
+    // Command timings
+    pub async fn record_command_timing(
+        &self,
+        command: &str,
+        repo_path: Option<&str>,
+        duration_ms: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO command_timings (command, repo_path, duration_ms, recorded_at) VALUES (?1, ?2, ?3, ?4)"
+        )
+        .bind(command)
+        .bind(repo_path)
+        .bind(duration_ms)
+        .bind(Utc::now())
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// Per-command totals since `since`, slowest on average first.
+    pub async fn get_command_timings(&self, since: DateTime<Utc>) -> Result<Vec<CommandTiming>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT command, COUNT(*) AS calls, AVG(duration_ms) AS avg_ms,
+                   MAX(duration_ms) AS max_ms, SUM(duration_ms) AS total_ms
+            FROM command_timings WHERE recorded_at >= ?1
+            GROUP BY command ORDER BY avg_ms DESC
+            "#
+        )
+        .bind(since)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| CommandTiming {
+            command: r.get("command"),
+            calls: r.get("calls"),
+            avg_ms: r.get("avg_ms"),
+            max_ms: r.get("max_ms"),
+            total_ms: r.get("total_ms"),
+        }).collect())
+    }
+
+    /// Repositories with the most time spent in commands since `since`.
+    pub async fn get_slowest_repositories(
+        &self,
+        since: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<RepositoryTiming>, sqlx::Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT t.repo_path, COUNT(*) AS calls, AVG(t.duration_ms) AS avg_ms,
+                   MAX(t.duration_ms) AS max_ms, SUM(t.duration_ms) AS total_ms,
+                   (SELECT s.command FROM command_timings s
+                    WHERE s.repo_path = t.repo_path AND s.recorded_at >= ?1
+                    ORDER BY s.duration_ms DESC LIMIT 1) AS slowest_command
+            FROM command_timings t
+            WHERE t.repo_path IS NOT NULL AND t.recorded_at >= ?1
+            GROUP BY t.repo_path ORDER BY total_ms DESC LIMIT ?2
+            "#
+        )
+        .bind(since)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.into_iter().map(|r| RepositoryTiming {
+            repo_path: r.get("repo_path"),
+            calls: r.get("calls"),
+            avg_ms: r.get("avg_ms"),
+            max_ms: r.get("max_ms"),
+            total_ms: r.get("total_ms"),
+            slowest_command: r.get("slowest_command"),
+        }).collect())
+    }
+
+    pub async fn clear_command_timings(&self) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM command_timings")
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
 }
\ No newline at end of file
@@ -3,14 +3,69 @@
 
 use git2::{Repository, Signature, PushOptions, RemoteCallbacks, Cred, FetchOptions};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::env;
 use std::fs;
+use std::time::{Duration, Instant};
+use tauri::Manager;
 
 mod database;
 mod commands;
 
+use commands::ahead_behind::*;
+use commands::archive::*;
+use commands::attributes::*;
+use commands::audit::*;
+use commands::avatars::*;
+use commands::batch::*;
+use commands::blame::*;
+use commands::branching::*;
+use commands::bundle::*;
+use commands::checks::*;
+use commands::clean::*;
+use commands::cli::*;
+use commands::client_import::*;
+use commands::commit_cache::*;
+use commands::commit_message::*;
 use commands::database::*;
+use commands::deep_links::*;
+use commands::discovery::*;
+use commands::encoding::*;
+use commands::error::*;
+use commands::external_tools::*;
+use commands::forks::*;
+use commands::gerrit::*;
+use commands::git_command::*;
+use commands::health::*;
+use commands::hooks::*;
+use commands::identity::*;
+use commands::ignore::*;
+use commands::inventory::*;
+use commands::issues::*;
+use commands::journal::*;
+use commands::lfs::*;
+use commands::limits::*;
+use commands::line_endings::*;
+use commands::line_staging::*;
+use commands::maintenance::*;
+use commands::operations::*;
+use commands::pipelines::*;
+use commands::profiling::*;
+use commands::provider_repositories::*;
+use commands::pull_requests::*;
+use commands::rebase::*;
+use commands::reflog::*;
+use commands::releases::*;
+use commands::scopes::*;
+use commands::secrets::*;
+use commands::send_email::*;
+use commands::session::*;
+use commands::status::*;
+use commands::tags::*;
+use commands::time_machine::*;
+use commands::watcher::*;
+use commands::web_urls::*;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct GitCommit {
@@ -19,6 +74,7 @@ struct GitCommit {
     author: String,
     email: String,
     timestamp: i64,
+    truncated: bool, // Message cut at max_commit_message_bytes; see get_commit_message
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -27,6 +83,19 @@ struct GitStatus {
     added: Vec<String>,
     deleted: Vec<String>,
     untracked: Vec<String>,
+    is_delta: bool, // Only changes since the previous since_last call
+    cleared: Vec<String>, // With is_delta: paths that no longer have changes
+    counts: GitStatusCounts, // Totals after filtering, before limit/offset
+    total_entries: usize,
+    has_more: bool,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct GitStatusCounts {
+    modified: usize,
+    added: usize,
+    deleted: usize,
+    untracked: usize,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -35,6 +104,9 @@ struct GitDiff {
     old_content: String,
     new_content: String,
     hunks: Vec<DiffHunk>,
+    is_lfs_pointer: bool,
+    lfs_size: Option<u64>,
+    truncated: bool, // A side exceeds max_diff_bytes; contents and hunks are left out
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -62,6 +134,48 @@ struct GitBranch {
     target: Option<String>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct GitReference {
+    name: String,
+    shorthand: String,
+    kind: String, // branch, remote, tag, other
+    target: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ReferencePage {
+    references: Vec<GitReference>,
+    total: usize,
+    has_more: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RepositoryFetchResult {
+    repository_id: String,
+    name: String,
+    path: String,
+    success: bool,
+    message: String,
+}
+
+/// Emitted as `organization-fetch-progress` each time a repository finishes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct OrganizationFetchProgress {
+    organization_id: String,
+    completed: usize,
+    total: usize,
+    result: RepositoryFetchResult,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrganizationFetchResult {
+    organization_id: String,
+    total: usize,
+    succeeded: usize,
+    failed: usize,
+    results: Vec<RepositoryFetchResult>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GitRemote {
     name: String,
@@ -78,6 +192,14 @@ struct GitStash {
     timestamp: i64,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct SequencerState {
+    operation: String,
+    commit_id: Option<String>,
+    message: Option<String>,
+    conflicts: Vec<String>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct MergeConflict {
     file_path: String,
@@ -96,6 +218,7 @@ struct LogEntry {
     timestamp: i64,
     parents: Vec<String>,
     refs: Vec<String>,
+    truncated: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -111,7 +234,8 @@ struct RebaseCommit {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 enum RebaseAction {
     Pick,
-    Squash,
+    Squash, // Meld into the previous commit, keeping both messages
+    Fixup, // Meld into the previous commit, keeping only its message
     Edit,
     Reword,
     Drop,
@@ -123,6 +247,24 @@ struct RebasePlan {
     onto_branch: String,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct BackupRef {
+    name: String,
+    operation: String,
+    commit_id: String,
+    message: String,
+    created_at: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct TransferProgress {
+    target: String,
+    received_objects: usize,
+    indexed_objects: usize,
+    total_objects: usize,
+    received_bytes: usize,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 struct GitSubmodule {
     name: String,
@@ -134,6 +276,19 @@ struct GitSubmodule {
     status: SubmoduleStatus,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+struct SubmoduleDrift {
+    name: String,
+    path: String,
+    recorded_id: Option<String>,
+    checked_out_id: Option<String>,
+    ahead: Option<usize>,
+    behind: Option<usize>,
+    is_initialized: bool,
+    is_dirty: bool,
+    needs_attention: bool,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 enum SubmoduleStatus {
     Uninitialized,
@@ -149,7 +304,11 @@ struct RepositoryInfo {
     current_branch: String,
     last_commit: String,
     is_dirty: bool,
+    is_bare: bool,
+    kind: String, // standard, bare, worktree, submodule
+    main_repository_path: Option<String>, // For linked worktrees, the repository that owns them
     last_accessed: i64,
+    identity_warning: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -158,6 +317,22 @@ struct FileContent {
     content: String,
     is_binary: bool,
     size: u64,
+    truncated: bool, // More content follows; continue from next_line or with read_file_range
+    encoding: String, // utf-8, utf-8-bom, utf-16le, utf-16be, latin-1, binary
+    start_line: usize,
+    line_count: usize,
+    next_line: Option<usize>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct FileChunk {
+    path: String,
+    offset: u64,
+    length: u64, // Bytes covered by content; the next chunk starts at offset + length
+    content: String,
+    encoding: String,
+    size: u64,
+    is_eof: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -165,9 +340,12 @@ struct BranchInfo {
     name: String,
     is_current: bool,
     is_remote: bool,
-    commit_count: usize,
+    target: Option<String>, // Tip commit; ahead/behind the default branch comes from get_branch_ahead_behind
     last_commit_message: String,
     last_commit_date: i64,
+    upstream: Option<String>, // Tracked remote branch, e.g. "origin/main"; local branches only
+    ahead: Option<usize>, // Commits not pushed to the upstream
+    behind: Option<usize>, // Upstream commits not pulled yet
 }
 
 #[tauri::command]
@@ -176,27 +354,20 @@ fn greet(name: &str) -> String {
 }
 
 #[tauri::command]
-fn discover_repositories() -> Result<Vec<RepositoryInfo>, String> {
-    let home = env::var("HOME").unwrap_or_else(|_| "/".to_string());
+fn discover_repositories(app: tauri::AppHandle) -> Result<Vec<RepositoryInfo>, AppError> {
     let mut repositories = Vec::new();
+    let filter = DiscoveryFilter::load(&app);
     
     println!("🔍 Starting repository discovery...");
     
     // Buscar apenas em diretórios muito específicos para evitar travamentos
-    let search_paths = vec![
-        PathBuf::from(format!("{}/Projetos", home)),
-        PathBuf::from(format!("{}/Projects", home)),
-        PathBuf::from(format!("{}/Developer", home)),
-        PathBuf::from(format!("{}/Code", home)),
-        // Adicionar o próprio diretório do CodeGit para teste
-        PathBuf::from("/Users/diogo/Projetos/NovosProjetos/GitHub/codegit"),
-    ];
+    let search_paths = discovery_search_paths();
 
     for search_path in &search_paths {
         if search_path.exists() && search_path.is_dir() {
             println!("🔍 Searching in: {}", search_path.display());
             // Busca recursiva limitada a 1 nível apenas para evitar travamentos
-            search_repositories_recursive(search_path, &mut repositories, 0, 1);
+            search_repositories_recursive(search_path, &mut repositories, &filter, 0, 1);
             
             // Parar se já encontramos repositórios suficientes
             if repositories.len() >= 20 {
@@ -217,18 +388,120 @@ fn discover_repositories() -> Result<Vec<RepositoryInfo>, String> {
     // Ordenar por nome para melhor visualização
     repositories.sort_by(|a, b| a.name.cmp(&b.name));
     
+    // Avisar quando o user.email efetivo não corresponde ao perfil de identidade atribuído
+    for repository in repositories.iter_mut() {
+        repository.identity_warning = identity_warning(&app, &repository.path);
+    }
+    
     println!("✅ Repository discovery completed: {} unique repositories", repositories.len());
     
     Ok(repositories)
 }
 
+/// Home directory on every platform (HOME on Unix, USERPROFILE or HOMEDRIVE+HOMEPATH on Windows).
+fn user_home_dir() -> Option<PathBuf> {
+    tauri::api::path::home_dir()
+        .or_else(|| env::var("HOME").ok().map(PathBuf::from))
+        .or_else(|| env::var("USERPROFILE").ok().map(PathBuf::from))
+        .or_else(|| {
+            let drive = env::var("HOMEDRIVE").ok()?;
+            let path = env::var("HOMEPATH").ok()?;
+            Some(PathBuf::from(format!("{}{}", drive, path)))
+        })
+}
+
+fn discovery_search_paths() -> Vec<PathBuf> {
+    let mut search_paths = Vec::new();
+    
+    if let Some(home) = user_home_dir() {
+        for folder in ["Projetos", "Projects", "Developer", "Code", "src", "dev", "git", "workspace", "source/repos"] {
+            search_paths.push(home.join(folder));
+        }
+    }
+    
+    // No Windows os projetos costumam ficar na raiz de outros discos (D:\Projects, E:\src, ...)
+    #[cfg(target_os = "windows")]
+    for drive in local_drives() {
+        for folder in ["Projects", "Projetos", "Code", "src", "dev", "git", "repos"] {
+            search_paths.push(drive.join(folder));
+        }
+    }
+    
+    search_paths
+}
+
+/// Roots of the local fixed and removable drives from C: on. Network shares and optical
+/// drives are left out: probing them can stall for seconds or spin up a disc.
+#[cfg(target_os = "windows")]
+fn local_drives() -> Vec<PathBuf> {
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetLogicalDrives() -> u32;
+        fn GetDriveTypeW(root_path_name: *const u16) -> u32;
+    }
+    const DRIVE_NO_ROOT_DIR: u32 = 1;
+    const DRIVE_REMOTE: u32 = 4;
+    const DRIVE_CDROM: u32 = 5;
+    
+    let mask = unsafe { GetLogicalDrives() };
+    (2..26u8)
+        .filter(|bit| mask & (1 << bit) != 0)
+        .map(|bit| format!("{}:\\", (b'A' + bit) as char))
+        .filter(|root| {
+            let wide: Vec<u16> = root.encode_utf16().chain(std::iter::once(0)).collect();
+            let drive_type = unsafe { GetDriveTypeW(wide.as_ptr()) };
+            !matches!(drive_type, DRIVE_NO_ROOT_DIR | DRIVE_REMOTE | DRIVE_CDROM)
+        })
+        .map(PathBuf::from)
+        .collect()
+}
+
+/// Path usable by std::fs on Windows beyond MAX_PATH (260 characters) via the \\?\ prefix.
+#[cfg(target_os = "windows")]
+fn fs_path(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if raw.len() < 260 || raw.starts_with(r"\\?\") || !path.is_absolute() {
+        return path.to_path_buf();
+    }
+    
+    let raw = raw.replace('/', "\\");
+    match raw.strip_prefix(r"\\") {
+        Some(unc) => PathBuf::from(format!(r"\\?\UNC\{}", unc)),
+        None => PathBuf::from(format!(r"\\?\{}", raw)),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn fs_path(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+/// `path` without the \\?\ prefix canonicalize() adds on Windows, as users and git write it.
+#[cfg(target_os = "windows")]
+fn without_verbatim_prefix(path: &Path) -> PathBuf {
+    let raw = path.to_string_lossy();
+    if let Some(unc) = raw.strip_prefix(r"\\?\UNC\") {
+        return PathBuf::from(format!(r"\\{}", unc));
+    }
+    match raw.strip_prefix(r"\\?\") {
+        Some(local) => PathBuf::from(local),
+        None => path.to_path_buf(),
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn without_verbatim_prefix(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
 fn search_repositories_recursive(
     dir: &PathBuf, 
     repositories: &mut Vec<RepositoryInfo>, 
+    filter: &DiscoveryFilter,
     current_depth: usize, 
     max_depth: usize
 ) {
-    if current_depth > max_depth {
+    if current_depth > max_depth || filter.is_excluded(dir) {
         return;
     }
     
@@ -246,7 +519,7 @@ fn search_repositories_recursive(
     }
     
     // Buscar em subdiretórios com tratamento de erro melhorado
-    match fs::read_dir(dir) {
+    match fs::read_dir(fs_path(dir)) {
         Ok(entries) => {
             let mut processed = 0;
             for entry in entries.flatten() {
@@ -258,16 +531,13 @@ fn search_repositories_recursive(
                 
                 if let Ok(file_type) = entry.file_type() {
                     if file_type.is_dir() {
-                        let path = entry.path();
-                        // Pular diretórios ocultos, node_modules e outros problemáticos
+                        // Join to the original path so a long-path prefix never leaks into results
+                        let path = dir.join(entry.file_name());
+                        // Pular diretórios ocultos e os excluídos pelas regras de descoberta
                         if let Some(name) = path.file_name() {
                             let name_str = name.to_string_lossy();
-                            if !name_str.starts_with('.') 
-                                && name_str != "node_modules" 
-                                && name_str != "target" 
-                                && name_str != "dist" 
-                                && name_str != "build" {
-                                search_repositories_recursive(&path, repositories, current_depth + 1, max_depth);
+                            if !name_str.starts_with('.') && !filter.is_excluded(&path) {
+                                search_repositories_recursive(&path, repositories, filter, current_depth + 1, max_depth);
                                 processed += 1;
                             }
                         }
@@ -282,9 +552,8 @@ fn search_repositories_recursive(
 }
 
 fn check_git_repository(path: &PathBuf) -> Option<RepositoryInfo> {
-    // Verificar se existe pasta .git
-    let git_dir = path.join(".git");
-    if !git_dir.exists() {
+    // Verificar se existe .git (pasta, ou arquivo em worktrees e submódulos), ou se o próprio diretório é um repositório bare
+    if !fs_path(&path.join(".git")).exists() && !is_bare_repository_dir(&fs_path(path)) {
         return None;
     }
     
@@ -321,14 +590,16 @@ fn check_git_repository(path: &PathBuf) -> Option<RepositoryInfo> {
         })
         .unwrap_or_else(|| "no-commits".to_string());
     
-    // Verificar status com timeout
+    // Verificar status com timeout (repositórios bare não têm working tree)
+    let is_bare = repo.is_bare();
+    let (kind, main_repository_path) = repository_kind(&repo);
     let is_dirty = match repo.statuses(None) {
-        Ok(statuses) => !statuses.is_empty(),
-        Err(_) => false, // Em caso de erro, assumir clean
+        Ok(statuses) if !is_bare => !statuses.is_empty(),
+        _ => false, // Em caso de erro, assumir clean
     };
     
-    // Obter timestamp com fallback
-    let last_accessed = git_dir
+    // Obter timestamp com fallback (repo.path() é o git dir real, mesmo quando .git é um arquivo)
+    let last_accessed = fs_path(repo.path())
         .metadata()
         .and_then(|meta| meta.modified())
         .map(|time| time.duration_since(std::time::UNIX_EPOCH).unwrap_or_default())
@@ -346,92 +617,271 @@ fn check_git_repository(path: &PathBuf) -> Option<RepositoryInfo> {
         current_branch,
         last_commit,
         is_dirty,
+        is_bare,
+        kind,
+        main_repository_path,
         last_accessed,
+        identity_warning: None,
     })
 }
 
+/// Classifies a repository for discovery: bare repos are their own git dir, linked
+/// worktrees and submodules have a .git file pointing into another repository.
+fn repository_kind(repo: &Repository) -> (String, Option<String>) {
+    if repo.is_bare() {
+        return ("bare".to_string(), None);
+    }
+    
+    if repo.is_worktree() {
+        // The commondir file points (usually relatively) at the owning repository's git dir,
+        // which is <main>/.git or the bare repository itself
+        let main_repository_path = fs::read_to_string(repo.path().join("commondir"))
+            .ok()
+            .map(|content| repo.path().join(content.trim()))
+            .and_then(|common_dir| common_dir.canonicalize().ok())
+            .map(|common_dir| without_verbatim_prefix(&common_dir))
+            .map(|common_dir| match common_dir.file_name() {
+                Some(name) if name == ".git" => common_dir.parent().map(Path::to_path_buf).unwrap_or(common_dir),
+                _ => common_dir,
+            })
+            .map(|main_path| main_path.to_string_lossy().to_string());
+        return ("worktree".to_string(), main_repository_path);
+    }
+    
+    // Submodule git dirs live under the superproject's .git/modules/
+    let components: Vec<_> = repo.path().components().map(|component| component.as_os_str()).collect();
+    let is_submodule = components.windows(2).any(|pair| pair[0] == ".git" && pair[1] == "modules");
+    let has_git_file = repo.workdir()
+        .map(|workdir| workdir.join(".git").is_file())
+        .unwrap_or(false);
+    if has_git_file && is_submodule {
+        return ("submodule".to_string(), None);
+    }
+    
+    ("standard".to_string(), None)
+}
+
+/// A bare repository keeps HEAD, objects/ and refs/ directly in its directory.
+fn is_bare_repository_dir(path: &Path) -> bool {
+    path.join("HEAD").is_file() && path.join("objects").is_dir() && path.join("refs").is_dir()
+}
+
+/// Reads up to `max_bytes` from `offset`, returning the bytes and the file size.
+fn read_file_bytes(path: &str, offset: u64, max_bytes: usize) -> Result<(Vec<u8>, u64), String> {
+    use std::io::{Read, Seek, SeekFrom};
+    
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let size = file.metadata()
+        .map_err(|e| format!("Failed to read file: {}", e))?
+        .len();
+    
+    file.seek(SeekFrom::Start(offset))
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    let mut bytes = Vec::new();
+    file.take(max_bytes as u64).read_to_end(&mut bytes)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+    
+    Ok((bytes, size))
+}
+
+/// `file_path` under the working tree at `repo_path`. Absolute paths and `..` are rejected,
+/// so a request can't read or write outside the repository.
+fn worktree_file(repo_path: &str, file_path: &str) -> Result<String, AppError> {
+    check_path(file_path).map_err(AppError::invalid_input)?;
+    Ok(Path::new(repo_path).join(file_path).to_string_lossy().to_string())
+}
+
+/// Text of a worktree file, `line_count` lines from `start_line` (0-based) when given.
+/// Lines are streamed from disk, so reading a page of a huge file doesn't load the rest.
 #[tauri::command]
-fn get_file_content(repo_path: String, file_path: String) -> Result<FileContent, String> {
-    let full_path = format!("{}/{}", repo_path, file_path);
-    
-    match fs::read(&full_path) {
-        Ok(content_bytes) => {
-            let is_binary = content_bytes.iter().any(|&b| b == 0);
-            let content = if is_binary {
-                format!("Binary file ({} bytes)", content_bytes.len())
-            } else {
-                String::from_utf8_lossy(&content_bytes).to_string()
-            };
-            
-            Ok(FileContent {
+fn get_file_content(
+    app: tauri::AppHandle,
+    repo_path: String,
+    file_path: String,
+    start_line: Option<usize>,
+    line_count: Option<usize>,
+) -> Result<FileContent, AppError> {
+    timed(&app, "get_file_content", &repo_path, || {
+        use std::io::BufRead;
+        
+        open_worktree_repository(&repo_path)?;
+        
+        let full_path = worktree_file(&repo_path, &file_path)?;
+        let limits = payload_limits(&app);
+        
+        let file = fs::File::open(&full_path)
+            .map_err(|e| AppError::wrap(e, "Failed to read file"))?;
+        let size = file.metadata()
+            .map_err(|e| AppError::wrap(e, "Failed to read file"))?
+            .len();
+        
+        let mut reader = std::io::BufReader::new(file);
+        let encoding = detect_encoding(reader.fill_buf().map_err(|e| AppError::wrap(e, "Failed to read file"))?);
+        let start_line = start_line.unwrap_or(0);
+        
+        if encoding == TextEncoding::Binary {
+            return Ok(FileContent {
                 path: file_path,
-                content,
-                is_binary,
-                size: content_bytes.len() as u64,
-            })
+                content: format!("Binary file ({} bytes)", size),
+                is_binary: true,
+                size,
+                truncated: false,
+                encoding: encoding.name().to_string(),
+                start_line,
+                line_count: 0,
+                next_line: None,
+            });
         }
-        Err(e) => Err(format!("Failed to read file: {}", e)),
-    }
+        
+        let page = read_text_lines(reader, encoding, start_line, line_count, limits.max_file_bytes)?;
+        
+        Ok(FileContent {
+            path: file_path,
+            content: page.content,
+            is_binary: false,
+            size,
+            truncated: page.has_more,
+            encoding: encoding.name().to_string(),
+            start_line,
+            line_count: page.line_count,
+            next_line: if page.has_more { Some(start_line + page.line_count) } else { None },
+        })
+    })
 }
 
+/// Follow-up to a truncated get_file_content: `length` bytes from `offset`, capped at
+/// max_file_bytes, decoded with the encoding detected from the start of the file. A
+/// character split by the range is left for the next chunk.
 #[tauri::command]
-fn get_detailed_branches(repo_path: String) -> Result<Vec<BranchInfo>, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+fn read_file_range(app: tauri::AppHandle, repo_path: String, file_path: String, offset: u64, length: usize) -> Result<FileChunk, AppError> {
+    open_worktree_repository(&repo_path)?;
     
-    let mut branches = Vec::new();
-    let current_branch = repo.head()
-        .ok()
-        .and_then(|head| head.shorthand().map(|s| s.to_string()));
-    
-    // Branches locais
-    if let Ok(local_branches) = repo.branches(Some(git2::BranchType::Local)) {
-        for branch_result in local_branches {
-            if let Ok((branch, _)) = branch_result {
-                if let Some(name) = branch.name().unwrap_or(None) {
-                    let is_current = current_branch.as_ref() == Some(&name.to_string());
+    let full_path = worktree_file(&repo_path, &file_path)?;
+    let length = length.min(payload_limits(&app).max_file_bytes);
+    
+    let (head, _) = read_file_bytes(&full_path, 0, 8000)?;
+    let encoding = detect_encoding(&head);
+    if encoding == TextEncoding::Binary {
+        return Err(AppError::invalid_input(format!("{} is a binary file", file_path)));
+    }
+    if matches!(encoding, TextEncoding::Utf16Le | TextEncoding::Utf16Be) && offset % 2 != 0 {
+        return Err(AppError::invalid_input("UTF-16 chunks must start at an even offset"));
+    }
+    
+    let (mut bytes, size) = read_file_bytes(&full_path, offset, length)?;
+    if offset + (bytes.len() as u64) < size {
+        let complete = complete_prefix_len(&bytes, encoding);
+        // Keep a lone partial character rather than returning an empty chunk forever
+        if complete > 0 {
+            bytes.truncate(complete);
+        }
+    }
+    // The BOM belongs to no chunk's content
+    let bom = (encoding.bom_len() as u64).saturating_sub(offset).min(bytes.len() as u64) as usize;
+    
+    Ok(FileChunk {
+        path: file_path,
+        offset,
+        length: bytes.len() as u64,
+        content: decode(&bytes[bom..], encoding),
+        encoding: encoding.name().to_string(),
+        size,
+        is_eof: offset + bytes.len() as u64 >= size,
+    })
+}
+
+#[tauri::command]
+fn get_detailed_branches(app: tauri::AppHandle, repo_path: String) -> Result<Vec<BranchInfo>, AppError> {
+    timed(&app, "get_detailed_branches", &repo_path, || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        let mut branches = Vec::new();
+        let current_branch = repo.head()
+            .ok()
+            .and_then(|head| head.shorthand().map(|s| s.to_string()));
+        
+        // Branches locais
+        if let Ok(local_branches) = repo.branches(Some(git2::BranchType::Local)) {
+            for branch_result in local_branches {
+                if let Ok((branch, _)) = branch_result {
+                    if let Some(name) = branch.name().unwrap_or(None) {
+                        let is_current = current_branch.as_ref() == Some(&name.to_string());
                     
-                    let (last_commit_message, last_commit_date, commit_count) = 
-                        get_branch_info(&repo, name);
+                        let (last_commit_message, last_commit_date, target) = 
+                            get_branch_info(&repo, name);
+                        let (upstream, ahead, behind) = upstream_tracking(&app, &repo, &repo_path, &branch);
                     
-                    branches.push(BranchInfo {
-                        name: name.to_string(),
-                        is_current,
-                        is_remote: false,
-                        commit_count,
-                        last_commit_message,
-                        last_commit_date,
-                    });
+                        branches.push(BranchInfo {
+                            name: name.to_string(),
+                            is_current,
+                            is_remote: false,
+                            target,
+                            last_commit_message,
+                            last_commit_date,
+                            upstream,
+                            ahead,
+                            behind,
+                        });
+                    }
                 }
             }
         }
-    }
-    
-    // Branches remotos
-    if let Ok(remote_branches) = repo.branches(Some(git2::BranchType::Remote)) {
-        for branch_result in remote_branches {
-            if let Ok((branch, _)) = branch_result {
-                if let Some(name) = branch.name().unwrap_or(None) {
-                    let (last_commit_message, last_commit_date, commit_count) = 
-                        get_branch_info(&repo, name);
+        
+        // Branches remotos
+        if let Ok(remote_branches) = repo.branches(Some(git2::BranchType::Remote)) {
+            for branch_result in remote_branches {
+                if let Ok((branch, _)) = branch_result {
+                    if let Some(name) = branch.name().unwrap_or(None) {
+                        let (last_commit_message, last_commit_date, target) = 
+                            get_branch_info(&repo, name);
                     
-                    branches.push(BranchInfo {
-                        name: name.to_string(),
-                        is_current: false,
-                        is_remote: true,
-                        commit_count,
-                        last_commit_message,
-                        last_commit_date,
-                    });
+                        branches.push(BranchInfo {
+                            name: name.to_string(),
+                            is_current: false,
+                            is_remote: true,
+                            target,
+                            last_commit_message,
+                            last_commit_date,
+                            upstream: None,
+                            ahead: None,
+                            behind: None,
+                        });
+                    }
                 }
             }
         }
-    }
+        
+        Ok(branches)
+    })
+}
+
+/// The upstream a local branch tracks and how far apart they are. The counts are None when
+/// the upstream is configured but its remote branch is gone.
+fn upstream_tracking(app: &tauri::AppHandle, repo: &Repository, repo_path: &str, branch: &git2::Branch) -> (Option<String>, Option<usize>, Option<usize>) {
+    let upstream = match branch.upstream() {
+        Ok(upstream) => upstream,
+        Err(_) => {
+            // Configured but not fetched yet: still worth showing which branch is tracked
+            let tracked = branch.get().name()
+                .and_then(|ref_name| repo.branch_upstream_name(ref_name).ok())
+                .and_then(|name| name.as_str().map(|name| name.trim_start_matches("refs/remotes/").to_string()));
+            return (tracked, None, None);
+        }
+    };
+    let name = upstream.name().ok().flatten().map(|name| name.to_string());
     
-    Ok(branches)
+    let counts = match (branch.get().target(), upstream.get().target()) {
+        (Some(tip), Some(upstream_tip)) => {
+            cached_ahead_behind(&app.state::<AheadBehindCache>(), repo, repo_path, tip, upstream_tip).ok()
+        }
+        _ => None,
+    };
+    (name, counts.map(|(ahead, _)| ahead), counts.map(|(_, behind)| behind))
 }
 
-fn get_branch_info(repo: &Repository, branch_name: &str) -> (String, i64, usize) {
+fn get_branch_info(repo: &Repository, branch_name: &str) -> (String, i64, Option<String>) {
     if let Ok(branch_ref) = repo.find_reference(&format!("refs/heads/{}", branch_name))
         .or_else(|_| repo.find_reference(&format!("refs/remotes/{}", branch_name))) {
         
@@ -439,31 +889,74 @@ fn get_branch_info(repo: &Repository, branch_name: &str) -> (String, i64, usize)
             let message = commit.message().unwrap_or("No message").to_string();
             let timestamp = commit.time().seconds();
             
-            // Contar commits na branch
-            let mut revwalk = repo.revwalk().unwrap_or_else(|_| repo.revwalk().unwrap());
-            revwalk.push(commit.id()).unwrap_or_default();
-            let commit_count = revwalk.count();
-            
-            return (message, timestamp, commit_count);
+            return (message, timestamp, Some(commit.id().to_string()));
         }
     }
     
-    ("Unknown".to_string(), 0, 0)
+    ("Unknown".to_string(), 0, None)
 }
 
 #[tauri::command]
-fn open_repository(path: String) -> Result<String, String> {
+fn open_repository(path: String) -> Result<String, AppError> {
     match Repository::open(&path) {
         Ok(_) => Ok(format!("Successfully opened repository at: {}", path)),
-        Err(e) => Err(format!("Failed to open repository: {}", e)),
+        Err(e) => Err(AppError::wrap(e, "Failed to open repository")),
     }
 }
 
+/// Opens a repository for an operation that needs a working tree, rejecting bare repositories.
+fn open_worktree_repository(repo_path: &str) -> Result<Repository, String> {
+    let repo = Repository::open(repo_path)
+        .map_err(|e| format!("Failed to open repository: {}", e))?;
+    
+    if repo.is_bare() {
+        return Err(format!("{} is a bare repository; this operation requires a working tree", repo_path));
+    }
+    
+    Ok(repo)
+}
+
 #[tauri::command]
-fn get_commits(repo_path: String, limit: Option<usize>) -> Result<Vec<GitCommit>, String> {
+async fn get_commits(
+    app: tauri::AppHandle,
+    repo_path: String,
+    limit: Option<usize>,
+    operation_id: Option<String>
+) -> Result<Vec<GitCommit>, AppError> {
+    let task_app = app.clone();
+    let timed_path = repo_path.clone();
+    let started = Instant::now();
+    let result = run_operation(&app, "log", operation_id, move |token| get_commits_blocking(task_app, repo_path, limit, token)).await;
+    record_timing(&app, "get_commits", Some(&timed_path), started);
+    result
+}
+
+fn get_commits_blocking(app: tauri::AppHandle, repo_path: String, limit: Option<usize>, token: OperationToken) -> Result<Vec<GitCommit>, String> {
     let repo = Repository::open(&repo_path)
         .map_err(|e| format!("Failed to open repository: {}", e))?;
     
+    let limits = payload_limits(&app);
+    let max_commits = limit.unwrap_or(50).min(limits.max_log_entries);
+    let scope = active_scope(&app, &repo_path)?;
+    // The cache holds the unfiltered history, so scoped logs walk the repository
+    let cached = if scope.is_active() { None } else { cached_history(&app, &repo, &repo_path, max_commits, &token)? };
+    if let Some(history) = cached {
+        return Ok(history
+            .into_iter()
+            .map(|commit| {
+                let (message, truncated) = truncate_text(&commit.message, limits.max_commit_message_bytes);
+                GitCommit {
+                    id: commit.id,
+                    message,
+                    author: commit.author,
+                    email: commit.email,
+                    timestamp: commit.timestamp,
+                    truncated,
+                }
+            })
+            .collect());
+    }
+    
     let mut revwalk = repo.revwalk()
         .map_err(|e| format!("Failed to create revwalk: {}", e))?;
     
@@ -471,260 +964,854 @@ fn get_commits(repo_path: String, limit: Option<usize>) -> Result<Vec<GitCommit>
         .map_err(|e| format!("Failed to push HEAD: {}", e))?;
     
     let mut commits = Vec::new();
-    let max_commits = limit.unwrap_or(50);
     
-    for (i, oid_result) in revwalk.enumerate() {
-        if i >= max_commits {
+    for oid_result in revwalk {
+        if commits.len() >= max_commits {
             break;
         }
+        token.check()?;
         
         let oid = oid_result.map_err(|e| format!("Failed to get OID: {}", e))?;
         let commit = repo.find_commit(oid)
             .map_err(|e| format!("Failed to find commit: {}", e))?;
+        if !scope.touches(&repo, &commit)? {
+            continue;
+        }
         
         let author = commit.author();
+        let (message, truncated) = truncate_text(commit.message().unwrap_or("No message"), limits.max_commit_message_bytes);
         
         commits.push(GitCommit {
             id: oid.to_string(),
-            message: commit.message().unwrap_or("No message").to_string(),
+            message,
             author: author.name().unwrap_or("Unknown").to_string(),
             email: author.email().unwrap_or("unknown@email.com").to_string(),
             timestamp: commit.time().seconds(),
+            truncated,
         });
     }
     
     Ok(commits)
 }
 
+/// Full message of a commit whose log entry was truncated.
 #[tauri::command]
-fn get_repository_status(repo_path: String) -> Result<GitStatus, String> {
+fn get_commit_message(repo_path: String, commit_id: String) -> Result<String, AppError> {
     let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
     
-    let statuses = repo.statuses(None)
-        .map_err(|e| format!("Failed to get status: {}", e))?;
-    
-    let mut status = GitStatus {
-        modified: Vec::new(),
-        added: Vec::new(),
-        deleted: Vec::new(),
-        untracked: Vec::new(),
-    };
+    let oid = git2::Oid::from_str(&commit_id)
+        .map_err(|e| AppError::wrap(e, "Invalid commit ID"))?;
+    let commit = repo.find_commit(oid)
+        .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
     
-    for entry in statuses.iter() {
-        let path = entry.path().unwrap_or("").to_string();
-        let flags = entry.status();
+    Ok(commit.message().unwrap_or("No message").to_string())
+}
+
+#[tauri::command]
+fn get_repository_status(
+    app: tauri::AppHandle,
+    repo_path: String,
+    since_last: Option<bool>,
+    pathspec: Option<Vec<String>>,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<GitStatus, AppError> {
+    timed(&app, "get_repository_status", &repo_path, || {
+        let repo = open_worktree_repository(&repo_path)?;
+        let watchers = app.state::<RepositoryWatchers>();
+        let status_cache = app.state::<StatusCache>();
         
-        if flags.contains(git2::Status::WT_MODIFIED) {
-            status.modified.push(path.clone());
-        }
-        if flags.contains(git2::Status::INDEX_NEW) {
-            status.added.push(path.clone());
+        let statuses = status_cache.statuses(&repo, &repo_path, watchers.is_watching(&repo_path))?;
+        
+        let mut status = GitStatus {
+            modified: Vec::new(),
+            added: Vec::new(),
+            deleted: Vec::new(),
+            untracked: Vec::new(),
+            is_delta: false,
+            cleared: Vec::new(),
+            counts: GitStatusCounts::default(),
+            total_entries: 0,
+            has_more: false,
+        };
+        
+        let since_last = since_last.unwrap_or(false);
+        if since_last && (limit.is_some() || offset.is_some()) {
+            return Err(AppError::invalid_input("since_last cannot be combined with limit or offset"));
         }
-        if flags.contains(git2::Status::WT_DELETED) {
-            status.deleted.push(path.clone());
+        
+        // Same matching rules as `git status -- <pathspec>`: directory prefixes and globs
+        let patterns = pathspec.unwrap_or_default();
+        let pathspec = if patterns.is_empty() {
+            None
+        } else {
+            Some(git2::Pathspec::new(patterns.iter())
+                .map_err(|e| AppError::wrap(e, "Invalid pathspec"))?)
+        };
+        let scope = active_scope(&app, &repo_path)?;
+        let statuses: StatusMap = statuses
+            .into_iter()
+            .filter(|(path, _)| scope.matches(path))
+            .filter(|(path, _)| match &pathspec {
+                Some(pathspec) => pathspec.matches_path(Path::new(path), git2::PathspecFlags::DEFAULT),
+                None => true,
+            })
+            .collect();
+        
+        // The delta is taken after filtering, against the last result for the same filter,
+        // so paths outside it are neither reported as changed nor as cleared
+        let entries: Vec<(String, git2::Status)> = if since_last {
+            let filter = format!("{:?}|{:?}", scope.pathspecs(), patterns);
+            let delta = status_cache.changes_since_last(&repo_path, &filter, &statuses);
+            status.is_delta = true;
+            status.cleared = delta.cleared;
+            delta.changed.into_iter().collect()
+        } else {
+            statuses.into_iter().collect()
+        };
+        
+        for (_, flags) in &entries {
+            if flags.contains(git2::Status::WT_MODIFIED) {
+                status.counts.modified += 1;
+            }
+            if flags.contains(git2::Status::INDEX_NEW) {
+                status.counts.added += 1;
+            }
+            if flags.contains(git2::Status::WT_DELETED) {
+                status.counts.deleted += 1;
+            }
+            if flags.contains(git2::Status::WT_NEW) {
+                status.counts.untracked += 1;
+            }
         }
-        if flags.contains(git2::Status::WT_NEW) {
-            status.untracked.push(path.clone());
+        
+        let offset = offset.unwrap_or(0);
+        status.total_entries = entries.len();
+        status.has_more = matches!(limit, Some(limit) if offset.saturating_add(limit) < entries.len());
+        
+        for (path, flags) in entries.into_iter().skip(offset).take(limit.unwrap_or(usize::MAX)) {
+            if flags.contains(git2::Status::WT_MODIFIED) {
+                status.modified.push(path.clone());
+            }
+            if flags.contains(git2::Status::INDEX_NEW) {
+                status.added.push(path.clone());
+            }
+            if flags.contains(git2::Status::WT_DELETED) {
+                status.deleted.push(path.clone());
+            }
+            if flags.contains(git2::Status::WT_NEW) {
+                status.untracked.push(path.clone());
+            }
         }
-    }
-    
-    Ok(status)
+        
+        Ok(status)
+    })
 }
 
 #[tauri::command]
-fn stage_file(repo_path: String, file_path: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let mut index = repo.index()
-        .map_err(|e| format!("Failed to get index: {}", e))?;
-    
-    index.add_path(Path::new(&file_path))
-        .map_err(|e| format!("Failed to stage file: {}", e))?;
-    
-    index.write()
-        .map_err(|e| format!("Failed to write index: {}", e))?;
-    
-    Ok(format!("Staged file: {}", file_path))
+fn stage_file(app: tauri::AppHandle, repo_path: String, file_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "stage_file", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let mut index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        index.add_path(Path::new(&file_path))
+            .map_err(|e| AppError::wrap(e, "Failed to stage file"))?;
+        
+        index.write()
+            .map_err(|e| AppError::wrap(e, "Failed to write index"))?;
+        
+        Ok(format!("Staged file: {}", file_path))
+    })
 }
 
 #[tauri::command]
-fn unstage_file(repo_path: String, file_path: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let head = repo.head()
-        .map_err(|e| format!("Failed to get HEAD: {}", e))?;
-    
-    let _head_tree = head.peel_to_tree()
-        .map_err(|e| format!("Failed to get HEAD tree: {}", e))?;
-    
-    let target_obj = head.peel(git2::ObjectType::Commit)
-        .map_err(|e| format!("Failed to peel to commit: {}", e))?;
-    repo.reset_default(Some(&target_obj), &[Path::new(&file_path)])
-        .map_err(|e| format!("Failed to unstage file: {}", e))?;
-    
-    Ok(format!("Unstaged file: {}", file_path))
+fn unstage_file(app: tauri::AppHandle, repo_path: String, file_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "unstage_file", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let head = repo.head()
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD"))?;
+        
+        let _head_tree = head.peel_to_tree()
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD tree"))?;
+        
+        let target_obj = head.peel(git2::ObjectType::Commit)
+            .map_err(|e| AppError::wrap(e, "Failed to peel to commit"))?;
+        repo.reset_default(Some(&target_obj), &[Path::new(&file_path)])
+            .map_err(|e| AppError::wrap(e, "Failed to unstage file"))?;
+        
+        Ok(format!("Unstaged file: {}", file_path))
+    })
 }
 
 #[tauri::command]
-fn commit_changes(repo_path: String, message: String, author_name: String, author_email: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let signature = Signature::now(&author_name, &author_email)
-        .map_err(|e| format!("Failed to create signature: {}", e))?;
-    
-    let mut index = repo.index()
-        .map_err(|e| format!("Failed to get index: {}", e))?;
-    
-    let tree_id = index.write_tree()
-        .map_err(|e| format!("Failed to write tree: {}", e))?;
-    
-    let tree = repo.find_tree(tree_id)
-        .map_err(|e| format!("Failed to find tree: {}", e))?;
-    
-    let parent_commit = match repo.head() {
-        Ok(head) => Some(head.peel_to_commit().map_err(|e| format!("Failed to get parent commit: {}", e))?),
-        Err(_) => None, // First commit
-    };
-    
-    let parents = match &parent_commit {
-        Some(commit) => vec![commit],
-        None => vec![],
-    };
-    
-    let commit_id = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        &message,
-        &tree,
-        &parents,
-    ).map_err(|e| format!("Failed to create commit: {}", e))?;
-    
-    Ok(format!("Created commit: {}", commit_id))
+fn commit_changes(
+    app: tauri::AppHandle,
+    repo_path: String,
+    message: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    no_verify: Option<bool>,
+) -> Result<String, AppError> {
+    audited(&app, &repo_path, "commit_changes", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let updated_ref = head_ref_name(&repo);
+        let old_target = ref_target(&repo, &updated_ref);
+        
+        // Same hook sequence as `git commit`; no_verify mirrors --no-verify
+        let verify = !no_verify.unwrap_or(false);
+        let mut message = message;
+        if verify {
+            run_blocking_hook(&app, &repo, "pre-commit", &[])?;
+        
+            let message_file = repo.path().join("COMMIT_EDITMSG");
+            fs::write(&message_file, &message)
+                .map_err(|e| AppError::wrap(e, "Failed to write commit message"))?;
+            run_blocking_hook(&app, &repo, "commit-msg", &[&message_file.to_string_lossy()])?;
+        
+            // commit-msg hooks may rewrite the message (e.g. adding a Change-Id)
+            message = fs::read_to_string(&message_file)
+                .map_err(|e| AppError::wrap(e, "Failed to read commit message"))?;
+        }
+        
+        let signature = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        let message = with_sign_off(&app, &repo_path, &message, &signature);
+        
+        let mut index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        let tree_id = index.write_tree()
+            .map_err(|e| AppError::wrap(e, "Failed to write tree"))?;
+        
+        let tree = repo.find_tree(tree_id)
+            .map_err(|e| AppError::wrap(e, "Failed to find tree"))?;
+        
+        let parent_commit = match repo.head() {
+            Ok(head) => Some(head.peel_to_commit().map_err(|e| AppError::wrap(e, "Failed to get parent commit"))?),
+            Err(_) => None, // First commit
+        };
+        
+        let parents = match &parent_commit {
+            Some(commit) => vec![commit],
+            None => vec![],
+        };
+        
+        let signing = signing_config(&app, &repo, &repo_path, "commit.gpgsign");
+        let commit_id = create_commit(
+            &repo,
+            signing.as_ref(),
+            Some("HEAD"),
+            &signature,
+            &signature,
+            &message,
+            &tree,
+            &parents,
+        ).map_err(|e| AppError::wrap(e, "Failed to create commit"))?;
+        
+        // post-commit cannot affect the outcome of the commit
+        if let Err(e) = run_hook(&app, &repo, "post-commit", &[]) {
+            println!("⚠️  {}", e);
+        }
+        
+        journal_operation(
+            &app,
+            &repo_path,
+            "commit",
+            format!("commit \"{}\"", message.lines().next().unwrap_or("")),
+            "soft",
+            vec![ref_change(&repo, &updated_ref, old_target)],
+        );
+        extend_commit_cache(&app, &repo, &repo_path);
+        
+        Ok(format!("Created commit: {}", commit_id))
+    })
 }
 
-#[tauri::command]
-fn get_file_diff(repo_path: String, file_path: String) -> Result<GitDiff, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+/// Where one side of a file diff is read from.
+enum DiffSide<'a> {
+    Tree(Option<&'a git2::Tree<'a>>), // None before the first commit
+    Index(&'a git2::Index),
+    Workdir,
+}
+
+impl DiffSide<'_> {
+    fn blob_id(&self, file_path: &str) -> Option<git2::Oid> {
+        match self {
+            DiffSide::Tree(tree) => tree.and_then(|tree| tree.get_path(Path::new(file_path)).ok()).map(|entry| entry.id()),
+            DiffSide::Index(index) => index.get_path(Path::new(file_path), 0).map(|entry| entry.id),
+            DiffSide::Workdir => None,
+        }
+    }
     
-    let head_tree = match repo.head() {
-        Ok(head) => Some(head.peel_to_tree().map_err(|e| format!("Failed to get HEAD tree: {}", e))?),
-        Err(_) => None,
-    };
+    fn size(&self, repo: &Repository, file_path: &str) -> u64 {
+        match self {
+            DiffSide::Workdir => repo.workdir()
+                .and_then(|workdir| fs::metadata(workdir.join(file_path)).ok())
+                .map(|meta| meta.len())
+                .unwrap_or(0),
+            side => side.blob_id(file_path)
+                .and_then(|id| repo.odb().ok()?.read_header(id).ok())
+                .map(|(size, _)| size as u64)
+                .unwrap_or(0),
+        }
+    }
     
+    fn bytes(&self, repo: &Repository, file_path: &str) -> Vec<u8> {
+        match self {
+            DiffSide::Workdir => repo.workdir()
+                .and_then(|workdir| fs::read(workdir.join(file_path)).ok())
+                .unwrap_or_default(),
+            side => side.blob_id(file_path)
+                .and_then(|id| repo.find_blob(id).ok())
+                .map(|blob| blob.content().to_vec())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+fn file_diff_options(app: &tauri::AppHandle, repo_path: &str, file_path: &str) -> git2::DiffOptions {
     let mut diff_opts = git2::DiffOptions::new();
-    diff_opts.pathspec(&file_path);
-    
-    let _diff = repo.diff_tree_to_workdir(head_tree.as_ref(), Some(&mut diff_opts))
-        .map_err(|e| format!("Failed to get diff: {}", e))?;
-    
-    // For now, return a basic diff structure  
-    let git_diff = GitDiff {
-        file_path: file_path.clone(),
-        old_content: String::new(),
-        new_content: String::new(),
-        hunks: Vec::new(),
-    };
+    diff_opts.pathspec(file_path);
+    diff_opts.disable_pathspec_match(true);
+    // New files are diffed against nothing, so they show as one added hunk
+    diff_opts.include_untracked(true).show_untracked_content(true);
+    apply_whitespace_setting(&mut diff_opts, &repo_settings_for(app, repo_path).diff_whitespace);
+    diff_opts
+}
+
+/// The GitDiff of one file between two sides; `diff` must be limited to that file.
+fn file_diff(
+    app: &tauri::AppHandle,
+    repo: &Repository,
+    file_path: &str,
+    diff: &git2::Diff,
+    old: DiffSide,
+    new: DiffSide,
+) -> Result<GitDiff, AppError> {
+    check_path(file_path).map_err(AppError::invalid_input)?;
+    // Vendored bundles and generated files can be huge; don't ship them to the webview
+    let max_diff_bytes = payload_limits(app).max_diff_bytes as u64;
+    let old_size = old.size(repo, file_path);
+    let new_size = new.size(repo, file_path);
+    let truncated = old_size > max_diff_bytes || new_size > max_diff_bytes;
+    
+    let mut old_content = String::new();
+    let mut new_content = String::new();
+    let mut hunks = Vec::new();
+    let mut lfs_pointer = None;
+    if !truncated {
+        let old_bytes = old.bytes(repo, file_path);
+        let new_bytes = new.bytes(repo, file_path);
+        // LFS pointers are shown as a placeholder rather than as pointer text
+        lfs_pointer = parse_lfs_pointer(&new_bytes).or_else(|| parse_lfs_pointer(&old_bytes));
+        
+        let old_encoding = detect_encoding(&old_bytes[..old_bytes.len().min(8000)]);
+        let new_encoding = detect_encoding(&new_bytes[..new_bytes.len().min(8000)]);
+        if lfs_pointer.is_none() && old_encoding != TextEncoding::Binary && new_encoding != TextEncoding::Binary {
+            old_content = decode(&old_bytes, old_encoding);
+            new_content = decode(&new_bytes, new_encoding);
+            hunks = diff_hunks(diff, new_encoding)?;
+        }
+    }
     
-    Ok(git_diff)
+    Ok(GitDiff {
+        file_path: file_path.to_string(),
+        old_content,
+        new_content,
+        hunks,
+        is_lfs_pointer: lfs_pointer.is_some(),
+        lfs_size: lfs_pointer.map(|pointer| pointer.size),
+        truncated,
+    })
+}
+
+fn check_diff_scope(app: &tauri::AppHandle, repo_path: &str, file_path: &str) -> Result<(), AppError> {
+    if !active_scope(app, repo_path)?.matches(file_path) {
+        return Err(AppError::invalid_input(format!("{} is outside the active scope", file_path)));
+    }
+    Ok(())
 }
 
+/// All changes of a file, staged or not: HEAD → working tree.
 #[tauri::command]
-fn get_branches(repo_path: String) -> Result<Vec<GitBranch>, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let branches = repo.branches(None)
-        .map_err(|e| format!("Failed to get branches: {}", e))?;
-    
-    let mut git_branches = Vec::new();
+fn get_file_diff(app: tauri::AppHandle, repo_path: String, file_path: String) -> Result<GitDiff, AppError> {
+    timed(&app, "get_file_diff", &repo_path, || {
+        let repo = open_worktree_repository(&repo_path)?;
+        check_diff_scope(&app, &repo_path, &file_path)?;
+        
+        let head_tree = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(|e| AppError::wrap(e, "Failed to get HEAD tree"))?),
+            Err(_) => None,
+        };
+        
+        let diff = repo.diff_tree_to_workdir(head_tree.as_ref(), Some(&mut file_diff_options(&app, &repo_path, &file_path)))
+            .map_err(|e| AppError::wrap(e, "Failed to get diff"))?;
+        
+        file_diff(&app, &repo, &file_path, &diff, DiffSide::Tree(head_tree.as_ref()), DiffSide::Workdir)
+    })
+}
+
+/// What `git diff --cached` shows for a file: HEAD → index.
+#[tauri::command]
+fn get_staged_diff(app: tauri::AppHandle, repo_path: String, file_path: String) -> Result<GitDiff, AppError> {
+    timed(&app, "get_staged_diff", &repo_path, || {
+        let repo = open_worktree_repository(&repo_path)?;
+        check_diff_scope(&app, &repo_path, &file_path)?;
+        
+        let head_tree = match repo.head() {
+            Ok(head) => Some(head.peel_to_tree().map_err(|e| AppError::wrap(e, "Failed to get HEAD tree"))?),
+            Err(_) => None,
+        };
+        let index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        let diff = repo.diff_tree_to_index(head_tree.as_ref(), Some(&index), Some(&mut file_diff_options(&app, &repo_path, &file_path)))
+            .map_err(|e| AppError::wrap(e, "Failed to get staged diff"))?;
+        
+        file_diff(&app, &repo, &file_path, &diff, DiffSide::Tree(head_tree.as_ref()), DiffSide::Index(&index))
+    })
+}
+
+/// What `git diff` shows for a file: index → working tree. Untracked files show as added.
+#[tauri::command]
+fn get_unstaged_diff(app: tauri::AppHandle, repo_path: String, file_path: String) -> Result<GitDiff, AppError> {
+    timed(&app, "get_unstaged_diff", &repo_path, || {
+        let repo = open_worktree_repository(&repo_path)?;
+        check_diff_scope(&app, &repo_path, &file_path)?;
+        
+        let index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        let diff = repo.diff_index_to_workdir(Some(&index), Some(&mut file_diff_options(&app, &repo_path, &file_path)))
+            .map_err(|e| AppError::wrap(e, "Failed to get unstaged diff"))?;
+        
+        file_diff(&app, &repo, &file_path, &diff, DiffSide::Index(&index), DiffSide::Workdir)
+    })
+}
+
+/// Hunks of a diff with every line, including the "\ No newline at end of file"
+/// markers (origins '=', '>' and '<'). Binary files have no hunks.
+fn diff_hunks(diff: &git2::Diff, encoding: TextEncoding) -> Result<Vec<DiffHunk>, AppError> {
+    // Both callbacks append to the hunk list, so it is shared through a RefCell
+    let hunks = std::cell::RefCell::new(Vec::<DiffHunk>::new());
+    
+    diff.foreach(
+        &mut |_, _| true,
+        None,
+        Some(&mut |_, hunk| {
+            hunks.borrow_mut().push(DiffHunk {
+                old_start: hunk.old_start(),
+                old_lines: hunk.old_lines(),
+                new_start: hunk.new_start(),
+                new_lines: hunk.new_lines(),
+                lines: Vec::new(),
+            });
+            true
+        }),
+        Some(&mut |_, _, line| {
+            let content = decode(line.content(), encoding);
+            if let Some(hunk) = hunks.borrow_mut().last_mut() {
+                hunk.lines.push(DiffLine {
+                    origin: line.origin(),
+                    content: content.trim_end_matches(['\r', '\n']).to_string(),
+                    old_lineno: line.old_lineno(),
+                    new_lineno: line.new_lineno(),
+                });
+            }
+            true
+        }),
+    )
+    .map_err(|e| AppError::wrap(e, "Failed to read diff"))?;
     
-    for branch_result in branches {
-        let (branch, branch_type) = branch_result.map_err(|e| format!("Failed to get branch: {}", e))?;
+    Ok(hunks.into_inner())
+}
+
+fn apply_whitespace_setting(diff_opts: &mut git2::DiffOptions, diff_whitespace: &str) {
+    match diff_whitespace {
+        "all" => { diff_opts.ignore_whitespace(true); }
+        "change" => { diff_opts.ignore_whitespace_change(true); }
+        "eol" => { diff_opts.ignore_whitespace_eol(true); }
+        _ => {}
+    }
+}
+
+#[tauri::command]
+fn get_branches(app: tauri::AppHandle, repo_path: String) -> Result<Vec<GitBranch>, AppError> {
+    timed(&app, "get_branches", &repo_path, || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
         
-        let name = branch.name().map_err(|e| format!("Failed to get branch name: {}", e))?
-            .unwrap_or("Unknown").to_string();
+        let branches = repo.branches(None)
+            .map_err(|e| AppError::wrap(e, "Failed to get branches"))?;
         
-        let is_head = branch.is_head();
-        let is_remote = branch_type == git2::BranchType::Remote;
-        let target = branch.get().target().map(|oid| oid.to_string());
+        let mut git_branches = Vec::new();
         
-        git_branches.push(GitBranch {
-            name,
-            is_head,
-            is_remote,
-            target,
+        for branch_result in branches {
+            let (branch, branch_type) = branch_result.map_err(|e| AppError::wrap(e, "Failed to get branch"))?;
+        
+            let name = branch.name().map_err(|e| AppError::wrap(e, "Failed to get branch name"))?
+                .unwrap_or("Unknown").to_string();
+        
+            let is_head = branch.is_head();
+            let is_remote = branch_type == git2::BranchType::Remote;
+            let target = branch.get().target().map(|oid| oid.to_string());
+        
+            git_branches.push(GitBranch {
+                name,
+                is_head,
+                is_remote,
+                target,
+            });
+        }
+        
+        Ok(git_branches)
+    })
+}
+
+/// One page of references, sorted by name. Only names are read for the whole set;
+/// targets are resolved for the returned page, which keeps repositories with tens of
+/// thousands of refs cheap to browse.
+#[tauri::command]
+fn list_references(
+    repo_path: String,
+    prefix: Option<String>,
+    filter: Option<String>,
+    offset: Option<usize>,
+    limit: Option<usize>,
+) -> Result<ReferencePage, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let glob = format!("{}*", prefix.unwrap_or_else(|| "refs/".to_string()));
+    let mut references = repo.references_glob(&glob)
+        .map_err(|e| AppError::wrap(e, "Failed to get references"))?;
+    
+    let filter = filter.map(|filter| filter.to_lowercase());
+    let mut names: Vec<String> = references
+        .names()
+        .flatten()
+        .filter(|name| filter.as_ref().map(|filter| name.to_lowercase().contains(filter)).unwrap_or(true))
+        .map(|name| name.to_string())
+        .collect();
+    names.sort();
+    
+    let total = names.len();
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(200);
+    
+    let mut page = Vec::new();
+    for name in names.iter().skip(offset).take(limit) {
+        // A ref deleted since the names were listed is simply left out
+        let reference = match repo.find_reference(name) {
+            Ok(reference) => reference,
+            Err(_) => continue,
+        };
+        let kind = if reference.is_branch() {
+            "branch"
+        } else if reference.is_remote() {
+            "remote"
+        } else if reference.is_tag() {
+            "tag"
+        } else {
+            "other"
+        };
+        
+        page.push(GitReference {
+            name: name.clone(),
+            shorthand: reference.shorthand().unwrap_or(name).to_string(),
+            kind: kind.to_string(),
+            target: reference.resolve().ok().and_then(|resolved| resolved.target()).map(|oid| oid.to_string()),
         });
     }
     
-    Ok(git_branches)
+    Ok(ReferencePage {
+        references: page,
+        total,
+        has_more: offset.saturating_add(limit) < total,
+    })
 }
 
-#[tauri::command] 
-fn create_branch(repo_path: String, branch_name: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let head_commit = repo.head()
-        .map_err(|e| format!("Failed to get HEAD: {}", e))?
-        .peel_to_commit()
-        .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
-    
-    repo.branch(&branch_name, &head_commit, false)
-        .map_err(|e| format!("Failed to create branch: {}", e))?;
+#[tauri::command]
+fn create_branch(app: tauri::AppHandle, repo_path: String, branch_name: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "create_branch", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        let head_commit = repo.head()
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD"))?
+            .peel_to_commit()
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))?;
+        
+        repo.branch(&branch_name, &head_commit, false)
+            .map_err(|e| AppError::wrap(e, "Failed to create branch"))?;
+        
+        Ok(format!("Created branch: {}", branch_name))
+    })
+}
+
+/// Checks out `commit`'s tree. "safe" refuses to overwrite local changes, listing the files
+/// in the way; "force" discards them.
+fn checkout_commit_tree(repo: &Repository, commit_id: git2::Oid, strategy: &str, branch_name: &str) -> Result<(), AppError> {
+    let commit = repo.find_commit(commit_id)
+        .map_err(|e| AppError::wrap(e, "Failed to get commit"))?;
+    
+    let mut blocking_paths = Vec::new();
+    let result = {
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        if strategy == "force" {
+            checkout.force();
+        } else {
+            checkout.safe();
+        }
+        checkout
+            .notify_on(git2::CheckoutNotificationType::CONFLICT)
+            .notify(|_, path, _, _, _| {
+                if let Some(path) = path {
+                    blocking_paths.push(path.to_string_lossy().to_string());
+                }
+                true
+            });
+        repo.checkout_tree(commit.as_object(), Some(&mut checkout))
+    };
     
-    Ok(format!("Created branch: {}", branch_name))
+    match result {
+        Err(e) if e.code() == git2::ErrorCode::Conflict && !blocking_paths.is_empty() => Err(AppError::conflict(
+            format!(
+                "Local changes would be overwritten by switching to '{}': {}. Commit or stash them, or switch with autostash",
+                branch_name,
+                blocking_paths.join(", ")
+            ),
+            blocking_paths,
+        )),
+        result => result.map_err(|e| AppError::wrap(e, "Failed to checkout tree")),
+    }
 }
 
+/// Switches to a local branch. `strategy` decides what happens to local changes in the way:
+/// "safe" (the default) refuses, "force" discards them and "merge" carries them over like
+/// `git checkout -m`, leaving conflict markers where they clash. With `autostash` local
+/// changes are stashed first and reapplied afterwards; on conflicts the stash is kept.
 #[tauri::command]
-fn switch_branch(repo_path: String, branch_name: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let branch = repo.find_branch(&branch_name, git2::BranchType::Local)
-        .map_err(|e| format!("Failed to find branch: {}", e))?;
-    
-    let branch_ref = branch.get();
-    let target_commit = branch_ref.peel_to_commit()
-        .map_err(|e| format!("Failed to get commit: {}", e))?;
-    
-    repo.checkout_tree(target_commit.as_object(), None)
-        .map_err(|e| format!("Failed to checkout tree: {}", e))?;
-    
-    repo.set_head(&format!("refs/heads/{}", branch_name))
-        .map_err(|e| format!("Failed to set HEAD: {}", e))?;
-    
-    Ok(format!("Switched to branch: {}", branch_name))
+fn switch_branch(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branch_name: String,
+    strategy: Option<String>,
+    autostash: Option<bool>,
+) -> Result<String, AppError> {
+    audited(&app, &repo_path, "switch_branch", || {
+        let mut repo = open_worktree_repository(&repo_path)?;
+        
+        let strategy = strategy.unwrap_or_else(|| "safe".to_string());
+        if !matches!(strategy.as_str(), "safe" | "force" | "merge") {
+            return Err(AppError::invalid_input(format!("Unknown checkout strategy: {}", strategy)));
+        }
+        let target_id = repo.find_branch(&branch_name, git2::BranchType::Local)
+            .map_err(|e| AppError::wrap(e, "Failed to find branch"))?
+            .get()
+            .peel_to_commit()
+            .map_err(|e| AppError::wrap(e, "Failed to get commit"))?
+            .id();
+        
+        // "merge" and autostash carry the changes over in a stash
+        let carry_changes = (strategy == "merge" || autostash.unwrap_or(false)) && has_uncommitted_changes(&repo)?;
+        if carry_changes {
+            let signature = resolve_signature(&app, &repo, &repo_path, None, None)?;
+            let message = format!("autostash before switching to {}", branch_name);
+            repo.stash_save(&signature, &message, Some(git2::StashFlags::DEFAULT))
+                .map_err(|e| AppError::wrap(e, "Failed to stash local changes"))?;
+        }
+        
+        let checkout_strategy = if strategy == "force" { "force" } else { "safe" };
+        if let Err(e) = checkout_commit_tree(&repo, target_id, checkout_strategy, &branch_name) {
+            if carry_changes {
+                // Still on the old branch: put the changes back where they were, staged ones
+                // included
+                let mut options = git2::StashApplyOptions::new();
+                options.reinstantiate_index();
+                repo.stash_pop(0, Some(&mut options))
+                    .map_err(|e| AppError::wrap(e, "Failed to restore stashed changes; they are in stash@{0}"))?;
+            }
+            return Err(e);
+        }
+        
+        repo.set_head(&format!("refs/heads/{}", branch_name))
+            .map_err(|e| AppError::wrap(e, "Failed to set HEAD"))?;
+        
+        if carry_changes {
+            repo.stash_apply(0, None)
+                .map_err(|e| AppError::wrap(e, "Switched, but failed to reapply local changes; they are in stash@{0}"))?;
+            let index = repo.index()
+                .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+            let conflicts = if index.has_conflicts() { conflicted_paths(&index)? } else { Vec::new() };
+            
+            // `git checkout -m` keeps nothing but the conflict markers; autostash keeps the stash
+            if conflicts.is_empty() || strategy == "merge" {
+                repo.stash_drop(0)
+                    .map_err(|e| AppError::wrap(e, "Failed to drop autostash"))?;
+            }
+            if !conflicts.is_empty() {
+                let kept = if strategy == "merge" { "" } else { "; your changes are also kept in stash@{0}" };
+                return Err(AppError::conflict(
+                    format!("Switched to branch {}, but local changes conflict in: {}{}", branch_name, conflicts.join(", "), kept),
+                    conflicts,
+                ));
+            }
+        }
+        
+        Ok(format!("Switched to branch: {}", branch_name))
+    })
+}
+
+/// Deletes a local branch. Unless `force` is set, only branches whose commits are all in
+/// their upstream (or HEAD, without one) can go, like `git branch -d`.
+#[tauri::command]
+fn delete_branch(app: tauri::AppHandle, repo_path: String, name: String, force: bool) -> Result<String, AppError> {
+    audited(&app, &repo_path, "delete_branch", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        let mut branch = repo.find_branch(&name, git2::BranchType::Local)
+            .map_err(|_| AppError::not_found(format!("Branch '{}' does not exist", name)))?;
+        if branch.is_head() {
+            return Err(AppError::conflict(format!("Cannot delete '{}': it is the current branch", name), Vec::new()));
+        }
+        
+        let ref_name = format!("refs/heads/{}", name);
+        let old_target = ref_target(&repo, &ref_name);
+        if !force {
+            let tip = branch.get().peel_to_commit()
+                .map_err(|e| AppError::wrap(e, "Failed to get branch commit"))?
+                .id();
+            let base = branch.upstream().ok()
+                .and_then(|upstream| upstream.get().target())
+                .or_else(|| repo.head().ok().and_then(|head| head.target()));
+            let merged = base.is_some_and(|base| base == tip || repo.graph_descendant_of(base, tip).unwrap_or(false));
+            if !merged {
+                return Err(AppError::conflict(
+                    format!("Branch '{}' is not fully merged; force the deletion to lose its commits", name),
+                    Vec::new(),
+                ));
+            }
+        }
+        
+        branch.delete()
+            .map_err(|e| AppError::wrap(e, &format!("Failed to delete branch '{}'", name)))?;
+        journal_operation(
+            &app,
+            &repo_path,
+            "delete_branch",
+            format!("deletion of branch '{}'", name),
+            "hard",
+            vec![ref_change(&repo, &ref_name, old_target)],
+        );
+        
+        Ok(format!("Deleted branch: {}", name))
+    })
+}
+
+/// Renames a local branch, keeping its reflog and upstream. The current branch can be
+/// renamed too; HEAD follows it.
+#[tauri::command]
+fn rename_branch(app: tauri::AppHandle, repo_path: String, old_name: String, new_name: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "rename_branch", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        let new_name = new_name.trim();
+        if new_name.is_empty() || !git2::Branch::name_is_valid(new_name).unwrap_or(false) {
+            return Err(AppError::invalid_input(format!("Invalid branch name: {}", new_name)));
+        }
+        let mut branch = repo.find_branch(&old_name, git2::BranchType::Local)
+            .map_err(|_| AppError::not_found(format!("Branch '{}' does not exist", old_name)))?;
+        if repo.find_branch(new_name, git2::BranchType::Local).is_ok() {
+            return Err(AppError::conflict(format!("Branch '{}' already exists", new_name), Vec::new()));
+        }
+        
+        branch.rename(new_name, false)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to rename branch '{}'", old_name)))?;
+        
+        Ok(format!("Renamed branch {} to {}", old_name, new_name))
+    })
+}
+
+/// Makes `branch` track `remote_branch` on `remote`, so pull and push default to it. The
+/// remote branch doesn't have to exist yet, e.g. before the first push.
+#[tauri::command]
+fn set_upstream(app: tauri::AppHandle, repo_path: String, branch: String, remote: String, remote_branch: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "set_upstream", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        repo.find_branch(&branch, git2::BranchType::Local)
+            .map_err(|_| AppError::not_found(format!("Branch '{}' does not exist", branch)))?;
+        repo.find_remote(&remote)
+            .map_err(|_| AppError::not_found(format!("Remote '{}' does not exist", remote)))?;
+        let merge_ref = format!("refs/heads/{}", remote_branch.trim());
+        if remote_branch.trim().is_empty() || !git2::Reference::is_valid_name(&merge_ref) {
+            return Err(AppError::invalid_input(format!("Invalid branch name: {}", remote_branch)));
+        }
+        
+        let mut config = repo.config()
+            .map_err(|e| AppError::wrap(e, "Failed to open repository config"))?;
+        config.set_str(&format!("branch.{}.remote", branch), &remote)
+            .and_then(|_| config.set_str(&format!("branch.{}.merge", branch), &merge_ref))
+            .map_err(|e| AppError::wrap(e, "Failed to set upstream"))?;
+        
+        Ok(format!("Branch '{}' now tracks {}/{}", branch, remote, remote_branch.trim()))
+    })
+}
+
+/// Stops `branch` from tracking a remote branch.
+#[tauri::command]
+fn unset_upstream(app: tauri::AppHandle, repo_path: String, branch: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "unset_upstream", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        repo.find_branch(&branch, git2::BranchType::Local)
+            .map_err(|_| AppError::not_found(format!("Branch '{}' does not exist", branch)))?;
+        
+        let mut config = repo.config()
+            .map_err(|e| AppError::wrap(e, "Failed to open repository config"))?;
+        for key in ["remote", "merge"] {
+            match config.remove(&format!("branch.{}.{}", branch, key)) {
+                Err(e) if e.code() != git2::ErrorCode::NotFound => return Err(AppError::wrap(e, "Failed to unset upstream")),
+                _ => {}
+            }
+        }
+        
+        Ok(format!("Branch '{}' no longer tracks a remote branch", branch))
+    })
 }
 
 #[tauri::command]
-fn get_remotes(repo_path: String) -> Result<Vec<GitRemote>, String> {
+fn get_remotes(repo_path: String) -> Result<Vec<GitRemote>, AppError> {
     let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
     
     let remote_names = repo.remotes()
-        .map_err(|e| format!("Failed to get remotes: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to get remotes"))?;
     
     let mut remotes = Vec::new();
     
     for remote_name in remote_names.iter() {
         if let Some(name) = remote_name {
             let remote = repo.find_remote(name)
-                .map_err(|e| format!("Failed to find remote {}: {}", name, e))?;
+                .map_err(|e| AppError::wrap(e, &format!("Failed to find remote {}", name)))?;
             
             let url = remote.url().unwrap_or("").to_string();
             let fetch_refspecs = remote.fetch_refspecs()
-                .map_err(|e| format!("Failed to get fetch refspecs: {}", e))?
+                .map_err(|e| AppError::wrap(e, "Failed to get fetch refspecs"))?
                 .iter()
                 .filter_map(|s| s.map(|s| s.to_string()))
                 .collect();
             let push_refspecs = remote.push_refspecs()
-                .map_err(|e| format!("Failed to get push refspecs: {}", e))?
+                .map_err(|e| AppError::wrap(e, "Failed to get push refspecs"))?
                 .iter()
                 .filter_map(|s| s.map(|s| s.to_string()))
                 .collect();
@@ -742,25 +1829,29 @@ fn get_remotes(repo_path: String) -> Result<Vec<GitRemote>, String> {
 }
 
 #[tauri::command]
-fn add_remote(repo_path: String, name: String, url: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    repo.remote(&name, &url)
-        .map_err(|e| format!("Failed to add remote: {}", e))?;
-    
-    Ok(format!("Added remote '{}' with URL: {}", name, url))
+fn add_remote(app: tauri::AppHandle, repo_path: String, name: String, url: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "add_remote", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        repo.remote(&name, &url)
+            .map_err(|e| AppError::wrap(e, "Failed to add remote"))?;
+        
+        Ok(format!("Added remote '{}' with URL: {}", name, url))
+    })
 }
 
 #[tauri::command]
-fn remove_remote(repo_path: String, name: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    repo.remote_delete(&name)
-        .map_err(|e| format!("Failed to remove remote: {}", e))?;
-    
-    Ok(format!("Removed remote: {}", name))
+fn remove_remote(app: tauri::AppHandle, repo_path: String, name: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "remove_remote", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        repo.remote_delete(&name)
+            .map_err(|e| AppError::wrap(e, "Failed to remove remote"))?;
+        
+        Ok(format!("Removed remote: {}", name))
+    })
 }
 
 fn get_credentials_callback() -> RemoteCallbacks<'static> {
@@ -773,25 +1864,26 @@ fn get_credentials_callback() -> RemoteCallbacks<'static> {
             let ssh_pub_key_path = format!("{}/.ssh/id_rsa.pub", home);
             
             if Path::new(&ssh_key_path).exists() {
+                let passphrase = load_secret(SSH_PASSPHRASE_SECRET).ok().flatten();
                 return Cred::ssh_key(
                     username_from_url.unwrap_or("git"),
                     Some(Path::new(&ssh_pub_key_path)),
                     Path::new(&ssh_key_path),
-                    None
+                    passphrase.as_deref()
                 );
             }
         }
         
-        // Fallback to username/password from environment or prompt
-        if let (Ok(username), Ok(password)) = (env::var("GIT_USERNAME"), env::var("GIT_PASSWORD")) {
-            return Cred::userpass_plaintext(&username, &password);
+        // Fallback to username/password from the secrets store
+        let password = load_secret(GIT_PASSWORD_SECRET).ok().flatten();
+        let username = load_secret(GIT_USERNAME_SECRET).ok().flatten();
+        if let (Some(username), Some(password)) = (&username, &password) {
+            return Cred::userpass_plaintext(username, password);
         }
         
         // Try default username if provided
-        if let Some(username) = username_from_url {
-            if let Ok(password) = env::var("GIT_PASSWORD") {
-                return Cred::userpass_plaintext(username, &password);
-            }
+        if let (Some(username), Some(password)) = (username_from_url, &password) {
+            return Cred::userpass_plaintext(username, password);
         }
         
         Cred::default()
@@ -800,128 +1892,539 @@ fn get_credentials_callback() -> RemoteCallbacks<'static> {
     callbacks
 }
 
+/// Builds a system `git` invocation that never prompts on the terminal.
+fn git_cli(repo_path: Option<&str>) -> std::process::Command {
+    let mut command = std::process::Command::new("git");
+    command.env("GIT_TERMINAL_PROMPT", "0");
+    if let Some(repo_path) = repo_path {
+        command.current_dir(repo_path);
+    }
+    
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW: don't flash a console window from the GUI
+        command.creation_flags(0x08000000);
+    }
+    
+    command
+}
+
+/// Runs a git CLI command and returns stdout, turning a non-zero exit into an error with stderr.
+fn run_git(repo_path: &str, args: &[&str], action: &str) -> Result<String, String> {
+    let output = git_cli(Some(repo_path))
+        .args(args)
+        .output()
+        .map_err(|e| format!("Failed to run git: {}", e))?;
+    
+    if !output.status.success() {
+        return Err(format!(
+            "Failed to {}: {}",
+            action,
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Path of the user's global git config (~/.gitconfig), even if it does not exist yet.
+fn global_git_config_path() -> Result<PathBuf, String> {
+    if let Ok(path) = git2::Config::find_global() {
+        return Ok(path);
+    }
+    
+    let home = env::var("HOME")
+        .or_else(|_| env::var("USERPROFILE"))
+        .map_err(|_| "Failed to locate the home directory".to_string())?;
+    
+    Ok(PathBuf::from(home).join(".gitconfig"))
+}
+
+/// The user's global config file, created on first write if it does not exist yet.
+fn global_git_config() -> Result<git2::Config, String> {
+    git2::Config::open(&global_git_config_path()?)
+        .map_err(|e| format!("Failed to open global config: {}", e))
+}
+
+/// Credential callbacks that also report transfer progress as `event` to the frontend.
+fn progress_callbacks(app: &tauri::AppHandle, event: &str, target: &str) -> RemoteCallbacks<'static> {
+    let mut callbacks = get_credentials_callback();
+    let app = app.clone();
+    let event = event.to_string();
+    let target = target.to_string();
+    let mut last_percent = None;
+    
+    callbacks.transfer_progress(move |stats| {
+        let total = stats.total_objects();
+        let percent = (stats.received_objects() * 100).checked_div(total).unwrap_or(0);
+        
+        // Only emit when the percentage moves to avoid flooding the IPC channel
+        if last_percent != Some(percent) {
+            last_percent = Some(percent);
+            let _ = app.emit_all(&event, TransferProgress {
+                target: target.clone(),
+                received_objects: stats.received_objects(),
+                indexed_objects: stats.indexed_objects(),
+                total_objects: total,
+                received_bytes: stats.received_bytes(),
+            });
+        }
+        true
+    });
+    
+    callbacks
+}
+
+/// Credential callbacks whose transfers abort once `token` is cancelled.
+fn cancellable_callbacks(token: &OperationToken) -> RemoteCallbacks<'static> {
+    let mut callbacks = get_credentials_callback();
+    
+    let transfer_token = token.clone();
+    callbacks.transfer_progress(move |_| !transfer_token.is_cancelled());
+    let sideband_token = token.clone();
+    callbacks.sideband_progress(move |_| !sideband_token.is_cancelled());
+    
+    callbacks
+}
+
 #[tauri::command]
-fn fetch_from_remote(repo_path: String, remote_name: String) -> Result<String, String> {
+async fn fetch_from_remote(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: Option<String>,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "fetch", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "fetch_from_remote", || {
+            fetch_from_remote_blocking(task_app.clone(), repo_path, remote_name, token)
+        })
+    })
+    .await
+}
+
+fn fetch_from_remote_blocking(app: tauri::AppHandle, repo_path: String, remote_name: Option<String>, token: OperationToken) -> Result<String, AppError> {
     let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let remote_name = remote_name.unwrap_or_else(|| default_remote_name(&app, &repo_path));
     
     let mut remote = repo.find_remote(&remote_name)
-        .map_err(|e| format!("Failed to find remote '{}': {}", remote_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
     
-    let callbacks = get_credentials_callback();
+    let host = remote.url().and_then(remote_host);
+    let callbacks = cancellable_callbacks(&token);
     let mut fetch_options = FetchOptions::new();
     fetch_options.remote_callbacks(callbacks);
     
     remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)
-        .map_err(|e| format!("Failed to fetch from remote '{}': {}", remote_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to fetch from remote '{}'", remote_name)).with_host(host))?;
+    extend_commit_cache(&app, &repo, &repo_path);
+    refresh_commit_graph(&repo, &repo_path);
     
     Ok(format!("Successfully fetched from remote: {}", remote_name))
 }
 
+const AUTO_FETCH_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Fetches each repository whose settings set auto_fetch_interval once per interval. Runs
+/// for the lifetime of the app; a failed fetch is retried at the next interval.
+fn auto_fetch_loop(app: tauri::AppHandle) {
+    let mut last_fetched: HashMap<String, Instant> = HashMap::new();
+    loop {
+        std::thread::sleep(AUTO_FETCH_CHECK_INTERVAL);
+        let intervals = match app.try_state::<DatabaseState>() {
+            Some(db_state) => tauri::async_runtime::block_on(async {
+                let db = db_state.lock().await;
+                db.get_auto_fetch_intervals().await
+            }),
+            None => continue,
+        };
+        let intervals = match intervals {
+            Ok(intervals) => intervals,
+            Err(e) => {
+                println!("⚠️  Failed to load auto-fetch settings: {}", e);
+                continue;
+            }
+        };
+        
+        for (repo_path, minutes) in intervals {
+            let interval = Duration::from_secs(minutes.max(1) as u64 * 60);
+            if last_fetched.get(&repo_path).is_some_and(|fetched| fetched.elapsed() < interval) {
+                continue;
+            }
+            last_fetched.insert(repo_path.clone(), Instant::now());
+            if let Err(e) = audited(&app, &repo_path, "auto_fetch", || {
+                fetch_from_remote_blocking(app.clone(), repo_path.clone(), None, OperationToken::default())
+            }) {
+                println!("⚠️  Auto-fetch of {} failed: {}", repo_path, e.message);
+            }
+        }
+    }
+}
+
+// Enough to overlap network latency without saturating the connection or the disk
+const ORGANIZATION_FETCH_CONCURRENCY: usize = 4;
+
+/// Fetches every repository of an organization in parallel. Failures are reported per
+/// repository instead of aborting the batch.
 #[tauri::command]
-fn pull_from_remote(repo_path: String, remote_name: String, branch_name: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+async fn fetch_organization(
+    app: tauri::AppHandle,
+    db_state: tauri::State<'_, DatabaseState>,
+    org_id: String,
+    operation_id: Option<String>
+) -> Result<OrganizationFetchResult, AppError> {
+    let repositories = {
+        let db = db_state.lock().await;
+        db.get_repositories_by_organization(&org_id)
+            .await
+            .map_err(|e| AppError::wrap(e, "Failed to get repositories"))?
+    };
+    
+    let task_app = app.clone();
+    run_operation(&app, "fetch", operation_id, move |token| {
+        fetch_organization_blocking(task_app, org_id, repositories, token)
+    })
+    .await
+}
+
+fn fetch_organization_blocking(
+    app: tauri::AppHandle,
+    org_id: String,
+    repositories: Vec<database::Repository>,
+    token: OperationToken,
+) -> Result<OrganizationFetchResult, String> {
+    use std::sync::{Arc, Mutex};
+    
+    let total = repositories.len();
+    let queue = Arc::new(Mutex::new(repositories.into_iter().collect::<std::collections::VecDeque<_>>()));
+    let results = Arc::new(Mutex::new(Vec::new()));
+    
+    let workers: Vec<_> = (0..ORGANIZATION_FETCH_CONCURRENCY.min(total))
+        .map(|_| {
+            let (app, org_id, token) = (app.clone(), org_id.clone(), token.clone());
+            let (queue, results) = (queue.clone(), results.clone());
+            std::thread::spawn(move || loop {
+                if token.is_cancelled() {
+                    return;
+                }
+                let repository = match queue.lock().ok().and_then(|mut queue| queue.pop_front()) {
+                    Some(repository) => repository,
+                    None => return,
+                };
+                
+                let outcome = audited(&app, &repository.path, "fetch_from_remote", || {
+                    fetch_from_remote_blocking(app.clone(), repository.path.clone(), None, token.clone())
+                });
+                let result = RepositoryFetchResult {
+                    repository_id: repository.id,
+                    name: repository.name,
+                    path: repository.path,
+                    success: outcome.is_ok(),
+                    message: outcome.unwrap_or_else(|e| e.message),
+                };
+                
+                let completed = match results.lock() {
+                    Ok(mut results) => {
+                        results.push(result.clone());
+                        results.len()
+                    }
+                    Err(_) => return,
+                };
+                let _ = app.emit_all("organization-fetch-progress", OrganizationFetchProgress {
+                    organization_id: org_id.clone(),
+                    completed,
+                    total,
+                    result,
+                });
+            })
+        })
+        .collect();
+    
+    for worker in workers {
+        let _ = worker.join();
+    }
+    token.check()?;
+    
+    let results = results.lock()
+        .map(|results| results.clone())
+        .map_err(|_| "Failed to collect fetch results".to_string())?;
+    let succeeded = results.iter().filter(|result| result.success).count();
+    
+    Ok(OrganizationFetchResult {
+        organization_id: org_id,
+        total,
+        succeeded,
+        failed: results.len() - succeeded,
+        results,
+    })
+}
+
+#[tauri::command]
+async fn pull_from_remote(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: Option<String>,
+    branch_name: String,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "pull", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "pull_from_remote", || {
+            pull_from_remote_blocking(task_app.clone(), repo_path, remote_name, branch_name, token)
+        })
+    })
+    .await
+}
+
+fn pull_from_remote_blocking(app: tauri::AppHandle, repo_path: String, remote_name: Option<String>, branch_name: String, token: OperationToken) -> Result<String, AppError> {
+    let repo = open_worktree_repository(&repo_path)?;
+    
+    let settings = repo_settings_for(&app, &repo_path);
+    let remote_name = remote_name
+        .or_else(|| tracked_remote_name(&repo, &branch_name))
+        .unwrap_or_else(|| default_remote_name(&app, &repo_path));
+    let upstream = upstream_branch_name(&repo, &branch_name, &remote_name).unwrap_or_else(|| branch_name.clone());
     
     // First fetch
     let mut remote = repo.find_remote(&remote_name)
-        .map_err(|e| format!("Failed to find remote '{}': {}", remote_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
     
-    let callbacks = get_credentials_callback();
+    let host = remote.url().and_then(remote_host);
+    let callbacks = cancellable_callbacks(&token);
     let mut fetch_options = FetchOptions::new();
     fetch_options.remote_callbacks(callbacks);
     
     remote.fetch(&[] as &[&str], Some(&mut fetch_options), None)
-        .map_err(|e| format!("Failed to fetch from remote '{}': {}", remote_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to fetch from remote '{}'", remote_name)).with_host(host))?;
+    extend_commit_cache(&app, &repo, &repo_path);
+    refresh_commit_graph(&repo, &repo_path);
+    // Last point where cancelling leaves the working tree untouched
+    token.check()?;
     
     // Get the remote branch reference
-    let remote_branch_name = format!("refs/remotes/{}/{}", remote_name, branch_name);
+    let remote_branch_name = format!("refs/remotes/{}/{}", remote_name, upstream);
     let remote_branch_ref = repo.find_reference(&remote_branch_name)
-        .map_err(|e| format!("Failed to find remote branch '{}': {}", remote_branch_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote branch '{}'", remote_branch_name)))?;
     
     let remote_commit = remote_branch_ref.peel_to_commit()
-        .map_err(|e| format!("Failed to get remote commit: {}", e))?;
-    
-    // Get current head
-    let head = repo.head()
-        .map_err(|e| format!("Failed to get HEAD: {}", e))?;
-    let current_commit = head.peel_to_commit()
-        .map_err(|e| format!("Failed to get current commit: {}", e))?;
-    
-    // Check if fast-forward is possible
-    let merge_base = repo.merge_base(current_commit.id(), remote_commit.id())
-        .map_err(|e| format!("Failed to find merge base: {}", e))?;
-    
-    if merge_base == current_commit.id() {
-        // Fast-forward merge
-        let mut reference = repo.find_reference(&format!("refs/heads/{}", branch_name))
-            .map_err(|e| format!("Failed to find local branch: {}", e))?;
-        
-        reference.set_target(remote_commit.id(), "Fast-forward merge")
-            .map_err(|e| format!("Failed to update branch reference: {}", e))?;
-        
-        repo.checkout_tree(remote_commit.as_object(), None)
-            .map_err(|e| format!("Failed to checkout: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to get remote commit"))?;
+    
+    // The pulled branch need not be checked out: its own tip decides, and only a
+    // checked-out branch touches the working tree
+    let branch_ref_name = format!("refs/heads/{}", branch_name);
+    let branch_commit = repo.find_reference(&branch_ref_name)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(|e| AppError::wrap(e, "Failed to find local branch"))?;
+    let is_checked_out = head_ref_name(&repo) == branch_ref_name;
+    
+    let merge_base = repo.merge_base(branch_commit.id(), remote_commit.id())
+        .map_err(|e| AppError::wrap(e, "Failed to find merge base"))?;
+    
+    if merge_base == remote_commit.id() {
+        Ok(format!("Branch '{}' is already up to date with '{}/{}'", branch_name, remote_name, upstream))
+    } else if merge_base == branch_commit.id() {
+        // Check out first: a safe checkout refuses to overwrite local changes, and the
+        // branch must not move if it does
+        if is_checked_out {
+            repo.checkout_tree(remote_commit.as_object(), None)
+                .map_err(|e| AppError::wrap(e, "Failed to checkout"))?;
+        }
+        repo.reference(&branch_ref_name, remote_commit.id(), true, &format!("pull: Fast-forward from {}/{}", remote_name, upstream))
+            .map_err(|e| AppError::wrap(e, "Failed to update branch reference"))?;
+        journal_operation(
+            &app,
+            &repo_path,
+            "pull",
+            format!("fast-forward '{}' to '{}/{}'", branch_name, remote_name, upstream),
+            "hard",
+            vec![ref_change(&repo, &branch_ref_name, Some(branch_commit.id().to_string()))],
+        );
         
         Ok(format!("Successfully pulled and fast-forwarded branch '{}' from '{}'", branch_name, remote_name))
+    } else if settings.pull_strategy == "ff-only" {
+        Err(AppError::conflict(
+            format!("Cannot fast-forward '{}': it has diverged from '{}/{}'", branch_name, remote_name, upstream),
+            Vec::new(),
+        ))
+    } else if !is_checked_out {
+        Err(AppError::invalid_input(format!(
+            "Check out '{}' to pull into it with {}",
+            branch_name, settings.pull_strategy
+        )))
+    } else if settings.pull_strategy == "rebase" {
+        // Like `git pull --rebase`: the local commits are replayed linearly, merges dropped
+        let mut plan = prepare_interactive_rebase(repo_path.clone(), remote_branch_name, merge_base.to_string(), Some(false))?;
+        plan.commits.retain(|entry| {
+            git2::Oid::from_str(&entry.id)
+                .and_then(|oid| repo.find_commit(oid))
+                .map(|commit| commit.parent_count() <= 1)
+                .unwrap_or(true)
+        });
+        commands::rebase::start_interactive_rebase(&app, &repo_path, plan, None, None, &token)
     } else {
-        Ok(format!("Pull completed with fetch. Manual merge may be required."))
+        let target = repo.reference_to_annotated_commit(&remote_branch_ref)
+            .map_err(|e| AppError::wrap(e, "Failed to get remote commit"))?;
+        merge_into_head(&app, &repo_path, &repo, &target, &format!("{}/{}", remote_name, upstream), None, None)
     }
 }
 
 #[tauri::command]
-fn push_to_remote(repo_path: String, remote_name: String, branch_name: String) -> Result<String, String> {
+async fn push_to_remote(
+    app: tauri::AppHandle,
+    repo_path: String,
+    remote_name: Option<String>,
+    branch_name: String,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "push", operation_id, move |token| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "push_to_remote", || {
+            push_to_remote_blocking(task_app.clone(), repo_path, remote_name, branch_name, token)
+        })
+    })
+    .await
+}
+
+fn push_to_remote_blocking(app: tauri::AppHandle, repo_path: String, remote_name: Option<String>, branch_name: String, token: OperationToken) -> Result<String, AppError> {
     let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let settings = repo_settings_for(&app, &repo_path);
+    let remote_name = remote_name
+        .or_else(|| tracked_remote_name(&repo, &branch_name))
+        .unwrap_or_else(|| default_remote_name(&app, &repo_path));
+    
+    // "gerrit" uploads the branch for review instead of updating it
+    if settings.push_behavior == "gerrit" {
+        let result = push_for_review_blocking(&app, &repo_path, Some(remote_name), &branch_name, &GerritPushOptions::default(), &token)?;
+        return Ok(match result.change_urls.first() {
+            Some(url) => format!("Uploaded branch '{}' for review: {}", branch_name, url),
+            None => format!("Uploaded branch '{}' for review to {}", branch_name, result.target_ref),
+        });
+    }
     
     let mut remote = repo.find_remote(&remote_name)
-        .map_err(|e| format!("Failed to find remote '{}': {}", remote_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find remote '{}'", remote_name)))?;
     
-    let callbacks = get_credentials_callback();
+    let host = remote.url().and_then(remote_host);
+    let callbacks = cancellable_callbacks(&token);
     let mut push_options = PushOptions::new();
     push_options.remote_callbacks(callbacks);
     
-    let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
+    // "upstream" pushes to the branch's tracking branch name, "current" to the same name.
+    // Like push.default=simple, "current" refuses when the upstream has another name
+    // rather than creating a second branch next to it.
+    let upstream = upstream_branch_name(&repo, &branch_name, &remote_name);
+    let remote_branch = match (settings.push_behavior.as_str(), upstream) {
+        ("upstream", Some(upstream)) => upstream,
+        (_, Some(upstream)) if upstream != branch_name => {
+            return Err(AppError::conflict(
+                format!(
+                    "'{}' tracks '{}/{}', which has a different name; set the push behavior to upstream to push there",
+                    branch_name, remote_name, upstream
+                ),
+                Vec::new(),
+            ));
+        }
+        _ => branch_name.clone(),
+    };
+    let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, remote_branch);
     
+    // Push progress callbacks can't abort the upload, only the negotiation before it
+    token.check()?;
     remote.push(&[&refspec], Some(&mut push_options))
-        .map_err(|e| format!("Failed to push to remote '{}': {}", remote_name, e))?;
+        .map_err(|e| AppError::wrap(e, &format!("Failed to push to remote '{}'", remote_name)).with_host(host))?;
     
     Ok(format!("Successfully pushed branch '{}' to remote '{}'", branch_name, remote_name))
 }
 
-#[tauri::command]
-fn clone_repository(url: String, path: String) -> Result<String, String> {
-    let callbacks = get_credentials_callback();
-    let mut fetch_options = FetchOptions::new();
-    fetch_options.remote_callbacks(callbacks);
-    
-    let mut builder = git2::build::RepoBuilder::new();
-    builder.fetch_options(fetch_options);
-    
-    builder.clone(&url, Path::new(&path))
-        .map_err(|e| format!("Failed to clone repository: {}", e))?;
-    
-    Ok(format!("Successfully cloned repository to: {}", path))
+/// Remote used when none is given: the repository setting, otherwise "origin".
+fn default_remote_name(app: &tauri::AppHandle, repo_path: &str) -> String {
+    repo_settings_for(app, repo_path)
+        .default_remote
+        .unwrap_or_else(|| "origin".to_string())
+}
+
+/// The remote a local branch tracks (branch.<name>.remote), if any.
+fn tracked_remote_name(repo: &Repository, branch_name: &str) -> Option<String> {
+    repo.config().ok()?
+        .get_string(&format!("branch.{}.remote", branch_name))
+        .ok()
+        .filter(|remote| remote != ".") // A local branch as upstream
+}
+
+/// Name of the branch's upstream on `remote_name`, e.g. "main" for origin/main. Read from
+/// the config, so it is known before the remote branch has been fetched.
+fn upstream_branch_name(repo: &Repository, branch_name: &str, remote_name: &str) -> Option<String> {
+    let upstream_name = repo.branch_upstream_name(&format!("refs/heads/{}", branch_name)).ok()?;
+    upstream_name
+        .as_str()?
+        .strip_prefix(&format!("refs/remotes/{}/", remote_name))
+        .map(|name| name.to_string())
 }
 
 #[tauri::command]
-fn create_stash(repo_path: String, message: String, author_name: String, author_email: String) -> Result<String, String> {
-    let mut repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+async fn clone_repository(
+    app: tauri::AppHandle,
+    url: String,
+    path: String,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "clone", operation_id, move |token| {
+        let audit_path = path.clone();
+        audited(&task_app, &audit_path, "clone_repository", || {
+            clone_repository_blocking(task_app.clone(), url, path, token)
+        })
+    })
+    .await
+}
+
+fn clone_repository_blocking(app: tauri::AppHandle, url: String, path: String, token: OperationToken) -> Result<String, AppError> {
+    let callbacks = cancellable_callbacks(&token);
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(callbacks);
     
-    let signature = Signature::now(&author_name, &author_email)
-        .map_err(|e| format!("Failed to create signature: {}", e))?;
+    let mut builder = git2::build::RepoBuilder::new();
+    builder.fetch_options(fetch_options);
+    
+    let repo = builder.clone(&url, Path::new(&path))
+        .map_err(|e| AppError::wrap(e, "Failed to clone repository").with_host(remote_host(&url)))?;
     
-    let stash_id = repo.stash_save(&signature, &message, Some(git2::StashFlags::DEFAULT))
-        .map_err(|e| format!("Failed to create stash: {}", e))?;
+    // Without this an LFS repository only contains pointer files after cloning
+    if !lfs_tracked_patterns(&repo)?.is_empty() {
+        if let Err(e) = pull_lfs_objects(&app, &path) {
+            return Ok(format!("Cloned repository to: {} (LFS files not downloaded: {})", path, e));
+        }
+    }
     
-    Ok(format!("Created stash: {}", stash_id))
+    Ok(format!("Successfully cloned repository to: {}", path))
 }
 
 #[tauri::command]
-fn get_stashes(repo_path: String) -> Result<Vec<GitStash>, String> {
+fn create_stash(app: tauri::AppHandle, repo_path: String, message: String, author_name: Option<String>, author_email: Option<String>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "create_stash", || {
+        let mut repo = open_worktree_repository(&repo_path)?;
+        
+        let signature = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        
+        let stash_id = repo.stash_save(&signature, &message, Some(git2::StashFlags::DEFAULT))
+            .map_err(|e| AppError::wrap(e, "Failed to create stash"))?;
+        
+        Ok(format!("Created stash: {}", stash_id))
+    })
+}
+
+#[tauri::command]
+fn get_stashes(repo_path: String) -> Result<Vec<GitStash>, AppError> {
     let mut repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
     
     let mut stashes = Vec::new();
     let mut temp_stashes = Vec::new();
@@ -930,7 +2433,7 @@ fn get_stashes(repo_path: String) -> Result<Vec<GitStash>, String> {
     repo.stash_foreach(|index, message, oid| {
         temp_stashes.push((index, message.to_string(), *oid));
         true
-    }).map_err(|e| format!("Failed to iterate stashes: {}", e))?;
+    }).map_err(|e| AppError::wrap(e, "Failed to iterate stashes"))?;
     
     // Then, process each stash to get commit details
     for (index, message, oid) in temp_stashes {
@@ -949,182 +2452,760 @@ fn get_stashes(repo_path: String) -> Result<Vec<GitStash>, String> {
 }
 
 #[tauri::command]
-fn apply_stash(repo_path: String, index: usize) -> Result<String, String> {
-    let mut repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+fn apply_stash(app: tauri::AppHandle, repo_path: String, index: usize) -> Result<String, AppError> {
+    audited(&app, &repo_path, "apply_stash", || {
+        let mut repo = open_worktree_repository(&repo_path)?;
+        
+        repo.stash_apply(index, None)
+            .map_err(|e| AppError::wrap(e, "Failed to apply stash"))?;
+        
+        Ok(format!("Applied stash at index: {}", index))
+    })
+}
+
+#[tauri::command]
+fn drop_stash(app: tauri::AppHandle, repo_path: String, index: usize) -> Result<String, AppError> {
+    audited(&app, &repo_path, "drop_stash", || {
+        let mut repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        let mut dropped_stash = None;
+        repo.stash_foreach(|stash_index, _, oid| {
+            if stash_index == index {
+                dropped_stash = Some(oid.to_string());
+            }
+            true
+        }).map_err(|e| AppError::wrap(e, "Failed to iterate stashes"))?;
+        
+        repo.stash_drop(index)
+            .map_err(|e| AppError::wrap(e, "Failed to drop stash"))?;
+        
+        journal_operation(
+            &app,
+            &repo_path,
+            "stash_drop",
+            format!("drop of stash@{{{}}}", index),
+            "stash",
+            vec![ref_change(&repo, "refs/stash", dropped_stash)],
+        );
+        
+        Ok(format!("Dropped stash at index: {}", index))
+    })
+}
+
+#[tauri::command]
+async fn merge_branch(
+    app: tauri::AppHandle,
+    repo_path: String,
+    branch_name: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "merge", operation_id, move |_| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "merge_branch", || {
+            merge_branch_blocking(task_app.clone(), repo_path, branch_name, author_name, author_email)
+        })
+    })
+    .await
+}
+
+fn merge_branch_blocking(app: tauri::AppHandle, repo_path: String, branch_name: String, author_name: Option<String>, author_email: Option<String>) -> Result<String, AppError> {
+    let repo = open_worktree_repository(&repo_path)?;
+    
+    let target_branch = repo.find_branch(&branch_name, git2::BranchType::Local)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to find branch '{}'", branch_name)))?;
+    let target = repo.reference_to_annotated_commit(target_branch.get())
+        .map_err(|e| AppError::wrap(e, "Failed to get target commit"))?;
+    
+    merge_into_head(&app, &repo_path, &repo, &target, &branch_name, author_name, author_email)
+}
+
+/// Merges `target` (named `branch_name` in messages) into HEAD: fast-forwards when merge.ff
+/// allows it, otherwise commits a three-way merge or stops on conflicts.
+fn merge_into_head(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    repo: &Repository,
+    target: &git2::AnnotatedCommit,
+    branch_name: &str,
+    author_name: Option<String>,
+    author_email: Option<String>,
+) -> Result<String, AppError> {
+    ensure_no_operation_in_progress(repo)?;
+    
+    let updated_ref = head_ref_name(repo);
+    let old_target = ref_target(repo, &updated_ref);
+    
+    // merge.ff in git config decides between fast-forwarding and a merge commit
+    let (analysis, preference) = repo.merge_analysis(&[target])
+        .map_err(|e| AppError::wrap(e, "Failed to analyze merge"))?;
+    
+    if analysis.is_up_to_date() {
+        return Ok(format!("Already up to date with '{}'", branch_name));
+    }
+    if analysis.is_unborn() {
+        return Err(AppError::invalid_input("Cannot merge into a branch without commits"));
+    }
+    
+    create_backup_ref(repo, "merge")?;
+    
+    if analysis.is_fast_forward() && !preference.is_no_fast_forward() {
+        let target_commit = repo.find_commit(target.id())
+            .map_err(|e| AppError::wrap(e, "Failed to get target commit"))?;
+        
+        // Check out first: a safe checkout refuses to overwrite local changes, and the
+        // branch must not move if it does
+        repo.checkout_tree(target_commit.as_object(), None)
+            .map_err(|e| AppError::wrap(e, "Failed to checkout"))?;
+        
+        repo.reference(
+            &updated_ref,
+            target_commit.id(),
+            true,
+            &format!("Fast-forward merge of {}", branch_name)
+        ).map_err(|e| AppError::wrap(e, "Failed to update HEAD"))?;
+        
+        journal_operation(
+            app,
+            repo_path,
+            "merge",
+            format!("fast-forward merge of '{}'", branch_name),
+            "hard",
+            vec![ref_change(repo, &updated_ref, old_target)],
+        );
+        
+        return Ok(format!("Fast-forward merged branch '{}'", branch_name));
+    }
+    if preference.is_fastforward_only() {
+        return Err(AppError::conflict(
+            format!("'{}' cannot be fast-forwarded and merge.ff is set to only", branch_name),
+            Vec::new(),
+        ));
+    }
     
-    repo.stash_apply(index, None)
-        .map_err(|e| format!("Failed to apply stash: {}", e))?;
+    // Three-way merge into the index and working tree; writes MERGE_HEAD and MERGE_MSG
+    repo.merge(&[target], None, None)
+        .map_err(|e| AppError::wrap(e, &format!("Failed to merge '{}'", branch_name)))?;
     
-    Ok(format!("Applied stash at index: {}", index))
+    let index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+    if index.has_conflicts() {
+        // The conflicted entries stay in the index; resolve them, then merge_continue
+        let conflicted = conflicted_paths(&index)?;
+        return Err(AppError::conflict(
+            format!("Merge of '{}' stopped due to conflicts in: {}", branch_name, conflicted.join(", ")),
+            conflicted,
+        ));
+    }
+    
+    let signature = resolve_signature(app, repo, repo_path, author_name, author_email)?;
+    let merge_commit = commit_merge(app, repo_path, repo, &signature)?;
+    
+    Ok(format!("Merged branch '{}' with commit {}", branch_name, merge_commit))
 }
 
+/// Commits the index of a merge in progress with HEAD and MERGE_HEAD as parents, using
+/// MERGE_MSG, and clears the merge state.
+fn commit_merge(app: &tauri::AppHandle, repo_path: &str, repo: &Repository, signature: &Signature) -> Result<git2::Oid, AppError> {
+    let mut index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+    if index.has_conflicts() {
+        let conflicted = conflicted_paths(&index)?;
+        return Err(AppError::conflict(
+            format!("Resolve the remaining conflicts first: {}", conflicted.join(", ")),
+            conflicted,
+        ));
+    }
+    
+    let head_commit = repo.head()
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD"))?
+        .peel_to_commit()
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))?;
+    // One line per merged commit; mergehead_foreach would need the repository mutably
+    let merge_heads = fs::read_to_string(repo.path().join("MERGE_HEAD"))
+        .map_err(|e| AppError::wrap(e, "Failed to read MERGE_HEAD"))?;
+    let merged: Vec<git2::Commit> = merge_heads
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| git2::Oid::from_str(line.trim()).and_then(|oid| repo.find_commit(oid)))
+        .collect::<Result<_, _>>()
+        .map_err(|e| AppError::wrap(e, "Failed to find merged commit"))?;
+    let mut parents = vec![&head_commit];
+    parents.extend(merged.iter());
+    
+    let tree_id = index.write_tree()
+        .map_err(|e| AppError::wrap(e, "Failed to write tree"))?;
+    let tree = repo.find_tree(tree_id)
+        .map_err(|e| AppError::wrap(e, "Failed to find tree"))?;
+    
+    let message = prepared_message(repo)
+        .unwrap_or_else(|_| "Merge".to_string());
+    let message = with_sign_off(app, repo_path, &message, signature);
+    
+    let updated_ref = head_ref_name(repo);
+    let old_target = ref_target(repo, &updated_ref);
+    let signing = signing_config(app, repo, repo_path, "commit.gpgsign");
+    let merge_commit = create_commit(
+        repo,
+        signing.as_ref(),
+        Some("HEAD"),
+        signature,
+        signature,
+        &message,
+        &tree,
+        &parents
+    ).map_err(|e| AppError::wrap(e, "Failed to create merge commit"))?;
+    
+    repo.cleanup_state()
+        .map_err(|e| AppError::wrap(e, "Failed to clean up merge state"))?;
+    
+    journal_operation(
+        app,
+        repo_path,
+        "merge",
+        format!("merge \"{}\"", message.lines().next().unwrap_or("")),
+        "hard",
+        vec![ref_change(repo, &updated_ref, old_target)],
+    );
+    extend_commit_cache(app, repo, repo_path);
+    
+    Ok(merge_commit)
+}
+
+/// Finishes a merge that stopped on conflicts, once they are resolved.
 #[tauri::command]
-fn drop_stash(repo_path: String, index: usize) -> Result<String, String> {
-    let mut repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+fn merge_continue(app: tauri::AppHandle, repo_path: String, author_name: Option<String>, author_email: Option<String>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "merge_continue", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        if repo.state() != git2::RepositoryState::Merge {
+            return Err(AppError::conflict("No merge in progress", Vec::new()));
+        }
+        
+        let signature = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        let merge_commit = commit_merge(&app, &repo_path, &repo, &signature)?;
+        
+        Ok(format!("Merged with commit {}", merge_commit))
+    })
+}
+
+/// Backs out of a merge that stopped on conflicts: the index and working tree go back to
+/// HEAD and MERGE_HEAD is removed.
+#[tauri::command]
+fn abort_merge(app: tauri::AppHandle, repo_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "abort_merge", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        if repo.state() != git2::RepositoryState::Merge {
+            return Err(AppError::conflict("No merge in progress", Vec::new()));
+        }
+        
+        abort_sequencer(&repo)?;
+        
+        Ok("Merge aborted".to_string())
+    })
+}
+
+/// Backs out of a rebase in progress; see rebase_abort.
+#[tauri::command]
+fn abort_rebase(app: tauri::AppHandle, repo_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "abort_rebase", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        abort_rebase_in_progress(&repo, &repo_path)
+    })
+}
+
+#[tauri::command]
+fn get_merge_conflicts(repo_path: String) -> Result<Vec<MergeConflict>, AppError> {
+    let repo = open_worktree_repository(&repo_path)?;
     
-    repo.stash_drop(index)
-        .map_err(|e| format!("Failed to drop stash: {}", e))?;
+    let index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+    
+    let mut conflicts = Vec::new();
+    
+    if index.has_conflicts() {
+        let conflict_iter = index.conflicts()
+            .map_err(|e| AppError::wrap(e, "Failed to get conflicts"))?;
+        
+        for conflict in conflict_iter {
+            let conflict = conflict.map_err(|e| AppError::wrap(e, "Failed to get conflict entry"))?;
+            
+            if let (Some(ancestor), Some(our), Some(their)) = (&conflict.ancestor, &conflict.our, &conflict.their) {
+                let file_path = String::from_utf8_lossy(&our.path).to_string();
+                
+                let ancestor_blob = repo.find_blob(ancestor.id)
+                    .map_err(|e| AppError::wrap(e, "Failed to find ancestor blob"))?;
+                let our_blob = repo.find_blob(our.id)
+                    .map_err(|e| AppError::wrap(e, "Failed to find our blob"))?;
+                let their_blob = repo.find_blob(their.id)
+                    .map_err(|e| AppError::wrap(e, "Failed to find their blob"))?;
+                
+                conflicts.push(MergeConflict {
+                    file_path,
+                    ancestor_content: String::from_utf8_lossy(ancestor_blob.content()).to_string(),
+                    our_content: String::from_utf8_lossy(our_blob.content()).to_string(),
+                    their_content: String::from_utf8_lossy(their_blob.content()).to_string(),
+                    resolution: None,
+                });
+            }
+        }
+    }
     
-    Ok(format!("Dropped stash at index: {}", index))
+    Ok(conflicts)
 }
 
 #[tauri::command]
-fn merge_branch(repo_path: String, branch_name: String, author_name: String, author_email: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+fn resolve_conflict(app: tauri::AppHandle, repo_path: String, file_path: String, resolution: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "resolve_conflict", || {
+        use std::fs;
+        
+        let full_path = worktree_file(&repo_path, &file_path)?;
+        
+        fs::write(&full_path, resolution)
+            .map_err(|e| AppError::wrap(e, "Failed to write resolution"))?;
+        
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let mut index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        index.add_path(Path::new(&file_path))
+            .map_err(|e| AppError::wrap(e, "Failed to add resolved file"))?;
+        
+        index.write()
+            .map_err(|e| AppError::wrap(e, "Failed to write index"))?;
+        
+        Ok(format!("Resolved conflict in file: {}", file_path))
+    })
+}
+
+#[tauri::command]
+fn cherry_pick_commit(
+    app: tauri::AppHandle,
+    repo_path: String,
+    commit_id: String,
+    author_name: Option<String>,
+    author_email: Option<String>,
+    append_origin: Option<bool>,
+) -> Result<String, AppError> {
+    audited(&app, &repo_path, "cherry_pick_commit", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let commit_oid = git2::Oid::from_str(&commit_id)
+            .map_err(|e| AppError::wrap(e, "Invalid commit ID"))?;
+        
+        let commit = repo.find_commit(commit_oid)
+            .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
+        
+        if commit.parent_count() > 1 {
+            return Err(AppError::invalid_input(format!("Commit {} is a merge commit and cannot be cherry-picked", commit_id)));
+        }
+        ensure_no_operation_in_progress(&repo)?;
+        
+        let head_commit = repo.head()
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD"))?
+            .peel_to_commit()
+            .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))?;
+        
+        // Apply only the changes introduced by the commit (3-way merge against HEAD)
+        repo.cherrypick(&commit, None)
+            .map_err(|e| AppError::wrap(e, "Failed to cherry-pick commit"))?;
+        
+        let message = cherry_pick_message(&commit, append_origin.unwrap_or(false));
+        
+        let mut index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        if index.has_conflicts() {
+            // Keep CHERRY_PICK_HEAD so the conflicts can be resolved and the pick finished later
+            fs::write(repo.path().join("MERGE_MSG"), &message)
+                .map_err(|e| AppError::wrap(e, "Failed to write MERGE_MSG"))?;
+        
+            let conflicted = conflicted_paths(&index)?;
+            return Err(AppError::conflict(
+                format!("Cherry-pick of {} stopped due to conflicts in: {}", commit_id, conflicted.join(", ")),
+                conflicted,
+            ));
+        }
+        
+        let tree_id = index.write_tree()
+            .map_err(|e| AppError::wrap(e, "Failed to write tree"))?;
+        
+        if tree_id == head_commit.tree_id() {
+            repo.cleanup_state()
+                .map_err(|e| AppError::wrap(e, "Failed to clean up cherry-pick state"))?;
+            return Err(AppError::conflict(format!("Cherry-pick of {} is empty: its changes are already applied", commit_id), Vec::new()));
+        }
+        
+        let committer = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        let message = with_sign_off(&app, &repo_path, &message, &committer);
+        let cherry_pick_commit = commit_sequencer_result(&app, &repo_path, &repo, "cherry_pick", &commit.author(), &committer, &message)?;
+        
+        Ok(format!("Cherry-picked commit {} as {}", commit_id, cherry_pick_commit))
+    })
+}
+
+fn cherry_pick_message(commit: &git2::Commit, append_origin: bool) -> String {
+    let message = commit.message().unwrap_or("").trim_end().to_string();
     
-    let target_branch = repo.find_branch(&branch_name, git2::BranchType::Local)
-        .map_err(|e| format!("Failed to find branch '{}': {}", branch_name, e))?;
+    if append_origin {
+        format!("{}\n\n(cherry picked from commit {})\n", message, commit.id())
+    } else {
+        format!("{}\n", message)
+    }
+}
+
+fn conflicted_paths(index: &git2::Index) -> Result<Vec<String>, String> {
+    let conflicts = index.conflicts()
+        .map_err(|e| format!("Failed to get conflicts: {}", e))?;
+    
+    let mut paths = Vec::new();
+    for conflict in conflicts {
+        let conflict = conflict.map_err(|e| format!("Failed to get conflict entry: {}", e))?;
+        if let Some(entry) = conflict.our.or(conflict.their).or(conflict.ancestor) {
+            paths.push(String::from_utf8_lossy(&entry.path).to_string());
+        }
+    }
+    
+    Ok(paths)
+}
+
+/// Commits the current index on top of HEAD, journals it as `operation` and clears the
+/// cherry-pick/revert state.
+fn commit_sequencer_result(
+    app: &tauri::AppHandle,
+    repo_path: &str,
+    repo: &Repository,
+    operation: &str,
+    author: &Signature,
+    committer: &Signature,
+    message: &str,
+) -> Result<git2::Oid, AppError> {
+    let mut index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
     
-    let target_commit = target_branch.get().peel_to_commit()
-        .map_err(|e| format!("Failed to get target commit: {}", e))?;
+    if index.has_conflicts() {
+        let conflicted = conflicted_paths(&index)?;
+        return Err(AppError::conflict(
+            format!("Resolve the remaining conflicts first: {}", conflicted.join(", ")),
+            conflicted,
+        ));
+    }
+    
+    let tree_id = index.write_tree()
+        .map_err(|e| AppError::wrap(e, "Failed to write tree"))?;
+    
+    let tree = repo.find_tree(tree_id)
+        .map_err(|e| AppError::wrap(e, "Failed to find tree"))?;
     
+    let head_commit = repo.head()
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD"))?
+        .peel_to_commit()
+        .map_err(|e| AppError::wrap(e, "Failed to get HEAD commit"))?;
+    
+    let updated_ref = head_ref_name(repo);
+    let old_target = ref_target(repo, &updated_ref);
+    let signing = signing_config(app, repo, repo_path, "commit.gpgsign");
+    let commit_id = create_commit(
+        repo,
+        signing.as_ref(),
+        Some("HEAD"),
+        author,
+        committer,
+        message,
+        &tree,
+        &[&head_commit]
+    ).map_err(|e| AppError::wrap(e, "Failed to create commit"))?;
+    
+    repo.cleanup_state()
+        .map_err(|e| AppError::wrap(e, "Failed to clean up repository state"))?;
+    
+    journal_operation(
+        app,
+        repo_path,
+        operation,
+        format!("{} \"{}\"", operation.replace('_', "-"), message.lines().next().unwrap_or("")),
+        "hard",
+        vec![ref_change(repo, &updated_ref, old_target)],
+    );
+    extend_commit_cache(app, repo, repo_path);
+    
+    Ok(commit_id)
+}
+
+/// A cherry-pick or revert started on top of another one would overwrite its state files.
+fn ensure_no_operation_in_progress(repo: &Repository) -> Result<(), AppError> {
+    match operation_name(repo.state()) {
+        None => Ok(()),
+        Some(operation) => Err(AppError::conflict(
+            format!("A {} is in progress; continue or abort it first", operation),
+            Vec::new(),
+        )),
+    }
+}
+
+/// Paths an interrupted cherry-pick, revert or merge wrote to: what the incoming commit
+/// changes, plus anything left conflicted. Falls back to everything staged, which the
+/// operation started from clean.
+fn operation_paths(repo: &Repository) -> Result<Vec<String>, String> {
+    let index = repo.index()
+        .map_err(|e| format!("Failed to get index: {}", e))?;
+    let mut paths = conflicted_paths(&index)?;
     let head_commit = repo.head()
         .map_err(|e| format!("Failed to get HEAD: {}", e))?
         .peel_to_commit()
         .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
     
-    // Check if fast-forward is possible
-    let merge_base = repo.merge_base(head_commit.id(), target_commit.id())
-        .map_err(|e| format!("Failed to find merge base: {}", e))?;
+    let incoming = ["CHERRY_PICK_HEAD", "REVERT_HEAD", "MERGE_HEAD"]
+        .iter()
+        .find_map(|ref_name| sequencer_commit(repo, ref_name).ok().map(|commit| (*ref_name, commit)));
+    let diff = match incoming {
+        Some((ref_name, incoming)) => {
+            let base = if ref_name == "MERGE_HEAD" {
+                repo.merge_base(head_commit.id(), incoming.id()).ok().and_then(|oid| repo.find_commit(oid).ok())
+            } else {
+                incoming.parent(0).ok()
+            };
+            let base_tree = base.map(|commit| commit.tree()).transpose()
+                .map_err(|e| format!("Failed to get tree: {}", e))?;
+            let incoming_tree = incoming.tree()
+                .map_err(|e| format!("Failed to get tree: {}", e))?;
+            repo.diff_tree_to_tree(base_tree.as_ref(), Some(&incoming_tree), None)
+        }
+        None => {
+            let head_tree = head_commit.tree()
+                .map_err(|e| format!("Failed to get tree: {}", e))?;
+            repo.diff_tree_to_index(Some(&head_tree), Some(&index), None)
+        }
+    }
+    .map_err(|e| format!("Failed to diff: {}", e))?;
+    
+    for delta in diff.deltas() {
+        for file in [delta.old_file(), delta.new_file()] {
+            if let Some(path) = file.path() {
+                paths.push(path.to_string_lossy().to_string());
+            }
+        }
+    }
+    paths.sort();
+    paths.dedup();
+    
+    Ok(paths)
+}
+
+/// Discards the changes of an interrupted cherry-pick, revert or merge and clears its state,
+/// like `git reset --merge`: only the paths the operation touched go back to HEAD, so local
+/// changes elsewhere survive.
+fn abort_sequencer(repo: &Repository) -> Result<(), String> {
+    let paths = operation_paths(repo)?;
+    
+    if !paths.is_empty() {
+        let mut checkout = git2::build::CheckoutBuilder::new();
+        checkout.force();
+        for path in &paths {
+            checkout.path(path);
+        }
+        repo.checkout_head(Some(&mut checkout))
+            .map_err(|e| format!("Failed to reset working tree: {}", e))?;
+    }
+    
+    repo.cleanup_state()
+        .map_err(|e| format!("Failed to clean up repository state: {}", e))?;
     
-    if merge_base == head_commit.id() {
-        // Fast-forward merge
-        let head_ref = repo.head()
-            .map_err(|e| format!("Failed to get HEAD reference: {}", e))?;
+    Ok(())
+}
+
+/// MERGE_MSG as git would commit it: without its '#' comment lines, such as the
+/// "#Conflicts:" list, and without trailing blank lines.
+fn prepared_message(repo: &Repository) -> Result<String, git2::Error> {
+    let message = repo.message()?;
+    let kept: Vec<&str> = message.lines().filter(|line| !line.starts_with('#')).collect();
+    Ok(kept.join("\n").trim_end().to_string())
+}
+
+fn sequencer_commit<'r>(repo: &'r Repository, ref_name: &str) -> Result<git2::Commit<'r>, String> {
+    repo.find_reference(ref_name)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(|e| format!("Failed to read {}: {}", ref_name, e))
+}
+
+#[tauri::command]
+fn get_sequencer_state(repo_path: String) -> Result<SequencerState, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let (operation, ref_name) = match repo.state() {
+        git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence => ("cherry-pick", Some("CHERRY_PICK_HEAD")),
+        git2::RepositoryState::Revert | git2::RepositoryState::RevertSequence => ("revert", Some("REVERT_HEAD")),
+        git2::RepositoryState::Merge => ("merge", Some("MERGE_HEAD")),
+        git2::RepositoryState::Rebase
+        | git2::RepositoryState::RebaseInteractive
+        | git2::RepositoryState::RebaseMerge
+        | git2::RepositoryState::ApplyMailboxOrRebase => ("rebase", None),
+        git2::RepositoryState::Clean => ("none", None),
+        _ => ("other", None),
+    };
+    
+    let commit_id = ref_name
+        .and_then(|name| repo.refname_to_id(name).ok())
+        .map(|oid| oid.to_string());
+    
+    let index = repo.index()
+        .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+    let conflicts = if index.has_conflicts() {
+        conflicted_paths(&index)?
+    } else {
+        Vec::new()
+    };
+    
+    Ok(SequencerState {
+        operation: operation.to_string(),
+        commit_id,
+        message: repo.message().ok(),
+        conflicts,
+    })
+}
+
+#[tauri::command]
+fn cherry_pick_continue(app: tauri::AppHandle, repo_path: String, author_name: Option<String>, author_email: Option<String>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "cherry_pick_continue", || {
+        let repo = open_worktree_repository(&repo_path)?;
         
-        repo.reference(
-            head_ref.name().unwrap(),
-            target_commit.id(),
-            true,
-            &format!("Fast-forward merge of {}", branch_name)
-        ).map_err(|e| format!("Failed to update HEAD: {}", e))?;
+        if !matches!(repo.state(), git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence) {
+            return Err(AppError::conflict("No cherry-pick in progress", Vec::new()));
+        }
         
-        repo.checkout_tree(target_commit.as_object(), None)
-            .map_err(|e| format!("Failed to checkout: {}", e))?;
+        let picked = sequencer_commit(&repo, "CHERRY_PICK_HEAD")?;
+        let message = prepared_message(&repo)
+            .unwrap_or_else(|_| cherry_pick_message(&picked, false));
         
-        Ok(format!("Fast-forward merged branch '{}'", branch_name))
-    } else {
-        // Three-way merge
-        let signature = Signature::now(&author_name, &author_email)
-            .map_err(|e| format!("Failed to create signature: {}", e))?;
+        let committer = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        let message = with_sign_off(&app, &repo_path, &message, &committer);
+        let commit_id = commit_sequencer_result(&app, &repo_path, &repo, "cherry_pick", &picked.author(), &committer, &message)?;
         
-        let merge_commit = repo.commit(
-            Some("HEAD"),
-            &signature,
-            &signature,
-            &format!("Merge branch '{}'", branch_name),
-            &target_commit.tree().map_err(|e| format!("Failed to get target tree: {}", e))?,
-            &[&head_commit, &target_commit]
-        ).map_err(|e| format!("Failed to create merge commit: {}", e))?;
+        Ok(format!("Cherry-picked commit {} as {}", picked.id(), commit_id))
+    })
+}
+
+#[tauri::command]
+fn cherry_pick_abort(app: tauri::AppHandle, repo_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "cherry_pick_abort", || {
+        let repo = open_worktree_repository(&repo_path)?;
         
-        Ok(format!("Merged branch '{}' with commit {}", branch_name, merge_commit))
-    }
+        if !matches!(repo.state(), git2::RepositoryState::CherryPick | git2::RepositoryState::CherryPickSequence) {
+            return Err(AppError::conflict("No cherry-pick in progress", Vec::new()));
+        }
+        
+        abort_sequencer(&repo)?;
+        
+        Ok("Cherry-pick aborted".to_string())
+    })
+}
+
+#[tauri::command]
+fn revert_commit(app: tauri::AppHandle, repo_path: String, commit_id: String, author_name: Option<String>, author_email: Option<String>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "revert_commit", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let commit_oid = git2::Oid::from_str(&commit_id)
+            .map_err(|e| AppError::wrap(e, "Invalid commit ID"))?;
+        
+        let commit = repo.find_commit(commit_oid)
+            .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
+        
+        if commit.parent_count() > 1 {
+            return Err(AppError::invalid_input(format!("Commit {} is a merge commit and cannot be reverted", commit_id)));
+        }
+        ensure_no_operation_in_progress(&repo)?;
+        
+        // Writes REVERT_HEAD and MERGE_MSG so an interrupted revert can be continued
+        repo.revert(&commit, None)
+            .map_err(|e| AppError::wrap(e, "Failed to revert commit"))?;
+        
+        let index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        
+        if index.has_conflicts() {
+            let conflicted = conflicted_paths(&index)?;
+            return Err(AppError::conflict(
+                format!("Revert of {} stopped due to conflicts in: {}", commit_id, conflicted.join(", ")),
+                conflicted,
+            ));
+        }
+        
+        let message = prepared_message(&repo)
+            .map_err(|e| AppError::wrap(e, "Failed to read revert message"))?;
+        
+        let signature = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        let message = with_sign_off(&app, &repo_path, &message, &signature);
+        let revert_commit = commit_sequencer_result(&app, &repo_path, &repo, "revert", &signature, &signature, &message)?;
+        
+        Ok(format!("Reverted commit {} with {}", commit_id, revert_commit))
+    })
+}
+
+#[tauri::command]
+fn revert_continue(app: tauri::AppHandle, repo_path: String, author_name: Option<String>, author_email: Option<String>) -> Result<String, AppError> {
+    audited(&app, &repo_path, "revert_continue", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        if !matches!(repo.state(), git2::RepositoryState::Revert | git2::RepositoryState::RevertSequence) {
+            return Err(AppError::conflict("No revert in progress", Vec::new()));
+        }
+        
+        let reverted = sequencer_commit(&repo, "REVERT_HEAD")?;
+        let message = prepared_message(&repo)
+            .map_err(|e| AppError::wrap(e, "Failed to read revert message"))?;
+        
+        let signature = resolve_signature(&app, &repo, &repo_path, author_name, author_email)?;
+        let message = with_sign_off(&app, &repo_path, &message, &signature);
+        let commit_id = commit_sequencer_result(&app, &repo_path, &repo, "revert", &signature, &signature, &message)?;
+        
+        Ok(format!("Reverted commit {} with {}", reverted.id(), commit_id))
+    })
 }
 
 #[tauri::command]
-fn get_merge_conflicts(repo_path: String) -> Result<Vec<MergeConflict>, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let index = repo.index()
-        .map_err(|e| format!("Failed to get index: {}", e))?;
-    
-    let mut conflicts = Vec::new();
-    
-    if index.has_conflicts() {
-        let conflict_iter = index.conflicts()
-            .map_err(|e| format!("Failed to get conflicts: {}", e))?;
+fn revert_abort(app: tauri::AppHandle, repo_path: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "revert_abort", || {
+        let repo = open_worktree_repository(&repo_path)?;
         
-        for conflict in conflict_iter {
-            let conflict = conflict.map_err(|e| format!("Failed to get conflict entry: {}", e))?;
-            
-            if let (Some(ancestor), Some(our), Some(their)) = (&conflict.ancestor, &conflict.our, &conflict.their) {
-                let file_path = String::from_utf8_lossy(&our.path).to_string();
-                
-                let ancestor_blob = repo.find_blob(ancestor.id)
-                    .map_err(|e| format!("Failed to find ancestor blob: {}", e))?;
-                let our_blob = repo.find_blob(our.id)
-                    .map_err(|e| format!("Failed to find our blob: {}", e))?;
-                let their_blob = repo.find_blob(their.id)
-                    .map_err(|e| format!("Failed to find their blob: {}", e))?;
-                
-                conflicts.push(MergeConflict {
-                    file_path,
-                    ancestor_content: String::from_utf8_lossy(ancestor_blob.content()).to_string(),
-                    our_content: String::from_utf8_lossy(our_blob.content()).to_string(),
-                    their_content: String::from_utf8_lossy(their_blob.content()).to_string(),
-                    resolution: None,
-                });
-            }
+        if !matches!(repo.state(), git2::RepositoryState::Revert | git2::RepositoryState::RevertSequence) {
+            return Err(AppError::conflict("No revert in progress", Vec::new()));
         }
-    }
-    
-    Ok(conflicts)
+        
+        abort_sequencer(&repo)?;
+        
+        Ok("Revert aborted".to_string())
+    })
 }
 
 #[tauri::command]
-fn resolve_conflict(repo_path: String, file_path: String, resolution: String) -> Result<String, String> {
-    use std::fs;
-    
-    let full_path = format!("{}/{}", repo_path, file_path);
-    
-    fs::write(&full_path, resolution)
-        .map_err(|e| format!("Failed to write resolution: {}", e))?;
-    
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let mut index = repo.index()
-        .map_err(|e| format!("Failed to get index: {}", e))?;
-    
-    index.add_path(Path::new(&file_path))
-        .map_err(|e| format!("Failed to add resolved file: {}", e))?;
-    
-    index.write()
-        .map_err(|e| format!("Failed to write index: {}", e))?;
-    
-    Ok(format!("Resolved conflict in file: {}", file_path))
+async fn rebase_interactive(
+    app: tauri::AppHandle,
+    repo_path: String,
+    onto_branch: String,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "rebase", operation_id, move |_| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "rebase_interactive", || {
+            rebase_interactive_blocking(task_app.clone(), repo_path, onto_branch).map_err(AppError::from)
+        })
+    })
+    .await
 }
 
-#[tauri::command]
-fn cherry_pick_commit(repo_path: String, commit_id: String, author_name: String, author_email: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
-    
-    let commit_oid = git2::Oid::from_str(&commit_id)
-        .map_err(|e| format!("Invalid commit ID: {}", e))?;
-    
-    let commit = repo.find_commit(commit_oid)
-        .map_err(|e| format!("Failed to find commit: {}", e))?;
-    
-    let head_commit = repo.head()
-        .map_err(|e| format!("Failed to get HEAD: {}", e))?
-        .peel_to_commit()
-        .map_err(|e| format!("Failed to get HEAD commit: {}", e))?;
-    
-    let signature = Signature::now(&author_name, &author_email)
-        .map_err(|e| format!("Failed to create signature: {}", e))?;
-    
-    // Create cherry-pick commit
-    let cherry_pick_commit = repo.commit(
-        Some("HEAD"),
-        &signature,
-        &signature,
-        &format!("Cherry-pick: {}", commit.message().unwrap_or("")),
-        &commit.tree().map_err(|e| format!("Failed to get commit tree: {}", e))?,
-        &[&head_commit]
-    ).map_err(|e| format!("Failed to create cherry-pick commit: {}", e))?;
+fn rebase_interactive_blocking(app: tauri::AppHandle, repo_path: String, onto_branch: String) -> Result<String, String> {
+    let repo = open_worktree_repository(&repo_path)?;
     
-    Ok(format!("Cherry-picked commit {} as {}", commit_id, cherry_pick_commit))
-}
-
-#[tauri::command]
-fn rebase_interactive(repo_path: String, onto_branch: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+    let updated_ref = head_ref_name(&repo);
+    let old_target = ref_target(&repo, &updated_ref);
     
     let onto_branch_ref = repo.find_branch(&onto_branch, git2::BranchType::Local)
         .map_err(|e| format!("Failed to find branch '{}': {}", onto_branch, e))?;
@@ -1145,6 +3226,8 @@ fn rebase_interactive(repo_path: String, onto_branch: String) -> Result<String,
         return Ok("Already up to date".to_string());
     }
     
+    create_backup_ref(&repo, "rebase")?;
+    
     // For now, we'll do a simple rebase (move HEAD to onto_commit)
     // In a full implementation, you'd replay commits one by one
     let head_ref = repo.head()
@@ -1160,14 +3243,78 @@ fn rebase_interactive(repo_path: String, onto_branch: String) -> Result<String,
     repo.checkout_tree(onto_commit.as_object(), None)
         .map_err(|e| format!("Failed to checkout: {}", e))?;
     
+    journal_operation(
+        &app,
+        &repo_path,
+        "rebase",
+        format!("rebase onto '{}'", onto_branch),
+        "hard",
+        vec![ref_change(&repo, &updated_ref, old_target)],
+    );
+    
     Ok(format!("Rebased onto branch '{}'", onto_branch))
 }
 
 #[tauri::command]
-fn get_log_graph(repo_path: String, limit: Option<usize>) -> Result<Vec<LogEntry>, String> {
+async fn get_log_graph(
+    app: tauri::AppHandle,
+    repo_path: String,
+    limit: Option<usize>,
+    operation_id: Option<String>
+) -> Result<Vec<LogEntry>, AppError> {
+    let task_app = app.clone();
+    let timed_path = repo_path.clone();
+    let started = Instant::now();
+    let result = run_operation(&app, "log", operation_id, move |token| get_log_graph_blocking(task_app, repo_path, limit, token)).await;
+    record_timing(&app, "get_log_graph", Some(&timed_path), started);
+    result
+}
+
+/// Short names of the references pointing directly at each commit, built in a single
+/// pass so decorating the log doesn't rescan every ref per commit.
+fn refs_by_target(repo: &Repository) -> Result<HashMap<git2::Oid, Vec<String>>, String> {
+    let mut refs: HashMap<git2::Oid, Vec<String>> = HashMap::new();
+    let ref_iter = repo.references()
+        .map_err(|e| format!("Failed to get references: {}", e))?;
+    
+    for reference in ref_iter.flatten() {
+        if let (Some(target_oid), Some(name)) = (reference.target(), reference.shorthand()) {
+            refs.entry(target_oid).or_default().push(name.to_string());
+        }
+    }
+    
+    Ok(refs)
+}
+
+fn get_log_graph_blocking(app: tauri::AppHandle, repo_path: String, limit: Option<usize>, token: OperationToken) -> Result<Vec<LogEntry>, String> {
     let repo = Repository::open(&repo_path)
         .map_err(|e| format!("Failed to open repository: {}", e))?;
     
+    let limits = payload_limits(&app);
+    let max_entries = limit.unwrap_or(100).min(limits.max_log_entries);
+    let mut refs = refs_by_target(&repo)?;
+    let scope = active_scope(&app, &repo_path)?;
+    let cached = if scope.is_active() { None } else { cached_history(&app, &repo, &repo_path, max_entries, &token)? };
+    if let Some(history) = cached {
+        let mut entries = Vec::new();
+        for commit in history {
+            let oid = git2::Oid::from_str(&commit.id)
+                .map_err(|e| format!("Invalid commit ID: {}", e))?;
+            let (message, truncated) = truncate_text(&commit.message, limits.max_commit_message_bytes);
+            entries.push(LogEntry {
+                refs: refs.remove(&oid).unwrap_or_default(),
+                id: commit.id,
+                message,
+                author: commit.author,
+                email: commit.email,
+                timestamp: commit.timestamp,
+                parents: commit.parents,
+                truncated,
+            });
+        }
+        return Ok(entries);
+    }
+    
     let mut revwalk = repo.revwalk()
         .map_err(|e| format!("Failed to create revwalk: {}", e))?;
     
@@ -1175,45 +3322,33 @@ fn get_log_graph(repo_path: String, limit: Option<usize>) -> Result<Vec<LogEntry
         .map_err(|e| format!("Failed to push HEAD: {}", e))?;
     
     let mut entries = Vec::new();
-    let max_entries = limit.unwrap_or(100);
     
-    for (i, oid_result) in revwalk.enumerate() {
-        if i >= max_entries {
+    for oid_result in revwalk {
+        if entries.len() >= max_entries {
             break;
         }
+        token.check()?;
         
         let oid = oid_result.map_err(|e| format!("Failed to get OID: {}", e))?;
         let commit = repo.find_commit(oid)
             .map_err(|e| format!("Failed to find commit: {}", e))?;
+        if !scope.touches(&repo, &commit)? {
+            continue;
+        }
         
         let author = commit.author();
         let parents: Vec<String> = commit.parents().map(|p| p.id().to_string()).collect();
-        
-        // Get references pointing to this commit
-        let mut refs = Vec::new();
-        let ref_iter = repo.references()
-            .map_err(|e| format!("Failed to get references: {}", e))?;
-        
-        for reference in ref_iter {
-            if let Ok(reference) = reference {
-                if let Some(target_oid) = reference.target() {
-                    if target_oid == oid {
-                        if let Some(name) = reference.shorthand() {
-                            refs.push(name.to_string());
-                        }
-                    }
-                }
-            }
-        }
+        let (message, truncated) = truncate_text(commit.message().unwrap_or("No message"), limits.max_commit_message_bytes);
         
         entries.push(LogEntry {
             id: oid.to_string(),
-            message: commit.message().unwrap_or("No message").to_string(),
+            message,
             author: author.name().unwrap_or("Unknown").to_string(),
             email: author.email().unwrap_or("unknown@email.com").to_string(),
             timestamp: commit.time().seconds(),
             parents,
-            refs,
+            refs: refs.remove(&oid).unwrap_or_default(),
+            truncated,
         });
     }
     
@@ -1221,52 +3356,154 @@ fn get_log_graph(repo_path: String, limit: Option<usize>) -> Result<Vec<LogEntry
 }
 
 #[tauri::command]
-fn reset_to_commit(repo_path: String, commit_id: String, reset_type: String) -> Result<String, String> {
-    let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+fn reset_to_commit(app: tauri::AppHandle, repo_path: String, commit_id: String, reset_type: String) -> Result<String, AppError> {
+    audited(&app, &repo_path, "reset_to_commit", || {
+        let repo = Repository::open(&repo_path)
+            .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+        
+        let updated_ref = head_ref_name(&repo);
+        let old_target = ref_target(&repo, &updated_ref);
+        
+        let commit_oid = git2::Oid::from_str(&commit_id)
+            .map_err(|e| AppError::wrap(e, "Invalid commit ID"))?;
+        
+        let commit = repo.find_commit(commit_oid)
+            .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
+        
+        let reset_mode = match reset_type.as_str() {
+            "soft" => git2::ResetType::Soft,
+            "mixed" => git2::ResetType::Mixed,
+            "hard" => git2::ResetType::Hard,
+            _ => return Err(AppError::invalid_input("Invalid reset type. Use 'soft', 'mixed', or 'hard'")),
+        };
+        
+        // Bare repositories have no index or working tree to reset
+        if repo.is_bare() && reset_mode != git2::ResetType::Soft {
+            return Err(AppError::invalid_input(format!("{} is a bare repository; only soft resets are possible", repo_path)));
+        }
+        
+        create_backup_ref(&repo, "reset")?;
+        
+        repo.reset(commit.as_object(), reset_mode, None)
+            .map_err(|e| AppError::wrap(e, "Failed to reset"))?;
+        
+        journal_operation(
+            &app,
+            &repo_path,
+            "reset",
+            format!("{} reset to {}", reset_type, commit_id),
+            &reset_type,
+            vec![ref_change(&repo, &updated_ref, old_target)],
+        );
+        
+        Ok(format!("Reset to commit {} ({:?})", commit_id, reset_mode))
+    })
+}
+
+/// Name of the ref HEAD points to (e.g. refs/heads/main), or "HEAD" when detached.
+fn head_ref_name(repo: &Repository) -> String {
+    repo.find_reference("HEAD")
+        .ok()
+        .and_then(|head| head.symbolic_target().map(|name| name.to_string()))
+        .unwrap_or_else(|| "HEAD".to_string())
+}
+
+fn ref_target(repo: &Repository, ref_name: &str) -> Option<String> {
+    repo.refname_to_id(ref_name).ok().map(|oid| oid.to_string())
+}
+
+/// Records the current HEAD in ORIG_HEAD and under refs/codegit/backup/ before
+/// an operation that rewrites history, so the previous state can be recovered.
+fn create_backup_ref(repo: &Repository, operation: &str) -> Result<Option<String>, String> {
+    let head_commit = match repo.head().and_then(|head| head.peel_to_commit()) {
+        Ok(commit) => commit,
+        Err(_) => return Ok(None), // Nothing to back up on an unborn branch
+    };
     
-    let commit_oid = git2::Oid::from_str(&commit_id)
-        .map_err(|e| format!("Invalid commit ID: {}", e))?;
+    let log_message = format!("codegit: backup before {}", operation);
     
-    let commit = repo.find_commit(commit_oid)
-        .map_err(|e| format!("Failed to find commit: {}", e))?;
+    repo.reference("ORIG_HEAD", head_commit.id(), true, &log_message)
+        .map_err(|e| format!("Failed to write ORIG_HEAD: {}", e))?;
     
-    let reset_type = match reset_type.as_str() {
-        "soft" => git2::ResetType::Soft,
-        "mixed" => git2::ResetType::Mixed,
-        "hard" => git2::ResetType::Hard,
-        _ => return Err("Invalid reset type. Use 'soft', 'mixed', or 'hard'".to_string()),
-    };
+    let backup_name = format!(
+        "refs/codegit/backup/{}/{}",
+        operation,
+        chrono::Utc::now().timestamp_millis()
+    );
     
-    repo.reset(commit.as_object(), reset_type, None)
-        .map_err(|e| format!("Failed to reset: {}", e))?;
+    repo.reference(&backup_name, head_commit.id(), false, &log_message)
+        .map_err(|e| format!("Failed to create backup ref: {}", e))?;
     
-    Ok(format!("Reset to commit {} ({:?})", commit_id, reset_type))
+    Ok(Some(backup_name))
 }
 
 #[tauri::command]
-fn prepare_interactive_rebase(repo_path: String, onto_branch: String, from_commit: String) -> Result<RebasePlan, String> {
+fn list_backups(repo_path: String) -> Result<Vec<BackupRef>, AppError> {
     let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let references = repo.references_glob("refs/codegit/backup/*")
+        .map_err(|e| AppError::wrap(e, "Failed to list backups"))?;
+    
+    let mut backups = Vec::new();
+    
+    for reference in references.flatten() {
+        let name = match reference.name() {
+            Some(name) => name.to_string(),
+            None => continue,
+        };
+        let commit = match reference.peel_to_commit() {
+            Ok(commit) => commit,
+            Err(_) => continue,
+        };
+        
+        // refs/codegit/backup/<operation>/<timestamp in millis>
+        let mut parts = name.trim_start_matches("refs/codegit/backup/").rsplitn(2, '/');
+        let created_at = parts.next()
+            .and_then(|millis| millis.parse::<i64>().ok())
+            .map(|millis| millis / 1000)
+            .unwrap_or(0);
+        let operation = parts.next().unwrap_or("unknown").to_string();
+        
+        backups.push(BackupRef {
+            name,
+            operation,
+            commit_id: commit.id().to_string(),
+            message: commit.summary().unwrap_or("").to_string(),
+            created_at,
+        });
+    }
+    
+    backups.sort_by_key(|backup| std::cmp::Reverse(backup.created_at));
+    
+    Ok(backups)
+}
+
+/// The commits after `from_commit` up to HEAD, oldest first, all picked. With `autosquash`
+/// (rebase.autoSquash, on by default) fixup!/squash! commits are moved under their targets.
+#[tauri::command]
+fn prepare_interactive_rebase(repo_path: String, onto_branch: String, from_commit: String, autosquash: Option<bool>) -> Result<RebasePlan, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
 
     let mut revwalk = repo.revwalk()
-        .map_err(|e| format!("Failed to create revwalk: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to create revwalk"))?;
     
     revwalk.push_head()
-        .map_err(|e| format!("Failed to push HEAD: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to push HEAD"))?;
     
     let from_oid = git2::Oid::from_str(&from_commit)
-        .map_err(|e| format!("Invalid commit ID: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Invalid commit ID"))?;
     
     revwalk.hide(from_oid)
-        .map_err(|e| format!("Failed to hide commit: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to hide commit"))?;
 
     let mut commits = Vec::new();
     
     for oid in revwalk {
-        let oid = oid.map_err(|e| format!("Failed to get commit ID: {}", e))?;
+        let oid = oid.map_err(|e| AppError::wrap(e, "Failed to get commit ID"))?;
         let commit = repo.find_commit(oid)
-            .map_err(|e| format!("Failed to find commit: {}", e))?;
+            .map_err(|e| AppError::wrap(e, "Failed to find commit"))?;
         
         let author = commit.author();
         commits.push(RebaseCommit {
@@ -1280,6 +3517,13 @@ fn prepare_interactive_rebase(repo_path: String, onto_branch: String, from_commi
     }
 
     commits.reverse();
+    
+    let autosquash_enabled = autosquash.unwrap_or_else(|| {
+        repo.config().and_then(|config| config.get_bool("rebase.autoSquash")).unwrap_or(true)
+    });
+    if autosquash_enabled {
+        commits = commands::rebase::autosquash(commits);
+    }
 
     Ok(RebasePlan {
         commits,
@@ -1287,143 +3531,483 @@ fn prepare_interactive_rebase(repo_path: String, onto_branch: String, from_commi
     })
 }
 
+/// Runs a plan from prepare_interactive_rebase. Conflicts and Edit entries pause the
+/// rebase; resume it with rebase_continue or rebase_skip, or back out with rebase_abort.
 #[tauri::command]
-fn execute_interactive_rebase(
-    repo_path: String, 
+async fn execute_interactive_rebase(
+    app: tauri::AppHandle,
+    repo_path: String,
     rebase_plan: RebasePlan,
-    author_name: String,
-    author_email: String
-) -> Result<String, String> {
+    author_name: Option<String>,
+    author_email: Option<String>,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "rebase", operation_id, move |token| {
+        audited(&task_app, &repo_path, "execute_interactive_rebase", || {
+            start_interactive_rebase(&task_app, &repo_path, rebase_plan, author_name, author_email, &token)
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+fn get_submodules(repo_path: String) -> Result<Vec<GitSubmodule>, AppError> {
     let repo = Repository::open(&repo_path)
-        .map_err(|e| format!("Failed to open repository: {}", e))?;
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let submodules = repo.submodules()
+        .map_err(|e| AppError::wrap(e, "Failed to list submodules"))?;
+    
+    let mut result = Vec::new();
+    
+    for submodule in submodules {
+        let name = submodule.name().unwrap_or("").to_string();
+        let head_id = submodule.head_id();
+        let workdir_id = submodule.workdir_id();
+        
+        let flags = repo.submodule_status(&name, git2::SubmoduleIgnore::None)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to get status of submodule '{}'", name)))?;
+        
+        result.push(GitSubmodule {
+            name,
+            path: submodule.path().to_string_lossy().to_string(),
+            url: submodule.url().unwrap_or("").to_string(),
+            branch: submodule.branch().map(|branch| branch.to_string()),
+            head_id: head_id.map(|oid| oid.to_string()).unwrap_or_default(),
+            workdir_id: workdir_id.map(|oid| oid.to_string()),
+            status: submodule_status_from_flags(flags, head_id, workdir_id),
+        });
+    }
+    
+    Ok(result)
+}
 
-    let signature = Signature::now(&author_name, &author_email)
-        .map_err(|e| format!("Failed to create signature: {}", e))?;
+fn submodule_status_from_flags(
+    flags: git2::SubmoduleStatus,
+    head_id: Option<git2::Oid>,
+    workdir_id: Option<git2::Oid>,
+) -> SubmoduleStatus {
+    if flags.contains(git2::SubmoduleStatus::WD_UNINITIALIZED) || !flags.contains(git2::SubmoduleStatus::IN_WD) {
+        return SubmoduleStatus::Uninitialized;
+    }
+    
+    let modified = git2::SubmoduleStatus::WD_MODIFIED
+        | git2::SubmoduleStatus::WD_INDEX_MODIFIED
+        | git2::SubmoduleStatus::WD_WD_MODIFIED
+        | git2::SubmoduleStatus::WD_UNTRACKED
+        | git2::SubmoduleStatus::INDEX_MODIFIED;
+    
+    if flags.intersects(modified) {
+        SubmoduleStatus::Modified
+    } else if head_id.is_some() && head_id == workdir_id {
+        SubmoduleStatus::UpToDate
+    } else {
+        SubmoduleStatus::Initialized
+    }
+}
 
-    let onto_commit = repo.revparse_single(&rebase_plan.onto_branch)
-        .map_err(|e| format!("Failed to find target: {}", e))?
-        .peel_to_commit()
-        .map_err(|e| format!("Failed to get commit: {}", e))?;
+#[tauri::command]
+fn get_submodule_drift(repo_path: String) -> Result<Vec<SubmoduleDrift>, AppError> {
+    let repo = Repository::open(&repo_path)
+        .map_err(|e| AppError::wrap(e, "Failed to open repository"))?;
+    
+    let submodules = repo.submodules()
+        .map_err(|e| AppError::wrap(e, "Failed to list submodules"))?;
+    
+    let mut drifts = Vec::new();
+    
+    for submodule in submodules {
+        let name = submodule.name().unwrap_or("").to_string();
+        let recorded_id = submodule.head_id();
+        let checked_out_id = submodule.workdir_id();
+        
+        let flags = repo.submodule_status(&name, git2::SubmoduleIgnore::None)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to get status of submodule '{}'", name)))?;
+        
+        let is_initialized = flags.contains(git2::SubmoduleStatus::IN_WD)
+            && !flags.contains(git2::SubmoduleStatus::WD_UNINITIALIZED);
+        let is_dirty = flags.intersects(
+            git2::SubmoduleStatus::WD_INDEX_MODIFIED
+                | git2::SubmoduleStatus::WD_WD_MODIFIED
+                | git2::SubmoduleStatus::WD_UNTRACKED
+        );
+        
+        // Ahead/behind of the checked-out commit relative to the recorded gitlink,
+        // unknown when the submodule isn't cloned or the recorded commit hasn't been fetched
+        let (ahead, behind) = match (recorded_id, checked_out_id, submodule.open()) {
+            (Some(recorded), Some(checked_out), Ok(sub_repo)) => sub_repo
+                .graph_ahead_behind(checked_out, recorded)
+                .map(|(ahead, behind)| (Some(ahead), Some(behind)))
+                .unwrap_or((None, None)),
+            _ => (None, None),
+        };
+        
+        let needs_attention = !is_initialized
+            || is_dirty
+            || recorded_id != checked_out_id;
+        
+        drifts.push(SubmoduleDrift {
+            name,
+            path: submodule.path().to_string_lossy().to_string(),
+            recorded_id: recorded_id.map(|oid| oid.to_string()),
+            checked_out_id: checked_out_id.map(|oid| oid.to_string()),
+            ahead,
+            behind,
+            is_initialized,
+            is_dirty,
+            needs_attention,
+        });
+    }
+    
+    Ok(drifts)
+}
 
-    let mut new_commits = Vec::new();
-    let mut current_commit = onto_commit;
+#[tauri::command]
+async fn add_submodule(
+    app: tauri::AppHandle,
+    repo_path: String,
+    url: String,
+    path: String,
+    branch: Option<String>,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "submodule", operation_id, move |_| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "add_submodule", || {
+            add_submodule_blocking(task_app.clone(), repo_path, url, path, branch).map_err(AppError::from)
+        })
+    })
+    .await
+}
 
-    for rebase_commit in &rebase_plan.commits {
-        match rebase_commit.action {
-            RebaseAction::Pick => {
-                let commit = repo.find_commit(git2::Oid::from_str(&rebase_commit.id)
-                    .map_err(|e| format!("Invalid commit ID: {}", e))?)
-                    .map_err(|e| format!("Failed to find commit: {}", e))?;
-                
-                let tree = commit.tree().map_err(|e| format!("Failed to get tree: {}", e))?;
-                let new_commit = repo.commit(
-                    None,
-                    &signature,
-                    &signature,
-                    &commit.message().unwrap_or("<no message>"),
-                    &tree,
-                    &[&current_commit],
-                ).map_err(|e| format!("Failed to create commit: {}", e))?;
-
-                current_commit = repo.find_commit(new_commit)
-                    .map_err(|e| format!("Failed to find new commit: {}", e))?;
-                new_commits.push(new_commit);
-            },
-            RebaseAction::Reword => {
-                let commit = repo.find_commit(git2::Oid::from_str(&rebase_commit.id)
-                    .map_err(|e| format!("Invalid commit ID: {}", e))?)
-                    .map_err(|e| format!("Failed to find commit: {}", e))?;
-
-                let tree = commit.tree().map_err(|e| format!("Failed to get tree: {}", e))?;
-                let new_commit = repo.commit(
-                    None,
-                    &signature,
-                    &signature,
-                    &rebase_commit.message,
-                    &tree,
-                    &[&current_commit],
-                ).map_err(|e| format!("Failed to create reworded commit: {}", e))?;
-
-                current_commit = repo.find_commit(new_commit)
-                    .map_err(|e| format!("Failed to find new commit: {}", e))?;
-                new_commits.push(new_commit);
-            },
-            RebaseAction::Drop => {
-                continue;
-            },
-            _ => {
-                let commit = repo.find_commit(git2::Oid::from_str(&rebase_commit.id)
-                    .map_err(|e| format!("Invalid commit ID: {}", e))?)
-                    .map_err(|e| format!("Failed to find commit: {}", e))?;
-                
-                let tree = commit.tree().map_err(|e| format!("Failed to get tree: {}", e))?;
-                let new_commit = repo.commit(
-                    None,
-                    &signature,
-                    &signature,
-                    &commit.message().unwrap_or("<no message>"),
-                    &tree,
-                    &[&current_commit],
-                ).map_err(|e| format!("Failed to create commit: {}", e))?;
-
-                current_commit = repo.find_commit(new_commit)
-                    .map_err(|e| format!("Failed to find new commit: {}", e))?;
-                new_commits.push(new_commit);
-            }
-        }
+fn add_submodule_blocking(app: tauri::AppHandle, repo_path: String, url: String, path: String, branch: Option<String>) -> Result<String, String> {
+    let mut repo = open_worktree_repository(&repo_path)?;
+    
+    if repo.workdir().map(|workdir| workdir.join(&path).exists()).unwrap_or(false) {
+        return Err(format!("Path '{}' already exists in the working tree", path));
     }
-
-    if let Some(last_commit_id) = new_commits.last() {
-        let mut head = repo.head()
-            .map_err(|e| format!("Failed to get HEAD: {}", e))?;
+    
+    // Writes the .gitmodules entry and prepares .git/modules/<name>
+    let mut submodule = repo.submodule(&url, Path::new(&path), true)
+        .map_err(|e| format!("Failed to set up submodule: {}", e))?;
+    
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(progress_callbacks(&app, "submodule-progress", &path));
+    
+    let mut update_options = git2::SubmoduleUpdateOptions::new();
+    update_options.fetch(fetch_options);
+    
+    let sub_repo = submodule.clone(Some(&mut update_options))
+        .map_err(|e| format!("Failed to clone submodule: {}", e))?;
+    
+    if let Some(branch_name) = &branch {
+        checkout_remote_branch(&sub_repo, "origin", branch_name)?;
+    }
+    
+    // Stages .gitmodules and the gitlink
+    submodule.add_finalize()
+        .map_err(|e| format!("Failed to stage submodule: {}", e))?;
+    
+    let name = submodule.name().unwrap_or(&path).to_string();
+    drop(submodule);
+    
+    if let Some(branch_name) = &branch {
+        repo.submodule_set_branch(&name, branch_name)
+            .map_err(|e| format!("Failed to set submodule branch: {}", e))?;
         
-        head.set_target(*last_commit_id, "Interactive rebase completed")
-            .map_err(|e| format!("Failed to update HEAD: {}", e))?;
+        let mut index = repo.index()
+            .map_err(|e| format!("Failed to get index: {}", e))?;
+        index.add_path(Path::new(".gitmodules"))
+            .map_err(|e| format!("Failed to stage .gitmodules: {}", e))?;
+        index.add_path(Path::new(&path))
+            .map_err(|e| format!("Failed to stage submodule: {}", e))?;
+        index.write()
+            .map_err(|e| format!("Failed to write index: {}", e))?;
     }
+    
+    Ok(format!("Added submodule '{}' at {}", name, path))
+}
 
-    Ok(format!("Interactive rebase completed successfully. {} commits processed.", new_commits.len()))
+/// Creates (or resets) a local branch from `<remote>/<branch>` and checks it out.
+fn checkout_remote_branch(repo: &Repository, remote_name: &str, branch_name: &str) -> Result<(), String> {
+    let remote_ref = format!("refs/remotes/{}/{}", remote_name, branch_name);
+    let commit = repo.find_reference(&remote_ref)
+        .and_then(|reference| reference.peel_to_commit())
+        .map_err(|e| format!("Failed to find branch '{}': {}", branch_name, e))?;
+    
+    let mut branch = repo.branch(branch_name, &commit, true)
+        .map_err(|e| format!("Failed to create branch '{}': {}", branch_name, e))?;
+    branch.set_upstream(Some(&format!("{}/{}", remote_name, branch_name)))
+        .map_err(|e| format!("Failed to set upstream: {}", e))?;
+    
+    repo.set_head(&format!("refs/heads/{}", branch_name))
+        .map_err(|e| format!("Failed to set HEAD: {}", e))?;
+    repo.checkout_head(Some(git2::build::CheckoutBuilder::new().force()))
+        .map_err(|e| format!("Failed to checkout: {}", e))?;
+    
+    Ok(())
 }
 
 #[tauri::command]
-fn get_submodules(_repo_path: String) -> Result<Vec<GitSubmodule>, String> {
-    // Submodule support requires advanced libgit2 API usage
-    // For now, return empty list
-    Ok(Vec::new())
+async fn update_submodule(
+    app: tauri::AppHandle,
+    repo_path: String,
+    submodule_name: String,
+    recursive: bool,
+    operation_id: Option<String>
+) -> Result<String, AppError> {
+    let task_app = app.clone();
+    run_operation(&app, "submodule", operation_id, move |_| {
+        let audit_path = repo_path.clone();
+        audited(&task_app, &audit_path, "update_submodule", || {
+            update_submodule_blocking(task_app.clone(), repo_path, submodule_name, recursive).map_err(AppError::from)
+        })
+    })
+    .await
 }
 
-#[tauri::command]
-fn add_submodule(_repo_path: String, _url: String, _path: String, _branch: Option<String>) -> Result<String, String> {
-    Err("Submodule operations not yet implemented".to_string())
+fn update_submodule_blocking(app: tauri::AppHandle, repo_path: String, submodule_name: String, recursive: bool) -> Result<String, String> {
+    let repo = open_worktree_repository(&repo_path)?;
+    
+    let mut submodule = repo.find_submodule(&submodule_name)
+        .map_err(|e| format!("Failed to find submodule '{}': {}", submodule_name, e))?;
+    
+    let mut updated = 0;
+    update_submodule_recursive(&app, &mut submodule, "", recursive, &mut updated)?;
+    
+    Ok(format!("Updated submodule '{}' ({} submodule(s) checked out)", submodule_name, updated))
 }
 
-#[tauri::command]
-fn update_submodule(_repo_path: String, _submodule_name: String, _recursive: bool) -> Result<String, String> {
-    Err("Submodule operations not yet implemented".to_string())
+/// Initializes, fetches and checks out the commit recorded in the parent's gitlink,
+/// descending into nested submodules when `recursive` is set.
+fn update_submodule_recursive(
+    app: &tauri::AppHandle,
+    submodule: &mut git2::Submodule,
+    parent_path: &str,
+    recursive: bool,
+    updated: &mut usize,
+) -> Result<(), String> {
+    let display_path = Path::new(parent_path).join(submodule.path()).to_string_lossy().to_string();
+    
+    let mut fetch_options = FetchOptions::new();
+    fetch_options.remote_callbacks(progress_callbacks(app, "submodule-progress", &display_path));
+    
+    let mut update_options = git2::SubmoduleUpdateOptions::new();
+    update_options.fetch(fetch_options);
+    
+    submodule.update(true, Some(&mut update_options))
+        .map_err(|e| format!("Failed to update submodule '{}': {}", display_path, e))?;
+    *updated += 1;
+    
+    if recursive {
+        let sub_repo = submodule.open()
+            .map_err(|e| format!("Failed to open submodule '{}': {}", display_path, e))?;
+        let nested = sub_repo.submodules()
+            .map_err(|e| format!("Failed to list submodules of '{}': {}", display_path, e))?;
+        
+        for mut nested_submodule in nested {
+            update_submodule_recursive(app, &mut nested_submodule, &display_path, recursive, updated)?;
+        }
+    }
+    
+    Ok(())
 }
 
+/// Removes a submodule like `git rm` plus deinit: its worktree, its repository under
+/// .git/modules, its config and .gitmodules entries and its gitlink. Local changes,
+/// untracked files and commits not on any remote branch would be lost, so the removal is
+/// refused when there are any unless `force`.
 #[tauri::command]
-fn remove_submodule(_repo_path: String, _submodule_name: String) -> Result<String, String> {
-    Err("Submodule operations not yet implemented".to_string())
+fn remove_submodule(app: tauri::AppHandle, repo_path: String, submodule_name: String, force: bool) -> Result<String, AppError> {
+    audited(&app, &repo_path, "remove_submodule", || {
+        let repo = open_worktree_repository(&repo_path)?;
+        
+        let workdir = repo.workdir()
+            .ok_or_else(|| AppError::invalid_input("Cannot remove submodules from a bare repository"))?
+            .to_path_buf();
+        
+        let submodule = repo.find_submodule(&submodule_name)
+            .map_err(|e| AppError::wrap(e, &format!("Failed to find submodule '{}'", submodule_name)))?;
+        let submodule_path = submodule.path().to_path_buf();
+        
+        if !force {
+            let flags = repo.submodule_status(&submodule_name, git2::SubmoduleIgnore::None)
+                .map_err(|e| AppError::wrap(e, &format!("Failed to get status of submodule '{}'", submodule_name)))?;
+            let dirty = git2::SubmoduleStatus::WD_MODIFIED
+                | git2::SubmoduleStatus::WD_UNTRACKED
+                | git2::SubmoduleStatus::WD_INDEX_MODIFIED
+                | git2::SubmoduleStatus::WD_WD_MODIFIED;
+            if flags.intersects(dirty) {
+                return Err(AppError::conflict(
+                    format!("Submodule '{}' has local changes or untracked files; force the removal to lose them", submodule_name),
+                    vec![submodule_path.to_string_lossy().to_string()],
+                ));
+            }
+            if let Ok(sub_repo) = submodule.open() {
+                let unpushed = unpushed_commit_count(&sub_repo)?;
+                if unpushed > 0 {
+                    return Err(AppError::conflict(
+                        format!(
+                            "Submodule '{}' has {} commit(s) that are on no remote branch; force the removal to lose them",
+                            submodule_name, unpushed
+                        ),
+                        vec![submodule_path.to_string_lossy().to_string()],
+                    ));
+                }
+            }
+        }
+        drop(submodule);
+        
+        // Files first: if one can't be removed, the submodule is still fully registered
+        let worktree_dir = workdir.join(&submodule_path);
+        if worktree_dir.exists() {
+            fs::remove_dir_all(&worktree_dir)
+                .map_err(|e| AppError::wrap(e, "Failed to remove submodule directory"))?;
+        }
+        
+        let modules_dir = repo.path().join("modules").join(&submodule_name);
+        if modules_dir.exists() {
+            fs::remove_dir_all(&modules_dir)
+                .map_err(|e| AppError::wrap(e, "Failed to remove .git/modules entry"))?;
+        }
+        
+        // Deinit: drop the submodule.<name>.* entries from .git/config
+        let mut local_config = repo.config()
+            .and_then(|config| config.open_level(git2::ConfigLevel::Local))
+            .map_err(|e| AppError::wrap(e, "Failed to open repository config"))?;
+        remove_submodule_entries(&mut local_config, &submodule_name)?;
+        
+        // Remove the .gitmodules section and the gitlink from the index
+        let gitmodules_path = workdir.join(".gitmodules");
+        if gitmodules_path.exists() {
+            let mut gitmodules = git2::Config::open(&gitmodules_path)
+                .map_err(|e| AppError::wrap(e, "Failed to open .gitmodules"))?;
+            remove_submodule_entries(&mut gitmodules, &submodule_name)?;
+        }
+        
+        let mut index = repo.index()
+            .map_err(|e| AppError::wrap(e, "Failed to get index"))?;
+        index.remove_path(&submodule_path)
+            .map_err(|e| AppError::wrap(e, "Failed to remove gitlink from index"))?;
+        if gitmodules_path.exists() {
+            index.add_path(Path::new(".gitmodules"))
+                .map_err(|e| AppError::wrap(e, "Failed to stage .gitmodules"))?;
+        }
+        index.write()
+            .map_err(|e| AppError::wrap(e, "Failed to write index"))?;
+        
+        Ok(format!("Removed submodule '{}'", submodule_name))
+    })
+}
+
+/// Commits reachable from the local branches or HEAD of `repo` but from no remote branch.
+fn unpushed_commit_count(repo: &Repository) -> Result<usize, AppError> {
+    let mut revwalk = repo.revwalk()
+        .map_err(|e| AppError::wrap(e, "Failed to create revwalk"))?;
+    revwalk.push_glob("refs/heads/*")
+        .map_err(|e| AppError::wrap(e, "Failed to walk submodule branches"))?;
+    // A detached HEAD is the usual state of a submodule
+    if let Ok(head) = repo.head() {
+        if let Some(target) = head.target() {
+            revwalk.push(target)
+                .map_err(|e| AppError::wrap(e, "Failed to walk submodule HEAD"))?;
+        }
+    }
+    revwalk.hide_glob("refs/remotes/*")
+        .map_err(|e| AppError::wrap(e, "Failed to walk submodule remote branches"))?;
+    
+    Ok(revwalk.count())
+}
+
+/// Deletes every submodule.<name>.* entry from `config`; git2 handles the header and
+/// quoting variants a hand-written parser would miss.
+fn remove_submodule_entries(config: &mut git2::Config, submodule_name: &str) -> Result<(), AppError> {
+    let prefix = format!("submodule.{}.", submodule_name);
+    let mut names: Vec<String> = Vec::new();
+    {
+        let mut entries = config.entries(None)
+            .map_err(|e| AppError::wrap(e, "Failed to read config"))?;
+        while let Some(entry) = entries.next() {
+            let entry = entry.map_err(|e| AppError::wrap(e, "Failed to read config"))?;
+            if let Some(name) = entry.name().filter(|name| name.starts_with(&prefix)) {
+                if !names.iter().any(|existing| existing == name) {
+                    names.push(name.to_string());
+                }
+            }
+        }
+    }
+    
+    for name in names {
+        config.remove_multivar(&name, ".*")
+            .map_err(|e| AppError::wrap(e, &format!("Failed to remove {}", name)))?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
-fn init_submodule(_repo_path: String, _submodule_name: String) -> Result<String, String> {
-    Err("Submodule operations not yet implemented".to_string())
+fn init_submodule(_repo_path: String, _submodule_name: String) -> Result<String, AppError> {
+    Err("Submodule operations not yet implemented".to_string().into())
 }
 
 #[tauri::command]
-fn sync_submodule(_repo_path: String, _submodule_name: String) -> Result<String, String> {
-    Err("Submodule operations not yet implemented".to_string())
+fn sync_submodule(_repo_path: String, _submodule_name: String) -> Result<String, AppError> {
+    Err("Submodule operations not yet implemented".to_string().into())
 }
 
 fn main() {
+    // A link from the OS or the command line goes to the running window when there is one;
+    // otherwise this launch opens the window with it
+    let args: Vec<String> = env::args().skip(1).collect();
+    let launch_link = match parse_cli(&args) {
+        Ok(CliRequest::Launch(link)) => link,
+        Ok(CliRequest::Help) => {
+            print_usage();
+            return;
+        }
+        Err(message) => {
+            eprintln!("{}", message);
+            std::process::exit(2);
+        }
+    };
+    if let Some(link) = &launch_link {
+        if forward_deep_link(link) {
+            println!("Sent to the running CodeGit window");
+            return;
+        }
+    }
+    
     tauri::Builder::default()
-        .setup(|app| {
+        .manage(RepositoryWatchers::default())
+        .manage(StatusCache::default())
+        .manage(CommitCacheBuilds::default())
+        .manage(Operations::default())
+        .manage(Profiler::default())
+        .manage(AheadBehindCache::default())
+        .manage(AvatarLimiter::default())
+        .manage(IssueSuggestionCache::default())
+        .setup(move |app| {
+            migrate_environment_credentials();
+            register_url_scheme();
+            start_deep_link_listener(app.handle());
+            
             // Initialize database on app startup
             let app_handle = app.handle();
             tauri::async_runtime::spawn(async move {
-                match init_database(app_handle).await {
-                    Ok(_) => println!("✅ Database initialized successfully"),
+                match init_database(app_handle.clone()).await {
+                    Ok(_) => {
+                        println!("✅ Database initialized successfully");
+                        load_profiling_setting(&app_handle).await;
+                        // Checkout links look repositories up in the database
+                        if let Some(link) = &launch_link {
+                            receive_deep_link(&app_handle, link);
+                        }
+                        let fetch_app = app_handle.clone();
+                        std::thread::spawn(move || auto_fetch_loop(fetch_app));
+                        // Warm the last repository while the window is still loading
+                        tauri::async_runtime::spawn_blocking(move || warm_start(&app_handle));
+                    }
                     Err(e) => println!("❌ Failed to initialize database: {}", e),
                 }
             });
@@ -1434,18 +4018,27 @@ fn main() {
             greet,
             open_repository,
             get_commits,
+            get_commit_message,
             get_repository_status,
             stage_file,
             unstage_file,
             commit_changes,
             get_file_diff,
+            get_staged_diff,
+            get_unstaged_diff,
             get_branches,
+            list_references,
             create_branch,
             switch_branch,
+            delete_branch,
+            rename_branch,
+            set_upstream,
+            unset_upstream,
             get_remotes,
             add_remote,
             remove_remote,
             fetch_from_remote,
+            fetch_organization,
             pull_from_remote,
             push_to_remote,
             clone_repository,
@@ -1454,15 +4047,161 @@ fn main() {
             apply_stash,
             drop_stash,
             merge_branch,
+            merge_continue,
+            abort_merge,
+            abort_rebase,
             get_merge_conflicts,
             resolve_conflict,
             cherry_pick_commit,
+            cherry_pick_continue,
+            cherry_pick_abort,
+            revert_commit,
+            revert_continue,
+            revert_abort,
+            get_sequencer_state,
             rebase_interactive,
             get_log_graph,
             reset_to_commit,
+            list_backups,
+            undo_last_operation,
+            get_operation_history,
+            get_performance_report,
+            set_profiling_enabled,
+            clear_performance_data,
+            create_pull_request,
+            list_pull_requests,
+            get_checks,
+            list_issues,
+            suggest_issue_references,
+            parse_issue_references,
+            get_avatar,
+            get_fork_info,
+            configure_upstream,
+            sync_fork,
+            get_remote_web_url,
+            list_provider_repositories,
+            list_pipeline_runs,
+            get_failed_job_log,
+            create_release,
+            find_client_repositories,
+            import_client_repositories,
+            export_repositories,
+            open_in_terminal,
+            open_in_editor,
+            get_external_tools,
+            save_external_tools,
+            get_smtp_account,
+            save_smtp_account,
+            send_patch_series,
+            push_for_review,
+            list_scopes,
+            save_scope,
+            delete_scope,
+            set_active_scope,
+            start_feature,
+            finish_feature,
+            suggest_commit_message,
+            get_repository_health,
+            resolve_revision_at,
+            list_tree_at,
+            read_file_at,
+            stage_files,
+            unstage_files,
+            discard_files,
+            clean_working_directory,
+            stage_lines,
+            unstage_lines,
+            get_tags,
+            create_tag,
+            delete_tag,
+            push_tag,
+            get_blame,
+            get_reflog,
+            restore_from_reflog,
             prepare_interactive_rebase,
             execute_interactive_rebase,
+            rebase_continue,
+            rebase_skip,
+            rebase_abort,
             get_submodules,
+            get_lfs_info,
+            download_lfs_objects,
+            verify_repository,
+            analyze_repository_size,
+            purge_history,
+            get_commit_graph_info,
+            write_commit_graph,
+            create_bundle,
+            fetch_from_bundle,
+            export_archive,
+            get_line_ending_config,
+            set_line_ending_config,
+            renormalize_line_endings,
+            get_gitattributes,
+            set_gitattributes_entry,
+            remove_gitattributes_entry,
+            check_attributes,
+            list_hooks,
+            read_hook,
+            toggle_hook,
+            save_identity_profile,
+            get_identity_profiles,
+            delete_identity_profile,
+            assign_identity_profile,
+            get_repository_identity,
+            check_repository_identity,
+            fix_repository_identity,
+            get_gitignore,
+            save_gitignore,
+            add_ignore_pattern,
+            check_ignored,
+            get_global_excludes,
+            save_global_excludes,
+            add_global_exclude_patterns,
+            run_git_command,
+            get_keybindings,
+            save_keybinding,
+            reset_keybindings,
+            get_panel_layouts,
+            save_panel_layout,
+            reset_panel_layouts,
+            get_repo_settings,
+            save_repo_settings,
+            save_theme,
+            get_themes,
+            delete_theme,
+            set_active_theme,
+            list_users,
+            set_active_user,
+            delete_user,
+            save_secret,
+            get_secret,
+            delete_secret,
+            backup_database,
+            restore_database,
+            create_workspace,
+            get_workspaces,
+            switch_workspace,
+            delete_workspace,
+            add_repo_tag,
+            remove_repo_tag,
+            list_repo_tags,
+            get_repositories_by_tag,
+            toggle_repository_favorite,
+            set_repository_pinned,
+            reorder_repositories,
+            get_discovery_exclusions,
+            add_discovery_exclusion,
+            remove_discovery_exclusion,
+            forget_repository,
+            watch_repository,
+            unwatch_repository,
+            list_operations,
+            save_session,
+            get_session,
+            clear_commit_cache,
+            cancel_operation,
+            get_submodule_drift,
             add_submodule,
             update_submodule,
             remove_submodule,
@@ -1470,7 +4209,10 @@ fn main() {
             sync_submodule,
             discover_repositories,
             get_file_content,
+            read_file_range,
+            get_payload_limits,
             get_detailed_branches,
+            get_branch_ahead_behind,
             // Database commands
             init_database,
             save_user_info,
@@ -17,6 +17,7 @@ interface GitCommit {
   author: string;
   email: string;
   timestamp: number;
+  truncated?: boolean;
 }
 
 interface GitBranch {
@@ -1,6 +1,7 @@
 import React, { useState, useEffect } from 'react';
 import { invoke } from '@tauri-apps/api/tauri';
 import { useAppState } from '../../contexts/AppStateContext';
+import { errorMessage } from '../../types/errors';
 
 interface DatabaseData {
   user: any;
@@ -56,7 +57,7 @@ const DataLocationDebug: React.FC = () => {
         migrationStatus
       });
     } catch (err: any) {
-      setError(`SQLite Error: ${err}`);
+      setError(`SQLite Error: ${errorMessage(err)}`);
       setDatabaseData(null);
     } finally {
       setLoading(false);
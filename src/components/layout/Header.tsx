@@ -3,6 +3,7 @@ import { AppState, HeaderState, Notification } from '../../types/state';
 import { useSmartNotifications } from '../../hooks/useSmartNotifications';
 import { useAppState } from '../../contexts/AppStateContext';
 import { invoke } from '@tauri-apps/api/tauri';
+import { errorMessage } from '../../types/errors';
 import Settings from '../../Settings';
 import Account from '../../Account';
 import './Header.css';
@@ -71,7 +72,7 @@ const Header: React.FC<HeaderProps> = ({
       const info = await invoke('get_database_info');
       setDbInfo(info);
     } catch (error) {
-      setDbInfo({ error: String(error) });
+      setDbInfo({ error: errorMessage(error) });
     }
   };
 
@@ -5,6 +5,7 @@ export interface UserInfo {
   name: string;
   email: string;
   workspace_name?: string;
+  is_active?: boolean;
   created_at?: string;
   updated_at?: string;
 }
@@ -15,6 +16,17 @@ export interface Organization {
   color: string;
   description?: string;
   avatar?: string;
+  user_id?: number | null;
+  workspace_id?: string | null;
+  created_at: string;
+  updated_at: string;
+}
+
+export interface Workspace {
+  id: string;
+  name: string;
+  color?: string | null;
+  is_active?: boolean;
   created_at: string;
   updated_at: string;
 }
@@ -29,7 +41,10 @@ export interface Repository {
   last_commit: string;
   is_dirty: boolean;
   is_favorite: boolean;
+  is_pinned?: boolean;
+  position?: number | null;
   tags: string[];
+  workspace_id?: string | null;
   last_accessed: string;
   created_at: string;
   updated_at: string;
@@ -43,6 +58,7 @@ export interface AppSettings {
   font_family: string;
   language: string;
   settings_json: Record<string, any>;
+  active_theme_id?: string | null;
   updated_at: string;
 }
 
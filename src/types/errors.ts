@@ -0,0 +1,38 @@
+// ===== BACKEND ERRORS =====
+
+// Mirrors ErrorKind in src-tauri/src/commands/error.rs
+export type ErrorKind =
+  | 'auth_required'
+  | 'conflict'
+  | 'not_found'
+  | 'invalid_input'
+  | 'cancelled'
+  | 'locked'
+  | 'network'
+  | 'io'
+  | 'database'
+  | 'git'
+  | 'internal';
+
+// What every rejected invoke() carries
+export interface AppError {
+  kind: ErrorKind;
+  code: string;
+  message: string;
+  details: {
+    paths?: string[];
+    host?: string;
+  };
+}
+
+export function isAppError(error: unknown): error is AppError {
+  return typeof error === 'object' && error !== null && 'kind' in error && 'message' in error;
+}
+
+// Display text for anything thrown by invoke() or by frontend code
+export function errorMessage(error: unknown): string {
+  if (isAppError(error) || error instanceof Error) {
+    return error.message;
+  }
+  return String(error);
+}
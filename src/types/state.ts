@@ -41,7 +41,11 @@ export interface RepositoryInfo {
   current_branch: string;
   last_commit: string;
   is_dirty: boolean;
+  is_bare?: boolean;
+  kind?: 'standard' | 'bare' | 'worktree' | 'submodule';
+  main_repository_path?: string | null;
   last_accessed: number;
+  identity_warning?: string | null;
   
   // NEW: Enterprise features
   organization_id?: string;